@@ -34,7 +34,7 @@ impl Collector {
             Pattern::Union(_, _, inner_pat) => {
                 self.collect_from_pattern(inner_pat);
             }
-            Pattern::Var(_, None) => {}
+            Pattern::Var(_, None) | Pattern::Lit(_, _) => {}
         }
     }
 }
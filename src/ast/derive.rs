@@ -0,0 +1,328 @@
+// AST types for the `DERIVE[TypeName, Trait, ...];` statement, and the trait implementations it
+// stands for.
+
+use crate::ast::name::{FullName, Name};
+use crate::ast::program::Program;
+use crate::ast::traits::TraitId;
+use crate::ast::typedecl::{Field, TypeDeclValue, TypeDefn};
+use crate::configuration::Configuration;
+use crate::constants::STD_NAME;
+use crate::error::Errors;
+use crate::parse::parser::parse_and_save_to_temporary_file;
+use crate::parse::sourcefile::Span;
+
+/// A trait of `Std` that `DERIVE` implements for a struct or a union, structurally over its fields.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DerivableTrait {
+    Eq,
+    LessThan,
+    LessThanOrEq,
+    ToString,
+}
+
+impl DerivableTrait {
+    /// Every derivable trait, in the order the error listing them names them.
+    pub const ALL: [DerivableTrait; 4] = [
+        DerivableTrait::Eq,
+        DerivableTrait::LessThan,
+        DerivableTrait::LessThanOrEq,
+        DerivableTrait::ToString,
+    ];
+
+    /// The name of the trait in `Std`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DerivableTrait::Eq => "Eq",
+            DerivableTrait::LessThan => "LessThan",
+            DerivableTrait::LessThanOrEq => "LessThanOrEq",
+            DerivableTrait::ToString => "ToString",
+        }
+    }
+
+    /// The trait the path `path` names: its full name, such as `Std::Eq`, or its name in `Std`.
+    pub fn from_path(path: &FullName) -> Option<Self> {
+        if path.namespace.is_absolute && path.namespace.names.is_empty() {
+            return None;
+        }
+        Self::ALL
+            .into_iter()
+            .find(|t| path.is_suffix_of(&FullName::from_strs(&[STD_NAME], t.name())))
+    }
+
+    /// The trait, as the trait environment knows it.
+    pub fn trait_id(&self) -> TraitId {
+        TraitId::from_fullname(FullName::from_strs(&[STD_NAME], self.name()))
+    }
+
+    /// The traits the type of each field has to implement for the implementation to be derived: a
+    /// comparison of two values compares the first fields in which they differ.
+    pub fn field_traits(&self) -> Vec<DerivableTrait> {
+        match self {
+            DerivableTrait::Eq => vec![DerivableTrait::Eq],
+            DerivableTrait::LessThan => vec![DerivableTrait::Eq, DerivableTrait::LessThan],
+            DerivableTrait::LessThanOrEq => vec![DerivableTrait::Eq, DerivableTrait::LessThanOrEq],
+            DerivableTrait::ToString => vec![DerivableTrait::ToString],
+        }
+    }
+
+    /// The source of the implementation of this trait for the type `defn` defines, written so that
+    /// it means the same in any module: every name in it is an absolute path.
+    ///
+    /// Each type parameter of kind `*` that a field mentions is required to implement the traits of
+    /// `field_traits`.
+    fn impl_source(&self, defn: &TypeDefn) -> String {
+        let fields = match &defn.value {
+            TypeDeclValue::Struct(s) => &s.fields,
+            TypeDeclValue::Union(u) => &u.fields,
+            TypeDeclValue::Alias(_) => unreachable!("an alias is rejected before its derivation"),
+        };
+        let is_union = matches!(defn.value, TypeDeclValue::Union(_));
+
+        // The head of the implementation, with its context.
+        let mut constraints = vec![];
+        for tv in &defn.tyvars {
+            if !tv.kind.is_star() {
+                constraints.push(format!("{} : {}", tv.name, tv.kind.to_string()));
+                continue;
+            }
+            let mentioned = fields
+                .iter()
+                .any(|field| field.syn_ty.free_vars().contains_key(&tv.name));
+            if mentioned {
+                for t in self.field_traits() {
+                    constraints.push(format!("{} : ::{}::{}", tv.name, STD_NAME, t.name()));
+                }
+            }
+        }
+        let mut src = "impl ".to_string();
+        if !constraints.is_empty() {
+            src += &format!("[{}] ", constraints.join(", "));
+        }
+        src += &format!("::{}", defn.name.to_string());
+        for tv in &defn.tyvars {
+            src += &format!(" {}", tv.name);
+        }
+        src += &format!(" : ::{}::{} {{\n", STD_NAME, self.name());
+
+        // The member.
+        let get = |value: &str, field: &Field| {
+            if is_union {
+                format!("{}.as_{}", value, field.name)
+            } else {
+                format!("{}.@{}", value, field.name)
+            }
+        };
+        match self {
+            DerivableTrait::Eq if is_union => {
+                src += "    eq = |x, y| (\n";
+                for field in fields {
+                    src += &format!(
+                        "        if x.is_{} {{ if y.is_{} {{ {} == {} }} else {{ false }} }};\n",
+                        field.name,
+                        field.name,
+                        get("x", field),
+                        get("y", field)
+                    );
+                }
+                src += "        false\n    );\n";
+            }
+            DerivableTrait::Eq => {
+                let eqs = fields
+                    .iter()
+                    .map(|field| format!("{} == {}", get("x", field), get("y", field)))
+                    .collect::<Vec<_>>();
+                if eqs.is_empty() {
+                    src += "    eq = |_, _| true;\n";
+                } else {
+                    src += &format!("    eq = |x, y| {};\n", eqs.join(" && "));
+                }
+            }
+            DerivableTrait::LessThan | DerivableTrait::LessThanOrEq => {
+                let (member, op, if_equal) = if *self == DerivableTrait::LessThan {
+                    ("less_than", "<", "false")
+                } else {
+                    ("less_than_or_eq", "<=", "true")
+                };
+                src += &format!("    {} = |x, y| (\n", member);
+                for (i, field) in fields.iter().enumerate() {
+                    if is_union {
+                        // A value of an earlier variant is less than one of a later variant.
+                        let y_is_earlier = fields[..i]
+                            .iter()
+                            .map(|earlier| format!("y.is_{}", earlier.name))
+                            .collect::<Vec<_>>();
+                        let y_is_later = if y_is_earlier.is_empty() {
+                            "true".to_string()
+                        } else {
+                            format!(
+                                "if {} {{ false }} else {{ true }}",
+                                y_is_earlier.join(" || ")
+                            )
+                        };
+                        src += &format!(
+                            "        if x.is_{} {{ if y.is_{} {{ {} {} {} }} else {{ {} }} }};\n",
+                            field.name,
+                            field.name,
+                            get("x", field),
+                            op,
+                            get("y", field),
+                            y_is_later
+                        );
+                    } else {
+                        let (x, y) = (get("x", field), get("y", field));
+                        src += &format!("        if {} != {} {{ {} {} {} }};\n", x, y, x, op, y);
+                    }
+                }
+                src += &format!("        {}\n    );\n", if_equal);
+            }
+            DerivableTrait::ToString => {
+                let to_string = |field: &Field| {
+                    format!("::{}::ToString::to_string({})", STD_NAME, get("x", field))
+                };
+                if is_union {
+                    src += "    to_string = |x| (\n";
+                    for field in fields {
+                        src += &format!(
+                            "        if x.is_{} {{ \"{}(\" + {} + \")\" }};\n",
+                            field.name,
+                            field.name,
+                            to_string(field)
+                        );
+                    }
+                    src += "        \"\"\n    );\n";
+                } else if fields.is_empty() {
+                    src += &format!("    to_string = |_| \"{} {{}}\";\n", defn.name.name);
+                } else {
+                    let shown = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| {
+                            let sep = if i == 0 { " " } else { ", " };
+                            format!("\"{}{}: \" + {}", sep, field.name, to_string(field))
+                        })
+                        .collect::<Vec<_>>();
+                    src += &format!(
+                        "    to_string = |x| \"{} {{\" + {} + \" }}\";\n",
+                        defn.name.name,
+                        shown.join(" + ")
+                    );
+                }
+            }
+        }
+        src += "}\n\n";
+        src
+    }
+}
+
+/// A `DERIVE[TypeName, Trait, ...];` statement: it implements each of the traits for the struct or
+/// union it names, which is defined in the module the statement is written in.
+#[derive(Clone)]
+pub struct DeriveStatement {
+    /// The full name of the type, formed at parse time by putting the path the user wrote under the
+    /// namespace the statement is written in.
+    pub type_name: FullName,
+    /// The span of the type name.
+    pub type_name_src: Option<Span>,
+    /// The traits to implement, with the span of the name of each.
+    pub traits: Vec<(DerivableTrait, Option<Span>)>,
+}
+
+impl DeriveStatement {
+    /// The errors of the fields whose types lack an implementation the derived ones need, found
+    /// ahead of the type check of the implementations, where they would be reported against the
+    /// generated source.
+    ///
+    /// A field is checked by the type constructor at the head of its type: a type parameter there is
+    /// constrained by the implementation, and for a type constructor some implementation of the
+    /// trait has to exist. What the implementation found requires in turn is left to the type check.
+    pub fn validate(&self, program: &Program) -> Errors {
+        let mut errors = Errors::empty();
+        let Some(defn) = program
+            .type_defns
+            .iter()
+            .find(|defn| defn.name == self.type_name)
+        else {
+            return errors;
+        };
+        let (fields, field_kind) = match &defn.value {
+            TypeDeclValue::Struct(s) => (&s.fields, "field"),
+            TypeDeclValue::Union(u) => (&u.fields, "variant"),
+            TypeDeclValue::Alias(_) => return errors,
+        };
+        for (derived, trait_src) in &self.traits {
+            for field in fields {
+                let Some(tycon) = field.ty.toplevel_tycon() else {
+                    continue;
+                };
+                for required in derived.field_traits() {
+                    let implemented =
+                        program
+                            .trait_env
+                            .impls
+                            .get(&required.trait_id())
+                            .map_or(false, |impls| {
+                                impls.iter().any(|imp| {
+                                    imp.qual_pred.predicate.ty.toplevel_tycon().as_ref()
+                                        == Some(&tycon)
+                                })
+                            });
+                    if !implemented {
+                        errors.append(Errors::from_msg_srcs(
+                            format!(
+                                "Cannot derive `{}` for `{}`: the {} `{}` is of type `{}`, which does not implement `{}`.",
+                                derived.name(),
+                                self.type_name.to_string(),
+                                field_kind,
+                                field.name,
+                                field.syn_ty.to_string(),
+                                required.name()
+                            ),
+                            &[trait_src, &field.source],
+                        ));
+                    }
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// The program of the trait implementations `derives` ask for, as a part of the module
+/// `module_name`, which defines each type in `type_defns`. The source is saved to a temporary file,
+/// which is what the spans of the implementations point into.
+pub fn make_derived_impls_mod(
+    module_name: &Name,
+    derives: &[DeriveStatement],
+    type_defns: &[TypeDefn],
+    config: &Configuration,
+) -> Result<Program, Errors> {
+    let mut errors = Errors::empty();
+    let mut src = format!("module {};\n\n", module_name);
+    for stmt in derives {
+        let Some(defn) = type_defns.iter().find(|defn| defn.name == stmt.type_name) else {
+            errors.append(Errors::from_msg_srcs(
+                format!(
+                    "`DERIVE` names `{}`, which is not a type defined in this module.",
+                    stmt.type_name.to_string()
+                ),
+                &[&stmt.type_name_src],
+            ));
+            continue;
+        };
+        if let TypeDeclValue::Alias(_) = defn.value {
+            errors.append(Errors::from_msg_srcs(
+                format!(
+                    "`DERIVE` cannot implement traits for `{}`, which is a type alias.",
+                    stmt.type_name.to_string()
+                ),
+                &[&stmt.type_name_src],
+            ));
+            continue;
+        }
+        for (derived, _) in &stmt.traits {
+            src += &derived.impl_source(defn);
+        }
+    }
+    errors.to_result()?;
+    parse_and_save_to_temporary_file(&src, &format!("derive_{}", module_name), config)
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::ast::kind_scope::{KindEnv, KindScope};
 use crate::ast::name::Name;
 use crate::ast::program::{EndNode, TypeEnv};
-use crate::ast::types::{is_opaque_tyvar, type_assocty, AssocType, TyVar, Type, TypeNode};
+use crate::ast::types::{is_opaque_tyvar, type_assocty, AssocType, NameRef, TyVar, Type, TypeNode};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::error::Errors;
 use crate::misc::Set;
@@ -160,7 +160,16 @@ impl Equality {
     }
 
     pub fn to_string(&self) -> String {
-        format!("{} = {}", self.lhs().to_string(), self.value.to_string())
+        self.to_string_with(&|name| name.to_string())
+    }
+
+    /// The equality written out as `to_string` does, each name in it written by `names`.
+    pub fn to_string_with(&self, names: &dyn Fn(NameRef) -> String) -> String {
+        format!(
+            "{} = {}",
+            self.lhs().to_string_with(names),
+            self.value.to_string_with(names)
+        )
     }
 
     pub fn free_vars_vec(&self, buf: &mut Vec<Arc<TyVar>>) {
@@ -16,6 +16,7 @@ use crate::object::create_obj;
 use crate::object::ObjectFieldType;
 use crate::parse::sourcefile::Span;
 use crate::rc_ir::ast::RcState;
+use inkwell::values::BasicValueEnum;
 use std::sync::Arc;
 
 // The export statement.
@@ -105,11 +106,6 @@ impl ExportStatement {
     // Requires `self.function_type` and `self.value_expr` to already be set.
     pub fn implement<'c, 'm>(&self, gc: &mut Generator<'c, 'm>) {
         let function_type = self.function_type.as_ref().unwrap();
-        let ExportedFunctionType {
-            doms,
-            codom,
-            io_type,
-        } = function_type.clone();
 
         // Take the name. An `FFI_CALL` of this C function has declared it by now — code generation
         // implements the program's symbols before it reaches here — and a declaration and this
@@ -123,68 +119,18 @@ impl ExportStatement {
             self.function_name
         );
 
-        // Each value the function exchanges travels in its Fix representation, and the C type the
-        // signature gave it names that same representation.
-        for (param_ty, dom) in signature.param_tys.iter().zip(doms.iter()) {
-            assert_crosses_as_c_type(param_ty, dom, gc);
-        }
-        if signature.ret_tycon.get_c_type(gc.context).is_some() {
-            assert_crosses_as_c_type(&signature.ret_tycon, &codom, gc);
-        }
-
         // Implement the function.
         let bb = gc.context.append_basic_block(func, "entry");
         gc.builder().position_at_end(bb);
 
-        // Create Fix values from arguments. Each parameter is the value's one scalar.
-        let param_vals = func.get_params();
-        let mut args = param_vals
-            .iter()
-            .enumerate()
-            .map(|(i, arg)| Object::from_parts(vec![*arg], doms[i].clone(), gc))
-            .collect::<Vec<_>>();
-
         // Get the Fix value to be exported. `value_expr` is a reference to the instantiated symbol
         // (see `instantiate_exported_value`), which the RC-IR back end has already implemented;
         // materialize that symbol's object here.
         let fix_expr = self.value_expr.clone().unwrap();
         let fix_name = fix_expr.get_var().name.clone();
-        let mut fix_value = gc.get_scoped_obj(&fix_name);
-
-        // Pass the arguments to the Fix value.
-        match io_type {
-            IOType::Pure => {}
-            IOType::IO => {}
-            IOType::IOState => {
-                let iostate = create_obj(make_iostate_ty(), &vec![], None, gc, Some("iostate"));
-                args.push(iostate);
-            }
-        }
-        while args.len() > 0 {
-            let arity = fix_value.ty.get_lambda_srcs().len();
-            let rest = args.split_off(arity);
-            fix_value = gc.apply_lambda(fix_value, args, false).unwrap();
-            args = rest;
-        }
-        match io_type {
-            IOType::Pure => {}
-            IOType::IO => {
-                fix_value = run_io(gc, &fix_value);
-            }
-            IOType::IOState => {
-                fix_value =
-                    ObjectFieldType::get_struct_fields(gc, &fix_value, &[1], RcState::Unknown)[0]
-                        .clone();
-            }
-        }
+        let fix_value = gc.get_scoped_obj(&fix_name);
 
-        // Return the result as its one scalar.
-        if codom.is_unit() {
-            gc.builder().build_return(None).unwrap();
-        } else {
-            let ret_val = fix_value.parts()[0];
-            gc.builder().build_return(Some(&ret_val)).unwrap();
-        }
+        function_type.build_call_and_return(fix_value, &func.get_params(), gc);
     }
 }
 
@@ -232,6 +178,118 @@ pub enum IOType {
 }
 
 impl ExportedFunctionType {
+    /// The exported function type of a value of type `ty` as code generation sees it, where newtypes
+    /// are unwrapped and an `IO` action is the function taking the `IOState` token.
+    ///
+    /// This is what `validate` gives for the type the value was written at, unwrapped the way
+    /// `unwrap_newtype` unwraps an export statement's.
+    pub fn of_unwrapped(ty: &Arc<TypeNode>) -> ExportedFunctionType {
+        let (mut doms, codom) = ty.collect_app_src(usize::MAX);
+        let takes_iostate = doms
+            .last()
+            .and_then(|dom| dom.toplevel_tycon())
+            .is_some_and(|tc| tc.is_iostate());
+        if takes_iostate {
+            // The function returns `(IOState, codom)`.
+            doms.pop();
+            let codom = codom.collect_type_arguments()[1].clone();
+            return ExportedFunctionType {
+                doms,
+                codom,
+                io_type: IOType::IOState,
+            };
+        }
+        if codom.is_io() {
+            let codom = codom.collect_type_arguments()[0].clone();
+            return ExportedFunctionType {
+                doms,
+                codom,
+                io_type: IOType::IO,
+            };
+        }
+        ExportedFunctionType {
+            doms,
+            codom,
+            io_type: IOType::Pure,
+        }
+    }
+
+    /// Finish the C function being built by applying `fix_value`, a value of this type, to the
+    /// parameters `param_vals` and returning its result. Each parameter and the result is the one
+    /// scalar of the Fix value it stands for.
+    ///
+    /// The function takes over `fix_value`.
+    pub fn build_call_and_return<'c, 'm>(
+        &self,
+        fix_value: Object<'c>,
+        param_vals: &[BasicValueEnum<'c>],
+        gc: &mut Generator<'c, 'm>,
+    ) {
+        // Each value the function exchanges travels in its Fix representation, and the C type the
+        // signature gave it names that same representation.
+        let signature = CSignature::of_ffi_export(self, gc.type_env());
+        for (param_ty, dom) in signature.param_tys.iter().zip(self.doms.iter()) {
+            assert_crosses_as_c_type(param_ty, dom, gc);
+        }
+        if signature.ret_tycon.get_c_type(gc.context).is_some() {
+            assert_crosses_as_c_type(&signature.ret_tycon, &self.codom, gc);
+        }
+
+        // Create Fix values from arguments.
+        let args = param_vals
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| Object::from_parts(vec![*arg], self.doms[i].clone(), gc))
+            .collect::<Vec<_>>();
+
+        // Pass the arguments to the Fix value.
+        let fix_value = self.call(fix_value, args, gc);
+
+        // Return the result as its one scalar.
+        if self.codom.is_unit() {
+            gc.builder().build_return(None).unwrap();
+        } else {
+            let ret_val = fix_value.parts()[0];
+            gc.builder().build_return(Some(&ret_val)).unwrap();
+        }
+    }
+
+    /// Apply `fix_value`, a value of this type, to `args`, one per type in `doms`, and produce its
+    /// result of type `codom`, running it where it is an `IO` action.
+    fn call<'c, 'm>(
+        &self,
+        mut fix_value: Object<'c>,
+        mut args: Vec<Object<'c>>,
+        gc: &mut Generator<'c, 'm>,
+    ) -> Object<'c> {
+        match self.io_type {
+            IOType::Pure => {}
+            IOType::IO => {}
+            IOType::IOState => {
+                let iostate = create_obj(make_iostate_ty(), &vec![], None, gc, Some("iostate"));
+                args.push(iostate);
+            }
+        }
+        while args.len() > 0 {
+            let arity = fix_value.ty.get_lambda_srcs().len();
+            let rest = args.split_off(arity);
+            fix_value = gc.apply_lambda(fix_value, args, false).unwrap();
+            args = rest;
+        }
+        match self.io_type {
+            IOType::Pure => {}
+            IOType::IO => {
+                fix_value = run_io(gc, &fix_value);
+            }
+            IOType::IOState => {
+                fix_value =
+                    ObjectFieldType::get_struct_fields(gc, &fix_value, &[1], RcState::Unknown)[0]
+                        .clone();
+            }
+        }
+        fix_value
+    }
+
     // Check if a type is valid for a value which is exported.
     // - src: Used for error messages.
     pub fn validate(
@@ -3,6 +3,7 @@ use crate::ast::name::{FullName, Name, NameSpace};
 use crate::ast::pattern::PatternNode;
 use crate::ast::program::{EndNode, TypeEnv};
 use crate::ast::types::{type_fun, TyCon, TypeNode};
+use crate::c_struct::c_struct_layout;
 use crate::constants::{CAP_NAME, FORMAT_LINE_LIMIT, HOLE_NAME, STD_NAME};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::error::Errors;
@@ -759,6 +760,32 @@ impl ExprNode {
         Arc::new(ret)
     }
 
+    /// This `FFI_CALL` with its signature written with the type constructors `ret_ty` and
+    /// `param_tys`.
+    pub fn set_ffi_call_tycons(
+        &self,
+        ret_ty: Arc<TyCon>,
+        param_tys: Vec<Arc<TyCon>>,
+    ) -> Arc<ExprNode> {
+        let mut ret = self.clone_except_fvs();
+        match &*self.expr {
+            Expr::FFICall(fun_name, _, _, is_va_args, args, is_io) => {
+                ret.expr = Arc::new(Expr::FFICall(
+                    fun_name.clone(),
+                    ret_ty,
+                    param_tys,
+                    *is_va_args,
+                    args.clone(),
+                    *is_io,
+                ));
+            }
+            _ => {
+                panic!()
+            }
+        }
+        Arc::new(ret)
+    }
+
     pub fn set_ffi_call_args(&self, args: Vec<Arc<ExprNode>>) -> Arc<ExprNode> {
         let mut ret = self.clone_except_fvs();
         match &*self.expr {
@@ -875,8 +902,20 @@ impl ExprNode {
                 }
                 Ok(expr)
             }
-            Expr::FFICall(_, _, _, _, args, _) => {
-                let mut expr = self.clone();
+            Expr::FFICall(_, ret_ty, param_tys, _, args, _) => {
+                // The parser gives a C scalar type and `()` as absolute names, so a relative name
+                // is a struct passed by value, named as a type is anywhere else.
+                let resolve_tycon = |tc: &Arc<TyCon>, ctx: &mut NameResolutionContext| {
+                    if tc.name.is_absolute() {
+                        return Ok(tc.clone());
+                    }
+                    let mut tc = tc.as_ref().clone();
+                    tc.resolve_namespace(ctx, &self.source)?;
+                    Ok::<_, Errors>(Arc::new(tc))
+                };
+                let ret_ty = resolve_tycon(ret_ty, ctx)?;
+                let param_tys = collect_results(param_tys.iter().map(|tc| resolve_tycon(tc, ctx)))?;
+                let mut expr = self.set_ffi_call_tycons(ret_ty, param_tys);
                 for (i, arg) in args.iter().enumerate() {
                     expr = expr.set_ffi_call_arg(arg.resolve_namespace(ctx)?, i);
                 }
@@ -960,7 +999,29 @@ impl ExprNode {
                 }
                 Ok(expr)
             }
-            Expr::FFICall(_, _, _, _, args, _) => {
+            Expr::FFICall(_, ret_ty, param_tys, _, args, _) => {
+                // A struct passed by value has to be one C can hold; the scalars and `()` need no
+                // check.
+                let mut errors = Errors::empty();
+                for tc in std::iter::once(ret_ty).chain(param_tys.iter()) {
+                    if !tc.is_c_struct() {
+                        continue;
+                    }
+                    if type_env.aliases.contains_key(tc) {
+                        errors.append(Errors::from_msg_srcs(
+                            format!(
+                                "In FFI_CALL, cannot use type alias `{}` instead of struct name.",
+                                tc.to_string()
+                            ),
+                            &[&self.source],
+                        ));
+                        continue;
+                    }
+                    if let Err(msg) = c_struct_layout(tc, type_env) {
+                        errors.append(Errors::from_msg_srcs(msg, &[&self.source]));
+                    }
+                }
+                errors.to_result()?;
                 let mut expr = self.clone();
                 for (i, arg) in args.iter().enumerate() {
                     expr = expr.set_ffi_call_arg(arg.resolve_type_aliases(type_env)?, i);
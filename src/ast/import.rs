@@ -89,6 +89,40 @@ impl ImportStatement {
         self.items.iter().any(|item| item.is_accessible(&name))
     }
 
+    /// The statement with the items through which none of `used` is accessible taken out, or
+    /// `None` where no item is left, together with each leaf item taken out: the path it names,
+    /// such as `Lib::Ns::f` or `Lib::*`, and the span of the token naming it, where there is one.
+    ///
+    /// A name of `used` counts for an item where it lies under the item and the `hiding` clause
+    /// leaves it in. The `hiding` clause is kept as it is.
+    pub fn retain_used_items(
+        &self,
+        used: &[FullName],
+    ) -> (Option<ImportStatement>, Vec<(String, Option<Span>)>) {
+        let used_here = used
+            .iter()
+            .filter(|name| self.is_accessible(name))
+            .map(|name| {
+                let mut name = name.clone();
+                assert!(name.pop_front_namespace());
+                name
+            })
+            .collect::<Vec<_>>();
+        let namespace = NameSpace::new(vec![self.module_name.clone()]);
+        let mut removed = vec![];
+        let items = self
+            .items
+            .iter()
+            .filter_map(|item| item.retain_used(&used_here, &namespace, &mut removed))
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            return (None, removed);
+        }
+        let mut stmt = self.clone();
+        stmt.items = items;
+        (Some(stmt), removed)
+    }
+
     /// The statement by which `module` brings in every item of its own.
     pub fn implicit_self_import(module: Name) -> ImportStatement {
         ImportStatement {
@@ -462,6 +496,55 @@ impl ImportTreeNode {
         items.push(ImportTreeNode::from_names(names));
     }
 
+    /// This node with the items covering none of `used` taken out, or `None` where nothing is
+    /// left. Each leaf taken out is pushed to `removed` with its path and span.
+    ///
+    /// # Arguments
+    /// * `used` — the names used, written relative to the namespace the node sits in.
+    /// * `namespace` — the path the walk down the tree has reached, which qualifies the paths
+    ///   pushed to `removed`.
+    fn retain_used(
+        &self,
+        used: &[FullName],
+        namespace: &NameSpace,
+        removed: &mut Vec<(String, Option<Span>)>,
+    ) -> Option<ImportTreeNode> {
+        let (path, span) = match self {
+            ImportTreeNode::NameSpace(name, items, span) => {
+                let inner_used = used
+                    .iter()
+                    .filter(|used_name| {
+                        !used_name.is_local() && used_name.namespace.names[0] == *name
+                    })
+                    .map(|used_name| {
+                        let mut used_name = used_name.clone();
+                        assert!(used_name.pop_front_namespace());
+                        used_name
+                    })
+                    .collect::<Vec<_>>();
+                let mut inner_namespace = namespace.clone();
+                inner_namespace.push_back(name.clone());
+                let items = items
+                    .iter()
+                    .filter_map(|item| item.retain_used(&inner_used, &inner_namespace, removed))
+                    .collect::<Vec<_>>();
+                if items.is_empty() {
+                    return None;
+                }
+                return Some(ImportTreeNode::NameSpace(name.clone(), items, span.clone()));
+            }
+            ImportTreeNode::Any(span) => (format!("{}::*", namespace.to_string()), span),
+            ImportTreeNode::Symbol(name, span) | ImportTreeNode::TypeOrTrait(name, span) => {
+                (FullName::new(namespace, name).to_string(), span)
+            }
+        };
+        if used.iter().any(|used_name| self.is_accessible(used_name)) {
+            return Some(self.clone());
+        }
+        removed.push((path, span.clone()));
+        None
+    }
+
     /// Whether this node covers `name`, a name written relative to the namespace the node sits in.
     pub fn is_accessible(&self, name: &FullName) -> bool {
         match self {
@@ -1,5 +1,6 @@
 pub mod collect_annotation_tyvars;
 pub mod deprecation;
+pub mod derive;
 pub mod equality;
 pub mod export_statement;
 pub mod expr;
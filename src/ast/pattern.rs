@@ -3,13 +3,15 @@ use crate::ast::name::{FullName, Name};
 use crate::ast::program::{EndNode, TypeEnv};
 use crate::ast::typedecl::Field;
 use crate::ast::types::{TyCon, TyConInfo, TypeNode};
+use crate::constants::{BOOL_NAME, ERR_NON_EXHAUSTIVE_MATCH, I64_NAME, STD_NAME};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::elaboration::typecheck::{TypeCheckContext, UnifOrOtherErr};
-use crate::error::Errors;
+use crate::error::{Error, Errors};
 use crate::fixstd::builtin::get_tuple_n;
 use crate::misc::{make_set, Map, Set};
 use crate::parse::sourcefile::{SourcePos, Span};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
 
 /// One node of a pattern tree: the pattern written at this position, together with what
@@ -163,6 +165,7 @@ impl PatternNode {
                 // Return the typed pattern.
                 Ok((self.set_type(ty).set_union_pat(subpat), var_to_ty))
             }
+            Pattern::Lit(_, ty) => Ok((self.set_type(ty.clone()), Map::default())),
         }
     }
 
@@ -215,6 +218,7 @@ impl PatternNode {
                 }
             }
             Pattern::Union(_, _, sub) => sub.collect_var_infos(out),
+            Pattern::Lit(_, _) => {}
         }
     }
 
@@ -285,6 +289,7 @@ impl PatternNode {
                 }
                 None
             }
+            Pattern::Lit(_, _) => None,
         }
     }
 
@@ -320,6 +325,8 @@ impl PatternNode {
                 let subpat = subpat.resolve_namespace(ctx)?;
                 Ok(self.set_union_pat(subpat))
             }
+            // The type of a literal is written by its absolute name.
+            Pattern::Lit(_, _) => Ok(Arc::new(self.clone())),
         }
     }
 
@@ -353,6 +360,7 @@ impl PatternNode {
                 let subpat = subpat.resolve_type_aliases(type_env)?;
                 Ok(self.set_union_pat(subpat))
             }
+            Pattern::Lit(_, _) => Ok(Arc::new(self.clone())),
         }
     }
 
@@ -381,6 +389,9 @@ impl PatternNode {
                 let new_subpat = subpat.global_to_absolute();
                 node.pattern = Pattern::Union(new_variant_name, variant_src.clone(), new_subpat);
             }
+            Pattern::Lit(val, ty) => {
+                node.pattern = Pattern::Lit(*val, ty.global_to_absolute());
+            }
         }
         Arc::new(node)
     }
@@ -464,6 +475,17 @@ impl PatternNode {
         matches!(&self.pattern, Pattern::Var(_, _))
     }
 
+    /// Whether this pattern matches one value of an integer type, written as a literal.
+    pub fn is_lit(&self) -> bool {
+        matches!(&self.pattern, Pattern::Lit(_, _))
+    }
+
+    /// Whether this pattern matches every value of its type: a variable, or a struct pattern, whose
+    /// sub-patterns cannot fail to match.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(&self.pattern, Pattern::Var(_, _) | Pattern::Struct(_, _))
+    }
+
     /// The variable this pattern binds. Panics unless this is a variable
     /// pattern.
     pub fn get_var(&self) -> Arc<Var> {
@@ -507,6 +529,15 @@ impl PatternNode {
         })
     }
 
+    /// A pattern matching the value of the integer type `ty` whose bits are `val`, as `expr_int_lit`
+    /// takes them.
+    pub fn make_lit(val: u64, ty: Arc<TypeNode>) -> Arc<PatternNode> {
+        Arc::new(PatternNode {
+            pattern: Pattern::Lit(val, ty),
+            info: PatternInfo::default(),
+        })
+    }
+
     /// A struct destructuring pattern matching each `(field name, sub-pattern)` pair of `fields`,
     /// with no source span recorded for any of the field names.
     pub fn make_struct(
@@ -612,6 +643,7 @@ impl PatternNode {
             Pattern::Union(_, _, pat) => {
                 *pat = pat.rename_by_map(rename);
             }
+            Pattern::Lit(_, _) => {}
         }
         Arc::new(node)
     }
@@ -645,6 +677,10 @@ pub enum Pattern {
     /// Matches one variant of a union and its payload against the sub-pattern. The span covers the
     /// bare variant name, without any namespace prefix written before it.
     Union(FullName, Option<Span>, Arc<PatternNode>),
+    /// Matches one value of an integer type (`I8` to `U64`, a `U8` character included), whose bits
+    /// are held the way `expr_int_lit` takes them. It stands only at the top of a `match` arm, and
+    /// binds nothing.
+    Lit(u64, Arc<TypeNode>),
 }
 
 impl Pattern {
@@ -668,6 +704,7 @@ impl Pattern {
                 ret
             }
             Pattern::Union(_, _, pat) => pat.pattern.count_vars(),
+            Pattern::Lit(_, _) => 0,
         }
     }
 
@@ -683,6 +720,7 @@ impl Pattern {
                 ret
             }
             Pattern::Union(_, _, pat) => pat.pattern.vars(),
+            Pattern::Lit(_, _) => Set::default(),
         }
     }
 
@@ -723,8 +761,26 @@ impl Pattern {
                 }
             }
             Pattern::Union(variant, _, pat) => {
+                if variant.namespace.names == [STD_NAME, BOOL_NAME] {
+                    // A `true` or `false` literal pattern is the pattern of a variant of `Bool`.
+                    return variant.name.trim_start_matches('_').to_string();
+                }
                 format!("{}({})", variant.to_string(), pat.to_string())
             }
+            Pattern::Lit(val, ty) => {
+                let ty_name = ty.toplevel_tycon().map(|tc| tc.name.name.clone());
+                let ty_name = ty_name.unwrap_or_default();
+                let val = if ty_name.starts_with('I') {
+                    (*val as i64).to_string()
+                } else {
+                    val.to_string()
+                };
+                if ty_name == I64_NAME {
+                    val
+                } else {
+                    format!("{}_{}", val, ty_name)
+                }
+            }
         }
     }
 
@@ -769,6 +825,7 @@ impl Pattern {
     /// # Arguments
     /// * `cond_tc`, `cond_ti` — the union the matched value has, and its declaration.
     /// * `match_src` — the span of the whole `match`, where a report of uncovered variants points.
+    ///   The report carries the names of those variants as its data, for the editor's quick fix.
     /// * `pats` — the arm patterns, in the order they are written.
     pub fn validate_match_cases_exhaustiveness(
         cond_tc: &TyCon,
@@ -800,19 +857,29 @@ impl Pattern {
                 Pattern::Var(_, _) | Pattern::Struct(_, _) => {
                     found_otherwise = true;
                 }
+                // The type check has reported a literal matched against a union.
+                Pattern::Lit(_, _) => {}
             }
         }
         if !found_otherwise && !uncovered_variants.is_empty() {
-            let msg = if uncovered_variants.len() == 1 {
+            // List the variants in the order the union declares them, which is the order the
+            // editor's quick fix adds arms for them in.
+            let missing = cond_ti
+                .fields
+                .iter()
+                .map(|f| &f.name)
+                .filter(|name| uncovered_variants.contains(name))
+                .collect::<Vec<_>>();
+            let msg = if missing.len() == 1 {
                 format!(
                     "Variant `{}` of union `{}` is not covered.",
-                    uncovered_variants.iter().next().unwrap(),
+                    missing[0],
                     cond_tc.to_string()
                 )
             } else {
                 format!(
                     "Variants {} of union `{}` are not covered.",
-                    uncovered_variants
+                    missing
                         .iter()
                         .map(|var| format!("`{}`", var))
                         .collect::<Vec<_>>()
@@ -820,7 +887,60 @@ impl Pattern {
                     cond_tc.to_string()
                 )
             };
-            return Err(Errors::from_msg_srcs(msg, &[&match_src]));
+            let mut err = Error::from_msg_srcs(msg, &[&match_src]);
+            err.code = Some(ERR_NON_EXHAUSTIVE_MATCH);
+            err.data = Some(json!(missing));
+            return Err(Errors::from_err(err));
+        }
+        Ok(())
+    }
+
+    /// Check the arms of a `match` on an integer value, some of which are literal patterns: no two
+    /// literals may be equal, and, as the literals cannot cover every value of the type, an arm
+    /// after them has to match the rest.
+    ///
+    /// # Arguments
+    /// * `match_src` — the span of the whole `match`, where a report of uncovered values points.
+    /// * `pats` — the arm patterns, in the order they are written.
+    pub fn validate_lit_match_cases(
+        match_src: &Option<Span>,
+        pats: impl Iterator<Item = Arc<PatternNode>>,
+    ) -> Result<(), Errors> {
+        let mut seen = Set::default();
+        let mut found_otherwise = false;
+        let mut ty = None;
+        for pat in pats {
+            match &pat.pattern {
+                Pattern::Lit(val, lit_ty) => {
+                    if !seen.insert(*val) {
+                        return Err(Errors::from_msg_srcs(
+                            format!(
+                                "Pattern `{}` is unreachable: an earlier arm matches the same value.",
+                                pat.pattern.to_string()
+                            ),
+                            &[&pat.info.source],
+                        ));
+                    }
+                    ty = Some(lit_ty.clone());
+                }
+                Pattern::Var(_, _) | Pattern::Struct(_, _) => {
+                    found_otherwise = true;
+                }
+                // The type check has reported a variant matched against an integer.
+                Pattern::Union(_, _, _) => {}
+            }
+        }
+        if !found_otherwise {
+            let ty = ty.map(|ty| ty.to_string()).unwrap_or_default();
+            let mut err = Error::from_msg_srcs(
+                format!(
+                    "The literal patterns do not cover every value of `{}`. Add an arm such as `_ => ...` after them.",
+                    ty
+                ),
+                &[&match_src],
+            );
+            err.code = Some(ERR_NON_EXHAUSTIVE_MATCH);
+            return Err(Errors::from_err(err));
         }
         Ok(())
     }
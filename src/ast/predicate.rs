@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::ast::kind_scope::{KindEnv, KindScope};
 use crate::ast::program::{EndNode, TypeEnv};
 use crate::ast::traits::{TraitAliasEnv, TraitId};
-use crate::ast::types::{is_opaque_tyvar, TyVar, Type, TypeNode};
+use crate::ast::types::{is_opaque_tyvar, NameRef, TyVar, Type, TypeNode};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::error::Errors;
 use crate::parse::sourcefile::{SourcePos, Span};
@@ -85,7 +85,16 @@ impl Predicate {
     }
 
     pub fn to_string(&self) -> String {
-        format!("{} : {}", self.ty.to_string(), self.trait_id.to_string())
+        self.to_string_with(&|name| name.to_string())
+    }
+
+    /// The predicate written out as `to_string` does, each name in it written by `names`.
+    pub fn to_string_with(&self, names: &dyn Fn(NameRef) -> String) -> String {
+        format!(
+            "{} : {}",
+            self.ty.to_string_with(names),
+            names(NameRef::Trait(&self.trait_id))
+        )
     }
 
     pub fn set_kinds(&mut self, scope: &KindScope) {
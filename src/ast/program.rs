@@ -1,4 +1,5 @@
 use crate::ast::deprecation::{DeprecationInfo, DeprecationStatement};
+use crate::ast::derive::DeriveStatement;
 use crate::ast::equality::Equality;
 use crate::ast::export_statement::{ExportStatement, ExportedFunctionType, IOType};
 use crate::ast::expr::{expr_var, Expr, ExprNode, Var};
@@ -9,18 +10,19 @@ use crate::ast::pattern::PatternNode;
 use crate::ast::traits::{TraitAlias, TraitDefn, TraitEnv, TraitId, TraitImpl};
 use crate::ast::typedecl::{Field, TypeDeclValue, TypeDefn};
 use crate::ast::types::{
-    is_opaque_tyvar, AssocType, Kind, OpaqueTyConResolution, Scheme, TyAliasInfo, TyCon, TyConInfo,
-    TyConVariant, TypeNode,
+    is_opaque_tyvar, type_fun, AssocType, Kind, OpaqueTyConResolution, Scheme, TyAliasInfo, TyCon,
+    TyConInfo, TyConVariant, TypeNode,
 };
 use crate::configuration::{
     Configuration, DeprecationMode, OutputFileType, ProjectSources, SubCommand,
 };
 use crate::constants::{
-    C_ENTRY_POINT_NAME, DOT_FIXLANG, INSTANCIATED_NAME_SEPARATOR, MAIN_FUNCTION_NAME,
-    MAIN_MODULE_NAME, MARK_THREADED_NAME, STD_NAME, STRUCT_ACT_SYMBOL, STRUCT_GETTER_SYMBOL,
-    STRUCT_MODIFIER_SYMBOL, STRUCT_PLUG_IN_FORCE_UNIQUE_SYMBOL, STRUCT_PLUG_IN_SYMBOL,
-    STRUCT_PUNCH_FORCE_UNIQUE_SYMBOL, STRUCT_PUNCH_SYMBOL, STRUCT_SETTER_SYMBOL,
-    TEST_FUNCTION_NAME, TEST_MODULE_NAME, TUPLE_SIZE_BASE, UNION_AS_SYMBOL, UNION_IS_SYMBOL,
+    BENCH_CASE_PREFIX, C_ENTRY_POINT_NAME, DOT_FIXLANG, FFI_NAME, GET_FUNPTR_CALLBACK_NAME,
+    INSTANCIATED_NAME_SEPARATOR, IO_FAIL_NAME, IO_NAME, MARK_THREADED_NAME, STD_NAME,
+    STRUCT_ACT_SYMBOL, STRUCT_GETTER_SYMBOL, STRUCT_MODIFIER_SYMBOL,
+    STRUCT_PLUG_IN_FORCE_UNIQUE_SYMBOL, STRUCT_PLUG_IN_SYMBOL, STRUCT_PUNCH_FORCE_UNIQUE_SYMBOL,
+    STRUCT_PUNCH_SYMBOL, STRUCT_SETTER_SYMBOL, TEST_CASE_PREFIX, TEST_FUNCTION_NAME,
+    TEST_MODULE_NAME, TEST_RESULT_RUNNER_NAME, TUPLE_SIZE_BASE, UNION_AS_SYMBOL, UNION_IS_SYMBOL,
     UNION_MOD_SYMBOL,
 };
 use crate::elaboration::desugar_opaque::{
@@ -28,11 +30,13 @@ use crate::elaboration::desugar_opaque::{
 };
 use crate::elaboration::name_resolution::{NameResolutionContext, NameResolutionEnv};
 use crate::elaboration::typecheck::TypeCheckContext;
-use crate::error::{panic_if_err, Error, Errors, WARN_DEPRECATED, WARN_UNDECLARED_DEPENDENCY};
+use crate::error::{
+    panic_if_err, Error, Errors, MessageFormat, WARN_DEPRECATED, WARN_UNDECLARED_DEPENDENCY,
+};
 use crate::ffi::{c_entry_point_signature, CSignature};
 use crate::fixstd::builtin::{
-    boxed_trait_instance, bulitin_tycons, make_io_unit_ty, make_unit_ty, struct_act,
-    struct_act_const, struct_act_identity, struct_act_tuple2, struct_get, struct_mod,
+    boxed_trait_instance, bulitin_tycons, make_io_result_unit_ty, make_io_unit_ty, make_unit_ty,
+    struct_act, struct_act_const, struct_act_identity, struct_act_tuple2, struct_get, struct_mod,
     struct_plug_in, struct_punch, struct_set, tuple_defn, union_as, union_is, union_mod_function,
     union_new,
 };
@@ -558,6 +562,11 @@ pub struct TypedExpr {
     /// The concrete type each opaque type constructor written in this expression stands for.
     #[serde(default)]
     pub opaque_types: Map<FullName, Vec<OpaqueTyConResolution>>,
+    /// The names this expression refers to that the module it is written in has to import. A
+    /// check that finds the expression in the type-check cache resolves no name, so the names are
+    /// kept with the expression for `Program::import_required` to be complete all the same.
+    #[serde(default)]
+    pub import_required: Vec<FullName>,
 }
 
 impl TypedExpr {
@@ -568,6 +577,7 @@ impl TypedExpr {
             expr,
             equalities: vec![],
             opaque_types: Map::default(),
+            import_required: vec![],
         }
     }
 
@@ -638,6 +648,21 @@ impl TraitMemberImpl {
     }
 }
 
+/// A test `fix test` runs: `Test::test`, or a value named `test_*` defined in a test source file.
+/// A benchmark `fix bench` measures, a value named `bench_*` defined in a benchmark source file, is
+/// one too: it is built and run the same way, and the runtime runs it over and over.
+#[derive(Clone)]
+pub struct TestCase {
+    /// The name of the value, which is what the test is reported and selected by.
+    pub name: FullName,
+    /// The instantiation of the value, of type `IO ()` or `IO (Result ErrMsg ())`.
+    pub expr: Arc<ExprNode>,
+    /// For a value of type `IO (Result ErrMsg ())`, the instantiation of the `Std` value that turns
+    /// it into an `IO ()` ending the test with a failure on `err`. `None` for a value of type
+    /// `IO ()`.
+    pub result_runner: Option<Arc<ExprNode>>,
+}
+
 /// A module of the program, and the sources it is made of.
 #[derive(Clone)]
 pub struct ModuleInfo {
@@ -673,16 +698,20 @@ pub struct Program {
     pub type_env: TypeEnv,
     /// The traits the program declares, with their aliases and their implementations.
     pub trait_env: TraitEnv,
-    /// The value the built program runs:
-    /// - the instantiation of `Main::main` in run or build mode,
-    /// - the instantiation of `Main::test` in test mode,
-    /// - `None` in library mode.
+    /// The value the built program runs: the instantiation of `Main::main` in run or build mode,
+    /// and `None` in test mode and in library mode.
     pub entry_io_value: Option<Arc<ExprNode>>,
+    /// The tests the built program runs in test mode, or the benchmarks it measures in bench mode,
+    /// sorted by name; empty in the other modes.
+    pub test_cases: Vec<TestCase>,
     /// The `FFI_EXPORT` statements, each naming a Fix value to publish under a C symbol.
     pub export_statements: Vec<ExportStatement>,
     /// `DEPRECATED[...]` pragmas, accumulated at parse time and consumed in
     /// elaboration to set per-symbol `deprecation` fields.
     pub deprecation_statements: Vec<DeprecationStatement>,
+    /// The `DERIVE[...]` statements. The implementations they ask for are generated at parse time;
+    /// the statements are kept to check the fields of each type ahead of the type check.
+    pub derive_statements: Vec<DeriveStatement>,
     /// The sizes of the tuples the program uses. The type `Std::Tuple{n}` and the trait
     /// implementations a tuple carries are generated for each size here, so two programs using
     /// different sets of sizes are elaborated with different sources of `Std`.
@@ -744,10 +773,15 @@ impl Program {
         None
     }
 
-    /// The expressions the entry point and the exported functions were instantiated as.
+    /// The expressions the entry point, the tests and the exported functions were instantiated as.
     pub fn root_value_exprs(&self) -> Vec<&Arc<ExprNode>> {
         self.entry_io_value
             .iter()
+            .chain(
+                self.test_cases
+                    .iter()
+                    .flat_map(|test| std::iter::once(&test.expr).chain(test.result_runner.iter())),
+            )
             .chain(
                 self.export_statements
                     .iter()
@@ -804,8 +838,10 @@ impl Program {
             used_tuple_sizes: (0..=TUPLE_SIZE_BASE).collect(),
             modules: Default::default(),
             entry_io_value: None,
+            test_cases: vec![],
             export_statements: vec![],
             deprecation_statements: vec![],
+            derive_statements: vec![],
             deferred_errors: Errors::empty(),
             import_required: Default::default(),
             optimization_step: 0,
@@ -1306,9 +1342,17 @@ impl Program {
         if cached_te.is_some() {
             // If cache is available,
             te = cached_te.unwrap();
+            nrctx.add_import_required(te.import_required.clone());
             return Ok((te, Errors::empty()));
         }
 
+        // The names the module is required to import only grow, so the ones this expression adds
+        // are those past the count it started with.
+        let import_required_before = nrctx
+            .import_required
+            .get(&def_mod.name)
+            .map_or(0, |names| names.len());
+
         // Perform namespace inference.
         te.expr = te.expr.resolve_namespace(nrctx)?;
 
@@ -1323,6 +1367,17 @@ impl Program {
         tc.fill_opaque_concrete_types(&mut te.opaque_types);
         te.equalities = tc.local_assumed_eqs;
 
+        // Add names required to be imported found in type-checking to NameResolutionContext's
+        // import_required, and keep them with the expression for a run that finds it in the cache.
+        nrctx.add_import_required(tc.import_required);
+        let mut import_required = nrctx
+            .import_required
+            .get(&def_mod.name)
+            .map_or(vec![], |names| names[import_required_before..].to_vec());
+        import_required.sort();
+        import_required.dedup();
+        te.import_required = import_required;
+
         // A run whose `load_cache` finds the expression returns it without checking it, so the
         // cache may hold only what a strict check accepted. Two things disqualify a result:
         //
@@ -1333,9 +1388,6 @@ impl Program {
             tc.cache.save_cache(&te, val_name, req_scm, version_hash);
         }
 
-        // Add names required to be imported found in type-checking to NameResolutionContext's import_required.
-        nrctx.add_import_required(tc.import_required);
-
         Ok((te, check_errors))
     }
 
@@ -1720,29 +1772,157 @@ impl Program {
         errors.to_result()
     }
 
-    /// Instantiates the program's entry point at type `IO ()` and stores it in
-    /// `entry_io_value`.
-    ///
-    /// # Arguments
-    /// * `test_mode` — when true the entry point is `Test::test`, as `fix test`
-    ///   runs it; otherwise it is `Main::main`.
+    /// Instantiates `main_func_name`, the value the program's entry point runs
+    /// (`Configuration::entry_io_value_name`), at type `IO ()` and stores it in `entry_io_value`.
     pub fn instantiate_entry_io_value(
         &mut self,
+        main_func_name: &FullName,
         tc: &TypeCheckContext,
-        test_mode: bool,
     ) -> Result<(), Errors> {
-        let main_func_name = if test_mode {
-            FullName::from_strs(&[TEST_MODULE_NAME], TEST_FUNCTION_NAME)
-        } else {
-            FullName::from_strs(&[MAIN_MODULE_NAME], MAIN_FUNCTION_NAME)
-        };
         let main_ty = make_io_unit_ty();
         let (expr, _ty) =
-            self.instantiate_exported_value(&main_func_name, Some(main_ty), &None, tc)?;
+            self.instantiate_exported_value(main_func_name, Some(main_ty), &None, tc)?;
         self.entry_io_value = Some(expr);
         Ok(())
     }
 
+    /// Finds the tests of the program, instantiates them and stores them in `test_cases`, sorted
+    /// by name.
+    ///
+    /// The tests are `Test::test`, if the program defines it, and each value whose name starts with
+    /// `test_`, which is defined in one of `test_files` and has the type `IO ()` or
+    /// `IO (Result ErrMsg ())`. A value of another type is taken for a helper of the tests, whatever
+    /// its name.
+    ///
+    /// # Arguments
+    /// * `test_files` — the source files the tests are looked for in, as `Configuration::test_source_files`
+    ///   gives them.
+    pub fn instantiate_test_cases(
+        &mut self,
+        tc: &TypeCheckContext,
+        test_files: &[PathBuf],
+    ) -> Result<(), Errors> {
+        let legacy_name = FullName::from_strs(&[TEST_MODULE_NAME], TEST_FUNCTION_NAME);
+        let not_found = format!(
+            "No test is found. Define `{}`, or values whose names start with `{}` and whose types are `IO ()` or `IO (Result ErrMsg ())`, in the source files of the `[build.test]` section.",
+            legacy_name.to_string(),
+            TEST_CASE_PREFIX
+        );
+        self.instantiate_cases(
+            tc,
+            test_files,
+            TEST_CASE_PREFIX,
+            Some(legacy_name),
+            not_found,
+        )
+    }
+
+    /// Finds the benchmarks of the program, instantiates them and stores them in `test_cases`,
+    /// sorted by name.
+    ///
+    /// The benchmarks are the values whose names start with `bench_`, which are defined in one of
+    /// `bench_files` and have the type `IO ()` or `IO (Result ErrMsg ())`, as the tests are.
+    ///
+    /// # Arguments
+    /// * `bench_files` — the source files the benchmarks are looked for in, as
+    ///   `Configuration::bench_source_files` gives them.
+    pub fn instantiate_bench_cases(
+        &mut self,
+        tc: &TypeCheckContext,
+        bench_files: &[PathBuf],
+    ) -> Result<(), Errors> {
+        let not_found = format!(
+            "No benchmark is found. Define values whose names start with `{}` and whose types are `IO ()` or `IO (Result ErrMsg ())` in the source files of the `[build.bench]` section.",
+            BENCH_CASE_PREFIX
+        );
+        self.instantiate_cases(tc, bench_files, BENCH_CASE_PREFIX, None, not_found)
+    }
+
+    /// Finds the values named `{prefix}*` of type `IO ()` or `IO (Result ErrMsg ())` defined in
+    /// `files`, and `legacy_name` wherever it is defined, instantiates them and stores them in
+    /// `test_cases`, sorted by name. Fails with `not_found` when there is none.
+    fn instantiate_cases(
+        &mut self,
+        tc: &TypeCheckContext,
+        files: &[PathBuf],
+        prefix: &str,
+        legacy_name: Option<FullName>,
+        not_found: String,
+    ) -> Result<(), Errors> {
+        let unit_ty = make_io_unit_ty();
+        let result_ty = make_io_result_unit_ty();
+        // Whether a value of scheme `scm` is of type `ty` and nothing more general.
+        let is_of_type = |scm: &Scheme, ty: &Arc<TypeNode>| {
+            scm.gen_vars.is_empty()
+                && scm.predicates.is_empty()
+                && scm.equalities.is_empty()
+                && scm.ty == *ty
+        };
+
+        let mut abs_files = Set::default();
+        for file in files {
+            abs_files.insert(to_absolute_path(file)?);
+        }
+        let mut found: Vec<(FullName, bool)> = vec![];
+        for (name, gv) in &self.global_values {
+            let returns_result = is_of_type(&gv.scm, &result_ty);
+            if Some(name) == legacy_name.as_ref() {
+                found.push((name.clone(), returns_result));
+                continue;
+            }
+            if !name.name.starts_with(prefix) || !matches!(gv.expr, SymbolExpr::Simple(_)) {
+                continue;
+            }
+            if !returns_result && !is_of_type(&gv.scm, &unit_ty) {
+                continue;
+            }
+            let in_file = gv.decl_src.as_ref().map_or(false, |src| {
+                to_absolute_path(&src.input.file_path)
+                    .map_or(false, |path| abs_files.contains(&path))
+            });
+            if in_file {
+                found.push((name.clone(), returns_result));
+            }
+        }
+        if found.is_empty() {
+            return Err(Errors::from_msg(not_found));
+        }
+        found.sort();
+
+        let runner_name =
+            FullName::from_strs(&[STD_NAME, IO_NAME, IO_FAIL_NAME], TEST_RESULT_RUNNER_NAME);
+        let mut test_cases = vec![];
+        for (name, returns_result) in found {
+            let (expr, result_runner) = if returns_result {
+                let symbol_name = self.require_instantiation(&name, &result_ty)?;
+                let runner_ty = type_fun(result_ty.clone(), unit_ty.clone());
+                let runner_symbol_name = self.require_instantiation(&runner_name, &runner_ty)?;
+                (
+                    expr_var(symbol_name, None).set_type(result_ty.clone()),
+                    Some(expr_var(runner_symbol_name, None).set_type(runner_ty)),
+                )
+            } else {
+                // `Test::test` of a type other than the two is reported here.
+                let (expr, _ty) =
+                    self.instantiate_exported_value(&name, Some(unit_ty.clone()), &None, tc)?;
+                (expr, None)
+            };
+            test_cases.push(TestCase {
+                name,
+                expr,
+                result_runner,
+            });
+        }
+        self.instantiate_symbols(tc)?;
+        self.test_cases = test_cases;
+        Ok(())
+    }
+
+    /// Whether the built program carries the C entry point: it runs `Main::main` or the tests.
+    pub fn has_entry_point(&self) -> bool {
+        self.entry_io_value.is_some() || !self.test_cases.is_empty()
+    }
+
     /// Instantiates the value named by each export statement, recording the
     /// instantiated expression and its exported function type back into the
     /// statement.
@@ -2159,6 +2339,52 @@ impl Program {
         Ok(())
     }
 
+    /// The C header declaring the functions the export statements define, for a C program built
+    /// against the library the program is compiled into.
+    ///
+    /// The prototypes stand in the order the statements were written, each preceded by the
+    /// documentation of the Fix value it exports as a `//` comment, inside an `extern "C"` block
+    /// that a C++ compiler reads and a C compiler skips.
+    ///
+    /// Call this once the exported values are instantiated, so that every statement carries the
+    /// function type it exports at.
+    ///
+    /// # Arguments
+    /// * `include_guard` — the macro the header defines to keep a second inclusion out.
+    pub fn c_header(&self, include_guard: &str) -> String {
+        let type_env = self.type_env();
+        let mut header = String::new();
+        header += "// Generated by `fix build`. Do not edit.\n\n";
+        header += &format!("#ifndef {}\n#define {}\n\n", include_guard, include_guard);
+        header += "#include <stdint.h>\n\n";
+        header += "#ifdef __cplusplus\nextern \"C\" {\n#endif\n";
+        for stmt in &self.export_statements {
+            let exported_ty = stmt
+                .function_type
+                .as_ref()
+                .expect("an export statement carries its function type once it is instantiated");
+            header += "\n";
+            let document = self
+                .global_values
+                .get(&stmt.value_name)
+                .and_then(|gv| gv.get_document());
+            if let Some(document) = document {
+                for line in document.trim_end().lines() {
+                    if line.is_empty() {
+                        header += "//\n";
+                    } else {
+                        header += &format!("// {}\n", line);
+                    }
+                }
+            }
+            let signature = CSignature::of_ffi_export(exported_ty, &type_env);
+            header += &format!("{};\n", signature.declaration_of(&stmt.function_name));
+        }
+        header += "\n#ifdef __cplusplus\n}\n#endif\n\n";
+        header += &format!("#endif // {}\n", include_guard);
+        header
+    }
+
     /// Report every C function an `FFI_CALL` names wrongly: one whose body the compiler writes, and
     /// one described at a signature another `FFI_CALL` or the `FFI_EXPORT` of that name gives
     /// differently.
@@ -2186,7 +2412,7 @@ impl Program {
         // The entry point, which a program reaches by calling `main` — that re-runs the program. It
         // carries no source location: the compiler writes it, so a disagreement is reported at the
         // call alone.
-        if self.entry_io_value.is_some() {
+        if self.has_entry_point() {
             descriptions.insert(
                 C_ENTRY_POINT_NAME.to_string(),
                 (c_entry_point_signature(), None),
@@ -2247,14 +2473,57 @@ impl Program {
         errors.to_result()
     }
 
-    /// Write the warning-severity items of `deferred_errors` to stderr and take them out of it,
-    /// leaving the error-severity items in place. Warnings reach the terminal this way even where
-    /// compilation succeeds.
-    pub fn flush_warnings_to_stderr(&mut self) {
-        let warnings = self.deferred_errors.take_warnings();
-        if warnings.has_diagnostics() {
-            eprint!("{}", warnings.to_string());
+    /// Reject a use of `Std::FFI::get_funptr_callback` at a type of function that no C function can
+    /// stand for.
+    ///
+    /// The C function it gives is built the way an exported function is, so the closure has to have
+    /// a type `FFI_EXPORT` would accept. Only the instances the program reaches are checked, so the
+    /// symbols have to be instantiated by the time this runs.
+    pub fn validate_c_callbacks(&self) -> Result<(), Errors> {
+        let type_env = self.type_env();
+        let get_funptr_callback =
+            FullName::from_strs(&[STD_NAME, FFI_NAME], GET_FUNPTR_CALLBACK_NAME);
+        let mut errors = Errors::empty();
+        // The symbols in name order, so that the errors come out the same from one build to the next.
+        let mut instances = self
+            .symbols
+            .values()
+            .filter(|symbol| symbol.generic_name == get_funptr_callback)
+            .collect::<Vec<_>>();
+        instances.sort_by(|a, b| a.name.cmp(&b.name));
+        for instance in instances {
+            // The instance has type `Lazy f -> Ptr`, where `Lazy f` is `() -> f`.
+            let ty = instance.ty.resolve_type_aliases(&type_env)?;
+            let fun_ty = ty.get_lambda_srcs()[0].get_lambda_dst();
+            let mut uses: Vec<(&FullName, Option<Span>)> = vec![];
+            for symbol in self.symbols.values() {
+                let expr = symbol.expr.as_ref().unwrap();
+                expr.walk_var_uses(&mut |var, src| {
+                    if var.name == instance.name {
+                        uses.push((&symbol.name, src.clone()));
+                    }
+                });
+            }
+            uses.sort_by(|a, b| a.0.cmp(b.0));
+            let src = uses.into_iter().find_map(|(_, src)| src);
+            let prefix = format!(
+                "`{}` cannot be used at the type `{}`: ",
+                get_funptr_callback.to_string(),
+                fun_ty.to_string()
+            );
+            errors.eat_err(
+                ExportedFunctionType::validate(Scheme::from_type(fun_ty), &type_env, prefix, &src)
+                    .map(|_| ()),
+            );
         }
+        errors.to_result()
+    }
+
+    /// Report the warning-severity items of `deferred_errors` in the given format and take them
+    /// out of it, leaving the error-severity items in place. Warnings reach the user this way even
+    /// where compilation succeeds.
+    pub fn flush_warnings(&mut self, format: MessageFormat) {
+        self.deferred_errors.take_warnings().report(format);
     }
 
     /// Reports the calls of `Std::mark_threaded` this program makes when multi-threading is off.
@@ -2777,6 +3046,16 @@ impl Program {
         self.trait_env.validate_structure()
     }
 
+    /// Reports each field of a type named by a `DERIVE` statement whose type lacks an implementation
+    /// of a trait the derived implementations need.
+    pub fn validate_derive_statements(&self) -> Result<(), Errors> {
+        let mut errors = Errors::empty();
+        for stmt in &self.derive_statements {
+            errors.append(stmt.validate(self));
+        }
+        errors.to_result()
+    }
+
     /// Reports each pair of implementations of one trait whose heads can denote the same type.
     pub fn validate_overlapping_instances(&self) -> Result<(), Errors> {
         self.trait_env
@@ -3108,6 +3387,9 @@ impl Program {
         self.deprecation_statements
             .append(&mut other.deprecation_statements);
 
+        // Merge derive statements.
+        self.derive_statements.append(&mut other.derive_statements);
+
         // Merge used_tuple_sizes.
         self.used_tuple_sizes.append(&mut other.used_tuple_sizes);
 
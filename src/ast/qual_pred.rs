@@ -2,7 +2,7 @@ use crate::ast::equality::Equality;
 use crate::ast::predicate::Predicate;
 use crate::ast::program::{EndNode, TypeEnv};
 use crate::ast::traits::KindSignature;
-use crate::ast::types::TyVar;
+use crate::ast::types::{NameRef, TyVar};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::error::Errors;
 use crate::parse::sourcefile::SourcePos;
@@ -74,18 +74,28 @@ impl QualPred {
     /// `[a : Eq] Array a : Eq`, and a head carrying no kind or trait constraint renders alone, as
     /// `Array I64 : Eq`.
     pub fn to_string(&self) -> String {
+        self.to_string_with(&|name| name.to_string())
+    }
+
+    /// The qualified predicate written out as `to_string` does, each type constructor, associated
+    /// type and trait it names written by `names`.
+    pub fn to_string_with(&self, names: &dyn Fn(NameRef) -> String) -> String {
         let mut s = String::default();
         if self.pred_constraints.len() > 0 || self.kind_constraints.len() > 0 {
             s += "[";
         }
         let mut constraints = vec![];
         constraints.extend(self.kind_constraints.iter().map(|c| c.to_string()));
-        constraints.extend(self.pred_constraints.iter().map(|c| c.to_string()));
+        constraints.extend(
+            self.pred_constraints
+                .iter()
+                .map(|c| c.to_string_with(names)),
+        );
         s += &constraints.join(", ");
         if self.pred_constraints.len() > 0 || self.kind_constraints.len() > 0 {
             s += "] ";
         }
-        s += &self.predicate.to_string();
+        s += &self.predicate.to_string_with(names);
         s
     }
 
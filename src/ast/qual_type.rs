@@ -5,7 +5,7 @@ use crate::ast::name::Name;
 use crate::ast::predicate::Predicate;
 use crate::ast::program::{EndNode, TypeEnv};
 use crate::ast::traits::KindSignature;
-use crate::ast::types::{TyVar, TypeNode};
+use crate::ast::types::{NameRef, TyVar, TypeNode};
 use crate::elaboration::name_resolution::NameResolutionContext;
 use crate::error::Errors;
 use crate::misc::Set;
@@ -53,18 +53,24 @@ impl QualType {
     /// The signature as a source line writes it: the kind annotations and the trait constraints in
     /// brackets, then the type.
     pub fn to_string(&self) -> String {
+        self.to_string_with(&|name| name.to_string())
+    }
+
+    /// The signature written out as `to_string` does, each type constructor, associated type and
+    /// trait it names written by `names`.
+    pub fn to_string_with(&self, names: &dyn Fn(NameRef) -> String) -> String {
         let mut s = String::default();
         if self.preds.len() > 0 || self.kind_signs.len() > 0 {
             s += "[";
         }
         let mut preds = vec![];
         preds.extend(self.kind_signs.iter().map(|p| p.to_string()));
-        preds.extend(self.preds.iter().map(|p| p.to_string()));
+        preds.extend(self.preds.iter().map(|p| p.to_string_with(names)));
         s += &preds.join(", ");
         if self.preds.len() > 0 || self.kind_signs.len() > 0 {
             s += "] ";
         }
-        s += &self.ty.to_string();
+        s += &self.ty.to_string_with(names);
         s
     }
 
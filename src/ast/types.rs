@@ -307,7 +307,6 @@ impl TyCon {
     }
 
     /// Whether this is the type `IOState`, the token that an `IO` action threads.
-    #[allow(dead_code)]
     pub fn is_iostate(&self) -> bool {
         return self.name == make_iostate_name();
     }
@@ -1632,6 +1631,30 @@ impl Clone for TypeNode {
     }
 }
 
+/// A name that a type, a constraint or a signature mentions, as it is handed to the function
+/// writing it out (see `TypeNode::to_string_with`). `fix docs` writes each one as a link to the
+/// page documenting it.
+#[derive(Clone, Copy)]
+pub enum NameRef<'a> {
+    /// A type constructor, such as `Std::Array`.
+    TyCon(&'a TyCon),
+    /// An associated type, such as `Std::Iterator::Item`.
+    AssocType(&'a AssocType),
+    /// A trait, such as `Std::ToString`.
+    Trait(&'a TraitId),
+}
+
+impl NameRef<'_> {
+    /// The name as `to_string` of the type mentioning it writes it.
+    pub fn to_string(&self) -> String {
+        match self {
+            NameRef::TyCon(tc) => tc.to_string(),
+            NameRef::AssocType(assoc_ty) => assoc_ty.name.to_string(),
+            NameRef::Trait(trait_id) => trait_id.to_string(),
+        }
+    }
+}
+
 // Variant of type
 #[derive(Eq, Hash, Serialize, Deserialize, Clone)]
 pub enum Type {
@@ -1715,6 +1738,12 @@ impl TypeNode {
 
     // Stringify.
     pub fn to_string(self: &Arc<TypeNode>) -> String {
+        self.to_string_with(&|name| name.to_string())
+    }
+
+    /// Stringifies the type as `to_string` does, writing each type constructor and associated type
+    /// it names by `names`.
+    pub fn to_string_with(self: &Arc<TypeNode>, names: &dyn Fn(NameRef) -> String) -> String {
         fn should_braced_as_arg(arg: &Arc<TypeNode>) -> bool {
             match &arg.ty {
                 Type::TyVar(_) => false,
@@ -1740,7 +1769,10 @@ impl TypeNode {
                     if let Some(n) = get_tuple_n(&tycon.name) {
                         // Tuple case.
                         let args = self.collect_type_arguments();
-                        let arg_strs = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+                        let arg_strs = args
+                            .iter()
+                            .map(|arg| arg.to_string_with(names))
+                            .collect::<Vec<_>>();
 
                         // In this case, we use special notation when n = 1 or n = args.len().
                         if n == 1 {
@@ -1758,35 +1790,35 @@ impl TypeNode {
                             if args[0].is_closure() {
                                 return format!(
                                     "({}) -> {}",
-                                    args[0].to_string(),
-                                    args[1].to_string()
+                                    args[0].to_string_with(names),
+                                    args[1].to_string_with(names)
                                 );
                             } else {
                                 return format!(
                                     "{} -> {}",
-                                    args[0].to_string(),
-                                    args[1].to_string()
+                                    args[0].to_string_with(names),
+                                    args[1].to_string_with(names)
                                 );
                             }
                         }
                     }
                 }
-                let tyfun = fun.to_string();
-                let arg_str = arg.to_string();
+                let tyfun = fun.to_string_with(names);
+                let arg_str = arg.to_string_with(names);
                 if should_braced_as_arg(arg) {
                     format!("{} ({})", tyfun, arg_str)
                 } else {
                     format!("{} {}", tyfun, arg_str)
                 }
             }
-            Type::TyCon(tc) => tc.to_string(),
+            Type::TyCon(tc) => names(NameRef::TyCon(tc)),
             Type::AssocTy(assoc_ty, args) => {
                 format!(
                     "{} {}",
-                    assoc_ty.name.to_string(),
+                    names(NameRef::AssocType(assoc_ty)),
                     args.iter()
                         .map(|arg| {
-                            let arg_str = arg.to_string();
+                            let arg_str = arg.to_string_with(names);
                             if should_braced_as_arg(arg) {
                                 format!("({})", arg_str)
                             } else {
@@ -2429,7 +2461,29 @@ impl Scheme {
     /// The scheme written out with `s` applied to its constraints and its type, the constraints
     /// standing in brackets in front of the type.
     fn to_string_substituted(&self, s: &Substitution) -> String {
-        // Substitute type variables in predicates, equalities and the type to chosen names.
+        self.to_string_substituted_with(s, &|name| name.to_string())
+    }
+
+    /// The scheme written out as `to_string_substituted` does, each type constructor, associated
+    /// type and trait it names written by `names`.
+    fn to_string_substituted_with(
+        &self,
+        s: &Substitution,
+        names: &dyn Fn(NameRef) -> String,
+    ) -> String {
+        self.constraints_to_string_substituted(s, names)
+            + &s.substitute_type(&self.ty).to_string_with(names)
+    }
+
+    /// The constraints of the scheme written out with `s` applied, in brackets and followed by a
+    /// space, or the empty string when there is none. The names are written by `names`, and the
+    /// constraints are ordered by how they read without it.
+    fn constraints_to_string_substituted(
+        &self,
+        s: &Substitution,
+        names: &dyn Fn(NameRef) -> String,
+    ) -> String {
+        // Substitute type variables in predicates and equalities to chosen names.
         let preds = self
             .predicates
             .iter()
@@ -2448,32 +2502,67 @@ impl Scheme {
                 eq
             })
             .collect::<Vec<_>>();
-        let ty = s.substitute_type(&self.ty);
 
         // Stringify.
-        let constraints_str = if preds.is_empty() && eqs.is_empty() {
+        if preds.is_empty() && eqs.is_empty() {
             "".to_string()
         } else {
             let mut constraint_strs = vec![];
 
-            let mut pred_strs = preds.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+            let mut pred_strs = preds
+                .iter()
+                .map(|p| (p.to_string(), p.to_string_with(names)))
+                .collect::<Vec<_>>();
             pred_strs.sort();
-            pred_strs.dedup();
-            constraint_strs.extend(pred_strs);
+            pred_strs.dedup_by(|lhs, rhs| lhs.0 == rhs.0);
+            constraint_strs.extend(pred_strs.into_iter().map(|(_, written)| written));
 
-            let mut eq_strs = eqs.iter().map(|eq| eq.to_string()).collect::<Vec<_>>();
+            let mut eq_strs = eqs
+                .iter()
+                .map(|eq| (eq.to_string(), eq.to_string_with(names)))
+                .collect::<Vec<_>>();
             eq_strs.sort();
-            eq_strs.dedup();
-            constraint_strs.extend(eq_strs);
+            eq_strs.dedup_by(|lhs, rhs| lhs.0 == rhs.0);
+            constraint_strs.extend(eq_strs.into_iter().map(|(_, written)| written));
 
             format!("[{}] ", constraint_strs.join(", "))
-        };
-        constraints_str + &ty.to_string()
+        }
     }
 
     /// The scheme written out with its generalized variables renamed `a`, `b`, ... in the order
     /// `gen_vars` lists them, so that the names the source happened to use stay out of the text.
     pub fn to_string_normalize(&self) -> String {
+        self.to_string_substituted(&self.normalizing_substitution())
+    }
+
+    /// The scheme written out as `to_string_normalize` does, split for showing it as the signature
+    /// of a function: the constraints (see `constraints_to_string_substituted`), the types of the
+    /// parameters, and the type of the result. Each argument a function type takes, one by one,
+    /// is a parameter; a parameter of a function type is parenthesized.
+    pub fn to_signature_strings_normalize(&self) -> (String, Vec<String>, String) {
+        let s = self.normalizing_substitution();
+        let mut ty = s.substitute_type(&self.ty);
+        let mut params = vec![];
+        while ty.is_closure() || ty.is_funptr() {
+            for src in ty.get_lambda_srcs() {
+                if src.is_closure() || src.is_funptr() {
+                    params.push(format!("({})", src.to_string()));
+                } else {
+                    params.push(src.to_string());
+                }
+            }
+            ty = ty.get_lambda_dst();
+        }
+        (
+            self.constraints_to_string_substituted(&s, &|name| name.to_string()),
+            params,
+            ty.to_string(),
+        )
+    }
+
+    /// The substitution that renames the generalized type variables to `a`, `b`, ... in the order
+    /// `gen_vars` lists them.
+    fn normalizing_substitution(&self) -> Substitution {
         // Change names of generalized type variables to a, b, ...
         let mut s = Substitution::default();
         let mut tyvar_num = -1;
@@ -2486,7 +2575,7 @@ impl Scheme {
             ));
             assert!(merge_ok, "`{}` is generalized twice.", tyvar.name);
         }
-        self.to_string_substituted(&s)
+        s
     }
 
     /// The scheme written out under the names its own variables carry.
@@ -2495,6 +2584,13 @@ impl Scheme {
         self.to_string_substituted(&s)
     }
 
+    /// The scheme written out as `to_string` does, each type constructor, associated type and trait
+    /// it names written by `names`.
+    pub fn to_string_with(&self, names: &dyn Fn(NameRef) -> String) -> String {
+        let s = Substitution::default();
+        self.to_string_substituted_with(&s, names)
+    }
+
     /// Appends to `buf` the type variables standing in the constraints and in the type that the
     /// scheme leaves free, that is, all but the generalized ones.
     pub fn free_vars_to_vec(&self, buf: &mut Vec<Arc<TyVar>>) {
@@ -100,7 +100,7 @@ pub fn build(config: &Configuration) -> Result<(), Errors> {
     }
 
     let mut program = elaborate_via_config(&config)?;
-    program.flush_warnings_to_stderr();
+    program.flush_warnings(config.message_format);
     // Surface any errors that were deferred to the diagnostic stage —
     // most importantly, deprecation diagnostics promoted to errors by
     // `--deny-deprecated`.
@@ -108,34 +108,214 @@ pub fn build(config: &Configuration) -> Result<(), Errors> {
         return Err(program.deferred_errors);
     }
     program.check_multi_threading_requirement(&config)?;
+    // The header is rendered before the program goes to the code generator, which consumes it, and
+    // written once the library it describes is produced.
+    let c_header = if config.output_file_type.is_library() {
+        let header_path = config.get_c_header_path();
+        let header = program.c_header(&c_header_include_guard(&header_path));
+        Some((header_path, header))
+    } else {
+        None
+    };
     let obj_files = build_object_files(program, &config)?;
 
-    let mut library_search_path_opts: Vec<String> = vec![];
-    for path in &config.library_search_paths {
-        library_search_path_opts.push(format!("-L{}", path.to_str().unwrap()));
-    }
-    let mut libs_opts = vec![];
-    let mut warned_on_mac = false;
-    for (lib_name, link_type) in &config.linked_libraries {
-        if env::consts::OS != "macos" {
-            match link_type {
-                LinkType::Static => libs_opts.push("-Wl,-Bstatic".to_string()),
-                LinkType::Dynamic => libs_opts.push("-Wl,-Bdynamic".to_string()),
-            }
+    let runtime_obj_path = compile_runtime(&config, false)?;
+
+    let mut obj_paths = obj_files.obj_paths;
+    obj_paths.append(&mut config.object_files.clone());
+    obj_paths.push(runtime_obj_path);
+
+    if matches!(config.output_file_type, OutputFileType::StaticLibrary) {
+        archive_static_library(&config, &out_path, &obj_paths)?;
+    } else {
+        let mut com = c_compiler_command(&config)?;
+        com.arg("-Wno-unused-command-line-argument");
+        if matches!(config.output_file_type, OutputFileType::DynamicLibrary) {
+            com.arg("-shared");
         } else {
-            if !warned_on_mac {
-                info_msg("On MacOS, it is not possible to specify whether a library should be dynamically or statically linked. \
-                If a dynamic library and a static library with the same name exist, the unintended one may be used.");
-                warned_on_mac = true;
-            }
+            com.arg("-no-pie");
+        }
+        if env::consts::OS == "macos" {
+            com.arg("-Wl,-dead_strip");
+        } else {
+            com.arg("-Wl,--gc-sections");
         }
-        libs_opts.push(format!("-l{}", lib_name));
+        com.arg("-o").arg(out_path.to_str().unwrap());
+        for obj_path in obj_paths {
+            com.arg(obj_path.to_str().unwrap());
+        }
+        com.args(library_link_options(&config));
+        run_c_compiler(&mut com, "link the output file")?;
     }
-    for ld_flag in &config.ld_flags {
-        libs_opts.push(ld_flag.clone());
+
+    if let Some((header_path, header)) = c_header {
+        write_beside_output(&header_path, &header, "C header")?;
+    }
+    if config.debug_info {
+        write_debugger_scripts(&config)?;
+    }
+
+    Ok(())
+}
+
+/// Archives `obj_paths` — the object files of the program, the ones the configuration links, and
+/// the runtime — into the static library at `out_path`, and writes beside it the flags a program
+/// linking the library also needs.
+///
+/// An archive records no dependencies of its own, so the libraries the program is linked with, and
+/// the ones the runtime calls into, are left for the program linking it to name. The flags are
+/// written to a file under the library's name with the extension `.ldflags`, for a build script to
+/// read, and reported to the user as well.
+fn archive_static_library(
+    config: &Configuration,
+    out_path: &Path,
+    obj_paths: &[PathBuf],
+) -> Result<(), Errors> {
+    // `ar` adds to an archive that already exists, which would keep the members of an earlier build.
+    if out_path.exists() {
+        fs::remove_file(out_path).map_err(|e| {
+            Errors::from_msg(format!(
+                "Failed to remove the earlier \"{}\": {}.",
+                out_path.display(),
+                e
+            ))
+        })?;
+    }
+    // Members are appended (`q`) rather than inserted (`r`): an archive names a member by its file
+    // name alone, so inserting one object file would replace another of the same name from a
+    // different directory.
+    let mut com = Command::new("ar");
+    com.arg("qcs").arg(out_path);
+    for obj_path in obj_paths {
+        com.arg(obj_path);
+    }
+    let output = com.output().map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to archive the static library: could not run `ar`: {}.",
+            e
+        ))
+    })?;
+    if output.stderr.len() > 0 {
+        eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    }
+    if !output.status.success() {
+        return Err(Errors::from_msg(format!(
+            "Failed to archive the static library: ar exited with code {}.",
+            output.status.code().unwrap_or(-1)
+        )));
+    }
+
+    // The runtime calls into the threads library, and the generated code may call into the math
+    // library for an operation LLVM lowers to a libm function.
+    let mut link_config = config.clone();
+    for lib_name in ["pthread", "m"] {
+        if !link_config
+            .linked_libraries
+            .iter()
+            .any(|(name, _)| name == lib_name)
+        {
+            link_config.add_dynamic_library(lib_name);
+        }
+    }
+    let mut link_flags = library_link_options(&link_config);
+    // The instrumentation of a sanitized build calls into the sanitizer runtime, which the program
+    // linking the library has to bring.
+    if matches!(config.sanitizer, Sanitizer::Thread) {
+        link_flags.insert(0, "-fsanitize=thread".to_string());
+    }
+    let link_flags = link_flags.join(" ");
+    let link_flags_path = out_path.with_extension("ldflags");
+    fs::write(&link_flags_path, format!("{}\n", link_flags)).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to write the link flags \"{}\": {}.",
+            link_flags_path.display(),
+            e
+        ))
+    })?;
+    info_msg(&format!(
+        "A program linking \"{}\" also needs the flags `{}`, which are written to \"{}\".",
+        out_path.display(),
+        link_flags,
+        link_flags_path.display()
+    ));
+    Ok(())
+}
+
+/// The macro guarding the C header at `header_path` against a second inclusion: the header's file
+/// name in upper case, with every character a C identifier cannot hold replaced by `_`, so that
+/// `my-lib.h` is guarded by `MY_LIB_H`.
+fn c_header_include_guard(header_path: &Path) -> String {
+    let file_name = header_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut guard = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    // A C identifier does not begin with a digit.
+    if guard.chars().next().map_or(true, |c| c.is_ascii_digit()) {
+        guard.insert(0, '_');
+    }
+    guard
+}
+
+/// Writes `content`, a file the build produces beside its output, to `path`, creating the
+/// directory it goes in. A failure names the file as `what`.
+fn write_beside_output(path: &Path, content: &str, what: &str) -> Result<(), Errors> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).map_err(|e| {
+                Errors::from_msg(format!(
+                    "Failed to create the directory \"{}\" for the {}: {}.",
+                    parent.display(),
+                    what,
+                    e
+                ))
+            })?;
+        }
     }
+    fs::write(path, content).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to write the {} \"{}\": {}.",
+            what,
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Writes beside the output of a build with debug information the scripts teaching a debugger to
+/// show Fix values: an `Array` by its elements, a `String` by its text, a union by its active
+/// variant, and a boxed value by what it holds. gdb loads its script with the program once the
+/// directory is on its auto-load safe path; lldb loads its own by `command script import`.
+fn write_debugger_scripts(config: &Configuration) -> Result<(), Errors> {
+    write_beside_output(
+        &config.get_gdb_script_path(),
+        include_str!("../fixstd/gdb_printers.py"),
+        "gdb pretty-printer script",
+    )?;
+    write_beside_output(
+        &config.get_lldb_script_path(),
+        include_str!("../fixstd/lldb_formatters.py"),
+        "lldb formatter script",
+    )
+}
 
-    // Build runtime.c to object file.
+/// Compile the runtime, returning the path of what it was compiled into, which is kept in the
+/// intermediate directory for the builds of the same configuration to reuse.
+///
+/// Unless `shared`, the runtime is compiled into an object file for the linker. A `shared` runtime is a
+/// shared library for `fix repl` to load into its own process, which also carries the object files and
+/// libraries the program is linked with, since the code the session generates in memory is linked
+/// against nothing else.
+pub(crate) fn compile_runtime(config: &Configuration, shared: bool) -> Result<PathBuf, Errors> {
     let mut runtime_obj_hash_source = "".to_string();
     runtime_obj_hash_source += build_time_utc!();
     runtime_obj_hash_source += &config.runtime_c_macro.join("_");
@@ -143,9 +323,27 @@ pub fn build(config: &Configuration) -> Result<(), Errors> {
     // A sanitized build compiles the runtime with the instrumentation, so an object built without it
     // is a different object.
     runtime_obj_hash_source += &config.sanitizer.to_string();
+    // A shared runtime carries the object files and libraries the program is linked with.
+    if shared {
+        runtime_obj_hash_source += "shared";
+        for path in &config.library_search_paths {
+            runtime_obj_hash_source += &path.to_string_lossy();
+        }
+        for (lib_name, _) in &config.linked_libraries {
+            runtime_obj_hash_source += lib_name;
+        }
+        runtime_obj_hash_source += &config.ld_flags.join(" ");
+        for obj_path in &config.object_files {
+            runtime_obj_hash_source += &obj_path.to_string_lossy();
+            if let Ok(modified) = fs::metadata(obj_path).and_then(|m| m.modified()) {
+                runtime_obj_hash_source += &format!("{:?}", modified);
+            }
+        }
+    }
     let runtime_obj_path = PathBuf::from(INTERMEDIATE_PATH).join(format!(
-        "fixruntime.{:x}.o",
-        md5::compute(runtime_obj_hash_source)
+        "fixruntime.{:x}.{}",
+        md5::compute(runtime_obj_hash_source),
+        if shared { "so" } else { "o" }
     ));
     if !runtime_obj_path.exists() {
         // Random number for temporary file name.
@@ -163,23 +361,31 @@ pub fn build(config: &Configuration) -> Result<(), Errors> {
             runtime_c_path.to_string_lossy().to_string()
         ));
         // Create library object file.
-        let mut com = c_compiler_command(&config)?;
+        let mut com = c_compiler_command(config)?;
         let mut com = com.arg("-ffunction-sections").arg("-fdata-sections");
         // Keep frame pointers for better backtraces on macOS when backtrace is enabled
         if config.no_elim_frame_pointers() {
             com = com.arg("-fno-omit-frame-pointer");
         }
-        let mut com = com
-            .arg("-o")
-            .arg(runtime_tmp_path.to_str().unwrap())
-            .arg("-c")
-            .arg(runtime_c_path.to_str().unwrap());
+        let mut com = com.arg("-o").arg(runtime_tmp_path.to_str().unwrap());
+        if shared {
+            com = com.arg("-shared").arg("-fPIC");
+        } else {
+            com = com.arg("-c");
+        }
+        com = com.arg(runtime_c_path.to_str().unwrap());
         for m in &config.runtime_c_macro {
             com = com.arg(format!("-D{}", m));
         }
-        if matches!(config.output_file_type, OutputFileType::DynamicLibrary) {
+        if config.output_file_type.is_library() {
             com = com.arg("-fPIC");
         }
+        if shared {
+            for obj_path in &config.object_files {
+                com = com.arg(obj_path.to_str().unwrap());
+            }
+            com = com.args(library_link_options(config));
+        }
         run_c_compiler(com, "compile the runtime")?;
 
         // Rename the temporary file to the final file.
@@ -189,30 +395,34 @@ pub fn build(config: &Configuration) -> Result<(), Errors> {
             runtime_obj_path.to_string_lossy().to_string()
         ));
     }
+    Ok(runtime_obj_path)
+}
 
-    let mut com = c_compiler_command(&config)?;
-    com.arg("-Wno-unused-command-line-argument");
-    if matches!(config.output_file_type, OutputFileType::DynamicLibrary) {
-        com.arg("-shared");
-    } else {
-        com.arg("-no-pie");
+/// The options that make the linker search the library paths and link the libraries the configuration
+/// names.
+fn library_link_options(config: &Configuration) -> Vec<String> {
+    let mut opts: Vec<String> = vec![];
+    for path in &config.library_search_paths {
+        opts.push(format!("-L{}", path.to_str().unwrap()));
     }
-    if env::consts::OS == "macos" {
-        com.arg("-Wl,-dead_strip");
-    } else {
-        com.arg("-Wl,--gc-sections");
+    let mut warned_on_mac = false;
+    for (lib_name, link_type) in &config.linked_libraries {
+        if env::consts::OS != "macos" {
+            match link_type {
+                LinkType::Static => opts.push("-Wl,-Bstatic".to_string()),
+                LinkType::Dynamic => opts.push("-Wl,-Bdynamic".to_string()),
+            }
+        } else {
+            if !warned_on_mac {
+                info_msg("On MacOS, it is not possible to specify whether a library should be dynamically or statically linked. \
+                If a dynamic library and a static library with the same name exist, the unintended one may be used.");
+                warned_on_mac = true;
+            }
+        }
+        opts.push(format!("-l{}", lib_name));
     }
-    com.arg("-o").arg(out_path.to_str().unwrap());
-
-    let mut obj_paths = obj_files.obj_paths;
-    obj_paths.append(&mut config.object_files.clone());
-    for obj_path in obj_paths {
-        com.arg(obj_path.to_str().unwrap());
+    for ld_flag in &config.ld_flags {
+        opts.push(ld_flag.clone());
     }
-    com.arg(runtime_obj_path.to_str().unwrap())
-        .args(library_search_path_opts)
-        .args(libs_opts);
-    run_c_compiler(&mut com, "link the output file")?;
-
-    Ok(())
+    opts
 }
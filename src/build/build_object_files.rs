@@ -3,11 +3,11 @@ use crate::{
         export_statement::ExportStatement,
         expr::ExprNode,
         name::FullName,
-        program::{Program, Symbol, TypeEnv},
+        program::{Program, Symbol, TestCase, TypeEnv},
         types::TypeNode,
     },
     build::{compile_unit::CompileUnit, cpu_features::CpuFeatures},
-    configuration::{Configuration, OutputFileType},
+    configuration::{Configuration, SubCommand},
     constants::{
         C_ENTRY_POINT_NAME, DOT_FIXLANG, GLOBAL_VAR_NAME_ARGC, GLOBAL_VAR_NAME_ARGV,
         UNITS_CACHE_PATH,
@@ -16,7 +16,7 @@ use crate::{
     ffi::c_entry_point_signature,
     fixstd::{
         builtin::run_io_or_ios_runner,
-        runtime::{self, BuildMode},
+        runtime::{self, BuildMode, RUNTIME_RUN_BENCHES, RUNTIME_RUN_ISOLATED, RUNTIME_RUN_TESTS},
     },
     generator::{enum_attribute_kind_id, module_functions, Generator},
     hash::HashSource,
@@ -25,7 +25,9 @@ use crate::{
     rc_ir::{
         ast::RcProgram,
         borrow::{borrow_ify, cancel, param_ownership_shapes, split_rc_units},
-        dead_code_elim, locality,
+        dead_code_elim,
+        explain_uniqueness::explain_kept_unique_checks,
+        locality,
         lower::lower_program,
         print::{program_to_string_annotated, Annotations},
         provenance::analyze_program,
@@ -38,11 +40,11 @@ use crate::{
 use inkwell::{
     attributes::AttributeLoc,
     context::Context,
-    module::Module,
+    module::{Linkage, Module},
     passes::PassBuilderOptions,
     targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
-    values::BasicValue,
-    OptimizationLevel,
+    values::{BasicValue, FunctionValue},
+    AddressSpace, OptimizationLevel,
 };
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
@@ -51,7 +53,7 @@ use std::{
     fs::{self, create_dir_all, File},
     mem,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 /// What a build produced, as `build_object_files` reports it.
@@ -276,24 +278,47 @@ fn dump_rc_ir_stages(program: &Program, config: &Configuration) {
     dump_rc_ir(&optimized, &type_env, filter, "post", config);
 }
 
-/// Compile the program into object files, and return their paths for the linker.
-pub fn build_object_files<'c>(
-    mut program: Program,
-    config: &Configuration,
-) -> Result<BuildObjFilesResult, Errors> {
-    let _sw = StopWatch::new("build_object_files", config.show_build_times);
+/// The notes `--explain-uniqueness` reports: each operation in the user's sources that keeps its
+/// run-time uniqueness check once the RC IR of the whole program is optimized, with why. `program` is
+/// the program as the optimizations over it leave it.
+fn kept_unique_check_notes(program: &Program, config: &Configuration) -> Errors {
+    let type_env = program.type_env();
+    let all_symbols: Vec<Symbol> = program.symbols.values().cloned().collect();
+    let global_types = program.global_types();
+    let roots = reachability_roots(&all_symbols, &program.root_value_names(), config);
+    let base = lower_and_insert_rc(&type_env, &all_symbols, &global_types, roots, config);
+    let optimized = optimize_rc_program(base, &type_env, &global_types, config);
+    explain_kept_unique_checks(&optimized, &type_env, config)
+}
 
-    // Return cached object files if available.
-    // This cache is especially effective when running "fix run" repeatedly without editing the source code.
-    if let Some(cached) = load_build_object_files_cache(&program, config) {
-        if config.verbose {
-            info_msg("Using cached object files.");
-        }
-        return Ok(cached);
+/// Report the notes of `--explain-uniqueness` when it is given. Unique-check elimination runs at the
+/// `max` optimization level and above; below it every check stays, which is said instead.
+fn report_kept_unique_checks(program: &Program, config: &Configuration) {
+    if !config.explain_uniqueness {
+        return;
     }
+    if !config.enable_borrow_optimization() {
+        warn_msg("`--explain-uniqueness` reports on the uniqueness checks the `max` optimization level removes, so it reports nothing at a lower level, where every check stays.");
+        return;
+    }
+    kept_unique_check_notes(program, config).report(config.message_format);
+}
+
+/// The notes `--explain-uniqueness` reports on an elaborated program, for the language server, which
+/// generates no code: the program is optimized here as a build optimizes it.
+pub fn explain_uniqueness_of(mut program: Program, config: &Configuration) -> Errors {
+    if !config.enable_borrow_optimization() {
+        return Errors::empty();
+    }
+    optimization::run(&mut program, config);
+    kept_unique_check_notes(&program, config)
+}
 
+/// Run the optimizations over the whole program that precede code generation, and the checks and dumps
+/// made of the program they leave.
+fn optimize_program(program: &mut Program, config: &Configuration) -> Result<(), Errors> {
     // Run optimizations.
-    optimization::run(&mut program, &config);
+    optimization::run(program, config);
 
     // The layout validation before code generation runs on the program as elaboration left it, and
     // the optimizations that follow mint types of their own — a capture list, a punched type, the
@@ -305,7 +330,35 @@ pub fn build_object_files<'c>(
         program.validate_layouts()?;
     }
 
-    dump_rc_ir_stages(&program, config);
+    dump_rc_ir_stages(program, config);
+    report_kept_unique_checks(program, config);
+    Ok(())
+}
+
+/// Compile the program into object files, and return their paths for the linker.
+pub fn build_object_files<'c>(
+    mut program: Program,
+    config: &Configuration,
+) -> Result<BuildObjFilesResult, Errors> {
+    let _sw = StopWatch::new("build_object_files", config.show_build_times);
+
+    // Return cached object files if available.
+    // This cache is especially effective when running "fix run" repeatedly without editing the source code.
+    // The notes of `--explain-uniqueness` are made while the program is optimized, so a build asking
+    // for them optimizes it anew.
+    let cached = if config.explain_uniqueness {
+        None
+    } else {
+        load_build_object_files_cache(&program, config)
+    };
+    if let Some(cached) = cached {
+        if config.verbose {
+            info_msg("Using cached object files.");
+        }
+        return Ok(cached);
+    }
+
+    optimize_program(&mut program, config)?;
 
     // Determine compilation units.
     let mut units = vec![];
@@ -341,11 +394,12 @@ pub fn build_object_files<'c>(
         }
     }
 
-    // Paths of object files to be linked.
+    // Paths of object files to be linked, in the order of the units, whichever thread generates
+    // each.
     let mut obj_paths = vec![];
 
-    // Generate object files in parallel.
-    let mut threads = vec![];
+    // The units whose object files are to be generated.
+    let mut jobs = vec![];
     let units_count = units.len();
     for (i, unit) in units.into_iter().enumerate() {
         // The main unit is generated last.
@@ -362,106 +416,204 @@ pub fn build_object_files<'c>(
             }
             continue;
         }
-        if config.verbose {
-            info_msg(&format!("Generating object file for {}.", unit.to_string()));
-        }
 
+        // Entry points are only implemented by the main unit.
+        let entry_points = is_main_unit.then(|| EntryPoints {
+            export_statements: mem::replace(&mut program.export_statements, vec![]),
+            entry_io_value: program.entry_io_value.clone(),
+            test_cases: program.test_cases.clone(),
+        });
+        jobs.push((unit, entry_points));
+    }
+
+    // Generate object files in parallel, over `num_worker_thread` threads (at least one) taking the
+    // units from a shared queue, so a thread that finishes a small unit goes on with the next one.
+    // Each unit is generated in an LLVM context of its own and written to a path of its own, so the
+    // object files do not depend on which thread generated them or when. The queue is popped from
+    // its end, and is sorted so that the largest units go first: a large unit taken last would
+    // leave the other threads idle while it is generated.
+    jobs.sort_by_key(|(unit, _)| unit.symbols().len());
+    let workers_count = config.num_worker_thread.clamp(1, jobs.len().max(1));
+    let queue = Arc::new(Mutex::new(jobs));
+    let type_env = program.type_env();
+    let mut threads = vec![];
+    for _ in 0..workers_count {
+        let queue = queue.clone();
         let global_types = global_types.clone();
         let root_value_names = root_value_names.clone();
         let config = config.clone();
-        let type_env = program.type_env();
-
-        let export_statements = if is_main_unit {
-            // Export statements are only needed for the main unit.
-            mem::replace(&mut program.export_statements, vec![])
-        } else {
-            vec![]
-        };
-
-        let entry_io_value = program.entry_io_value.clone();
+        let type_env = type_env.clone();
         threads.push(spawn_compiler_thread(move || {
-            let context = Context::create();
             let target_machine = get_target_machine(config.get_llvm_opt_level(), &config);
-            let module = Generator::create_module(
-                &format!("Module-{}", unit.unit_hash()),
-                &context,
-                &target_machine,
-            );
-            let mut gc = Generator::new(
-                &context,
-                &module,
-                target_machine.get_target_data(),
-                config.clone(),
-                type_env,
-                global_types.clone(),
-            );
-
-            // In debug mode, create debug infos.
-            if config.debug_info {
-                gc.create_debug_info();
-            }
-
-            // Declare runtime functions.
-            runtime::build_runtime(&mut gc, BuildMode::Declare);
-
-            // Lower this unit's symbols to the RC IR, insert reference counting, and generate their
-            // LLVM. Only this unit's symbols are implemented; a symbol of another unit that this
-            // one calls is declared where code generation first reaches it, from the types of the
-            // program's globals the generator was given.
-            let unit_symbols = unit.symbols().to_vec();
-            let roots = reachability_roots(&unit_symbols, &root_value_names, &config);
-            let rc_prog =
-                lower_and_insert_rc(gc.type_env(), &unit_symbols, &global_types, roots, &config);
-            let rc_prog = optimize_rc_program(rc_prog, gc.type_env(), &global_types, &config);
-            gc.implement_rc_program(&rc_prog);
-
-            if is_main_unit {
-                // Implement runtime functions.
-                runtime::build_runtime(&mut gc, BuildMode::Implement);
-
-                // Implement exported C functions.
-                build_exported_c_functions(&mut gc, &export_statements);
-
-                // Implement the `main()` function.
-                if let Some(main_expr) = entry_io_value {
-                    build_main_function(&mut gc, main_expr.clone());
+            loop {
+                let (unit, entry_points) = match queue.lock().unwrap().pop() {
+                    Some(job) => job,
+                    None => break,
+                };
+                if config.verbose {
+                    info_msg(&format!("Generating object file for {}.", unit.to_string()));
                 }
+                let context = Context::create();
+                let module = generate_unit_module(
+                    &context,
+                    &target_machine,
+                    &unit,
+                    entry_points,
+                    type_env.clone(),
+                    global_types.clone(),
+                    &root_value_names,
+                    &config,
+                );
+                write_to_object_file(&module, &target_machine, &unit.object_file_path());
             }
+        }));
+    }
+    join_compiler_threads(threads);
 
-            // Every reader of this unit's globals is in the module by now.
-            gc.keep_initializers_out_of_shared_accessors();
+    // Save object files cache.
+    let obj_files = BuildObjFilesResult { obj_paths };
+    save_build_object_files_cache(&program, config, &obj_files);
 
-            gc.finalize_di();
+    Ok(obj_files)
+}
 
-            gc.assert_defined_symbols_fit_a_symbol_table();
+/// What the main unit implements besides its symbols: the places the C world enters the program.
+struct EntryPoints {
+    export_statements: Vec<ExportStatement>,
+    entry_io_value: Option<Arc<ExprNode>>,
+    test_cases: Vec<TestCase>,
+}
 
-            // Add frame-pointer attribute to all functions for better backtraces on macOS
-            if config.no_elim_frame_pointers() {
-                gc.add_frame_pointer_attribute_to_all_functions();
-            }
+/// Generate the LLVM module of `unit`, optimized and verified. `entry_points` is given for the main
+/// unit alone, which also implements the runtime.
+fn generate_unit_module<'c>(
+    context: &'c Context,
+    target_machine: &TargetMachine,
+    unit: &CompileUnit,
+    entry_points: Option<EntryPoints>,
+    type_env: TypeEnv,
+    global_types: Arc<Map<FullName, Arc<TypeNode>>>,
+    root_value_names: &[FullName],
+    config: &Configuration,
+) -> Module<'c> {
+    let module = Generator::create_module(
+        &format!("Module-{}", unit.unit_hash()),
+        context,
+        target_machine,
+    );
+    let mut gc = Generator::new(
+        context,
+        &module,
+        target_machine.get_target_data(),
+        config.clone(),
+        type_env,
+        global_types.clone(),
+    );
 
-            if config.emit_llvm {
-                // Print LLVM-IR to file before optimization.
-                emit_llvm(gc.module, &config, false);
-            }
+    // In debug mode, create debug infos.
+    if config.debug_info {
+        gc.create_debug_info();
+    }
+
+    // Declare runtime functions.
+    runtime::build_runtime(&mut gc, BuildMode::Declare);
+
+    // Lower this unit's symbols to the RC IR, insert reference counting, and generate their
+    // LLVM. Only this unit's symbols are implemented; a symbol of another unit that this
+    // one calls is declared where code generation first reaches it, from the types of the
+    // program's globals the generator was given.
+    let unit_symbols = unit.symbols().to_vec();
+    let roots = reachability_roots(&unit_symbols, root_value_names, config);
+    let rc_prog = lower_and_insert_rc(gc.type_env(), &unit_symbols, &global_types, roots, config);
+    let rc_prog = optimize_rc_program(rc_prog, gc.type_env(), &global_types, config);
+    gc.implement_rc_program(&rc_prog);
 
-            optimize_instrument_and_verify(gc.module, &target_machine, &config);
+    if let Some(entry_points) = entry_points {
+        // Implement runtime functions.
+        runtime::build_runtime(&mut gc, BuildMode::Implement);
 
-            if config.emit_llvm {
-                // Print LLVM-IR to file after optimization.
-                emit_llvm(gc.module, &config, true);
+        // Implement exported C functions.
+        build_exported_c_functions(&mut gc, &entry_points.export_statements);
+
+        // Implement the `main()` function.
+        if let Some(main_expr) = entry_points.entry_io_value {
+            if matches!(config.subcommand, SubCommand::Repl) {
+                build_repl_main_function(&mut gc, main_expr);
+            } else {
+                build_main_function(&mut gc, main_expr);
             }
+        } else if !entry_points.test_cases.is_empty() {
+            let runner = if matches!(config.subcommand, SubCommand::Bench(_)) {
+                RUNTIME_RUN_BENCHES
+            } else {
+                RUNTIME_RUN_TESTS
+            };
+            build_test_main_function(&mut gc, &entry_points.test_cases, runner);
+        }
+    }
 
-            write_to_object_file(gc.module, &target_machine, &unit.object_file_path());
-        }));
+    // Every reader of this unit's globals is in the module by now.
+    gc.keep_initializers_out_of_shared_accessors();
+
+    gc.finalize_di();
+
+    gc.assert_defined_symbols_fit_a_symbol_table();
+
+    // Add frame-pointer attribute to all functions for better backtraces on macOS
+    if config.no_elim_frame_pointers() {
+        gc.add_frame_pointer_attribute_to_all_functions();
     }
-    join_compiler_threads(threads);
 
-    // Save object files cache.
-    let obj_files = BuildObjFilesResult { obj_paths };
-    save_build_object_files_cache(&program, config, &obj_files);
+    if config.emit_llvm {
+        // Print LLVM-IR to file before optimization.
+        emit_llvm(gc.module, config, false);
+    }
 
-    Ok(obj_files)
+    optimize_instrument_and_verify(gc.module, target_machine, config);
+
+    if config.emit_llvm {
+        // Print LLVM-IR to file after optimization.
+        emit_llvm(gc.module, config, true);
+    }
+
+    drop(gc);
+    module
+}
+
+/// Compile the program into one LLVM module in `context`, for an execution engine to run in this
+/// process rather than for the linker. The module is generated as the main unit of a build without
+/// separated compilation is, and leaves the runtime's C functions to the process to provide.
+pub fn build_jit_module<'c>(
+    context: &'c Context,
+    mut program: Program,
+    config: &Configuration,
+) -> Result<Module<'c>, Errors> {
+    optimize_program(&mut program, config)?;
+
+    let mut symbols = program.symbols.values().cloned().collect::<Vec<_>>();
+    symbols.sort_by(|a, b| a.name.cmp(&b.name));
+    let global_types = Arc::new(program.global_types());
+    let root_value_names = program.root_value_names();
+    let modules = program.linked_mods().iter().cloned().collect::<Vec<_>>();
+    let mut unit = CompileUnit::new(symbols, modules);
+    unit.update_unit_hash(&program.module_dependency_hash_map(config), config);
+
+    let entry_points = EntryPoints {
+        export_statements: mem::replace(&mut program.export_statements, vec![]),
+        entry_io_value: program.entry_io_value.clone(),
+        test_cases: program.test_cases.clone(),
+    };
+    let target_machine = get_target_machine(config.get_llvm_opt_level(), config);
+    Ok(generate_unit_module(
+        context,
+        &target_machine,
+        &unit,
+        Some(entry_points),
+        program.type_env(),
+        global_types,
+        &root_value_names,
+        config,
+    ))
 }
 
 /// The object files a previous build of this program and configuration left behind, when the cache
@@ -569,7 +721,7 @@ fn build_object_files_cache_hash_or_warn(
 }
 
 /// The LLVM target machine to compile for: the host's CPU with the features it supports, minus the
-/// ones the configuration disables, generating code at `opt_level`. A dynamic library is compiled
+/// ones the configuration disables, generating code at `opt_level`. A library is compiled
 /// position-independent.
 pub(crate) fn get_target_machine(
     opt_level: OptimizationLevel,
@@ -587,7 +739,9 @@ pub(crate) fn get_target_machine(
     let cpu_name = TargetMachine::get_host_cpu_name();
     let mut features = CpuFeatures::parse(TargetMachine::get_host_cpu_features().to_str().unwrap());
     config.edit_cpu_features(&mut features);
-    let reloc_mode = if matches!(config.output_file_type, OutputFileType::DynamicLibrary) {
+    // A static library is compiled position-independent as well, so that it links into the
+    // position-independent executables C compilers produce by default.
+    let reloc_mode = if config.output_file_type.is_library() {
         RelocMode::PIC
     } else {
         RelocMode::Default
@@ -742,11 +896,151 @@ fn build_exported_c_functions<'c, 'm>(
 /// Implement the C `main` function of the program: store `argc` and `argv` into the global
 /// variables the runtime reads them from, run the `IO ()` action `main_expr` refers to, and return
 /// 0.
+fn build_main_function<'c, 'm>(gc: &mut Generator<'c, 'm>, main_expr: Arc<ExprNode>) {
+    begin_entry_point(gc);
+
+    // Run the main IO action. `main_expr` is a reference to the instantiated `main` symbol (see
+    // `instantiate_exported_value`), which the RC-IR back end has already implemented; materialize
+    // that symbol's object here.
+    let main_name = main_expr.get_var().name.clone();
+    let main_obj = gc.get_scoped_obj(&main_name); // A value of type `IO ()`.
+    run_io_or_ios_runner(gc, &main_obj);
+
+    // Return main function.
+    gc.builder()
+        .build_return(Some(&gc.context.i32_type().const_int(0, false)))
+        .unwrap();
+}
+
+/// Implement the C `main` function of a `fix repl` input: store `argc` and `argv` as
+/// `build_main_function` does, and hand a function running the `IO ()` action `main_expr` refers to
+/// to `fixruntime_run_isolated`, whose status `main` returns. An input that ends its process, by
+/// `exit` or by a failed check, so ends the process running it and not the session.
+fn build_repl_main_function<'c, 'm>(gc: &mut Generator<'c, 'm>, main_expr: Arc<ExprNode>) {
+    let input_fn_ty = gc.context.void_type().fn_type(&[], false);
+    let input_fn = gc.module.add_function(
+        "fixruntime_repl_input",
+        input_fn_ty,
+        Some(Linkage::Internal),
+    );
+    {
+        let bb = gc.context.append_basic_block(input_fn, "entry");
+        let _builder_guard = gc.push_builder();
+        gc.builder().position_at_end(bb);
+        let main_obj = gc.get_scoped_obj(&main_expr.get_var().name); // A value of type `IO ()`.
+        run_io_or_ios_runner(gc, &main_obj);
+        gc.builder().build_return(None).unwrap();
+    }
+
+    begin_entry_point(gc);
+    let status = gc
+        .call_runtime(
+            RUNTIME_RUN_ISOLATED,
+            &[input_fn.as_global_value().as_pointer_value().into()],
+        )
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value();
+    gc.builder().build_return(Some(&status)).unwrap();
+}
+
+/// Implement the C `main` function of a test build: store `argc` and `argv` as `build_main_function`
+/// does, and hand the tests to `fixruntime_run_tests`, which runs the ones the command line selects,
+/// each in a process of its own, reports how each went, and gives the exit status `main` returns.
+/// A benchmark build hands its benchmarks to `fixruntime_run_benches` the same way, which measures
+/// them in place of running them once.
+///
+/// Each test is run by a function of its own taking and returning nothing, which runs the test's
+/// `IO ()` action in the process it is called in; a test of type `IO (Result ErrMsg ())` is run
+/// through its runner, which turns an `err` into an exit with status 1.
+///
+/// # Arguments
+///
+/// * `runner` - The runtime function the tests are handed to, `RUNTIME_RUN_TESTS` or
+///   `RUNTIME_RUN_BENCHES`.
+fn build_test_main_function<'c, 'm>(
+    gc: &mut Generator<'c, 'm>,
+    test_cases: &[TestCase],
+    runner: &str,
+) {
+    let ptr_ty = gc.context.ptr_type(AddressSpace::from(0));
+    let test_fn_ty = gc.context.void_type().fn_type(&[], false);
+    let mut test_fns = vec![];
+    for (i, test) in test_cases.iter().enumerate() {
+        let func = gc.module.add_function(
+            &format!("fixruntime_test_case#{}", i),
+            test_fn_ty,
+            Some(Linkage::Internal),
+        );
+        let bb = gc.context.append_basic_block(func, "entry");
+        let _builder_guard = gc.push_builder();
+        gc.builder().position_at_end(bb);
+        let mut io = gc.get_scoped_obj(&test.expr.get_var().name);
+        if let Some(runner) = &test.result_runner {
+            let runner = gc.get_scoped_obj(&runner.get_var().name);
+            io = gc.apply_lambda(runner, vec![io], false).unwrap();
+        }
+        run_io_or_ios_runner(gc, &io);
+        gc.builder().build_return(None).unwrap();
+        test_fns.push(func);
+    }
+
+    let main_function = begin_entry_point(gc);
+
+    // The names of the tests and the functions running them, as two constant arrays in one order.
+    let names = test_cases
+        .iter()
+        .map(|test| {
+            gc.add_global_string(&test.name.to_string())
+                .as_pointer_value()
+        })
+        .collect::<Vec<_>>();
+    let fns = test_fns
+        .iter()
+        .map(|func| func.as_global_value().as_pointer_value())
+        .collect::<Vec<_>>();
+    let mut arrays = vec![];
+    for (values, name) in [(names, "test_names"), (fns, "test_functions")] {
+        let array_ty = ptr_ty.array_type(values.len() as u32);
+        let array = gc.module.add_global(array_ty, None, name);
+        array.set_initializer(&ptr_ty.const_array(&values));
+        array.set_linkage(Linkage::Private);
+        array.set_constant(true);
+        arrays.push(array.as_pointer_value());
+    }
+
+    let argc = main_function.get_nth_param(0).unwrap();
+    let argv = main_function.get_nth_param(1).unwrap();
+    let count = gc
+        .context
+        .i64_type()
+        .const_int(test_cases.len() as u64, false);
+    let status = gc
+        .call_runtime(
+            runner,
+            &[
+                argc.into(),
+                argv.into(),
+                count.into(),
+                arrays[0].into(),
+                arrays[1].into(),
+            ],
+        )
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value();
+    gc.builder().build_return(Some(&status)).unwrap();
+}
+
+/// Start the body of the C `main` function: store `argc` and `argv` into the global variables the
+/// runtime reads them from, leaving the builder at the end of the entry block.
 ///
 /// The body goes onto the declaration an `FFI_CALL` of `main` has left, where a program calls its
 /// own entry point. `Program::validate_c_function_calls` has held that call to
 /// `c_entry_point_signature`, so the declaration found here is the one this function builds.
-fn build_main_function<'c, 'm>(gc: &mut Generator<'c, 'm>, main_expr: Arc<ExprNode>) {
+fn begin_entry_point<'c, 'm>(gc: &mut Generator<'c, 'm>) -> FunctionValue<'c> {
     let main_function =
         c_entry_point_signature().get_or_declare_in_module(&C_ENTRY_POINT_NAME.to_string(), gc);
     assert_eq!(
@@ -771,16 +1065,5 @@ fn build_main_function<'c, 'm>(gc: &mut Generator<'c, 'm>, main_expr: Arc<ExprNo
             .into_pointer_value();
         gc.builder().build_store(gv_ptr, arg_val).unwrap();
     }
-
-    // Run the main IO action. `main_expr` is a reference to the instantiated `main` symbol (see
-    // `instantiate_exported_value`), which the RC-IR back end has already implemented; materialize
-    // that symbol's object here.
-    let main_name = main_expr.get_var().name.clone();
-    let main_obj = gc.get_scoped_obj(&main_name); // A value of type `IO ()`.
-    run_io_or_ios_runner(gc, &main_obj);
-
-    // Return main function.
-    gc.builder()
-        .build_return(Some(&gc.context.i32_type().const_int(0, false)))
-        .unwrap();
+    main_function
 }
@@ -0,0 +1,375 @@
+//! C structs passed by value across the FFI boundary: the layout C gives one, and how the C ABI of
+//! the target carries it in a call.
+//!
+//! A program describes a C struct as an `unbox struct` whose fields are C scalars or such structs,
+//! and names it in the signature of an `FFI_CALL`. Fix lays a value of it down as C lays down the
+//! structure with the same fields in the same order — each field at the next offset its alignment
+//! allows, the whole padded to a multiple of the largest alignment — and `c_struct_layout` is that
+//! layout, computed from the declaration alone.
+//!
+//! The layout is not what a call passes, though. LLVM hands an aggregate argument to the backend
+//! element by element, and the C ABI does not: System V AMD64 classifies each eightbyte of a
+//! structure and passes a structure larger than two of them in memory, and AAPCS64 passes a
+//! homogeneous floating-point aggregate in consecutive floating-point registers and any other
+//! structure as up to two integer registers, or behind a pointer beyond that. A C compiler lowers
+//! the structure to the values the ABI names before it reaches LLVM, and `CStructPassing` is that
+//! lowering, which `CSignature` applies at both the declaration and the call.
+
+use crate::ast::program::TypeEnv;
+use crate::ast::types::{TyCon, TyConVariant};
+use crate::ffi::CTypeShape;
+
+/// The layout C gives a struct.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CStructLayout {
+    /// The scalars the struct holds, with those of a nested struct in its place, each with its
+    /// offset in bytes from the start of the outermost struct.
+    pub scalars: Vec<(u64, CTypeShape)>,
+    /// The size in bytes, trailing padding included.
+    pub size: u64,
+    /// The alignment in bytes: that of the most aligned scalar.
+    pub align: u64,
+}
+
+impl CTypeShape {
+    /// The size in bytes of a C value of this shape, which is also its alignment on the targets Fix
+    /// builds for.
+    pub fn size(self) -> u64 {
+        match self {
+            CTypeShape::Integer { bits, .. } => bits as u64 / 8,
+            CTypeShape::Float32 => 4,
+            CTypeShape::Float64 => 8,
+            CTypeShape::Pointer => 8,
+        }
+    }
+}
+
+/// The sentence every rejection of a struct ends with, saying what a struct passed by value is.
+const C_STRUCT_REQUIREMENT: &str = "A struct passed to C by value is an `unbox struct` without type parameters, whose fields are C scalars (`I8` to `U64`, `F32`, `F64`, `Ptr`, and their aliases in `Std::FFI` such as `CInt`) or such structs.";
+
+/// The layout C gives the struct `tc`, or why `tc` is not a struct C can hold.
+pub fn c_struct_layout(tc: &TyCon, type_env: &TypeEnv) -> Result<CStructLayout, String> {
+    layout_of(tc, type_env, &mut vec![])
+}
+
+/// `c_struct_layout` of `tc`, reached as a field of each of `enclosing`, innermost last.
+fn layout_of(
+    tc: &TyCon,
+    type_env: &TypeEnv,
+    enclosing: &mut Vec<TyCon>,
+) -> Result<CStructLayout, String> {
+    let name = tc.to_string();
+    if enclosing.contains(tc) {
+        return Err(format!(
+            "`{}` contains itself, so C cannot hold it. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    }
+    let Some(ti) = type_env.tycons().get(tc) else {
+        return Err(format!(
+            "`{}` is not a struct. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    };
+    if ti.variant != TyConVariant::Struct {
+        return Err(format!(
+            "`{}` is not a struct. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    }
+    if !ti.is_unbox {
+        return Err(format!(
+            "`{}` is a boxed struct, which crosses to C as a pointer. Pass it as `Ptr`, or declare it `unbox`. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    }
+    if !ti.tyvars.is_empty() {
+        return Err(format!(
+            "`{}` takes type parameters. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    }
+    // C has no empty struct.
+    if ti.fields.is_empty() {
+        return Err(format!(
+            "`{}` has no fields. {}",
+            name, C_STRUCT_REQUIREMENT
+        ));
+    }
+
+    enclosing.push(tc.clone());
+    let mut layout = CStructLayout {
+        scalars: vec![],
+        size: 0,
+        align: 1,
+    };
+    for field in &ti.fields {
+        let field_layout = if !field.ty.is_tycon() {
+            None
+        } else if field.ty.as_tycon().is_c_scalar() {
+            let shape = field.ty.as_tycon().c_type_shape().unwrap();
+            Some(CStructLayout {
+                scalars: vec![(0, shape)],
+                size: shape.size(),
+                align: shape.size(),
+            })
+        } else if (type_env.tycons().get(field.ty.as_tycon()))
+            .is_some_and(|ti| ti.variant == TyConVariant::Struct)
+        {
+            Some(layout_of(field.ty.as_tycon(), type_env, enclosing)?)
+        } else {
+            None
+        };
+        let Some(field_layout) = field_layout else {
+            enclosing.pop();
+            return Err(format!(
+                "The field `{}` of `{}` has type `{}`, which C cannot hold in a struct. {}",
+                field.name,
+                name,
+                field.ty.to_string(),
+                C_STRUCT_REQUIREMENT
+            ));
+        };
+        let offset = layout.size.next_multiple_of(field_layout.align);
+        layout.scalars.extend(
+            (field_layout.scalars.iter())
+                .map(|(scalar_offset, shape)| (offset + scalar_offset, *shape)),
+        );
+        layout.size = offset + field_layout.size;
+        layout.align = layout.align.max(field_layout.align);
+    }
+    layout.size = layout.size.next_multiple_of(layout.align);
+    enclosing.pop();
+    Ok(layout)
+}
+
+/// The C ABI a target passes structs by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CStructAbi {
+    /// The System V AMD64 ABI, of x86-64 Linux and macOS.
+    SysVAmd64,
+    /// AAPCS64, of AArch64 Linux, and Apple's variant of it, which agrees on structs.
+    Aapcs64,
+}
+
+impl CStructAbi {
+    /// The ABI of the target `triple`, and `None` for one whose rules for structs Fix does not
+    /// implement. x86-64 Windows is among those: it passes a structure of 1, 2, 4 or 8 bytes as an
+    /// integer and any other behind a pointer, whatever its fields.
+    pub fn of_target(triple: &str) -> Option<CStructAbi> {
+        let arch = triple.split('-').next().unwrap();
+        match arch {
+            "x86_64" if !triple.contains("windows") => Some(CStructAbi::SysVAmd64),
+            "aarch64" | "arm64" => Some(CStructAbi::Aapcs64),
+            _ => None,
+        }
+    }
+}
+
+/// A register a struct passed in registers occupies, by what it holds of the struct.
+///
+/// System V AMD64 gives each eightbyte of the struct its own register, so a struct is one or two of
+/// these, covering bytes 0 to 7 and 8 to 15. AAPCS64 passes the whole struct as one block of
+/// consecutive registers, so a struct is one piece.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CRegisterPiece {
+    /// A general-purpose register holding the low `bits` bits of an eightbyte.
+    Integer { bits: u32 },
+    /// A floating-point register holding one `float`.
+    Float,
+    /// A floating-point register holding two `float`s, as the vector `<2 x float>`.
+    FloatPair,
+    /// A floating-point register holding one `double`.
+    Double,
+    /// `count` general-purpose registers, as `[count x i64]`.
+    IntegerArray { count: u32 },
+    /// `count` floating-point registers holding one `float` each, as `[count x float]`.
+    FloatArray { count: u32 },
+    /// `count` floating-point registers holding one `double` each, as `[count x double]`.
+    DoubleArray { count: u32 },
+}
+
+impl CRegisterPiece {
+    /// Whether this piece takes floating-point registers rather than general-purpose ones.
+    fn is_float(self) -> bool {
+        !matches!(
+            self,
+            CRegisterPiece::Integer { .. } | CRegisterPiece::IntegerArray { .. }
+        )
+    }
+}
+
+/// How a call carries a struct, as an argument or as the result.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CStructPassing {
+    /// In registers, as these pieces in order. An argument is one LLVM parameter per piece, and a
+    /// result is one piece, or a literal struct of several.
+    Direct(Vec<CRegisterPiece>),
+    /// An argument in memory: the caller copies the struct onto the stack, which the LLVM parameter
+    /// says with `byval`.
+    ByValue,
+    /// An argument behind a pointer to a copy the caller makes.
+    ByReference,
+    /// A result the callee writes through a pointer the caller passes ahead of the arguments, which
+    /// the LLVM parameter says with `sret`.
+    ThroughOutPointer,
+}
+
+/// One position of a C signature, as far as passing a struct is concerned.
+pub enum CPosition<'a> {
+    /// A scalar, which takes a register of its class where one is left.
+    Scalar(CTypeShape),
+    /// A struct of this layout.
+    Struct(&'a CStructLayout),
+}
+
+/// How a call carries the result `ret`, where it is a struct, and each parameter of `params` that
+/// is a struct, in the order of `params`.
+///
+/// The positions are classified together because System V AMD64 passes a struct in registers only
+/// while enough of them are left, counting those the parameters before it take, and the pointer to
+/// a result returned in memory takes the first.
+pub fn pass_structs(
+    abi: CStructAbi,
+    ret: Option<&CStructLayout>,
+    params: &[CPosition],
+) -> (Option<CStructPassing>, Vec<Option<CStructPassing>>) {
+    match abi {
+        CStructAbi::SysVAmd64 => pass_structs_sysv(ret, params),
+        CStructAbi::Aapcs64 => {
+            let ret = ret.map(|layout| match aapcs64_registers(layout) {
+                Some(pieces) => CStructPassing::Direct(pieces),
+                None => CStructPassing::ThroughOutPointer,
+            });
+            let params = params
+                .iter()
+                .map(|param| match param {
+                    CPosition::Scalar(_) => None,
+                    CPosition::Struct(layout) => Some(match aapcs64_registers(layout) {
+                        Some(pieces) => CStructPassing::Direct(pieces),
+                        None => CStructPassing::ByReference,
+                    }),
+                })
+                .collect();
+            (ret, params)
+        }
+    }
+}
+
+/// The general-purpose and the floating-point registers System V AMD64 passes arguments in.
+const SYSV_INTEGER_ARGUMENT_REGISTERS: usize = 6;
+const SYSV_SSE_ARGUMENT_REGISTERS: usize = 8;
+
+/// `pass_structs` on System V AMD64.
+fn pass_structs_sysv(
+    ret: Option<&CStructLayout>,
+    params: &[CPosition],
+) -> (Option<CStructPassing>, Vec<Option<CStructPassing>>) {
+    let ret = ret.map(|layout| match sysv_eightbytes(layout) {
+        Some(pieces) => CStructPassing::Direct(pieces),
+        None => CStructPassing::ThroughOutPointer,
+    });
+    let mut free_integer = SYSV_INTEGER_ARGUMENT_REGISTERS;
+    if ret == Some(CStructPassing::ThroughOutPointer) {
+        free_integer -= 1;
+    }
+    let mut free_sse = SYSV_SSE_ARGUMENT_REGISTERS;
+    let params = params
+        .iter()
+        .map(|param| match param {
+            CPosition::Scalar(shape) => {
+                match shape {
+                    CTypeShape::Float32 | CTypeShape::Float64 => {
+                        free_sse = free_sse.saturating_sub(1)
+                    }
+                    CTypeShape::Integer { .. } | CTypeShape::Pointer => {
+                        free_integer = free_integer.saturating_sub(1)
+                    }
+                }
+                None
+            }
+            CPosition::Struct(layout) => {
+                let Some(pieces) = sysv_eightbytes(layout) else {
+                    return Some(CStructPassing::ByValue);
+                };
+                // A struct goes in registers whole or not at all.
+                let sse = pieces.iter().filter(|piece| piece.is_float()).count();
+                let integer = pieces.len() - sse;
+                if integer > free_integer || sse > free_sse {
+                    return Some(CStructPassing::ByValue);
+                }
+                free_integer -= integer;
+                free_sse -= sse;
+                Some(CStructPassing::Direct(pieces))
+            }
+        })
+        .collect();
+    (ret, params)
+}
+
+/// The registers System V AMD64 passes a struct of `layout` in, and `None` for one it passes in
+/// memory: one larger than two eightbytes.
+///
+/// An eightbyte holding an integer or a pointer goes in a general-purpose register, and one holding
+/// floating point numbers alone in a floating-point register.
+fn sysv_eightbytes(layout: &CStructLayout) -> Option<Vec<CRegisterPiece>> {
+    if layout.size > 16 {
+        return None;
+    }
+    let count = layout.size.div_ceil(8);
+    let pieces = (0..count)
+        .map(|i| {
+            let (begin, end) = (i * 8, (i * 8 + 8).min(layout.size));
+            let scalars = (layout.scalars.iter())
+                .filter(|(offset, _)| begin <= *offset && *offset < end)
+                .map(|(offset, shape)| (offset - begin, *shape))
+                .collect::<Vec<_>>();
+            let is_sse = !scalars.is_empty()
+                && (scalars.iter())
+                    .all(|(_, shape)| matches!(shape, CTypeShape::Float32 | CTypeShape::Float64));
+            if !is_sse {
+                CRegisterPiece::Integer {
+                    bits: ((end - begin) * 8) as u32,
+                }
+            } else if scalars
+                .iter()
+                .any(|(_, shape)| *shape == CTypeShape::Float64)
+            {
+                CRegisterPiece::Double
+            } else if scalars.iter().any(|(offset, _)| *offset == 4) {
+                CRegisterPiece::FloatPair
+            } else {
+                CRegisterPiece::Float
+            }
+        })
+        .collect();
+    Some(pieces)
+}
+
+/// The registers AAPCS64 passes a struct of `layout` in, and `None` for one it passes behind a
+/// pointer: one larger than 16 bytes that is not a homogeneous floating-point aggregate.
+///
+/// A homogeneous floating-point aggregate — one to four floating point numbers of one width — goes
+/// one number to a floating-point register, and any other struct of up to 16 bytes in one or two
+/// general-purpose registers.
+fn aapcs64_registers(layout: &CStructLayout) -> Option<Vec<CRegisterPiece>> {
+    let count = layout.scalars.len() as u32;
+    let (_, first) = layout.scalars[0];
+    let is_homogeneous = (1..=4).contains(&count)
+        && matches!(first, CTypeShape::Float32 | CTypeShape::Float64)
+        && layout.scalars.iter().all(|(_, shape)| *shape == first);
+    if is_homogeneous {
+        return Some(vec![if first == CTypeShape::Float32 {
+            CRegisterPiece::FloatArray { count }
+        } else {
+            CRegisterPiece::DoubleArray { count }
+        }]);
+    }
+    if layout.size > 16 {
+        return None;
+    }
+    if layout.size <= 8 {
+        return Some(vec![CRegisterPiece::Integer { bits: 64 }]);
+    }
+    Some(vec![CRegisterPiece::IntegerArray { count: 2 }])
+}
@@ -0,0 +1,371 @@
+//! `fix bench`: measures the benchmarks of the project, and compares the times with a baseline.
+//!
+//! The benchmarks are the values named `bench_*` the source files of the `[build.bench]` section
+//! define. They are built into a program the way `fix test` builds the tests, at the highest
+//! optimization level, and the runtime (`fixruntime_run_benches`) runs each one over and over in a
+//! process of its own: first for a warm-up time, and then for a number of samples, each timing as
+//! many runs as fill its share of the measurement time. It writes the seconds one run took in each
+//! sample to a file, from which the statistics of each benchmark are computed here and compared with
+//! the ones saved as the baseline.
+
+use crate::commands::run::run;
+use crate::configuration::{BenchConfig, Configuration, SubCommand};
+use crate::constants::RUN_PATH;
+use crate::error::{panic_if_err, Errors};
+use crate::misc::{info_msg, warn_msg};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+/// The environment variable giving the benchmark program the seconds each benchmark is warmed up
+/// for.
+const WARM_UP_TIME_VAR: &str = "FIX_BENCH_WARM_UP_TIME";
+/// The environment variable giving the benchmark program the seconds each benchmark is measured
+/// for.
+const MEASUREMENT_TIME_VAR: &str = "FIX_BENCH_MEASUREMENT_TIME";
+/// The environment variable giving the benchmark program the number of samples it takes of each
+/// benchmark.
+const SAMPLES_VAR: &str = "FIX_BENCH_SAMPLES";
+/// The environment variable naming the file the benchmark program writes the times it measured to.
+const RESULTS_VAR: &str = "FIX_BENCH_RESULTS";
+
+/// How far the median time of a benchmark may move from the one of the baseline, as a fraction of
+/// it, and still be taken for noise rather than for a regression or an improvement.
+const NOISE_THRESHOLD: f64 = 0.05;
+
+/// What the samples of one benchmark came to, as the baseline saves it. Every time is in seconds
+/// and is the time one run of the benchmark took.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BenchStats {
+    /// The median of the samples, which the comparison with the baseline is made by, since a run
+    /// disturbed by the rest of the machine moves it the least.
+    pub median: f64,
+    /// The mean of the samples.
+    pub mean: f64,
+    /// The standard deviation of the samples.
+    pub std_dev: f64,
+    /// The fastest sample.
+    pub min: f64,
+    /// The slowest sample.
+    pub max: f64,
+    /// The number of samples.
+    pub samples: usize,
+    /// The number of runs each sample timed.
+    pub runs_per_sample: u64,
+}
+
+impl BenchStats {
+    /// The statistics of `samples`, the seconds one run took in each sample. There is at least one
+    /// sample.
+    fn from_samples(mut samples: Vec<f64>, runs_per_sample: u64) -> Self {
+        assert!(!samples.is_empty(), "a measured benchmark has a sample");
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2.0
+        };
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        BenchStats {
+            median,
+            mean,
+            std_dev: variance.sqrt(),
+            min: samples[0],
+            max: samples[n - 1],
+            samples: n,
+            runs_per_sample,
+        }
+    }
+}
+
+/// The baseline file: the statistics of each benchmark as a run of `fix bench` saved them, by the
+/// name of the benchmark.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Baseline {
+    /// The version of the compiler the benchmarks were built with when they were last saved.
+    pub fix_version: String,
+    /// The statistics of each benchmark, by its name.
+    pub benchmarks: BTreeMap<String, BenchStats>,
+}
+
+/// What the benchmark program wrote about one benchmark.
+enum BenchResult {
+    /// The benchmark was measured, with these statistics.
+    Measured(String, BenchStats),
+    /// The benchmark failed, and the program gave this outcome.
+    Failed(String, String),
+}
+
+/// Parses what the benchmark program wrote to its results file: a line for each benchmark, of
+/// fields separated by tabs, which is "ok", the name, the number of runs a sample timed and the
+/// seconds of each sample for a measured benchmark, and "failed", the name and the outcome for a
+/// failed one.
+fn parse_results(content: &str) -> Result<Vec<BenchResult>, Errors> {
+    let malformed = |line: &str| {
+        Errors::from_msg(format!(
+            "The benchmark program wrote a malformed result: \"{}\".",
+            line
+        ))
+    };
+    let mut results = vec![];
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        match fields.as_slice() {
+            ["ok", name, runs_per_sample, samples @ ..] if !samples.is_empty() => {
+                let runs_per_sample = runs_per_sample.parse().map_err(|_| malformed(line))?;
+                let samples = samples
+                    .iter()
+                    .map(|sample| sample.parse::<f64>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| malformed(line))?;
+                results.push(BenchResult::Measured(
+                    name.to_string(),
+                    BenchStats::from_samples(samples, runs_per_sample),
+                ));
+            }
+            ["failed", name, outcome] => {
+                results.push(BenchResult::Failed(name.to_string(), outcome.to_string()))
+            }
+            _ => return Err(malformed(line)),
+        }
+    }
+    Ok(results)
+}
+
+/// A time in seconds, written in the unit that puts it between 1 and 1000.
+fn format_time(seconds: f64) -> String {
+    let (value, unit) = if seconds >= 1.0 {
+        (seconds, "s")
+    } else if seconds >= 1e-3 {
+        (seconds * 1e3, "ms")
+    } else if seconds >= 1e-6 {
+        (seconds * 1e6, "µs")
+    } else {
+        (seconds * 1e9, "ns")
+    };
+    format!("{:.3} {}", value, unit)
+}
+
+/// Reads the baseline saved at `path`, or `None` when nothing is saved there.
+fn read_baseline(path: &PathBuf) -> Result<Option<Baseline>, Errors> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to read the baseline \"{}\": {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let baseline = serde_json::from_str(&content).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to parse the baseline \"{}\": {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(Some(baseline))
+}
+
+/// Saves `baseline` at `path`, creating the directories leading to it.
+fn write_baseline(path: &PathBuf, baseline: &Baseline) -> Result<(), Errors> {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(baseline)? + "\n")
+    };
+    write().map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to write the baseline \"{}\": {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Prints the statistics of each benchmark, against the ones of `baseline` where it has them, and
+/// a summary.
+fn report(results: &[BenchResult], baseline: Option<&Baseline>) {
+    let (mut measured, mut failed, mut regressed, mut improved) = (0, 0, 0, 0);
+    println!();
+    for result in results {
+        match result {
+            BenchResult::Measured(name, stats) => {
+                measured += 1;
+                println!("{}", name);
+                println!(
+                    "    time:   {} per run (median); mean {} ± {}, from {} to {}; {} samples of {} runs",
+                    format_time(stats.median),
+                    format_time(stats.mean),
+                    format_time(stats.std_dev),
+                    format_time(stats.min),
+                    format_time(stats.max),
+                    stats.samples,
+                    stats.runs_per_sample
+                );
+                let Some(old) = baseline.and_then(|baseline| baseline.benchmarks.get(name)) else {
+                    continue;
+                };
+                let change = (stats.median - old.median) / old.median;
+                let verdict = if change > NOISE_THRESHOLD {
+                    regressed += 1;
+                    "regressed"
+                } else if change < -NOISE_THRESHOLD {
+                    improved += 1;
+                    "improved"
+                } else {
+                    "no change"
+                };
+                println!(
+                    "    change: {:+.1}% against the baseline's {} ({})",
+                    change * 100.0,
+                    format_time(old.median),
+                    verdict
+                );
+            }
+            BenchResult::Failed(name, outcome) => {
+                failed += 1;
+                println!("{}", name);
+                println!("    {}", outcome);
+            }
+        }
+    }
+    println!(
+        "\nbench result: {}. {} measured; {} failed; {} regressed; {} improved",
+        if failed > 0 { "FAILED" } else { "ok" },
+        measured,
+        failed,
+        regressed,
+        improved
+    );
+}
+
+/// Builds the benchmarks, measures them, reports the times against the baseline, and saves them as
+/// the baseline when the settings ask for it or no baseline is saved yet. Benchmarks that were not
+/// run keep the times the baseline has for them.
+///
+/// # Returns
+///
+/// Whether every benchmark run was measured; a benchmark that ended its process is reported as
+/// failed.
+pub fn bench(mut config: Configuration) -> Result<bool, Errors> {
+    let bench_config: BenchConfig = match &config.subcommand {
+        SubCommand::Bench(bench_config) => bench_config.clone(),
+        subcommand => unreachable!(
+            "`fix bench` was run under the configuration of `fix {}`",
+            subcommand.command_type_string()
+        ),
+    };
+
+    // The benchmark program appends to the results file, which starts empty. The name is random,
+    // so that runs in parallel keep to files of their own.
+    fs::create_dir_all(RUN_PATH)
+        .map_err(|e| Errors::from_msg(format!("Failed to create \"{}\": {}", RUN_PATH, e)))?;
+    let results_path = PathBuf::from(format!(
+        "{}/bench{}.txt",
+        RUN_PATH,
+        rand::thread_rng().gen::<u64>()
+    ));
+    fs::write(&results_path, "").map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to create \"{}\": {}",
+            results_path.display(),
+            e
+        ))
+    })?;
+    config.run_program_env.extend([
+        (
+            WARM_UP_TIME_VAR.to_string(),
+            bench_config.warm_up_time.to_string(),
+        ),
+        (
+            MEASUREMENT_TIME_VAR.to_string(),
+            bench_config.measurement_time.to_string(),
+        ),
+        (SAMPLES_VAR.to_string(), bench_config.samples.to_string()),
+        (
+            RESULTS_VAR.to_string(),
+            results_path.to_string_lossy().to_string(),
+        ),
+    ]);
+
+    let output = run(config, true);
+    let content = fs::read_to_string(&results_path);
+    let _ = fs::remove_file(&results_path); // Ignore the error.
+    let output =
+        output?.map_err(|e| Errors::from_msg(format!("Failed to run the benchmarks: {}", e)))?;
+    let content = content.map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to read \"{}\": {}",
+            results_path.display(),
+            e
+        ))
+    })?;
+    let results = parse_results(&content)?;
+    if results.is_empty() && !output.status.success() {
+        return Err(Errors::from_msg(format!(
+            "The benchmark program ended with {} before it measured any benchmark.",
+            output.status
+        )));
+    }
+
+    // Compare with the baseline.
+    let baseline = read_baseline(&bench_config.baseline_path)?;
+    if let Some(baseline) = &baseline {
+        if baseline.fix_version != env!("CARGO_PKG_VERSION") {
+            warn_msg(&format!(
+                "The baseline was saved by Fix {}, and the benchmarks are now built by Fix {}.",
+                baseline.fix_version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+    }
+    report(&results, baseline.as_ref());
+
+    // Save the baseline.
+    if bench_config.save_baseline || baseline.is_none() {
+        let mut baseline = baseline.unwrap_or_default();
+        baseline.fix_version = env!("CARGO_PKG_VERSION").to_string();
+        for result in &results {
+            if let BenchResult::Measured(name, stats) = result {
+                baseline.benchmarks.insert(name.clone(), stats.clone());
+            }
+        }
+        write_baseline(&bench_config.baseline_path, &baseline)?;
+        info_msg(&format!(
+            "Saved the times as the baseline \"{}\".",
+            bench_config.baseline_path.display()
+        ));
+    }
+
+    Ok(results
+        .iter()
+        .all(|result| matches!(result, BenchResult::Measured(..))))
+}
+
+/// Runs `bench`, and exits the `fix` process with status 0 when every benchmark was measured and 1
+/// otherwise.
+pub fn bench_command(config: Configuration) {
+    let measured = panic_if_err(bench(config));
+    process::exit(if measured { 0 } else { 1 });
+}
+
+/// Runs `bench` for one run of `fix bench --watch`, reporting a failed benchmark as an error.
+pub fn bench_watched(config: &Configuration) -> Result<(), Errors> {
+    if bench(config.clone())? {
+        Ok(())
+    } else {
+        Err(Errors::from_msg("A benchmark failed.".to_string()))
+    }
+}
@@ -6,15 +6,20 @@ use crate::misc::info_msg;
 use std::path::PathBuf;
 
 pub fn check(mut config: Configuration) -> Result<(), Errors> {
-    info_msg("Checking...");
+    configure_check(&mut config)?;
+    check_configured(config)
+}
 
+/// Sets `config` up for checking the project in the working directory: by its project file, with
+/// its dependencies installed, and with every source file as a target of the diagnostics.
+pub fn configure_check(config: &mut Configuration) -> Result<(), Errors> {
     // Set up the configuration by the project file.
     let proj_file = ProjectFile::read_root_file()?;
-    proj_file.set_config(&mut config)?;
+    proj_file.set_config(config)?;
 
     // Automatically generate/update lock file and install dependencies.
     // Use Test mode to include test dependencies.
-    proj_file.install_dependencies(&mut config, BuildConfigType::Test)?;
+    proj_file.install_dependencies(config, BuildConfigType::Test)?;
 
     // Set all source files as diagnostics target files.
     let source_files: Vec<PathBuf> = config.source_files().cloned().collect();
@@ -24,13 +29,19 @@ pub fn check(mut config: Configuration) -> Result<(), Errors> {
         }
         _ => unreachable!(),
     }
+    Ok(())
+}
+
+/// Checks the project under a `config` that `configure_check` has set up.
+pub fn check_configured(config: Configuration) -> Result<(), Errors> {
+    info_msg("Checking...");
 
     // Elaborate (parse, resolve, type-check) all entities.
     let mut program = elaborate_via_config(&config)?;
 
     // Surface warnings (e.g. uses of `DEPRECATED` items) to the terminal,
     // even when compilation is otherwise successful.
-    program.flush_warnings_to_stderr();
+    program.flush_warnings(config.message_format);
 
     // Check for deferred errors (parse errors and type errors accumulated during diagnostics).
     if program.deferred_errors.has_error() {
@@ -1,8 +1,46 @@
-use crate::constants::DOT_FIXLANG;
-use std::fs::remove_dir_all;
+use crate::constants::{BENCH_BASELINE_PATH, DOT_FIXLANG, TYPE_CHECK_CACHE_PATH};
+use crate::elaboration::typecheckcache::FileCache;
+use std::fs::{read_dir, remove_dir_all, remove_file};
+use std::path::Path;
 
 // A function implementing `fix clean` command.
-pub fn clean_command() {
-    // Delete `.fixlang` directory.
-    let _ = remove_dir_all(DOT_FIXLANG);
+//
+// Everything under `.fixlang` is removed but the type-check cache, whose entries spare the next
+// build, check and language-server session from checking the unchanged sources again, and which is
+// garbage-collected instead, and the baseline of `fix bench`, whose times no build can give back.
+// With `all`, these go as well.
+pub fn clean_command(all: bool) {
+    if all {
+        // Delete `.fixlang` directory.
+        let _ = remove_dir_all(DOT_FIXLANG);
+        return;
+    }
+    remove_all_but(
+        Path::new(DOT_FIXLANG),
+        &[
+            Path::new(TYPE_CHECK_CACHE_PATH),
+            Path::new(BENCH_BASELINE_PATH),
+        ],
+    );
+    FileCache::new().collect_garbage();
+}
+
+// Removes everything under `dir` except the paths of `kept` and the directories leading to them.
+fn remove_all_but(dir: &Path, kept: &[&Path]) {
+    let Ok(entries) = read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if kept.contains(&path.as_path()) {
+            continue;
+        }
+        if kept.iter().any(|kept| kept.starts_with(&path)) {
+            remove_all_but(&path, kept);
+        } else if path.is_dir() {
+            let _ = remove_dir_all(&path);
+        } else {
+            let _ = remove_file(&path);
+        }
+    }
 }
@@ -2,8 +2,9 @@ use crate::configuration::BuildConfigType;
 use crate::dependency::lockfile::{DependecyLockFile, LockFileType};
 use crate::error::{panic_if_err, Errors};
 use crate::metafiles::config_file::ConfigFile;
-use crate::metafiles::project_file::ProjectFile;
+use crate::metafiles::project_file::{ProjectFile, ProjectFileDependency};
 use clap::ArgMatches;
+use serde::Serialize;
 
 fn get_build_mode(args: &ArgMatches) -> BuildConfigType {
     if args.contains_id("test") {
@@ -83,3 +84,255 @@ fn print_all_projects(fix_config: &ConfigFile, json: bool) -> Result<(), Errors>
 
     Ok(())
 }
+
+pub fn deps_tree_command(args: &ArgMatches) {
+    let mode = get_build_mode(args);
+    let json = args.contains_id("json");
+    panic_if_err(print_dependency_tree(mode, json));
+}
+
+pub fn deps_why_command(args: &ArgMatches) {
+    let name = args
+        .get_one::<String>("project")
+        .expect("the project of `fix deps why` is a required argument");
+    let json = args.contains_id("json");
+    panic_if_err(print_dependency_paths(name, json));
+}
+
+/// A project of the resolved dependency graph: the root project, or an entry of its lock file.
+#[derive(Serialize)]
+struct GraphProject {
+    name: String,
+    version: String,
+    /// "root" for the root project, "path" for a dependency on a directory, and "git" for one
+    /// cloned from a repository.
+    source: &'static str,
+    /// The directory the project sits in, or is installed at for a git dependency.
+    path: String,
+    /// The repository of a git dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+    /// The commit of the repository a git dependency is checked out at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rev: Option<String>,
+    dependencies: Vec<GraphEdge>,
+}
+
+/// A dependency of a project on another of the graph.
+#[derive(Serialize, Clone)]
+struct GraphEdge {
+    name: String,
+    /// Whether the dependency is a `test_dependencies` entry, which only the test build has.
+    test: bool,
+}
+
+/// The dependency graph the lock file of `mode` resolves, the root project first. Only the lock
+/// file and the project files of the installed dependencies are read; a lock file that is missing
+/// or out of date, or a dependency that is not installed, fails with a hint to install them.
+fn dependency_graph(mode: BuildConfigType) -> Result<Vec<GraphProject>, Errors> {
+    let install_command = match mode {
+        BuildConfigType::Test => "fix deps install --test",
+        BuildConfigType::Build => "fix deps install",
+    };
+    let proj_file = ProjectFile::read_root_file()?;
+    let lock_file = proj_file
+        .open_lock_file(LockFileType::from_build_config_type(mode))
+        .map_err(|_| {
+            Errors::from_msg(format!(
+                "The lock file is missing or out of date. Run `{}` first.",
+                install_command
+            ))
+        })?;
+
+    let edges = |deps: &[ProjectFileDependency], test: bool| {
+        deps.iter()
+            .map(|dep| GraphEdge {
+                name: dep.name.clone(),
+                test,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut root_deps = edges(&proj_file.dependencies, false);
+    if mode == BuildConfigType::Test {
+        root_deps.extend(edges(&proj_file.test_dependencies, true));
+    }
+    let mut projects = vec![GraphProject {
+        name: proj_file.general.name.clone(),
+        version: proj_file.general.version.clone(),
+        source: "root",
+        path: ".".to_string(),
+        repo: None,
+        rev: None,
+        dependencies: root_deps,
+    }];
+    for entry in &lock_file.dependencies {
+        // A dependency brings its own `dependencies` entries into the build, and never its
+        // `test_dependencies` ones.
+        let dep_proj_file = entry.project_file().map_err(|_| {
+            Errors::from_msg(format!(
+                "The dependency \"{}\" is not installed at \"{}\". Run `{}` first.",
+                entry.name(),
+                entry.path().to_string_lossy(),
+                install_command
+            ))
+        })?;
+        projects.push(GraphProject {
+            name: entry.name().to_string(),
+            version: entry.version().to_string(),
+            source: if entry.git().is_some() { "git" } else { "path" },
+            path: entry.path().to_string_lossy().to_string(),
+            repo: entry.git().map(|(repo, _)| repo.to_string()),
+            rev: entry.git().map(|(_, rev)| rev.to_string()),
+            dependencies: edges(&dep_proj_file.dependencies, false),
+        });
+    }
+
+    // Check that every dependency is resolved.
+    for project in &projects {
+        for dep in &project.dependencies {
+            if !projects.iter().any(|p| p.name == dep.name) {
+                return Err(Errors::from_msg(format!(
+                    "The lock file has no entry for \"{}\", which \"{}\" depends on. Try to run `fix deps update`.",
+                    dep.name, project.name
+                )));
+            }
+        }
+    }
+    Ok(projects)
+}
+
+/// The project `name` of `projects`, which `dependency_graph` has checked to be there.
+fn find_project<'a>(projects: &'a [GraphProject], name: &str) -> &'a GraphProject {
+    projects
+        .iter()
+        .find(|p| p.name == name)
+        .expect("every dependency of the graph is one of its projects")
+}
+
+/// The line `fix deps tree` writes for `project`: its name and version, and where it comes from.
+fn describe_project(project: &GraphProject) -> String {
+    let origin = match (&project.repo, &project.rev) {
+        (Some(repo), Some(rev)) => {
+            let short_rev = rev.chars().take(7).collect::<String>();
+            format!("git {} @ {}", repo, short_rev)
+        }
+        _ if project.source == "root" => "root".to_string(),
+        _ => format!("path {}", project.path),
+    };
+    format!("{}@{} ({})", project.name, project.version, origin)
+}
+
+fn print_dependency_tree(mode: BuildConfigType, json: bool) -> Result<(), Errors> {
+    let projects = dependency_graph(mode)?;
+    if json {
+        let json =
+            serde_json::to_string_pretty(&projects).map_err(|e| Errors::from_msg(e.to_string()))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    // A project whose dependencies were printed already is marked with `(*)` and not expanded
+    // again, as Cargo does.
+    fn print_subtree(
+        projects: &[GraphProject],
+        project: &GraphProject,
+        prefix: &str,
+        expanded: &mut Vec<String>,
+    ) {
+        if expanded.contains(&project.name) {
+            return;
+        }
+        expanded.push(project.name.clone());
+        for (i, edge) in project.dependencies.iter().enumerate() {
+            let last = i + 1 == project.dependencies.len();
+            let dep = find_project(projects, &edge.name);
+            let repeated = expanded.contains(&dep.name) && !dep.dependencies.is_empty();
+            println!(
+                "{}{}{}{}{}",
+                prefix,
+                if last { "└── " } else { "├── " },
+                describe_project(dep),
+                if edge.test { " [test]" } else { "" },
+                if repeated { " (*)" } else { "" }
+            );
+            let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            print_subtree(projects, dep, &child_prefix, expanded);
+        }
+    }
+    println!("{}", describe_project(&projects[0]));
+    print_subtree(&projects, &projects[0], "", &mut vec![]);
+    Ok(())
+}
+
+/// A step of a path from the root project to a dependency.
+#[derive(Serialize, Clone)]
+struct PathStep {
+    name: String,
+    version: String,
+    /// Whether the step is taken through a `test_dependencies` entry.
+    test: bool,
+}
+
+fn print_dependency_paths(name: &str, json: bool) -> Result<(), Errors> {
+    // Test-only dependencies are in the graph the test build resolves.
+    let projects = dependency_graph(BuildConfigType::Test)?;
+    let root = &projects[0];
+    if !projects.iter().any(|p| p.name == name) {
+        return Err(Errors::from_msg(format!(
+            "\"{}\" is not a dependency of \"{}\".",
+            name, root.name
+        )));
+    }
+
+    // Every path from the root to `name`, visiting no project twice.
+    fn collect_paths(
+        projects: &[GraphProject],
+        path: &mut Vec<PathStep>,
+        target: &str,
+        paths: &mut Vec<Vec<PathStep>>,
+    ) {
+        let current = find_project(projects, &path.last().unwrap().name);
+        if current.name == target {
+            paths.push(path.clone());
+            return;
+        }
+        for edge in &current.dependencies {
+            if path.iter().any(|step| step.name == edge.name) {
+                continue;
+            }
+            let dep = find_project(projects, &edge.name);
+            path.push(PathStep {
+                name: dep.name.clone(),
+                version: dep.version.clone(),
+                test: edge.test,
+            });
+            collect_paths(projects, path, target, paths);
+            path.pop();
+        }
+    }
+    let mut paths = vec![];
+    let mut path = vec![PathStep {
+        name: root.name.clone(),
+        version: root.version.clone(),
+        test: false,
+    }];
+    collect_paths(&projects, &mut path, name, &mut paths);
+
+    if json {
+        let json =
+            serde_json::to_string_pretty(&paths).map_err(|e| Errors::from_msg(e.to_string()))?;
+        println!("{}", json);
+        return Ok(());
+    }
+    for path in &paths {
+        let steps = path
+            .iter()
+            .map(|step| {
+                let test = if step.test { "[test] " } else { "" };
+                format!("{}{}@{}", test, step.name, step.version)
+            })
+            .collect::<Vec<_>>();
+        println!("{}", steps.join(" -> "));
+    }
+    Ok(())
+}
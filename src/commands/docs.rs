@@ -3,11 +3,18 @@ use crate::{
     ast::{
         name::{FullName, Name, NameSpace},
         program::Program,
-        traits::KindSignature,
+        traits::{KindSignature, TraitId, TraitImpl},
         typedecl::Field,
-        types::{kind_star, Kind, TyCon, TyConVariant, TyVar},
+        types::{kind_star, Kind, NameRef, TyCon, TyConVariant, TyVar},
     },
-    configuration::{BuildConfigType, Configuration, DocsConfig, SubCommand},
+    commands::{
+        docs_html,
+        docs_json::{
+            self, JsonAssocType, JsonEntry, JsonField, JsonImpl, JsonSpan, JsonTrait,
+            JsonTraitAlias, JsonTraitMember, JsonTyVar, JsonType, JsonTypeAlias, JsonValue,
+        },
+    },
+    configuration::{BuildConfigType, Configuration, DocsConfig, DocsFormat, SubCommand},
     constants::{
         STRUCT_ACT_SYMBOL, STRUCT_GETTER_SYMBOL, STRUCT_MODIFIER_SYMBOL, STRUCT_SETTER_SYMBOL,
         UNION_AS_SYMBOL, UNION_IS_SYMBOL, UNION_MOD_SYMBOL,
@@ -40,7 +47,7 @@ pub fn generate_docs_for_files(mut config: Configuration) -> Result<(), Errors>
 
     // Build the file and get the errors.
     let mut program = elaborate_via_config(&config)?;
-    program.flush_warnings_to_stderr();
+    program.flush_warnings(config.message_format);
     info_msg("Generating documentation...");
 
     let docs_config = match &config.subcommand {
@@ -69,6 +76,12 @@ pub fn generate_docs_for_files(mut config: Configuration) -> Result<(), Errors>
         mod_names
     };
 
+    if docs_config.format == DocsFormat::Html {
+        return docs_html::write_html_docs(&program, &mod_names, &proj_file, docs_config);
+    }
+    if docs_config.format == DocsFormat::Json {
+        return docs_json::write_json_docs(&program, &mod_names, &proj_file, docs_config);
+    }
     for mod_name in mod_names {
         info_msg(&format!(
             "Generating documentation for module \"{}\".",
@@ -79,6 +92,65 @@ pub fn generate_docs_for_files(mut config: Configuration) -> Result<(), Errors>
     Ok(())
 }
 
+/// How the documentation of an entry writes the text the generator makes up, the signatures it
+/// takes from the program, and the documentation comments, in the form the documentation is
+/// generated in. The sections of a module are assembled from what these return.
+pub trait DocWriter {
+    /// Text the generator makes up, such as "Values" or the name of an entry.
+    fn text(&self, text: &str) -> String;
+    /// A piece of code, such as a signature, whose names were written by `name`.
+    fn code(&self, code: &str) -> String;
+    /// A type constructor, an associated type or a trait that a piece of code names.
+    fn name(&self, name: NameRef) -> String;
+    /// A paragraph of a documentation comment, written in Markdown.
+    fn paragraph(&self, markdown: &str) -> String;
+    /// The title of a section of a documentation comment, written in Markdown.
+    fn title(&self, markdown: &str) -> String;
+}
+
+/// Writes the documentation in Markdown, as it is written in the source.
+struct MarkdownWriter;
+
+impl DocWriter for MarkdownWriter {
+    fn text(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn code(&self, code: &str) -> String {
+        format!("`{}`", code)
+    }
+
+    fn name(&self, name: NameRef) -> String {
+        name.to_string()
+    }
+
+    fn paragraph(&self, markdown: &str) -> String {
+        markdown.to_string()
+    }
+
+    fn title(&self, markdown: &str) -> String {
+        markdown.to_string()
+    }
+}
+
+/// The sections of a documentation comment, with their titles and paragraphs written by `w`.
+fn docstring_sections(docstring: &str, w: &dyn DocWriter) -> Vec<MarkdownSection> {
+    fn write(section: &mut MarkdownSection, w: &dyn DocWriter) {
+        section.title = w.title(&section.title);
+        for paragraph in section.paragraphs.iter_mut() {
+            *paragraph = w.paragraph(paragraph);
+        }
+        for subsection in section.subsections.iter_mut() {
+            write(subsection, w);
+        }
+    }
+    let mut sections = MarkdownSection::parse_many(docstring.lines().collect());
+    for section in sections.iter_mut() {
+        write(section, w);
+    }
+    sections
+}
+
 /*
 #[m] {title}
 
@@ -96,6 +168,9 @@ pub struct MarkdownSection {
     pub title: String,
     pub paragraphs: Vec<String>,
     pub subsections: Vec<MarkdownSection>,
+    // The identifier links to the section point at, such as `type.Std::Array` (see `anchor`).
+    // Only the HTML documentation writes it.
+    pub anchor: Option<String>,
 }
 
 impl MarkdownSection {
@@ -214,6 +289,7 @@ impl MarkdownSection {
             title,
             paragraphs: vec![],
             subsections: vec![],
+            anchor: None,
         }
     }
 
@@ -288,7 +364,7 @@ fn docgen_for_module(
         )));
     }
 
-    let markdown = write_module(program, mod_name, project, config)?;
+    let markdown = write_module(program, mod_name, project, config, &MarkdownWriter)?;
     let mut markdown_str = String::new();
     markdown.format(0, &mut markdown_str);
 
@@ -319,7 +395,7 @@ fn docgen_for_module(
     Ok(())
 }
 
-fn write_entries(mut entries: Vec<Entry>, doc: &mut MarkdownSection) {
+fn write_entries(mut entries: Vec<Entry>, doc: &mut MarkdownSection, w: &dyn DocWriter) {
     entries.sort();
 
     let mut last_ns = NameSpace::new(vec![]);
@@ -327,7 +403,7 @@ fn write_entries(mut entries: Vec<Entry>, doc: &mut MarkdownSection) {
     for entry in entries {
         if entry.name.namespace != last_ns {
             last_ns = entry.name.namespace.clone();
-            let title = format!("namespace {}", last_ns.to_string());
+            let title = w.text(&format!("namespace {}", last_ns.to_string()));
             subsections.push(MarkdownSection::new(title));
         }
         if let Some(current_section) = subsections.last_mut() {
@@ -340,61 +416,84 @@ fn write_entries(mut entries: Vec<Entry>, doc: &mut MarkdownSection) {
 }
 
 // Add the module name section to the documentation.
-fn write_module(
+pub(crate) fn write_module(
     program: &Program,
     mod_name: &Name,
     project: &ProjectFile,
     config: &DocsConfig,
+    w: &dyn DocWriter,
 ) -> Result<MarkdownSection, Errors> {
     // Add the module name section.
-    let mut doc = MarkdownSection::new(format!("{}", mod_name));
+    let mut doc = MarkdownSection::new(w.text(&format!("{}", mod_name)));
 
     // Add the project name including this module.
     let proj_name = &project.general.name;
     let proj_ver = &project.general.version;
-    doc.add_paragraph(format!("Defined in {}@{}", proj_name, proj_ver));
+    doc.add_paragraph(w.text(&format!("Defined in {}@{}", proj_name, proj_ver)));
 
     if let Some(mod_info) = program.modules.iter().find(|mi| mi.name == *mod_name) {
         let docstring = mod_info.source.get_document().ok().unwrap_or_default();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(&docstring, w));
     }
 
     {
-        let mut section = MarkdownSection::new("Values".to_string());
-        let entries = value_entries(program, mod_name, config)?;
-        write_entries(entries, &mut section);
+        let mut section = MarkdownSection::new(w.text("Values"));
+        let entries = value_entries(program, mod_name, config, w)?;
+        write_entries(entries, &mut section, w);
         doc.add_subsection(section);
     }
 
     {
-        let mut section = MarkdownSection::new("Types and aliases".to_string());
-        let entries = type_entries(program, mod_name, config)?;
-        write_entries(entries, &mut section);
+        let mut section = MarkdownSection::new(w.text("Types and aliases"));
+        let entries = type_entries(program, mod_name, config, w)?;
+        write_entries(entries, &mut section, w);
         doc.add_subsection(section);
     }
 
     {
-        let mut section = MarkdownSection::new("Traits and aliases".to_string());
-        let entries = trait_entries(program, mod_name, config)?;
-        write_entries(entries, &mut section);
+        let mut section = MarkdownSection::new(w.text("Traits and aliases"));
+        let entries = trait_entries(program, mod_name, config, w)?;
+        write_entries(entries, &mut section, w);
         doc.add_subsection(section);
     }
 
     {
-        let mut section = MarkdownSection::new("Trait implementations".to_string());
-        let entries = trait_impl_entries(program, mod_name)?;
-        write_entries(entries, &mut section);
+        let mut section = MarkdownSection::new(w.text("Trait implementations"));
+        let entries = trait_impl_entries(program, mod_name, w)?;
+        write_entries(entries, &mut section, w);
         doc.add_subsection(section);
     }
 
     Ok(doc)
 }
 
+/// The descriptions `fix docs --format json` writes of the entries of the module `mod_name`, each
+/// kind of entry in the order its section of the documentation lists them.
+pub(crate) fn module_json_entries(
+    program: &Program,
+    mod_name: &Name,
+    config: &DocsConfig,
+) -> Result<Vec<JsonEntry>, Errors> {
+    let w = &MarkdownWriter;
+    let mut api = vec![];
+    for mut entries in [
+        value_entries(program, mod_name, config, w)?,
+        type_entries(program, mod_name, config, w)?,
+        trait_entries(program, mod_name, config, w)?,
+        trait_impl_entries(program, mod_name, w)?,
+    ] {
+        entries.sort();
+        api.extend(entries.into_iter().map(|entry| entry.api));
+    }
+    Ok(api)
+}
+
 struct Entry {
     name: FullName,
     sort_key: String, // Additional key for sorting used when `name` is same.
     doc: MarkdownSection,
+    // The description of the entry `fix docs --format json` writes.
+    api: JsonEntry,
 }
 
 impl PartialEq for Entry {
@@ -423,6 +522,12 @@ impl Ord for Entry {
     }
 }
 
+/// The identifier of the section documenting the entry `name` of the kind `kind` ("value", "type"
+/// or "trait"), which the HTML documentation links to.
+pub(crate) fn anchor(kind: &str, name: &FullName) -> String {
+    format!("{}.{}", kind, name.to_string())
+}
+
 #[allow(dead_code)]
 fn to_markdown_link(header: &str) -> String {
     let mut link = header.to_lowercase();
@@ -431,7 +536,7 @@ fn to_markdown_link(header: &str) -> String {
     link
 }
 
-fn is_entry_should_be_documented(
+pub(crate) fn is_entry_should_be_documented(
     program: &Program,
     name: &FullName,
     mod_name: &Name,
@@ -502,6 +607,7 @@ fn type_entries(
     program: &Program,
     mod_name: &Name,
     config: &DocsConfig,
+    w: &dyn DocWriter,
 ) -> Result<Vec<Entry>, Errors> {
     fn kind_constraints_with_post_space(tyvars: &Vec<Arc<TyVar>>) -> String {
         if tyvars.is_empty() {
@@ -566,15 +672,19 @@ fn type_entries(
             }
         };
 
-        let mut doc = MarkdownSection::new(name.name.clone());
+        let mut doc = MarkdownSection::new(w.text(&name.name));
+        doc.anchor = Some(anchor("type", &name));
 
         let defined_as = format!(
-            "Defined as: `type {}{}{} = {} {}`",
-            kind_constraints_with_post_space(&ty_info.tyvars),
-            name.name,
-            tyvars_with_pre_space(&ty_info.tyvars),
-            box_or_unbox(ty_info.is_unbox),
-            def_rhs,
+            "Defined as: {}",
+            w.code(&format!(
+                "type {}{}{} = {} {}",
+                kind_constraints_with_post_space(&ty_info.tyvars),
+                name.name,
+                tyvars_with_pre_space(&ty_info.tyvars),
+                box_or_unbox(ty_info.is_unbox),
+                def_rhs,
+            ))
         );
         doc.add_paragraph(defined_as);
         let docstring = ty_info
@@ -582,32 +692,60 @@ fn type_entries(
             .unwrap_or_default()
             .trim()
             .to_string();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(&docstring, w));
 
+        let mut api_fields = vec![];
         if ty_info.variant == TyConVariant::Struct {
             for field in ty_info.fields.iter() {
                 if !config.include_private && field.name.starts_with("_") {
                     continue;
                 }
-                let field_sec = field_subsection(TyConVariant::Struct, field)?;
+                let field_sec = field_subsection(TyConVariant::Struct, field, w)?;
                 doc.add_subsection(field_sec);
+                api_fields.push(json_field(field)?);
             }
         }
+        let mut api_variants = vec![];
         if ty_info.variant == TyConVariant::Union {
             for variant in ty_info.fields.iter() {
                 if !config.include_private && variant.name.starts_with("_") {
                     continue;
                 }
-                let variant_sec = field_subsection(TyConVariant::Union, variant)?;
+                let variant_sec = field_subsection(TyConVariant::Union, variant, w)?;
                 doc.add_subsection(variant_sec);
+                api_variants.push(json_field(variant)?);
             }
         }
+        if config.format == DocsFormat::Html {
+            let is_for_type =
+                |impl_: &TraitImpl| impl_.impl_type().toplevel_tycon().as_deref() == Some(ty_name);
+            if let Some(section) = implementations_subsection(program, is_for_type, w) {
+                doc.add_subsection(section);
+            }
+        }
+
+        let api = JsonEntry::Type(JsonType {
+            name: name.to_string(),
+            variant: match ty_info.variant {
+                TyConVariant::Struct => "struct",
+                TyConVariant::Union => "union",
+                _ => "builtin",
+            }
+            .to_string(),
+            is_unbox: ty_info.is_unbox,
+            kind: ty_info.kind.to_string(),
+            tyvars: JsonTyVar::from_tyvars(&ty_info.tyvars),
+            fields: api_fields,
+            variants: api_variants,
+            document: docstring,
+            span: JsonSpan::from_span(&ty_info.source),
+        });
 
         let entry = Entry {
             name: name.clone(),
             sort_key: "".to_string(),
             doc,
+            api,
         };
 
         entries.push(entry);
@@ -619,13 +757,17 @@ fn type_entries(
             continue;
         }
 
-        let mut doc = MarkdownSection::new(name.name.clone());
+        let mut doc = MarkdownSection::new(w.text(&name.name));
+        doc.anchor = Some(anchor("type", &name));
         let defined_as = format!(
-            "Defined as: `type {}{}{} = {}`",
-            kind_constraints_with_post_space(&ty_info.tyvars),
-            name.name,
-            tyvars_with_pre_space(&ty_info.tyvars),
-            ty_info.value.to_string(),
+            "Defined as: {}",
+            w.code(&format!(
+                "type {}{}{} = {}",
+                kind_constraints_with_post_space(&ty_info.tyvars),
+                name.name,
+                tyvars_with_pre_space(&ty_info.tyvars),
+                ty_info.value.to_string_with(&|name| w.name(name)),
+            ))
         );
         doc.add_paragraph(defined_as);
 
@@ -635,42 +777,95 @@ fn type_entries(
             .map(|src| src.get_document())
             .transpose()?
             .unwrap_or_default();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(docstring, w));
+
+        let api = JsonEntry::TypeAlias(JsonTypeAlias {
+            name: name.to_string(),
+            kind: ty_info.kind.to_string(),
+            tyvars: JsonTyVar::from_tyvars(&ty_info.tyvars),
+            value: ty_info.value.to_string(),
+            document: docstring.trim().to_string(),
+            span: JsonSpan::from_span(&ty_info.source),
+        });
 
         let entry = Entry {
             name: name.clone(),
             sort_key: "".to_string(),
             doc,
+            api,
         };
         entries.push(entry);
     }
     Ok(entries)
 }
 
+/// The description `fix docs --format json` writes of a field or a variant.
+fn json_field(field: &Field) -> Result<JsonField, Errors> {
+    Ok(JsonField {
+        name: field.name.clone(),
+        ty: field.syn_ty.to_string(),
+        document: docstring_from_opt_span(&field.source)?,
+        span: JsonSpan::from_span(&field.source),
+    })
+}
+
 fn field_subsection(
     struct_or_union: TyConVariant,
     field: &Field,
+    w: &dyn DocWriter,
 ) -> Result<MarkdownSection, Errors> {
     let title = match struct_or_union {
-        TyConVariant::Struct => format!("field `{}`", field.name),
-        TyConVariant::Union => format!("variant `{}`", field.name),
+        TyConVariant::Struct => format!("field {}", w.code(&field.name)),
+        TyConVariant::Union => format!("variant {}", w.code(&field.name)),
         _ => unreachable!(),
     };
     let mut field_sec = MarkdownSection::new(title);
-    field_sec.add_paragraph(format!("Type: `{}`", field.syn_ty.to_string()));
+    let ty = field.syn_ty.to_string_with(&|name| w.name(name));
+    field_sec.add_paragraph(format!("Type: {}", w.code(&ty)));
     if let Some(src) = &field.source {
         let docstring = src.get_document()?;
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        field_sec.concatenate_many(docstring);
+        field_sec.concatenate_many(docstring_sections(&docstring, w));
     }
     Ok(field_sec)
 }
 
+/// A section listing the trait implementations of the program, of whichever module, that `filter`
+/// accepts, such as the ones for a type, or `None` when it accepts none. Each is written with the
+/// module defining it.
+fn implementations_subsection(
+    program: &Program,
+    filter: impl Fn(&TraitImpl) -> bool,
+    w: &dyn DocWriter,
+) -> Option<MarkdownSection> {
+    let mut impls = vec![];
+    for impl_ in program.trait_env.impls.values().flatten() {
+        // Skip impls for compiler-generated types / traits.
+        if !filter(impl_) || impl_.impl_type().to_string_normalize().contains("#") {
+            continue;
+        }
+        let written = format!(
+            "{} in {}",
+            w.code(&impl_.qual_pred.to_string_with(&|name| w.name(name))),
+            w.text(&impl_.define_module)
+        );
+        impls.push((impl_.qual_pred.to_string(), written));
+    }
+    if impls.is_empty() {
+        return None;
+    }
+    impls.sort();
+    let mut section = MarkdownSection::new(w.text("Implementations"));
+    for (_, written) in impls {
+        section.add_paragraph(written);
+    }
+    Some(section)
+}
+
 fn trait_entries(
     program: &Program,
     mod_name: &Name,
     config: &DocsConfig,
+    w: &dyn DocWriter,
 ) -> Result<Vec<Entry>, Errors> {
     fn kind_constraints_with_post_space(kind_signs: &Vec<KindSignature>) -> String {
         if kind_signs.is_empty() {
@@ -700,10 +895,14 @@ fn trait_entries(
 
         let kind_consts = kind_constraints_with_post_space(&info.kind_signs);
         let title = format!(
-            "trait `{}{} : {}`",
-            kind_consts, info.type_var.name, name.name
+            "trait {}",
+            w.code(&format!(
+                "{}{} : {}",
+                kind_consts, info.type_var.name, name.name
+            ))
         );
         let mut doc = MarkdownSection::new(title);
+        doc.anchor = Some(anchor("trait", &name));
 
         let docstring = &info
             .source
@@ -711,22 +910,25 @@ fn trait_entries(
             .map(|src| src.get_document())
             .transpose()?
             .unwrap_or_default();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(docstring, w));
 
+        let mut api_assoc_types = vec![];
         for (assoc_ty_name, assoc_ty_defn) in &info.assoc_types {
             let mut params = vec![info.type_var.name.clone()];
             for param in assoc_ty_defn.params.iter().skip(1) {
                 params.push(param.name.clone());
             }
-            let title = format!("type `{}`", assoc_ty_name,);
+            let title = format!("type {}", w.code(assoc_ty_name));
             let mut subsection = MarkdownSection::new(title);
             let defined_as = format!(
-                "Defined as: `{}{} {}{}`",
-                kind_constraints_with_post_space(&assoc_ty_defn.kind_signs),
-                assoc_ty_name,
-                params.join(" "),
-                kind_sign_with_pre_space(&assoc_ty_defn.kind_applied)
+                "Defined as: {}",
+                w.code(&format!(
+                    "{}{} {}{}",
+                    kind_constraints_with_post_space(&assoc_ty_defn.kind_signs),
+                    assoc_ty_name,
+                    params.join(" "),
+                    kind_sign_with_pre_space(&assoc_ty_defn.kind_applied)
+                ))
             );
             subsection.add_paragraph(defined_as);
             let docstring = assoc_ty_defn
@@ -735,24 +937,56 @@ fn trait_entries(
                 .map(|src| src.get_document())
                 .transpose()?
                 .unwrap_or_default();
-            let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-            subsection.concatenate_many(docstring);
+            subsection.concatenate_many(docstring_sections(&docstring, w));
             doc.add_subsection(subsection);
+            api_assoc_types.push(JsonAssocType {
+                name: assoc_ty_name.clone(),
+                params: JsonTyVar::from_tyvars(&assoc_ty_defn.params),
+                kind: assoc_ty_defn.kind_applied.to_string(),
+                document: docstring.trim().to_string(),
+                span: JsonSpan::from_span(&assoc_ty_defn.src),
+            });
         }
+        let mut api_members = vec![];
         for method in &info.members {
-            let title = format!("method `{}`", method.name);
+            let title = format!("method {}", w.code(&method.name));
             let mut subsection = MarkdownSection::new(title);
-            subsection.add_paragraph(format!("Type: `{}`", method.qual_ty.to_string()));
+            let ty = method.qual_ty.to_string_with(&|name| w.name(name));
+            subsection.add_paragraph(format!("Type: {}", w.code(&ty)));
             let docstring = docstring_from_opt_span(&method.decl_src)?;
-            let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-            subsection.concatenate_many(docstring);
+            subsection.concatenate_many(docstring_sections(&docstring, w));
             doc.add_subsection(subsection);
+            api_members.push(JsonTraitMember {
+                name: method.name.clone(),
+                ty: method.qual_ty.to_string(),
+                document: docstring,
+                span: JsonSpan::from_span(&method.decl_src),
+            });
+        }
+        if config.format == DocsFormat::Html {
+            let is_of_trait = |impl_: &TraitImpl| impl_.qual_pred.predicate.trait_id == *id;
+            if let Some(section) = implementations_subsection(program, is_of_trait, w) {
+                doc.add_subsection(section);
+            }
         }
 
+        let api = JsonEntry::Trait(JsonTrait {
+            name: name.to_string(),
+            type_var: JsonTyVar {
+                name: info.type_var.name.clone(),
+                kind: info.type_var.kind.to_string(),
+            },
+            assoc_types: api_assoc_types,
+            members: api_members,
+            document: docstring.trim().to_string(),
+            span: JsonSpan::from_span(&info.source),
+        });
+
         let entry = Entry {
             name: id.name.clone(),
             sort_key: "".to_string(),
             doc,
+            api,
         };
         entries.push(entry);
     }
@@ -765,17 +999,21 @@ fn trait_entries(
         }
 
         let title = format!(
-            "trait `{} = {}`",
-            name.name,
-            info.value
-                .iter()
-                .map(|(tr, _span)| tr.to_string())
-                .collect::<Vec<_>>()
-                .join(" + ")
+            "trait {}",
+            w.code(&format!(
+                "{} = {}",
+                name.name,
+                info.value
+                    .iter()
+                    .map(|(tr, _span)| w.name(NameRef::Trait(tr)))
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            ))
         );
         let mut doc = MarkdownSection::new(title);
+        doc.anchor = Some(anchor("trait", &name));
 
-        let kind = format!("Kind: `{}`", info.kind.to_string());
+        let kind = format!("Kind: {}", w.code(&info.kind.to_string()));
         doc.add_paragraph(kind);
 
         let docstring = &info
@@ -784,13 +1022,25 @@ fn trait_entries(
             .map(|src| src.get_document())
             .transpose()?
             .unwrap_or_default();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(docstring, w));
+
+        let api = JsonEntry::TraitAlias(JsonTraitAlias {
+            name: name.to_string(),
+            traits: info
+                .value
+                .iter()
+                .map(|(tr, _span)| tr.to_string())
+                .collect(),
+            kind: info.kind.to_string(),
+            document: docstring.trim().to_string(),
+            span: JsonSpan::from_span(&info.source),
+        });
 
         let entry = Entry {
             name: id.name.clone(),
             sort_key: "".to_string(),
             doc,
+            api,
         };
         entries.push(entry);
     }
@@ -798,7 +1048,11 @@ fn trait_entries(
     Ok(entries)
 }
 
-fn trait_impl_entries(program: &Program, mod_name: &Name) -> Result<Vec<Entry>, Errors> {
+fn trait_impl_entries(
+    program: &Program,
+    mod_name: &Name,
+    w: &dyn DocWriter,
+) -> Result<Vec<Entry>, Errors> {
     let mut entries = vec![];
 
     for (_id, impls) in &program.trait_env.impls {
@@ -812,17 +1066,26 @@ fn trait_impl_entries(program: &Program, mod_name: &Name) -> Result<Vec<Entry>,
                 continue;
             }
 
-            let title = format!("impl `{}`", impl_.qual_pred.to_string());
+            let qual_pred = impl_.qual_pred.to_string_with(&|name| w.name(name));
+            let title = format!("impl {}", w.code(&qual_pred));
             let mut doc = MarkdownSection::new(title);
 
             let docstring = docstring_from_opt_span(&impl_.source)?;
-            let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-            doc.concatenate_many(docstring);
+            doc.concatenate_many(docstring_sections(&docstring, w));
+
+            let api = JsonEntry::Impl(JsonImpl {
+                trait_: impl_.qual_pred.predicate.trait_id.to_string(),
+                ty: impl_.impl_type().to_string(),
+                predicate: impl_.qual_pred.to_string(),
+                document: docstring,
+                span: JsonSpan::from_span(&impl_.source),
+            });
 
             let entry = Entry {
                 name: FullName::from_strs(&[], ""),
                 sort_key: impl_.qual_pred.predicate.to_string(),
                 doc,
+                api,
             };
             entries.push(entry);
         }
@@ -844,6 +1107,7 @@ fn value_entries(
     program: &Program,
     mod_name: &Name,
     config: &DocsConfig,
+    w: &dyn DocWriter,
 ) -> Result<Vec<Entry>, Errors> {
     let mut entries = vec![];
 
@@ -855,33 +1119,48 @@ fn value_entries(
             continue;
         }
 
-        let mut doc = MarkdownSection::new(name.name.clone());
+        let mut doc = MarkdownSection::new(w.text(&name.name));
+        doc.anchor = Some(anchor("value", name));
 
         // Surface a `DEPRECATED[...]` pragma at the top of the entry, so it
         // appears immediately under the heading and before the type line.
         if let Some(info) = &gv.deprecation {
-            doc.add_paragraph(info.to_markdown());
+            doc.add_paragraph(w.paragraph(&info.to_markdown()));
         }
 
-        doc.add_paragraph(format!(
-            "Type: `{}`",
-            gv.syn_scm.as_ref().unwrap_or(&gv.scm).to_string()
-        ));
+        let scm = gv.syn_scm.as_ref().unwrap_or(&gv.scm);
+        let ty = scm.to_string_with(&|name| w.name(name));
+        doc.add_paragraph(format!("Type: {}", w.code(&ty)));
 
         // If `gv` is a trait member, also write which trait it belongs to.
+        let mut member_of = None;
         if matches!(&gv.expr, Method(_)) {
-            let trait_name = name.namespace.clone().to_fullname();
-            doc.add_paragraph(format!("Trait member of `{}`", trait_name.to_string()));
+            let trait_id = TraitId::from_fullname(name.namespace.clone().to_fullname());
+            doc.add_paragraph(format!(
+                "Trait member of {}",
+                w.code(&w.name(NameRef::Trait(&trait_id)))
+            ));
+            member_of = Some(trait_id.to_string());
         }
 
         let docstring = gv.get_document().unwrap_or_default();
-        let docstring = MarkdownSection::parse_many(docstring.lines().collect());
-        doc.concatenate_many(docstring);
+        doc.concatenate_many(docstring_sections(&docstring, w));
+
+        let api = JsonEntry::Value(JsonValue {
+            name: name.to_string(),
+            ty: scm.to_string(),
+            trait_: member_of,
+            deprecated: gv.deprecation.as_ref().map(|info| info.message.clone()),
+            compiler_defined: gv.compiler_defined_method,
+            document: docstring.trim().to_string(),
+            span: JsonSpan::from_span(&gv.decl_src),
+        });
 
         let entry = Entry {
             name: name.clone(),
             sort_key: "".to_string(),
             doc,
+            api,
         };
         entries.push(entry);
     }
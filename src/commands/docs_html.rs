@@ -0,0 +1,480 @@
+//! `fix docs --format html`: a page for each module, in which the type constructors, associated
+//! types and traits a signature names link to the sections documenting them, and the page of a
+//! type or a trait lists its implementations. Beside the pages go `index.html`, listing the
+//! modules, and a search index the pages' search box reads.
+//!
+//! The search index is written as a script assigning a global variable rather than as JSON, since a
+//! browser refuses to fetch a file from a page opened from the disk; so the documentation needs
+//! neither a server nor a network.
+
+use crate::ast::name::{FullName, Name};
+use crate::ast::program::Program;
+use crate::ast::types::NameRef;
+use crate::commands::docs::{
+    anchor, is_entry_should_be_documented, write_module, DocWriter, MarkdownSection,
+};
+use crate::configuration::DocsConfig;
+use crate::error::Errors;
+use crate::metafiles::project_file::ProjectFile;
+use crate::misc::{info_msg, Set};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+/// The style sheet of the pages.
+const STYLE_CSS: &str = include_str!("../docs/docs_style.css");
+
+/// The script of the search box, which reads the index `search-index.js` assigns.
+const SEARCH_JS: &str = include_str!("../docs/docs_search.js");
+
+/// The global variable `search-index.js` assigns the search index to.
+const SEARCH_INDEX_VARIABLE: &str = "FIX_DOCS_SEARCH_INDEX";
+
+/// Writes the documentation in HTML: one page for each module, with the names in signatures
+/// linked through the full names the program resolved them to.
+pub struct HtmlWriter<'a> {
+    program: &'a Program,
+    /// The modules a page is written for. A name defined elsewhere is written without a link.
+    documented: Set<Name>,
+    config: &'a DocsConfig,
+}
+
+impl HtmlWriter<'_> {
+    /// The link to the section documenting the entry `name` of the kind `kind`, or `None` when no
+    /// page documents it.
+    fn href(&self, kind: &str, name: &FullName) -> Option<String> {
+        let module = name.module();
+        if !self.documented.contains(&module)
+            || !is_entry_should_be_documented(self.program, name, &module, self.config)
+        {
+            return None;
+        }
+        Some(format!("{}#{}", page_file(&module), anchor(kind, name)))
+    }
+
+    /// `text` linked to the section documenting the entry `name` of the kind `kind`, where a page
+    /// documents it.
+    fn link(&self, kind: &str, name: &FullName, text: &str) -> String {
+        match self.href(kind, name) {
+            Some(href) => format!(
+                "<a href=\"{}\" title=\"{}\">{}</a>",
+                escape_html(&href),
+                escape_html(&name.to_string()),
+                escape_html(text)
+            ),
+            None => escape_html(text),
+        }
+    }
+}
+
+impl DocWriter for HtmlWriter<'_> {
+    fn text(&self, text: &str) -> String {
+        escape_html(text)
+    }
+
+    fn code(&self, code: &str) -> String {
+        format!("<code>{}</code>", code)
+    }
+
+    fn name(&self, name: NameRef) -> String {
+        let text = name.to_string();
+        match name {
+            NameRef::TyCon(tc) => {
+                let is_type = self.program.type_env.tycons().contains_key(tc)
+                    || self.program.type_env.aliases.contains_key(tc);
+                if !is_type {
+                    return escape_html(&text);
+                }
+                self.link("type", &tc.name, &text)
+            }
+            // An associated type is documented in the section of the trait declaring it.
+            NameRef::AssocType(assoc_ty) => {
+                let trait_name = assoc_ty.name.namespace.clone().to_fullname();
+                self.link("trait", &trait_name, &text)
+            }
+            NameRef::Trait(trait_id) => {
+                let is_trait = self.program.trait_env.traits.contains_key(trait_id)
+                    || self.program.trait_env.aliases.data.contains_key(trait_id);
+                if !is_trait {
+                    return escape_html(&text);
+                }
+                self.link("trait", &trait_id.name, &text)
+            }
+        }
+    }
+
+    fn paragraph(&self, markdown: &str) -> String {
+        markdown_to_html(markdown)
+    }
+
+    fn title(&self, markdown: &str) -> String {
+        inline_markdown_to_html(markdown)
+    }
+}
+
+/// An entry of the search index.
+#[derive(Serialize)]
+struct SearchItem {
+    /// The full name of the entry, such as `Std::Array::get`.
+    name: String,
+    /// "module", "value", "type" or "trait".
+    kind: String,
+    /// The link to the section documenting the entry.
+    href: String,
+    /// The type of a value, and the empty string for the other kinds.
+    detail: String,
+}
+
+/// Writes the HTML documentation of the modules `mod_names` of `program` into the output directory
+/// of `config`.
+pub fn write_html_docs(
+    program: &Program,
+    mod_names: &[Name],
+    project: &ProjectFile,
+    config: &DocsConfig,
+) -> Result<(), Errors> {
+    for mod_name in mod_names {
+        if !program.modules.iter().any(|mi| mi.name == *mod_name) {
+            return Err(Errors::from_msg(format!(
+                "Module \"{}\" does not exist in the project.",
+                mod_name
+            )));
+        }
+    }
+    let mut mod_names = mod_names.to_vec();
+    mod_names.sort();
+    mod_names.dedup();
+    let w = HtmlWriter {
+        program,
+        documented: mod_names.iter().cloned().collect(),
+        config,
+    };
+    let project_title = format!("{}@{}", project.general.name, project.general.version);
+
+    let mut search_items = vec![];
+    for mod_name in &mod_names {
+        info_msg(&format!(
+            "Generating documentation for module \"{}\".",
+            mod_name
+        ));
+        let section = write_module(program, mod_name, project, config, &w)?;
+        search_items.push(SearchItem {
+            name: mod_name.clone(),
+            kind: "module".to_string(),
+            href: page_file(mod_name),
+            detail: String::new(),
+        });
+        collect_search_items(program, mod_name, &section, &mut search_items);
+
+        let mut body = String::new();
+        section_to_html(&section, 0, &mut body);
+        let page = page_html(&escape_html(mod_name), &project_title, &mod_names, &body);
+        write_file(&config.out_dir, &page_file(mod_name), &page)?;
+    }
+
+    let mut body = format!(
+        "<h1>{}</h1>\n<ul class=\"modules\">\n",
+        escape_html(&project_title)
+    );
+    for mod_name in &mod_names {
+        body += &format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            escape_html(&page_file(mod_name)),
+            escape_html(mod_name)
+        );
+    }
+    body += "</ul>\n";
+    let index = page_html(
+        &escape_html(&project_title),
+        &project_title,
+        &mod_names,
+        &body,
+    );
+    write_file(&config.out_dir, "index.html", &index)?;
+
+    let search_index = serde_json::to_string(&search_items)
+        .map_err(|e| Errors::from_msg(format!("Failed to serialize the search index: {}", e)))?;
+    write_file(
+        &config.out_dir,
+        "search-index.js",
+        &format!("window.{} = {};\n", SEARCH_INDEX_VARIABLE, search_index),
+    )?;
+    write_file(&config.out_dir, "search.js", SEARCH_JS)?;
+    write_file(&config.out_dir, "style.css", STYLE_CSS)?;
+
+    info_msg(&format!(
+        "Saved documentation to \"{}\".",
+        config.out_dir.join("index.html").display()
+    ));
+    Ok(())
+}
+
+/// The file name of the page documenting the module `mod_name`.
+fn page_file(mod_name: &Name) -> String {
+    format!("{}.html", mod_name)
+}
+
+/// Adds to `items` the entries documented in `section`, a section of the page of `mod_name`: the
+/// sections carrying an anchor, which `anchor` made of the kind and the full name of the entry.
+fn collect_search_items(
+    program: &Program,
+    mod_name: &Name,
+    section: &MarkdownSection,
+    items: &mut Vec<SearchItem>,
+) {
+    if let Some(anchor) = &section.anchor {
+        if let Some((kind, name)) = anchor.split_once('.') {
+            let detail = match kind {
+                "value" => program
+                    .global_values
+                    .get(&FullName::parse(name).unwrap_or_else(|| FullName::local(name)))
+                    .map(|gv| gv.syn_scm.as_ref().unwrap_or(&gv.scm).to_string())
+                    .unwrap_or_default(),
+                _ => String::new(),
+            };
+            items.push(SearchItem {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                href: format!("{}#{}", page_file(mod_name), anchor),
+                detail,
+            });
+        }
+    }
+    for subsection in &section.subsections {
+        collect_search_items(program, mod_name, subsection, items);
+    }
+}
+
+/// Writes `section`, whose title and paragraphs are HTML already, as a `<section>` whose heading is
+/// of the level `level + 1`.
+fn section_to_html(section: &MarkdownSection, level: usize, output: &mut String) {
+    let heading = (level + 1).min(6);
+    match &section.anchor {
+        Some(anchor) => *output += &format!("<section id=\"{}\">\n", escape_html(anchor)),
+        None => *output += "<section>\n",
+    }
+    if !section.title.is_empty() {
+        *output += &format!("<h{}>{}", heading, section.title);
+        if let Some(anchor) = &section.anchor {
+            *output += &format!(
+                " <a class=\"anchor\" href=\"#{}\">§</a>",
+                escape_html(anchor)
+            );
+        }
+        *output += &format!("</h{}>\n", heading);
+    }
+    for paragraph in &section.paragraphs {
+        if paragraph.is_empty() {
+            continue;
+        }
+        *output += &format!("<div class=\"paragraph\">{}</div>\n", paragraph);
+    }
+    for subsection in &section.subsections {
+        section_to_html(subsection, level + 1, output);
+    }
+    *output += "</section>\n";
+}
+
+/// A whole page titled `title` (HTML already), showing `body` beside the search box and the list of
+/// the modules documented.
+fn page_html(title: &str, project_title: &str, mod_names: &[Name], body: &str) -> String {
+    let mut modules = String::new();
+    for mod_name in mod_names {
+        modules += &format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            escape_html(&page_file(mod_name)),
+            escape_html(mod_name)
+        );
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="style.css">
+<script src="search-index.js" defer></script>
+<script src="search.js" defer></script>
+</head>
+<body>
+<nav class="sidebar">
+<a class="project" href="index.html">{project}</a>
+<input id="search" type="search" placeholder="Search" autocomplete="off">
+<ul id="search-results"></ul>
+<h2>Modules</h2>
+<ul class="modules">
+{modules}</ul>
+</nav>
+<main>
+{body}</main>
+</body>
+</html>
+"#,
+        title = title,
+        project = escape_html(project_title),
+        modules = modules,
+        body = body
+    )
+}
+
+/// Writes `content` to the file `file_name` in `dir`, creating `dir` first.
+fn write_file(dir: &Path, file_name: &str, content: &str) -> Result<(), Errors> {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return Err(Errors::from_msg(format!(
+            "Failed to create directory \"{}\": {:?}",
+            dir.display(),
+            e
+        )));
+    }
+    let path = dir.join(file_name);
+    std::fs::write(&path, content).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to write file \"{}\": {:?}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// `text` with the characters HTML gives a meaning to replaced by their references, to be written
+/// as text or as the value of an attribute.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&#39;",
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Converts a paragraph of a documentation comment, as `MarkdownSection` splits it, into HTML. The
+/// Markdown the comments of the standard library use is understood: fenced code blocks, lists whose
+/// items begin with `-`, `*` or a number, and the inline elements of `inline_markdown_to_html`.
+fn markdown_to_html(markdown: &str) -> String {
+    /// A list being read: whether it is numbered, and its items.
+    struct List {
+        ordered: bool,
+        items: Vec<String>,
+    }
+
+    fn flush_text(lines: &mut Vec<&str>, output: &mut String) {
+        if !lines.is_empty() {
+            *output += &format!("<p>{}</p>", inline_markdown_to_html(&lines.join("\n")));
+            lines.clear();
+        }
+    }
+
+    fn flush_list(list: &mut Option<List>, output: &mut String) {
+        if let Some(list) = list.take() {
+            let tag = if list.ordered { "ol" } else { "ul" };
+            *output += &format!("<{}>", tag);
+            for item in list.items {
+                *output += &format!("<li>{}</li>", inline_markdown_to_html(&item));
+            }
+            *output += &format!("</{}>", tag);
+        }
+    }
+
+    // The text of a list item, and whether the list is numbered, if `line` begins an item.
+    fn list_item(line: &str) -> Option<(bool, &str)> {
+        if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            return Some((false, item));
+        }
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 {
+            if let Some(item) = line[digits..].strip_prefix(". ") {
+                return Some((true, item));
+            }
+        }
+        None
+    }
+
+    let mut output = String::new();
+    let mut text: Vec<&str> = vec![];
+    let mut list: Option<List> = None;
+    let mut lines = markdown.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            flush_text(&mut text, &mut output);
+            flush_list(&mut list, &mut output);
+            let mut code = vec![];
+            for line in lines.by_ref() {
+                if line.trim_start().starts_with("```") {
+                    break;
+                }
+                code.push(line);
+            }
+            output += &format!("<pre><code>{}</code></pre>", escape_html(&code.join("\n")));
+        } else if let Some((ordered, item)) = list_item(trimmed) {
+            flush_text(&mut text, &mut output);
+            if list.as_ref().map_or(false, |list| list.ordered != ordered) {
+                flush_list(&mut list, &mut output);
+            }
+            list.get_or_insert(List {
+                ordered,
+                items: vec![],
+            })
+            .items
+            .push(item.to_string());
+        } else if list.is_some() && line.starts_with(' ') {
+            // An indented line continues the item above it.
+            let items = &mut list.as_mut().unwrap().items;
+            let last = items.last_mut().unwrap();
+            *last += "\n";
+            *last += trimmed;
+        } else {
+            flush_list(&mut list, &mut output);
+            text.push(line);
+        }
+    }
+    flush_text(&mut text, &mut output);
+    flush_list(&mut list, &mut output);
+    output
+}
+
+/// Converts Markdown text without blocks into HTML: `code spans`, `**strong**` text and
+/// `[links](url)`; the rest is escaped.
+fn inline_markdown_to_html(markdown: &str) -> String {
+    let mut output = String::new();
+    // The text between backticks is code, and an unclosed backtick is written as it is.
+    let parts = markdown.split('`').collect::<Vec<_>>();
+    let closed = if parts.len() % 2 == 1 {
+        parts.len()
+    } else {
+        parts.len() - 1
+    };
+    for (i, part) in parts.iter().enumerate() {
+        if i >= closed {
+            output += "`";
+            output += &text_to_html(part);
+        } else if i % 2 == 1 {
+            output += &format!("<code>{}</code>", escape_html(part));
+        } else {
+            output += &text_to_html(part);
+        }
+    }
+    output
+}
+
+/// `**strong**` text in Markdown.
+static STRONG: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*\*([^*]+)\*\*").unwrap());
+
+/// A `[link](url)` in Markdown.
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap());
+
+/// Escapes `text`, and converts the `**strong**` text and the `[links](url)` in it.
+fn text_to_html(text: &str) -> String {
+    let escaped = escape_html(text);
+    let escaped = STRONG.replace_all(&escaped, "<strong>$1</strong>");
+    LINK.replace_all(&escaped, "<a href=\"$2\">$1</a>")
+        .into_owned()
+}
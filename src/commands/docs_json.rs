@@ -0,0 +1,338 @@
+//! `fix docs --format json`: a JSON file for each module, describing what the module declares for
+//! tools to read rather than for people, such as an API browser or a binding generator.
+//!
+//! Each file is one `JsonModule`, whose field `schema_version` is `DOCS_JSON_SCHEMA_VERSION`. The
+//! version is raised whenever a field is removed or changes its meaning, so that a tool can refuse
+//! a file it does not understand; adding a field keeps it.
+//!
+//! The entries are the ones the Markdown documentation has, gathered by the same functions, which
+//! fill a `JsonEntry` in beside each section they write. Types, schemes and predicates are written
+//! as Fix source writes them, with every name fully qualified.
+
+use crate::ast::name::Name;
+use crate::ast::program::Program;
+use crate::ast::types::TyVar;
+use crate::commands::docs::module_json_entries;
+use crate::configuration::DocsConfig;
+use crate::error::Errors;
+use crate::metafiles::project_file::ProjectFile;
+use crate::misc::info_msg;
+use crate::parse::sourcefile::Span;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The version of the layout of the JSON files `fix docs --format json` writes.
+pub const DOCS_JSON_SCHEMA_VERSION: u32 = 1;
+
+/// A range of a source file. Lines and columns count from 1, and the end is exclusive.
+#[derive(Serialize, Deserialize)]
+pub struct JsonSpan {
+    /// The path of the file, as the build was given it.
+    pub file: String,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl JsonSpan {
+    /// The range `span` covers, or `None` for a declaration no source was written for.
+    pub fn from_span(span: &Option<Span>) -> Option<JsonSpan> {
+        let span = span.as_ref()?;
+        let (start_line, start_column) = span.start_line_col();
+        let (end_line, end_column) = span.end_line_col();
+        Some(JsonSpan {
+            file: span.input.file_path.to_string_lossy().to_string(),
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        })
+    }
+}
+
+/// A type variable a declaration takes, with its kind.
+#[derive(Serialize, Deserialize)]
+pub struct JsonTyVar {
+    pub name: Name,
+    pub kind: String,
+}
+
+impl JsonTyVar {
+    /// The descriptions of `tyvars`, in order.
+    pub fn from_tyvars(tyvars: &[Arc<TyVar>]) -> Vec<JsonTyVar> {
+        tyvars
+            .iter()
+            .map(|tyvar| JsonTyVar {
+                name: tyvar.name.clone(),
+                kind: tyvar.kind.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// A field of a struct, or a variant of a union.
+#[derive(Serialize, Deserialize)]
+pub struct JsonField {
+    pub name: Name,
+    /// The type of the field, or the one the variant holds.
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A type the module defines, struct, union or built-in.
+#[derive(Serialize, Deserialize)]
+pub struct JsonType {
+    pub name: String,
+    /// "struct", "union" or "builtin".
+    pub variant: String,
+    pub is_unbox: bool,
+    /// The kind of the type constructor, such as `* -> *`.
+    pub kind: String,
+    pub tyvars: Vec<JsonTyVar>,
+    /// The fields of a struct, and empty for the other variants.
+    pub fields: Vec<JsonField>,
+    /// The variants of a union, and empty for the other variants.
+    pub variants: Vec<JsonField>,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A type alias the module defines.
+#[derive(Serialize, Deserialize)]
+pub struct JsonTypeAlias {
+    pub name: String,
+    pub kind: String,
+    pub tyvars: Vec<JsonTyVar>,
+    /// The type the alias stands for, in terms of `tyvars`.
+    pub value: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// An associated type a trait declares.
+#[derive(Serialize, Deserialize)]
+pub struct JsonAssocType {
+    pub name: Name,
+    /// The parameters, the first being the type variable of the trait.
+    pub params: Vec<JsonTyVar>,
+    /// The kind of the associated type applied to its parameters.
+    pub kind: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A member a trait declares.
+#[derive(Serialize, Deserialize)]
+pub struct JsonTraitMember {
+    pub name: Name,
+    /// The type of the member, in terms of the type variable of the trait.
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A trait the module defines.
+#[derive(Serialize, Deserialize)]
+pub struct JsonTrait {
+    pub name: String,
+    /// The type variable the trait constrains, with its kind.
+    pub type_var: JsonTyVar,
+    pub assoc_types: Vec<JsonAssocType>,
+    pub members: Vec<JsonTraitMember>,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A trait alias the module defines.
+#[derive(Serialize, Deserialize)]
+pub struct JsonTraitAlias {
+    pub name: String,
+    /// The traits the alias stands for together.
+    pub traits: Vec<String>,
+    /// The kind of the types the alias constrains.
+    pub kind: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A trait implementation the module defines.
+#[derive(Serialize, Deserialize)]
+pub struct JsonImpl {
+    #[serde(rename = "trait")]
+    pub trait_: String,
+    /// The type implementing the trait.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The implementation as its head is written, with its constraints, such as
+    /// `[a : Std::Eq] Std::Array a : Std::Eq`.
+    pub predicate: String,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// A value the module defines, a trait member among them.
+#[derive(Serialize, Deserialize)]
+pub struct JsonValue {
+    pub name: String,
+    /// The type scheme of the value, as it is declared.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The trait the value is a member of, for a trait member.
+    #[serde(rename = "trait")]
+    pub trait_: Option<String>,
+    /// The message of a `DEPRECATED` pragma naming the value.
+    pub deprecated: Option<String>,
+    /// Whether the compiler defines the value, as the accessors of a field.
+    pub compiler_defined: bool,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+}
+
+/// The description of an entry of the documentation of a module.
+pub enum JsonEntry {
+    Type(JsonType),
+    TypeAlias(JsonTypeAlias),
+    Trait(JsonTrait),
+    TraitAlias(JsonTraitAlias),
+    Impl(JsonImpl),
+    Value(JsonValue),
+}
+
+/// The project a module belongs to.
+#[derive(Serialize, Deserialize)]
+pub struct JsonProject {
+    pub name: String,
+    pub version: String,
+}
+
+/// The file written for a module.
+#[derive(Serialize, Deserialize)]
+pub struct JsonModule {
+    pub schema_version: u32,
+    pub name: Name,
+    pub project: JsonProject,
+    pub document: String,
+    pub span: Option<JsonSpan>,
+    pub values: Vec<JsonValue>,
+    pub types: Vec<JsonType>,
+    pub type_aliases: Vec<JsonTypeAlias>,
+    pub traits: Vec<JsonTrait>,
+    pub trait_aliases: Vec<JsonTraitAlias>,
+    pub impls: Vec<JsonImpl>,
+}
+
+impl JsonModule {
+    /// Reads the descriptions written into `dir`, refusing a file of another schema version.
+    pub fn read_dir(dir: &Path) -> Result<Vec<JsonModule>, Errors> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            Errors::from_msg_err(
+                &format!("Failed to read directory \"{}\"", dir.display()),
+                e,
+            )
+        })?;
+        let mut modules = vec![];
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let json = fs::read_to_string(&path).map_err(|e| {
+                Errors::from_msg_err(&format!("Failed to read \"{}\"", path.display()), e)
+            })?;
+            let module: JsonModule = serde_json::from_str(&json).map_err(|e| {
+                Errors::from_msg_err(&format!("Failed to parse \"{}\"", path.display()), e)
+            })?;
+            if module.schema_version != DOCS_JSON_SCHEMA_VERSION {
+                return Err(Errors::from_msg(format!(
+                    "\"{}\" is written in version {} of the schema, but version {} is read.",
+                    path.display(),
+                    module.schema_version,
+                    DOCS_JSON_SCHEMA_VERSION
+                )));
+            }
+            modules.push(module);
+        }
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(modules)
+    }
+}
+
+/// Writes the description of each module of `mod_names` as `<module>.json` into the output
+/// directory of `config`.
+pub fn write_json_docs(
+    program: &Program,
+    mod_names: &[Name],
+    project: &ProjectFile,
+    config: &DocsConfig,
+) -> Result<(), Errors> {
+    fs::create_dir_all(&config.out_dir).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to create directory \"{}\": {}",
+            config.out_dir.display(),
+            e
+        ))
+    })?;
+    for mod_name in mod_names {
+        info_msg(&format!(
+            "Generating documentation for module \"{}\".",
+            mod_name
+        ));
+        let Some(mod_info) = program.modules.iter().find(|mi| mi.name == *mod_name) else {
+            return Err(Errors::from_msg(format!(
+                "Module \"{}\" does not exist in the project.",
+                mod_name
+            )));
+        };
+        let mut module = JsonModule {
+            schema_version: DOCS_JSON_SCHEMA_VERSION,
+            name: mod_name.clone(),
+            project: JsonProject {
+                name: project.general.name.clone(),
+                version: project.general.version.clone(),
+            },
+            document: mod_info
+                .source
+                .get_document()
+                .ok()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            span: JsonSpan::from_span(&Some(mod_info.source.clone())),
+            values: vec![],
+            types: vec![],
+            type_aliases: vec![],
+            traits: vec![],
+            trait_aliases: vec![],
+            impls: vec![],
+        };
+        for entry in module_json_entries(program, mod_name, config)? {
+            match entry {
+                JsonEntry::Type(ty) => module.types.push(ty),
+                JsonEntry::TypeAlias(alias) => module.type_aliases.push(alias),
+                JsonEntry::Trait(trait_) => module.traits.push(trait_),
+                JsonEntry::TraitAlias(alias) => module.trait_aliases.push(alias),
+                JsonEntry::Impl(impl_) => module.impls.push(impl_),
+                JsonEntry::Value(value) => module.values.push(value),
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&module).map_err(|e| {
+            Errors::from_msg(format!(
+                "Failed to serialize the documentation of \"{}\": {}",
+                mod_name, e
+            ))
+        })?;
+        let path = config.out_dir.join(format!("{}.json", mod_name));
+        fs::write(&path, json + "\n").map_err(|e| {
+            Errors::from_msg(format!("Failed to write \"{}\": {}", path.display(), e))
+        })?;
+        info_msg(&format!("Saved documentation to \"{}\".", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,482 @@
+//! `fix test --doc`: builds and runs the code examples written in the documentation comments of
+//! the project's modules.
+//!
+//! An example is a code block fenced with "```fix" in a documentation comment, the comment
+//! `docs::docstring_from_opt_span` reads for the documentation. Words after `fix` in the fence,
+//! separated by commas or spaces, change how it is tested: `ignore` leaves it out, and `no_run`
+//! builds it without running it. A block fenced otherwise is not tested.
+//!
+//! Each example is written into a module `Main` of its own, which imports the module documented,
+//! and is built into a program together with the project, in place of the project's own `Main`.
+//! The block is taken as the declarations of that module, after its `module` and `import`
+//! declarations, or as the whole module when it begins with a `module` declaration itself. A block
+//! that declares no `main` is taken as the body of `main : IO ()`. An example passes when its
+//! program builds and exits with status 0, and the errors of a build are reported at the lines of
+//! the documentation comment the block is written in.
+
+use crate::ast::name::Name;
+use crate::ast::program::Program;
+use crate::commands::build::build;
+use crate::commands::run::run;
+use crate::configuration::{BuildConfigType, Configuration, DocsConfig, SubCommand};
+use crate::constants::{DOCTEST_PATH, MAIN_FUNCTION_NAME, MAIN_MODULE_NAME, RUN_PATH};
+use crate::elaboration::elaborate_via_config;
+use crate::error::{panic_if_err, Errors};
+use crate::misc::{to_absolute_path, warn_msg, Set};
+use crate::parse::sourcefile::{SourceFile, Span};
+use rand::Rng;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+/// How an example is tested, as the words after `fix` in its fence ask.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DocTestMode {
+    /// Built and run.
+    Run,
+    /// Built and not run, for `no_run`.
+    NoRun,
+    /// Not tested, for `ignore`.
+    Ignore,
+}
+
+/// A code example of a documentation comment.
+struct DocTest {
+    /// The name the example is reported and selected by: the documented entry and where the block
+    /// is written.
+    name: String,
+    /// The module whose documentation the example is written in, which the example imports.
+    module: Name,
+    /// How the example is tested.
+    mode: DocTestMode,
+    /// The lines of the block, between its fences.
+    lines: Vec<String>,
+    /// The file the documentation comment is written in.
+    file: SourceFile,
+    /// Where each line of `lines` begins in `file`, as a byte offset.
+    line_starts: Vec<usize>,
+    /// The opening fence of the block, at which an error outside the lines of the block is
+    /// reported.
+    fence: Span,
+}
+
+impl DocTest {
+    /// The source of the module `Main` the example is built as, and the line of it the first line of
+    /// the block is written on. The lines of the block are written one after another as they are,
+    /// so a position in them is the same distance from the start of the block in the comment.
+    fn source(&self) -> (String, usize) {
+        let import = format!("import {};", self.module);
+        let first_line = self.lines.iter().position(|line| !line.trim().is_empty());
+        let whole_module = first_line.map_or(false, |i| self.lines[i].starts_with("module "));
+        if whole_module {
+            // The import goes at the end of the `module` declaration's line, to keep the lines
+            // where they are.
+            let mut lines = self.lines.clone();
+            lines[first_line.unwrap()] += &format!(" {}", import);
+            return (lines.join("\n") + "\n", 0);
+        }
+        let mut source = format!("module {}; {}\n", MAIN_MODULE_NAME, import);
+        let declares_main = self.lines.iter().any(|line| {
+            line.strip_prefix(MAIN_FUNCTION_NAME)
+                .map_or(false, |rest| rest.trim_start().starts_with(':'))
+        });
+        if declares_main {
+            source += &(self.lines.join("\n") + "\n");
+            return (source, 1);
+        }
+        source += &format!(
+            "{} : IO ();\n{} = (\n",
+            MAIN_FUNCTION_NAME, MAIN_FUNCTION_NAME
+        );
+        source += &(self.lines.join("\n") + "\n);\n");
+        (source, 3)
+    }
+
+    /// The location in the documentation comment of `span`, a location in the file `source` is
+    /// written to, whose block begins on the line `first_line`. A location on a line the block
+    /// does not make is placed at the opening fence.
+    fn relocate(&self, span: &Span, source: &str, first_line: usize) -> Span {
+        let line_starts = line_starts(source);
+        let to_comment = |pos: usize| -> Option<usize> {
+            let line = line_starts.partition_point(|start| *start <= pos) - 1;
+            let i = line.checked_sub(first_line)?;
+            let text = self.lines.get(i)?;
+            let col = (pos - line_starts[line]).min(text.len());
+            Some(self.line_starts[i] + col)
+        };
+        match (to_comment(span.start), to_comment(span.end)) {
+            (Some(start), Some(end)) if start <= end => Span {
+                input: self.file.clone(),
+                start,
+                end,
+            },
+            _ => self.fence.clone(),
+        }
+    }
+}
+
+/// The byte offsets at which the lines of `text` begin.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// The examples of the documentation comment of `entry`, the declaration at `decl` of the module
+/// `module`.
+fn doc_tests_of(entry: &str, module: &Name, decl: &Span) -> Result<Vec<DocTest>, Errors> {
+    let document = decl.get_document()?;
+    let doc_lines = document.lines().collect::<Vec<_>>();
+    if doc_lines.is_empty() {
+        return Ok(vec![]);
+    }
+    // The comment is made of the lines just above the one the declaration begins on.
+    let source = decl.input.string()?;
+    let source_line_starts = line_starts(&source);
+    let first_doc_line = decl.start_line_no() - 1 - doc_lines.len();
+    // Where the text of each line of the comment begins, after its `//` and one space.
+    let text_starts = (0..doc_lines.len())
+        .map(|i| {
+            let line_start = source_line_starts[first_doc_line + i];
+            let line = &source[line_start..];
+            let slashes = line.find("//").unwrap_or(0) + 2;
+            let space = if line[slashes..].starts_with(' ') {
+                1
+            } else {
+                0
+            };
+            line_start + slashes + space
+        })
+        .collect::<Vec<_>>();
+
+    let mut doc_tests = vec![];
+    // The block the line is in: `Some(Some(_))` in an example, and `Some(None)` in a block of
+    // another language, whose fences are not taken for the ones of an example.
+    let mut open: Option<Option<DocTest>> = None;
+    for (i, line) in doc_lines.iter().enumerate() {
+        let fence = line.trim_start().strip_prefix("```");
+        if let Some(block) = &mut open {
+            if fence.is_some() {
+                doc_tests.extend(open.take().flatten());
+            } else if let Some(doc_test) = block {
+                doc_test.lines.push(line.to_string());
+                doc_test.line_starts.push(text_starts[i]);
+            }
+            continue;
+        }
+        let Some(info) = fence else {
+            continue;
+        };
+        let words = info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        if words.first() != Some(&"fix") {
+            open = Some(None);
+            continue;
+        }
+        let mode = if words.contains(&"ignore") {
+            DocTestMode::Ignore
+        } else if words.contains(&"no_run") {
+            DocTestMode::NoRun
+        } else {
+            DocTestMode::Run
+        };
+        open = Some(Some(DocTest {
+            name: format!(
+                "{} ({}:{})",
+                entry,
+                decl.input.file_path.display(),
+                first_doc_line + i + 1
+            ),
+            module: module.clone(),
+            mode,
+            lines: vec![],
+            file: decl.input.clone(),
+            line_starts: vec![],
+            fence: Span {
+                input: decl.input.clone(),
+                start: text_starts[i],
+                end: text_starts[i] + line.len(),
+            },
+        }));
+    }
+    // A block left open runs to the end of the comment, as in Markdown.
+    doc_tests.extend(open.flatten());
+    Ok(doc_tests)
+}
+
+/// The declarations of the module `module` that may carry a documentation comment, each with the
+/// name of the entry it declares.
+fn documented_declarations(program: &Program, module: &Name) -> Vec<(String, Span)> {
+    let mut decls = vec![];
+    if let Some(mod_info) = program.modules.iter().find(|mi| mi.name == *module) {
+        decls.push((module.clone(), mod_info.source.clone()));
+    }
+    for (tycon, info) in program.type_env.tycons().iter() {
+        if tycon.name.module() != *module {
+            continue;
+        }
+        decls.extend(info.source.clone().map(|src| (tycon.to_string(), src)));
+        for field in &info.fields {
+            let name = format!("{}::{}", tycon.to_string(), field.name);
+            decls.extend(field.source.clone().map(|src| (name, src)));
+        }
+    }
+    for (tycon, info) in program.type_env.aliases.iter() {
+        if tycon.name.module() == *module {
+            decls.extend(info.source.clone().map(|src| (tycon.to_string(), src)));
+        }
+    }
+    for (id, info) in &program.trait_env.traits {
+        if id.name.module() != *module {
+            continue;
+        }
+        decls.extend(info.source.clone().map(|src| (id.to_string(), src)));
+        for (name, assoc_ty) in &info.assoc_types {
+            let name = format!("{}::{}", id.to_string(), name);
+            decls.extend(assoc_ty.src.clone().map(|src| (name, src)));
+        }
+        for member in &info.members {
+            let name = format!("{}::{}", id.to_string(), member.name);
+            decls.extend(member.decl_src.clone().map(|src| (name, src)));
+        }
+    }
+    for (id, alias) in &program.trait_env.aliases.data {
+        if id.name.module() == *module {
+            decls.extend(alias.source.clone().map(|src| (id.to_string(), src)));
+        }
+    }
+    for impl_ in program.trait_env.impls.values().flatten() {
+        if impl_.define_module == *module {
+            let name = format!("impl {}", impl_.qual_pred.to_string());
+            decls.extend(impl_.source.clone().map(|src| (name, src)));
+        }
+    }
+    for (name, gv) in &program.global_values {
+        if name.module() == *module {
+            decls.extend(gv.decl_src.clone().map(|src| (name.to_string(), src)));
+        }
+    }
+    // A declaration reached twice, such as a trait member, which is a value as well, is taken
+    // once.
+    let mut seen = Set::default();
+    decls.retain(|(_, src)| seen.insert((src.input.file_path.clone(), src.start)));
+    decls
+}
+
+/// The examples of the documentation of the modules the root project's ordinary sources define,
+/// in the order they are written, found in `program` as the configuration `config` builds it.
+/// The module `Main` is left out, since an example takes its place; a warning is printed when it
+/// has examples.
+fn collect_doc_tests(program: &Program, config: &Configuration) -> Result<Vec<DocTest>, Errors> {
+    let mut other_files = Set::default();
+    for file in config
+        .root_test_only_source_files
+        .iter()
+        .chain(config.root_bench_only_source_files.iter())
+    {
+        other_files.insert(to_absolute_path(file)?);
+    }
+    let mut root_files = Set::default();
+    for file in &config.root_source_files {
+        let file = to_absolute_path(file)?;
+        if !other_files.contains(&file) {
+            root_files.insert(file);
+        }
+    }
+
+    let mut modules = vec![];
+    for mod_info in &program.modules {
+        if root_files.contains(&mod_info.absolute_source_path()?) {
+            modules.push(mod_info.name.clone());
+        }
+    }
+    modules.sort();
+
+    let mut doc_tests = vec![];
+    for module in modules {
+        let mut module_tests = vec![];
+        for (entry, decl) in documented_declarations(program, &module) {
+            module_tests.append(&mut doc_tests_of(&entry, &module, &decl)?);
+        }
+        if module == MAIN_MODULE_NAME {
+            if module_tests.iter().any(|t| t.mode != DocTestMode::Ignore) {
+                warn_msg(&format!(
+                    "The examples in the documentation of the module `{}` are not tested, since each example is built as a module `{}` of its own.",
+                    MAIN_MODULE_NAME, MAIN_MODULE_NAME
+                ));
+            }
+            continue;
+        }
+        module_tests.sort_by_key(|t| (t.file.file_path.clone(), t.fence.start));
+        doc_tests.append(&mut module_tests);
+    }
+    Ok(doc_tests)
+}
+
+/// How an example came out.
+enum Outcome {
+    /// The example built, and ran to exit status 0 unless it is `no_run`.
+    Passed,
+    /// The example failed, for the reason written here.
+    Failed(String),
+}
+
+/// Builds the example `doc_test` under `config`, and runs it unless it is `no_run`.
+fn run_doc_test(doc_test: &DocTest, config: &Configuration) -> Result<Outcome, Errors> {
+    let file = PathBuf::from(DOCTEST_PATH).join("main.fix");
+    let (source, first_line) = doc_test.source();
+    fs::write(&file, &source)
+        .map_err(|e| Errors::from_msg(format!("Failed to write \"{}\": {}", file.display(), e)))?;
+    let mut config = config.clone();
+    config.extra_source_files.push(file.clone());
+
+    let res = match doc_test.mode {
+        DocTestMode::NoRun => {
+            // Built into a file of its own, which is removed afterwards.
+            let exec_path = PathBuf::from(format!(
+                "{}/doctest{}.out",
+                RUN_PATH,
+                rand::thread_rng().gen::<u64>()
+            ));
+            config.out_file_path = Some(exec_path.clone());
+            let res = fs::create_dir_all(RUN_PATH)
+                .map_err(|e| Errors::from_msg(format!("Failed to create \"{}\": {}", RUN_PATH, e)))
+                .and_then(|_| build(&config));
+            let _ = fs::remove_file(&exec_path); // Ignore the error.
+            res.map(|_| None)
+        }
+        _ => run(config, false).map(Some),
+    };
+    let output = match res {
+        Ok(output) => output,
+        Err(mut errs) => {
+            let abs_file = to_absolute_path(&file)?;
+            errs.relocate_srcs(|span| {
+                let in_file =
+                    to_absolute_path(&span.input.file_path).ok() == Some(abs_file.clone());
+                in_file.then(|| doc_test.relocate(span, &source, first_line))
+            });
+            return Ok(Outcome::Failed(errs.to_string()));
+        }
+    };
+    let Some(output) = output else {
+        return Ok(Outcome::Passed);
+    };
+    let output =
+        output.map_err(|e| Errors::from_msg(format!("Failed to run the example: {}", e)))?;
+    if output.status.success() {
+        return Ok(Outcome::Passed);
+    }
+    let mut reason = format!("The example ended with {}.\n", output.status);
+    for (stream, content) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        let content = String::from_utf8_lossy(content);
+        if !content.trim().is_empty() {
+            reason += &format!("{}:\n{}\n", stream, content.trim_end());
+        }
+    }
+    Ok(Outcome::Failed(reason))
+}
+
+/// Tests the examples of the documentation of the project that `config`, the configuration of
+/// `fix test`, builds. The arguments after `--` select the examples whose names contain one of
+/// them. One line is printed for each example, and then the failures and a summary.
+///
+/// # Returns
+///
+/// Whether every example tested passed.
+pub fn doctest(config: &Configuration) -> Result<bool, Errors> {
+    let mut docs_config = config.clone();
+    docs_config.subcommand = SubCommand::Docs(DocsConfig {
+        mode: BuildConfigType::Test,
+        ..DocsConfig::default()
+    });
+    let program = elaborate_via_config(&docs_config)?;
+    let doc_tests = collect_doc_tests(&program, config)?;
+
+    // Each example is built as a program whose entry point is `Main::main`, in place of the one of
+    // the project, and takes no arguments.
+    let mut run_config = config.clone();
+    run_config.subcommand = SubCommand::Run;
+    let filters = std::mem::take(&mut run_config.run_program_args);
+    let mut main_files = Set::default();
+    for mod_info in program.modules.iter() {
+        if mod_info.name == MAIN_MODULE_NAME {
+            main_files.insert(mod_info.absolute_source_path()?);
+        }
+    }
+    let is_kept =
+        |file: &PathBuf| to_absolute_path(file).map_or(true, |file| !main_files.contains(&file));
+    for sources in run_config.project_sources.iter_mut() {
+        sources.files.retain(is_kept);
+    }
+    run_config.root_source_files.retain(is_kept);
+    fs::create_dir_all(DOCTEST_PATH)
+        .map_err(|e| Errors::from_msg(format!("Failed to create \"{}\": {}", DOCTEST_PATH, e)))?;
+
+    let selected = doc_tests
+        .iter()
+        .filter(|t| filters.is_empty() || filters.iter().any(|f| t.name.contains(f.as_str())))
+        .collect::<Vec<_>>();
+    println!(
+        "running {} doctest{}",
+        selected.len(),
+        if selected.len() == 1 { "" } else { "s" }
+    );
+    let (mut passed, mut ignored) = (0, 0);
+    let mut failures = vec![];
+    for doc_test in &selected {
+        let outcome = match doc_test.mode {
+            DocTestMode::Ignore => None,
+            _ => Some(run_doc_test(doc_test, &run_config)?),
+        };
+        let shown = match outcome {
+            None => {
+                ignored += 1;
+                "ignored"
+            }
+            Some(Outcome::Passed) => {
+                passed += 1;
+                "ok"
+            }
+            Some(Outcome::Failed(reason)) => {
+                failures.push((doc_test.name.clone(), reason));
+                "FAILED"
+            }
+        };
+        println!("doctest {} ... {}", doc_test.name, shown);
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, reason) in &failures {
+            println!("\n---- {} ----\n{}", name, reason.trim_end());
+        }
+    }
+    println!(
+        "\ndoctest result: {}. {} passed; {} failed; {} ignored; {} filtered out",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        passed,
+        failures.len(),
+        ignored,
+        doc_tests.len() - selected.len()
+    );
+    Ok(failures.is_empty())
+}
+
+/// Runs `doctest`, and exits the `fix` process with status 0 when every example passed and 1
+/// otherwise.
+pub fn doctest_command(config: &Configuration) {
+    let passed = panic_if_err(doctest(config));
+    process::exit(if passed { 0 } else { 1 });
+}
+
+/// Runs `doctest` for one run of `fix test --doc --watch`, reporting a failed example as an error.
+pub fn doctest_watched(config: &Configuration) -> Result<(), Errors> {
+    if doctest(config)? {
+        Ok(())
+    } else {
+        Err(Errors::from_msg("An example failed.".to_string()))
+    }
+}
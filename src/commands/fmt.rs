@@ -0,0 +1,75 @@
+use crate::configuration::BuildConfigType;
+use crate::edit::format::format_source;
+use crate::error::Errors;
+use crate::metafiles::project_file::ProjectFile;
+use crate::misc::{info_msg, warn_msg};
+use std::fs;
+use std::path::PathBuf;
+
+/// Runs `fix fmt`: formats each of `files` into the canonical layout, or, when `files` is empty,
+/// every source file the project file in the current directory lists, test files included. The
+/// source files of dependencies are left alone.
+///
+/// # Arguments
+///
+/// * `check` - Leaves the files as they are and only reports those that are not in the canonical
+///   layout, failing when there is any, as a CI check does.
+pub fn fmt_command(files: Vec<PathBuf>, check: bool) -> Result<(), Errors> {
+    let files = if files.is_empty() {
+        ProjectFile::read_root_file()?.get_files(BuildConfigType::Test)
+    } else {
+        files
+    };
+
+    // A file that fails to format is reported together with the others, so that one broken file
+    // does not hide the state of the rest.
+    let mut errors = Errors::empty();
+    let mut unformatted = vec![];
+    for file in &files {
+        match format_file(file, check) {
+            Ok(true) => unformatted.push(file),
+            Ok(false) => {}
+            Err(errs) => errors.append(errs),
+        }
+    }
+
+    if check {
+        for file in &unformatted {
+            warn_msg(&format!("\"{}\" is not formatted.", file.display()));
+        }
+        if !unformatted.is_empty() {
+            errors.append(Errors::from_msg(format!(
+                "{} of {} file(s) are not formatted. Run `fix fmt` to format them.",
+                unformatted.len(),
+                files.len()
+            )));
+        }
+    } else {
+        for file in &unformatted {
+            info_msg(&format!("Formatted \"{}\".", file.display()));
+        }
+    }
+    errors.to_result()
+}
+
+/// Formats the source file at `path`, writing the result back unless `check` is set. Tells whether
+/// the file was not in the canonical layout.
+fn format_file(path: &PathBuf, check: bool) -> Result<bool, Errors> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        Errors::from_msg(format!("Failed to read file \"{}\": {}", path.display(), e))
+    })?;
+    let formatted = format_source(path, &content)?;
+    if formatted == content {
+        return Ok(false);
+    }
+    if !check {
+        fs::write(path, formatted).map_err(|e| {
+            Errors::from_msg(format!(
+                "Failed to write file \"{}\": {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(true)
+}
@@ -5,7 +5,8 @@ use crate::ast::program::Program;
 use crate::ast::traits::{MissingTraitImplInfo, MissingTraitImplItem};
 use crate::ast::types::{type_assocty, type_tyvar_star, AssocType};
 use crate::constants::{
-    ERR_MISSING_STRUCT_FIELD, ERR_MISSING_TRAIT_IMPL, ERR_NO_VALUE_MATCH, ERR_UNKNOWN_NAME,
+    ERR_MISSING_STRUCT_FIELD, ERR_MISSING_TRAIT_IMPL, ERR_NON_EXHAUSTIVE_MATCH, ERR_NO_VALUE_MATCH,
+    ERR_UNKNOWN_NAME,
 };
 use crate::misc::{generate_fresh_varnames, Map, Set};
 use lsp_types::{
@@ -33,6 +34,8 @@ pub(super) fn handle_code_action(
             handle_missing_trait_impl(diag, params, uri_to_content, &mut actions);
         } else if diag.code == Some(NumberOrString::String(ERR_MISSING_STRUCT_FIELD.to_string())) {
             handle_missing_struct_field(diag, params, uri_to_content, &mut actions);
+        } else if diag.code == Some(NumberOrString::String(ERR_NON_EXHAUSTIVE_MATCH.to_string())) {
+            handle_non_exhaustive_match(diag, params, uri_to_content, &mut actions);
         }
     }
     send_response(id, Ok::<_, ()>(actions));
@@ -206,26 +209,90 @@ fn handle_missing_trait_impl(
 ///
 /// The diagnostic's `data` carries a JSON array of missing field names, and
 /// its `range` covers the whole MakeStruct expression — so `range.end` sits
-/// just past the closing `}`. Field/expression names in Fix are ASCII, so
-/// LSP UTF-16 columns coincide with char/byte columns at the positions we
-/// care about.
+/// just past the closing `}`.
 fn handle_missing_struct_field(
     diag: &lsp_types::Diagnostic,
     params: &CodeActionParams,
     uri_to_content: &mut Map<Uri, LatestContent>,
     actions: &mut Vec<CodeAction>,
 ) {
-    if diag.data.is_none() {
+    let Some(missing) = names_in_diagnostic_data(diag) else {
         return;
-    }
-    let missing: Vec<String> = match serde_json::from_value(diag.data.as_ref().unwrap().clone()) {
-        Ok(v) => v,
-        Err(_) => return,
     };
-    if missing.is_empty() {
+    let items = missing
+        .iter()
+        .map(|n| format!("{}: ?", n))
+        .collect::<Vec<_>>();
+    let title = if missing.len() == 1 {
+        format!("Add missing field `{}`", missing[0])
+    } else {
+        let list = missing
+            .iter()
+            .map(|n| format!("`{}`", n))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Add missing fields {}", list)
+    };
+    push_brace_insertion_action(diag, params, uri_to_content, &items, title, actions);
+}
+
+/// Offer a quick fix that inserts a `variant(_) => ?` arm for each union
+/// variant a `match` does not cover (e.g. `match opt { some(v) => v }`
+/// missing `none`).
+///
+/// The diagnostic's `data` carries a JSON array of the uncovered variant
+/// names, in declaration order, and its `range` covers the whole `match`
+/// expression — so `range.end` sits just past the closing `}` of the arms.
+fn handle_non_exhaustive_match(
+    diag: &lsp_types::Diagnostic,
+    params: &CodeActionParams,
+    uri_to_content: &mut Map<Uri, LatestContent>,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Some(missing) = names_in_diagnostic_data(diag) else {
         return;
+    };
+    let items = missing
+        .iter()
+        .map(|n| format!("{}(_) => ?", n))
+        .collect::<Vec<_>>();
+    let title = if missing.len() == 1 {
+        format!("Add missing variant `{}`", missing[0])
+    } else {
+        let list = missing
+            .iter()
+            .map(|n| format!("`{}`", n))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Add missing variants {}", list)
+    };
+    push_brace_insertion_action(diag, params, uri_to_content, &items, title, actions);
+}
+
+/// The names carried in the diagnostic's `data` as a JSON array of strings,
+/// or `None` if there is no such array or it is empty.
+fn names_in_diagnostic_data(diag: &lsp_types::Diagnostic) -> Option<Vec<String>> {
+    let names: Vec<String> = serde_json::from_value(diag.data.as_ref()?.clone()).ok()?;
+    if names.is_empty() {
+        return None;
     }
+    Some(names)
+}
 
+/// Push a quick fix titled `title` that inserts the comma-separated `items`
+/// before the closing `}` at the end of the diagnostic's range: the fields of
+/// a struct literal, or the arms of a `match`.
+///
+/// Names in Fix are ASCII, so LSP UTF-16 columns coincide with char/byte
+/// columns at the positions we care about.
+fn push_brace_insertion_action(
+    diag: &lsp_types::Diagnostic,
+    params: &CodeActionParams,
+    uri_to_content: &mut Map<Uri, LatestContent>,
+    items: &[String],
+    title: String,
+    actions: &mut Vec<CodeAction>,
+) {
     let uri = &params.text_document.uri;
     let latest_content = uri_to_content.get(uri);
     if latest_content.is_none() {
@@ -257,19 +324,14 @@ fn handle_missing_struct_field(
         // Single line: insert right after the previous non-whitespace
         // character so any trailing whitespace before `}` (e.g. the space in
         // `Vector3 { x: 1.0 }`) becomes the natural padding after the new
-        // field. Prefix with a separator that matches what precedes us.
+        // items. Prefix with a separator that matches what precedes us.
         let (insert_line, insert_col, prefix) = match last_nonws {
             Some((line, col_after, '{')) => (line, col_after, ""),
             Some((line, col_after, ',')) => (line, col_after, " "),
             Some((line, col_after, _)) => (line, col_after, ", "),
             None => (end_line, brace_col, ""),
         };
-        let fields_str = missing
-            .iter()
-            .map(|n| format!("{}: ?", n))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let new_text = format!("{}{}", prefix, fields_str);
+        let new_text = format!("{}{}", prefix, items.join(", "));
         let pos = Position {
             line: insert_line as u32,
             character: insert_col as u32,
@@ -282,14 +344,14 @@ fn handle_missing_struct_field(
             new_text,
         });
     } else {
-        // Multi-line: emit each new field on its own indented line ending in
-        // a trailing comma. If the previous field has no trailing comma, add
+        // Multi-line: emit each new item on its own indented line ending in
+        // a trailing comma. If the previous item has no trailing comma, add
         // one as a separate, non-overlapping edit.
-        let field_indent = compute_field_indent(&lines, start_line, end_line);
+        let item_indent = compute_field_indent(&lines, start_line, end_line);
         let mut body = String::new();
-        for name in &missing {
-            body.push_str(&field_indent);
-            body.push_str(&format!("{}: ?,\n", name));
+        for item in items {
+            body.push_str(&item_indent);
+            body.push_str(&format!("{},\n", item));
         }
 
         let need_trailing_comma = matches!(last_nonws, Some((_, _, c)) if c != '{' && c != ',');
@@ -324,17 +386,6 @@ fn handle_missing_struct_field(
         });
     }
 
-    let title = if missing.len() == 1 {
-        format!("Add missing field `{}`", missing[0])
-    } else {
-        let list = missing
-            .iter()
-            .map(|n| format!("`{}`", n))
-            .collect::<Vec<_>>()
-            .join(", ");
-        format!("Add missing fields {}", list)
-    };
-
     let action = CodeAction {
         title,
         kind: Some(CodeActionKind::QUICKFIX),
@@ -424,6 +424,7 @@ fn run_completion_elaborate(
         live_source_overrides: Arc::new(overrides),
         target_symbols,
         error_tolerant: true,
+        instantiate: false,
     };
     let mut config = Configuration::diagnostics_mode(diag_config)?;
     config.type_check_cache = typecheck_cache;
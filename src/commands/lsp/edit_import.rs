@@ -4,6 +4,7 @@ use crate::ast::import::{is_accessible, ImportStatement};
 use crate::ast::name::FullName;
 use crate::ast::program::ModuleInfo;
 use crate::constants::STD_NAME;
+use crate::elaboration::lint::UnusedImport;
 use crate::write_log;
 use lsp_types::TextEdit;
 
@@ -50,6 +51,30 @@ pub fn create_text_edits_to_erase_imports(
     text_edits
 }
 
+// Generate TextEdits that remove unused imports from a file: a statement nothing of which is used
+// is erased, and any other statement is rewritten with its unused items left out.
+pub fn create_text_edits_to_remove_unused_imports(
+    content: &str,
+    unused_imports: &[UnusedImport],
+) -> Vec<TextEdit> {
+    let erased = unused_imports
+        .iter()
+        .filter(|unused| unused.retained.is_none())
+        .map(|unused| unused.stmt.clone())
+        .collect::<Vec<_>>();
+    let mut text_edits = create_text_edits_to_erase_imports(content, &erased);
+    for unused in unused_imports {
+        let (Some(retained), Some(source)) = (&unused.retained, &unused.stmt.source) else {
+            continue;
+        };
+        text_edits.push(TextEdit {
+            range: span_to_range(source),
+            new_text: retained.stringify(),
+        });
+    }
+    text_edits
+}
+
 // Generate a TextEdit that inserts import statements at the end of the module definition.
 // Returns a TextEdit object that inserts the import statements.
 pub fn create_text_edit_to_insert_imports(
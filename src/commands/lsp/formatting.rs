@@ -0,0 +1,121 @@
+use super::server::{send_response, LatestContent};
+use crate::edit::format::{format_source_lines, join_formatted_lines, FormattedLines};
+use crate::misc::Map;
+use crate::write_log;
+use lsp_types::{
+    DocumentFormattingParams, DocumentRangeFormattingParams, Position, Range, TextEdit, Uri,
+};
+
+// Handle "textDocument/formatting" method.
+//
+// The whole buffer is replaced by one edit. When the buffer does not parse, the response is empty,
+// so that the editor leaves the buffer as it is.
+pub(super) fn handle_formatting(
+    id: u32,
+    params: &DocumentFormattingParams,
+    uri_to_content: &Map<Uri, LatestContent>,
+) {
+    let Some((content, lines)) = format_latest_content(&params.text_document.uri, uri_to_content)
+    else {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    };
+    let formatted = join_formatted_lines(&lines);
+    if formatted == content {
+        send_response(id, Ok::<_, ()>(Vec::<TextEdit>::new()));
+        return;
+    }
+    let edit = TextEdit {
+        range: Range {
+            start: Position::new(0, 0),
+            end: end_of_content(content),
+        },
+        new_text: formatted,
+    };
+    send_response(id, Ok::<_, ()>(vec![edit]))
+}
+
+// Handle "textDocument/rangeFormatting" method.
+//
+// The buffer is formatted as a whole, since the indentation of a line depends on the lines before
+// it, and only the lines that intersect the requested range are replaced. A token spanning lines,
+// such as a block comment, is never cut: its lines are replaced together.
+pub(super) fn handle_range_formatting(
+    id: u32,
+    params: &DocumentRangeFormattingParams,
+    uri_to_content: &Map<Uri, LatestContent>,
+) {
+    let Some((content, lines)) = format_latest_content(&params.text_document.uri, uri_to_content)
+    else {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    };
+
+    // A range ending at the head of a line does not select that line.
+    let first_line = params.range.start.line as usize;
+    let mut last_line = params.range.end.line as usize;
+    if params.range.end.character == 0 && last_line > first_line {
+        last_line -= 1;
+    }
+    let selected: Vec<FormattedLines> = lines
+        .into_iter()
+        .filter(|line| line.last_line >= first_line && line.first_line <= last_line)
+        .collect();
+    if selected.is_empty() {
+        send_response(id, Ok::<_, ()>(Vec::<TextEdit>::new()));
+        return;
+    }
+
+    let replaced_first = selected.first().unwrap().first_line;
+    let replaced_last = selected.last().unwrap().last_line;
+    let original_lines: Vec<&str> = content.split('\n').collect();
+    let original = original_lines[replaced_first..=replaced_last].join("\n") + "\n";
+    let formatted = join_formatted_lines(&selected);
+    if formatted == original {
+        send_response(id, Ok::<_, ()>(Vec::<TextEdit>::new()));
+        return;
+    }
+    let end = if replaced_last + 1 < original_lines.len() {
+        Position::new(replaced_last as u32 + 1, 0)
+    } else {
+        end_of_content(content)
+    };
+    let edit = TextEdit {
+        range: Range {
+            start: Position::new(replaced_first as u32, 0),
+            end,
+        },
+        new_text: formatted,
+    };
+    send_response(id, Ok::<_, ()>(vec![edit]))
+}
+
+// Formats the latest content of the document `uri`. Returns `None` when the content is unknown or
+// cannot be formatted; the reason is written to the log.
+fn format_latest_content<'c>(
+    uri: &Uri,
+    uri_to_content: &'c Map<Uri, LatestContent>,
+) -> Option<(&'c str, Vec<FormattedLines>)> {
+    let Some(latest) = uri_to_content.get(uri) else {
+        write_log!("No stored content for the uri \"{}\".", uri.as_str());
+        return None;
+    };
+    match format_source_lines(&latest.path, &latest.content) {
+        Ok(lines) => Some((&latest.content, lines)),
+        Err(errs) => {
+            write_log!(
+                "Failed to format \"{}\": {}",
+                latest.path.display(),
+                errs.to_string()
+            );
+            None
+        }
+    }
+}
+
+// The position just after the last character of `content`, in UTF-16 code units.
+fn end_of_content(content: &str) -> Position {
+    let line = content.matches('\n').count() as u32;
+    let last_line = content.rsplit('\n').next().unwrap_or("");
+    Position::new(line, last_line.encode_utf16().count() as u32)
+}
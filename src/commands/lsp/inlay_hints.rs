@@ -0,0 +1,290 @@
+// LSP `textDocument/inlayHint` support.
+//
+// A hint shows the type inferred for a local binder that is written without a type annotation:
+// the binder of a `let`, the parameter of a lambda `|x| ...`, and a variable of a pattern in a
+// `match` arm. The types are read from the typed AST of the last successful elaboration, so the
+// hints are positioned against the snapshot the program was elaborated from, and then moved onto
+// the live buffer through a snapshot-to-live line diff, as the semantic token overlay is. A hint on
+// a line edited since that elaboration is dropped rather than shown at a wrong place or with a
+// stale type; it comes back with the next successful elaboration.
+//
+// Lambdas desugar to `|#param| let pat = #param in body`, so a lambda parameter is the pattern of
+// a `let` whose bound value is the `#param` variable, and is told apart from a `let` binder by it.
+
+use super::semantic_tokens::{byte_to_line_col_utf16, line_start_offsets};
+use super::server::{send_response, DiagnosticsResult, LatestContent};
+use super::util::{corresponding_line_map, uri_to_path};
+use crate::ast::expr::{Expr, ExprNode};
+use crate::ast::pattern::{Pattern, PatternNode};
+use crate::ast::program::SymbolExpr;
+use crate::constants::PARAM_NAME;
+use crate::misc::{to_absolute_path, Map};
+use crate::parse::parser::{is_token_of, TokenCategory};
+use crate::parse::sourcefile::Span;
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams, Position, Uri};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Which kinds of binders get a hint. Set by the client through the `fix.inlayHints.*` settings of
+/// `workspace/didChangeConfiguration`; all of them are shown by default.
+pub struct InlayHintsConfig {
+    /// Show the type of a `let` binder (`fix.inlayHints.letBindings`).
+    pub let_bindings: bool,
+    /// Show the type of a lambda parameter (`fix.inlayHints.lambdaParameters`).
+    pub lambda_parameters: bool,
+    /// Show the type of a variable of a `match` arm pattern (`fix.inlayHints.patternVariables`).
+    pub pattern_variables: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        InlayHintsConfig {
+            let_bindings: true,
+            lambda_parameters: true,
+            pattern_variables: true,
+        }
+    }
+}
+
+impl InlayHintsConfig {
+    /// Apply the client's `fix.inlayHints.*` settings from a `workspace/didChangeConfiguration`
+    /// payload. Absent keys are left unchanged. As for the `fix.analyze.*` settings, the payload is
+    /// accepted both as the full settings tree and as the already-unwrapped `fix` section.
+    pub fn apply(&mut self, settings: &Value) {
+        let lookup = |key: &str| -> Option<bool> {
+            settings
+                .pointer(&format!("/fix/inlayHints/{}", key))
+                .or_else(|| settings.pointer(&format!("/inlayHints/{}", key)))
+                .and_then(|v| v.as_bool())
+        };
+        if let Some(on) = lookup("letBindings") {
+            self.let_bindings = on;
+        }
+        if let Some(on) = lookup("lambdaParameters") {
+            self.lambda_parameters = on;
+        }
+        if let Some(on) = lookup("patternVariables") {
+            self.pattern_variables = on;
+        }
+    }
+}
+
+/// The kinds of binders a hint is shown for.
+#[derive(Clone, Copy)]
+enum BinderKind {
+    Let,
+    LambdaParameter,
+    PatternVariable,
+}
+
+// Handle "textDocument/inlayHint" method.
+pub(super) fn handle_inlay_hint(
+    id: u32,
+    params: &InlayHintParams,
+    diag: &DiagnosticsResult,
+    config: &InlayHintsConfig,
+    uri_to_content: &Map<Uri, LatestContent>,
+) {
+    let uri = &params.text_document.uri;
+    let hints = (|| {
+        let live = &uri_to_content.get(uri)?.content;
+        let abs_file = to_absolute_path(&uri_to_path(uri)).ok()?;
+        let snapshot = diag.user_source_contents.get(&abs_file)?;
+
+        let mut collector = HintCollector {
+            config,
+            abs_file: &abs_file,
+            snapshot,
+            file_cache: Map::default(),
+            out: vec![],
+        };
+        collector.collect_program(diag);
+
+        // Move the hints from the snapshot onto the live buffer, keeping those on unchanged lines
+        // within the requested range.
+        let line_starts = line_start_offsets(snapshot);
+        let line_map = corresponding_line_map(snapshot, live);
+        // A binder of a guarded `match` arm is written once and bound twice, in the guard and in
+        // the arm's value, so its hint is collected twice.
+        let mut out = collector.out;
+        out.sort();
+        out.dedup();
+        let mut hints = vec![];
+        for (byte, label) in out {
+            let (snap_line, col) = byte_to_line_col_utf16(snapshot, &line_starts, byte);
+            let Some(Some(live_line)) = line_map.get(snap_line as usize) else {
+                continue;
+            };
+            let position = Position::new(*live_line, col);
+            if position < params.range.start || position > params.range.end {
+                continue;
+            }
+            hints.push(InlayHint {
+                position,
+                label: InlayHintLabel::String(label),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: None,
+                data: None,
+            });
+        }
+        hints.sort_by_key(|hint| hint.position);
+        Some(hints)
+    })();
+    send_response(id, Ok::<_, ()>(hints.unwrap_or_default()))
+}
+
+/// Walks the typed program and collects the hints of one file, each as the byte offset in the
+/// snapshot it is shown at and its label.
+struct HintCollector<'a> {
+    /// The kinds of binders to collect hints for.
+    config: &'a InlayHintsConfig,
+    /// The file whose hints are collected; spans from other files are skipped.
+    abs_file: &'a Path,
+    /// The source text the spans index into (the elaboration snapshot).
+    snapshot: &'a str,
+    /// Memoizes whether a source file path is `abs_file`.
+    file_cache: Map<PathBuf, bool>,
+    /// The collected hints.
+    out: Vec<(usize, String)>,
+}
+
+impl<'a> HintCollector<'a> {
+    /// Collect the hints of the bodies of the global values defined in the file.
+    fn collect_program(&mut self, diag: &DiagnosticsResult) {
+        for (_name, gv) in &diag.program.global_values {
+            if gv.compiler_defined_method {
+                continue;
+            }
+            let roots: Vec<&Arc<ExprNode>> = match &gv.expr {
+                SymbolExpr::Simple(te) => vec![&te.expr],
+                SymbolExpr::Method(impls) => impls.iter().map(|m| &m.expr.expr).collect(),
+            };
+            for root in roots {
+                if let Some(src) = &root.source {
+                    if self.is_in_file(src) {
+                        self.collect_expr(root);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Walk an expression tree, collecting the hints of the binders in it.
+    fn collect_expr(&mut self, expr: &Arc<ExprNode>) {
+        match &*expr.expr {
+            Expr::Var(_) | Expr::LLVM(_) => {}
+            Expr::App(func, args) => {
+                self.collect_expr(func);
+                for a in args {
+                    self.collect_expr(a);
+                }
+            }
+            Expr::Lam(_args, body) => self.collect_expr(body),
+            Expr::Let(pat, bound, val) => {
+                let kind = match &*bound.expr {
+                    Expr::Var(v) if v.name.is_local() && v.name.name == PARAM_NAME => {
+                        BinderKind::LambdaParameter
+                    }
+                    _ => BinderKind::Let,
+                };
+                self.collect_pattern(pat, kind);
+                self.collect_expr(bound);
+                self.collect_expr(val);
+            }
+            Expr::If(cond, then_e, else_e) => {
+                self.collect_expr(cond);
+                self.collect_expr(then_e);
+                self.collect_expr(else_e);
+            }
+            Expr::Match(cond, arms) => {
+                self.collect_expr(cond);
+                for (pat, val) in arms {
+                    self.collect_pattern(pat, BinderKind::PatternVariable);
+                    self.collect_expr(val);
+                }
+            }
+            Expr::TyAnno(e, _) => self.collect_expr(e),
+            Expr::MakeStruct(_, fields) => {
+                for (_name, _name_span, e) in fields {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::ArrayLit(elems) => {
+                for e in elems {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::FFICall(_, _, _, _, args, _) => {
+                for e in args {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::Eval(side, main) => {
+                self.collect_expr(side);
+                self.collect_expr(main);
+            }
+        }
+    }
+
+    /// Walk a pattern tree, collecting a hint after each variable written without a type
+    /// annotation.
+    fn collect_pattern(&mut self, pat: &PatternNode, kind: BinderKind) {
+        match &pat.pattern {
+            Pattern::Var(v, None) => {
+                let enabled = match kind {
+                    BinderKind::Let => self.config.let_bindings,
+                    BinderKind::LambdaParameter => self.config.lambda_parameters,
+                    BinderKind::PatternVariable => self.config.pattern_variables,
+                };
+                if !enabled || v.name.is_pattern_wildcard() || v.name.name.starts_with('#') {
+                    return;
+                }
+                let (Some(span), Some(ty)) = (&pat.info.source, &pat.info.type_) else {
+                    return;
+                };
+                // The span of a variable written without an annotation is its name; check it, so
+                // that a binder the compiler made up at a user's span gets no hint.
+                if !self.is_in_file(span) {
+                    return;
+                }
+                if !self
+                    .span_text(span)
+                    .map_or(false, |text| is_token_of(text, TokenCategory::Name))
+                {
+                    return;
+                }
+                self.out
+                    .push((span.end, format!(": {}", ty.to_string_normalize())));
+            }
+            Pattern::Var(_, Some(_)) | Pattern::Lit(_, _) => {}
+            Pattern::Struct(_, fields) => {
+                for (_name, _name_span, sub) in fields {
+                    self.collect_pattern(sub, kind);
+                }
+            }
+            Pattern::Union(_, _, sub) => self.collect_pattern(sub, kind),
+        }
+    }
+
+    /// Whether `span` belongs to the file being collected. Results are cached per source file path.
+    fn is_in_file(&mut self, span: &Span) -> bool {
+        let key = &span.input.file_path;
+        if let Some(is_in_file) = self.file_cache.get(key) {
+            return *is_in_file;
+        }
+        let is_in_file = to_absolute_path(key)
+            .map(|p| p.as_path() == self.abs_file)
+            .unwrap_or(false);
+        self.file_cache.insert(key.clone(), is_in_file);
+        is_in_file
+    }
+
+    /// The snapshot text of `span`, or `None` if it is out of bounds.
+    fn span_text(&self, span: &Span) -> Option<&'a str> {
+        self.snapshot.get(span.start..span.end)
+    }
+}
@@ -2,11 +2,14 @@ pub mod code_action;
 pub mod completion;
 pub mod document_symbol;
 pub mod edit_import;
+pub mod formatting;
 pub mod goto_definition;
 pub mod hover;
+pub mod inlay_hints;
 pub mod references;
 pub mod rename;
 pub mod semantic_tokens;
 pub mod server;
+pub mod signature_help;
 pub mod util;
 pub mod workspace_symbol;
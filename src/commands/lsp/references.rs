@@ -472,6 +472,7 @@ fn collect_pattern_var_refs(pat: &Arc<PatternNode>, target: &FullName, refs: &mu
         Pattern::Union(_, _, sub_pat) => {
             collect_pattern_var_refs(sub_pat, target, refs);
         }
+        Pattern::Lit(_, _) => {}
     }
 }
 
@@ -571,6 +572,7 @@ fn collect_pattern_type_refs(pat: &Arc<PatternNode>, target: &TyCon, refs: &mut
         Pattern::Union(_, _, sub_pat) => {
             collect_pattern_type_refs(sub_pat, target, refs);
         }
+        Pattern::Lit(_, _) => {}
     }
 }
 
@@ -895,7 +897,7 @@ fn collect_pattern_bare_field_occs(
     occs: &mut Vec<FieldOccurrence>,
 ) {
     match &pat.pattern {
-        Pattern::Var(_, _) => {}
+        Pattern::Var(_, _) | Pattern::Lit(_, _) => {}
         Pattern::Struct(pat_tc, fields) => {
             if pat_tc.as_ref() == tc {
                 for (fname, fname_src, _) in fields {
@@ -211,7 +211,7 @@ fn merge_overlay(
 
 /// The byte offset at which each line of `content` begins (index `i` is line
 /// `i`, 0-based).
-fn line_start_offsets(content: &str) -> Vec<usize> {
+pub(super) fn line_start_offsets(content: &str) -> Vec<usize> {
     let mut starts = vec![0usize];
     for (i, b) in content.bytes().enumerate() {
         if b == b'\n' {
@@ -226,7 +226,11 @@ fn line_start_offsets(content: &str) -> Vec<usize> {
 /// # Arguments
 /// * `line_starts` — the per-line byte offsets from `line_start_offsets`,
 ///   reused so each conversion avoids a fresh scan of `content`.
-fn byte_to_line_col_utf16(content: &str, line_starts: &[usize], byte: usize) -> (u32, u32) {
+pub(super) fn byte_to_line_col_utf16(
+    content: &str,
+    line_starts: &[usize],
+    byte: usize,
+) -> (u32, u32) {
     let line = match line_starts.binary_search(&byte) {
         Ok(i) => i,
         Err(i) => i - 1,
@@ -572,6 +576,8 @@ impl<'a> Overlay<'a> {
                 }
                 self.collect_pattern(sub);
             }
+            // The base layer colors the literal as it does one in an expression.
+            Pattern::Lit(_, _) => {}
         }
     }
 
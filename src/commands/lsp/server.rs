@@ -1,20 +1,25 @@
 use super::code_action;
 use super::completion;
 use super::document_symbol;
+use super::formatting;
 use super::goto_definition;
 use super::hover;
+use super::inlay_hints::{self, InlayHintsConfig};
 use super::references;
 use super::rename;
 use super::semantic_tokens;
+use super::signature_help;
 use super::util::{get_current_dir, path_to_uri, span_to_location, span_to_range, uri_to_path};
 use super::workspace_symbol;
 use crate::ast::import::ImportStatement;
 use crate::ast::program::{ModuleInfo, Program};
+use crate::build::build_object_files::explain_uniqueness_of;
 use crate::configuration::{BuildConfigType, Configuration, DiagnosticsConfig};
 use crate::constants::PROJECT_FILE_PATH;
 use crate::dependency::lockfile::LockFileType;
 use crate::elaboration::elaborate_via_config;
-use crate::elaboration::typecheckcache::{self, SharedTypeCheckCache};
+use crate::elaboration::lint::LINT_CODES;
+use crate::elaboration::typecheckcache::{self, FileCache, SharedTypeCheckCache};
 use crate::error::{any_to_string, Error, Errors, Severity, WARN_DEPRECATED};
 use crate::metafiles::project_file::ProjectFile;
 use crate::misc::{spawn_compiler_thread, to_absolute_path, Map, Set};
@@ -25,16 +30,18 @@ use lsp_types::{
     CallHierarchyServerCapability, CodeActionParams, CodeActionProviderCapability, CompletionItem,
     CompletionOptions, CompletionParams, Diagnostic, DiagnosticRelatedInformation,
     DiagnosticSeverity, DiagnosticTag, DidChangeConfigurationParams, DidChangeTextDocumentParams,
-    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentSymbolParams,
-    GotoDefinitionParams, HoverParams, HoverProviderCapability, InitializeParams, InitializeResult,
-    InitializedParams, NumberOrString, OneOf, Position, PositionEncodingKind, ProgressParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
+    DocumentRangeFormattingParams, DocumentSymbolParams, GotoDefinitionParams, HoverParams,
+    HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams,
+    InlayHintParams, NumberOrString, OneOf, Position, PositionEncodingKind, ProgressParams,
     ProgressParamsValue, ProgressToken, PublishDiagnosticsParams, Range, ReferenceParams,
     RenameOptions, RenameParams, SaveOptions, SemanticTokensFullOptions, SemanticTokensOptions,
     SemanticTokensParams, SemanticTokensServerCapabilities, ServerCapabilities,
-    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind,
-    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Uri, WorkDoneProgress,
-    WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
-    WorkDoneProgressOptions, WorkspaceSymbolParams,
+    SignatureHelpOptions, SignatureHelpParams, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Uri, WorkDoneProgress, WorkDoneProgressBegin,
+    WorkDoneProgressCreateParams, WorkDoneProgressEnd, WorkDoneProgressOptions,
+    WorkspaceSymbolParams,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
@@ -46,7 +53,7 @@ use std::{
     io::{Read, Write},
     path::PathBuf,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         mpsc::{self, Receiver, Sender},
         Arc,
     },
@@ -186,17 +193,25 @@ pub fn launch_language_server() {
     // `diagnostics_thread`) so that feature requests arriving before
     // the first successful diagnostics run — or while the saved
     // buffer doesn't parse and `last_diag` therefore stays `None` —
-    // can still drive their own elaborate without paying disk I/O
-    // for every cache lookup.
-    let typecheck_cache: SharedTypeCheckCache = Arc::new(typecheckcache::MemoryCache::new());
+    // can still drive their own elaborate. The entries are held in
+    // memory in front of the project's file cache: the session starts
+    // from what builds and checks of the project have filed, and a
+    // lookup repeated within the session stays off the disk.
+    let typecheck_cache: SharedTypeCheckCache = Arc::new(typecheckcache::MemoryAndFileCache::new());
 
     // Analyze (diagnostics) settings, configurable from the client via
     // `workspace/didChangeConfiguration`. `analyze_debounce_ms` is shared
     // with the diagnostics thread (which reads it before each debounce
     // wait); `analyze_on_save` gates whether saving triggers a run and is
-    // read only on this thread.
+    // read only on this thread; `explain_uniqueness` is shared with the
+    // diagnostics thread, which reads it at the end of each run.
     let analyze_debounce_ms: Arc<AtomicU64> = Arc::new(AtomicU64::new(DEFAULT_ANALYZE_DELAY_MS));
     let mut analyze_on_save: bool = DEFAULT_ANALYZE_ON_SAVE;
+    let explain_uniqueness: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+
+    // Inlay hint settings, configurable from the client via
+    // `workspace/didChangeConfiguration` (`fix.inlayHints.*`).
+    let mut inlay_hints_config = InlayHintsConfig::default();
 
     // The last diagnostics result.
     let mut last_diag: Option<DiagnosticsResult> = None;
@@ -236,6 +251,13 @@ pub fn launch_language_server() {
                 "workspace/semanticTokens/refresh".to_string(),
                 None::<()>,
             );
+            // Likewise for inlay hints, whose types come from the program.
+            server_request_id += 1;
+            send_request(
+                server_request_id,
+                "workspace/inlayHint/refresh".to_string(),
+                None::<()>,
+            );
         }
         if last_diag.is_some() {
             // If there are pending document symbol requests, process them.
@@ -373,6 +395,7 @@ pub fn launch_language_server() {
                     diag_res_send.clone(),
                     typecheck_cache.clone(),
                     analyze_debounce_ms.clone(),
+                    explain_uniqueness.clone(),
                 );
             } else if method == "shutdown" {
                 let id = parse_id(&message, method);
@@ -423,10 +446,20 @@ pub fn launch_language_server() {
                 if params.is_none() {
                     continue;
                 }
+                let settings = &params.unwrap().settings;
                 apply_analyze_config(
-                    &params.unwrap().settings,
+                    settings,
                     &analyze_debounce_ms,
                     &mut analyze_on_save,
+                    &explain_uniqueness,
+                );
+                inlay_hints_config.apply(settings);
+                // Have the client re-request the hints under the new settings.
+                server_request_id += 1;
+                send_request(
+                    server_request_id,
+                    "workspace/inlayHint/refresh".to_string(),
+                    None::<()>,
                 );
             } else if method == "textDocument/completion" {
                 // Don't gate on `last_diag.is_some()` — the dot-context
@@ -492,6 +525,45 @@ pub fn launch_language_server() {
                     program,
                     &uri_to_latest_content,
                 );
+            } else if method == "textDocument/signatureHelp" {
+                if last_diag.is_none() {
+                    continue;
+                }
+                let program = &last_diag.as_ref().unwrap().program;
+                let id = parse_id(&message, method);
+                if id.is_none() {
+                    continue;
+                }
+                let params: Option<SignatureHelpParams> = parase_params(message.params.unwrap());
+                if params.is_none() {
+                    continue;
+                }
+                signature_help::handle_signature_help(
+                    id.unwrap(),
+                    &params.unwrap(),
+                    program,
+                    &mut uri_to_latest_content,
+                );
+            } else if method == "textDocument/inlayHint" {
+                if last_diag.is_none() {
+                    continue;
+                }
+                let diag = last_diag.as_ref().unwrap();
+                let id = parse_id(&message, method);
+                if id.is_none() {
+                    continue;
+                }
+                let params: Option<InlayHintParams> = parase_params(message.params.unwrap());
+                if params.is_none() {
+                    continue;
+                }
+                inlay_hints::handle_inlay_hint(
+                    id.unwrap(),
+                    &params.unwrap(),
+                    diag,
+                    &inlay_hints_config,
+                    &uri_to_latest_content,
+                );
             } else if method == "textDocument/definition" {
                 if last_diag.is_none() {
                     continue;
@@ -666,6 +738,37 @@ pub fn launch_language_server() {
                     continue;
                 }
                 references::handle_call_hierarchy_outgoing(id.unwrap(), &params.unwrap(), program);
+            } else if method == "textDocument/formatting" {
+                // Formatting works on the live buffer alone, so it is not gated on `last_diag`.
+                let id = parse_id(&message, method);
+                if id.is_none() {
+                    continue;
+                }
+                let params: Option<DocumentFormattingParams> =
+                    parase_params(message.params.unwrap());
+                if params.is_none() {
+                    continue;
+                }
+                formatting::handle_formatting(
+                    id.unwrap(),
+                    &params.unwrap(),
+                    &uri_to_latest_content,
+                );
+            } else if method == "textDocument/rangeFormatting" {
+                let id = parse_id(&message, method);
+                if id.is_none() {
+                    continue;
+                }
+                let params: Option<DocumentRangeFormattingParams> =
+                    parase_params(message.params.unwrap());
+                if params.is_none() {
+                    continue;
+                }
+                formatting::handle_range_formatting(
+                    id.unwrap(),
+                    &params.unwrap(),
+                    &uri_to_latest_content,
+                );
             } else if method == "textDocument/semanticTokens/full" {
                 // Intentionally not gated on `last_diag`: semantic tokens are
                 // produced by a never-failing lexer over the live buffer, so
@@ -792,7 +895,11 @@ fn handle_initialize(id: u32, _params: &InitializeParams) {
                 work_done_progress_options: WorkDoneProgressOptions::default(),
                 completion_item: None,
             }),
-            signature_help_provider: None,
+            signature_help_provider: Some(SignatureHelpOptions {
+                trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                retrigger_characters: None,
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            }),
             definition_provider: Some(OneOf::Left(true)),
             type_definition_provider: None,
             implementation_provider: None,
@@ -802,8 +909,8 @@ fn handle_initialize(id: u32, _params: &InitializeParams) {
             workspace_symbol_provider: Some(OneOf::Left(true)),
             code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
             code_lens_provider: None,
-            document_formatting_provider: None,
-            document_range_formatting_provider: None,
+            document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
             document_on_type_formatting_provider: None,
             rename_provider: Some(OneOf::Right(RenameOptions {
                 prepare_provider: Some(true),
@@ -829,7 +936,7 @@ fn handle_initialize(id: u32, _params: &InitializeParams) {
             moniker_provider: None,
             linked_editing_range_provider: None,
             inline_value_provider: None,
-            inlay_hint_provider: None,
+            inlay_hint_provider: Some(OneOf::Left(true)),
             diagnostic_provider: None,
             experimental: None,
         },
@@ -858,13 +965,20 @@ fn handle_initialized(
     diag_res_send: Sender<DiagnosticsResult>,
     typecheck_cache: SharedTypeCheckCache,
     debounce_ms: Arc<AtomicU64>,
+    explain_uniqueness: Arc<AtomicBool>,
 ) {
     // Launch the diagnostics thread. It type-checks the user's program, recursing over an
     // expression tree of unbounded depth, so it gets the same deep-recursion stack as the batch
     // compiler's threads.
     spawn_compiler_thread(move || {
         let res = std::panic::catch_unwind(move || {
-            diagnostics_thread(diag_req_recv, diag_res_send, typecheck_cache, debounce_ms);
+            diagnostics_thread(
+                diag_req_recv,
+                diag_res_send,
+                typecheck_cache,
+                debounce_ms,
+                explain_uniqueness,
+            );
         });
         if res.is_err() {
             // If a panic occurs in the diagnostics thread,
@@ -985,7 +1099,12 @@ fn handle_textdocument_did_save(
 /// unchanged. The payload is accepted both as the full settings tree
 /// (`/fix/analyze/...`) and as the already-unwrapped `fix` section
 /// (`/analyze/...`), since clients differ in what they send.
-fn apply_analyze_config(settings: &Value, debounce_ms: &AtomicU64, analyze_on_save: &mut bool) {
+fn apply_analyze_config(
+    settings: &Value,
+    debounce_ms: &AtomicU64,
+    analyze_on_save: &mut bool,
+    explain_uniqueness: &AtomicBool,
+) {
     let lookup = |key: &str| -> Option<&Value> {
         settings
             .pointer(&format!("/fix/analyze/{}", key))
@@ -999,12 +1118,15 @@ fn apply_analyze_config(settings: &Value, debounce_ms: &AtomicU64, analyze_on_sa
     if let Some(on_save) = lookup("onSave").and_then(|v| v.as_bool()) {
         *analyze_on_save = on_save;
     }
+    if let Some(explain) = lookup("explainUniqueness").and_then(|v| v.as_bool()) {
+        explain_uniqueness.store(explain, Ordering::Relaxed);
+    }
 }
 
 /// Default debounce window: how long the diagnostics thread waits for
 /// input to go quiet before it runs. Overridable via the client setting
 /// `fix.analyze.delayMs`.
-const DEFAULT_ANALYZE_DELAY_MS: u64 = 400;
+pub(crate) const DEFAULT_ANALYZE_DELAY_MS: u64 = 400;
 
 /// Default for whether saving a file triggers analysis. Overridable via
 /// the client setting `fix.analyze.onSave`.
@@ -1027,7 +1149,12 @@ fn diagnostics_thread(
     res_send: Sender<DiagnosticsResult>,
     typecheck_cache: SharedTypeCheckCache,
     debounce_ms: Arc<AtomicU64>,
+    explain_uniqueness: Arc<AtomicBool>,
 ) {
+    // Remove the type-check cache entries the sessions before this one left over, once, off the
+    // runs that follow: the cache grows by at most one session's edits before it is pruned again.
+    FileCache::new().prune_current_build();
+
     let mut prev_err_paths = Set::default();
     // The latest coalesced request waiting to run, if any.
     let mut pending: Option<Arc<Map<PathBuf, String>>> = None;
@@ -1048,6 +1175,7 @@ fn diagnostics_thread(
                         &typecheck_cache,
                         &res_send,
                         &mut prev_err_paths,
+                        explain_uniqueness.load(Ordering::Relaxed),
                     );
                     continue;
                 }
@@ -1068,24 +1196,25 @@ fn diagnostics_thread(
 /// `textDocument/publishDiagnostics` notifications, and forward the
 /// elaborated program to the main thread. `prev_err_paths` carries the
 /// set of files that had diagnostics last time so cleared files can be
-/// reset; it is updated in place.
+/// reset; it is updated in place. With `explain_uniqueness` (the client
+/// setting `fix.analyze.explainUniqueness`), a run that finds no error
+/// also publishes the notes of `--explain-uniqueness` as hints.
 fn run_diagnostics_and_publish(
     overrides: Arc<Map<PathBuf, String>>,
     typecheck_cache: &SharedTypeCheckCache,
     res_send: &Sender<DiagnosticsResult>,
     prev_err_paths: &mut Set<PathBuf>,
+    explain_uniqueness: bool,
 ) {
     const WORK_DONE_PROGRESS_TOKEN: &str = "diagnostics";
     send_work_done_progress_create(WORK_DONE_PROGRESS_TOKEN, 0);
     send_work_done_progress_begin(WORK_DONE_PROGRESS_TOKEN, "Analyzing");
 
     // Run diagnostics against the coalesced live overrides.
-    let res = run_diagnostics(typecheck_cache.clone(), overrides);
-
-    send_work_done_progress_end(WORK_DONE_PROGRESS_TOKEN);
+    let res = run_diagnostics(typecheck_cache.clone(), overrides.clone());
 
     // Send the result to the main thread and language client.
-    let errs = match res {
+    let mut errs = match res {
         Ok(mut res) => {
             let errs = mem::replace(&mut res.program.deferred_errors, Errors::empty());
             res_send.send(res).unwrap();
@@ -1093,6 +1222,14 @@ fn run_diagnostics_and_publish(
         }
         Err(errs) => errs,
     };
+    if explain_uniqueness && !errs.has_error() {
+        errs.append(explain_uniqueness_diagnostics(
+            typecheck_cache.clone(),
+            overrides,
+        ));
+    }
+
+    send_work_done_progress_end(WORK_DONE_PROGRESS_TOKEN);
     *prev_err_paths = send_diagnostics_notification(errs, mem::take(prev_err_paths));
 }
 
@@ -1236,11 +1373,13 @@ fn error_to_diagnostics(err: &Error, cdir: &PathBuf) -> Diagnostic {
     let severity = match err.severity {
         Severity::Error => DiagnosticSeverity::ERROR,
         Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Note => DiagnosticSeverity::HINT,
     };
-    let tags = if err.code == Some(WARN_DEPRECATED) {
-        Some(vec![DiagnosticTag::DEPRECATED])
-    } else {
-        None
+    // Editors render deprecated code struck through, and the code a lint finds unused faded out.
+    let tags = match err.code {
+        Some(WARN_DEPRECATED) => Some(vec![DiagnosticTag::DEPRECATED]),
+        Some(code) if LINT_CODES.contains(&code) => Some(vec![DiagnosticTag::UNNECESSARY]),
+        _ => None,
     };
     Diagnostic {
         range,
@@ -1355,14 +1494,31 @@ pub fn run_diagnostics(
         }
     }
 
-    // Create the configuration. The live overrides swap in unsaved buffer
-    // contents for their paths; everything else is read from disk.
-    let mut config = Configuration::diagnostics_mode(DiagnosticsConfig {
+    // Build the file and get the errors.
+    let diag_config = DiagnosticsConfig {
         files,
         live_source_overrides: live_overrides,
         ..Default::default()
-    })?;
-    config.type_check_cache = typecheck_cache.clone();
+    };
+    let config = diagnostics_config(&proj_file, diag_config, typecheck_cache)?;
+    let program = elaborate_via_config(&config)?;
+
+    Ok(DiagnosticsResult {
+        program,
+        user_source_contents,
+    })
+}
+
+/// Create the configuration a diagnostics run under `diag_config`
+/// elaborates with. The live overrides of `diag_config` swap in unsaved
+/// buffer contents for their paths; everything else is read from disk.
+fn diagnostics_config(
+    proj_file: &ProjectFile,
+    diag_config: DiagnosticsConfig,
+    typecheck_cache: SharedTypeCheckCache,
+) -> Result<Configuration, Errors> {
+    let mut config = Configuration::diagnostics_mode(diag_config)?;
+    config.type_check_cache = typecheck_cache;
 
     // Set up the configuration by the project file.
     proj_file.set_config(&mut config)?;
@@ -1373,13 +1529,32 @@ pub fn run_diagnostics(
         .open_or_auto_update_lock_file(LockFileType::Lsp)?
         .set_config(&mut config)?;
 
-    // Build the file and get the errors.
-    let program = elaborate_via_config(&config)?;
+    Ok(config)
+}
 
-    Ok(DiagnosticsResult {
-        program,
-        user_source_contents,
-    })
+/// The notes of `--explain-uniqueness` for the program as the live
+/// buffers have it, to be published as hints. The program the diagnostics
+/// run elaborated has gone to the main thread and stops short of
+/// instantiation, so the program is elaborated again, this time
+/// instantiated; the typecheck cache spares the type inference. A failure,
+/// such as a project without `main`, yields no notes.
+fn explain_uniqueness_diagnostics(
+    typecheck_cache: SharedTypeCheckCache,
+    live_overrides: Arc<Map<PathBuf, String>>,
+) -> Errors {
+    let notes = || -> Result<Errors, Errors> {
+        let proj_file = ProjectFile::read_root_file()?;
+        let diag_config = DiagnosticsConfig {
+            files: proj_file.get_files(BuildConfigType::Test),
+            live_source_overrides: live_overrides,
+            instantiate: true,
+            ..Default::default()
+        };
+        let config = diagnostics_config(&proj_file, diag_config, typecheck_cache)?;
+        let program = elaborate_via_config(&config)?;
+        Ok(explain_uniqueness_of(program, &config))
+    };
+    notes().unwrap_or_else(|_| Errors::empty())
 }
 
 // Create work done progress.
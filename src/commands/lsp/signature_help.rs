@@ -0,0 +1,365 @@
+// LSP `textDocument/signatureHelp` support.
+//
+// The call the cursor is in is found textually in the live buffer, since the buffer being typed
+// rarely parses: the scan goes back from the cursor to the innermost unclosed `(`, counting the
+// arguments already written, and takes the name written before it as the callee. Arguments given
+// in earlier parentheses of the same call (`f(x)(y, |`) count too, as Fix applies a function to
+// them one by one.
+//
+// In method-call syntax `x.f(a, |` the receiver `x` is the last argument of `f`, so the arguments
+// in the parentheses are its first ones, and the receiver never becomes the active parameter.
+//
+// The callee is looked up among the global values of the last successful elaboration, by the name
+// as written (e.g. `fold` or `Array::fold`). A name matches several values more often than not,
+// so every match the module can access is offered as a signature, and the client lets the user
+// cycle through them.
+
+use super::server::{send_response, LatestContent};
+use super::util::{
+    parameters_section_of_global_value, position_to_bytes, scan_outside_comments, ScanState,
+};
+use crate::ast::import::is_accessible;
+use crate::ast::name::FullName;
+use crate::ast::program::Program;
+use crate::constants::chars_allowed_in_identifiers;
+use crate::misc::Map;
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureHelpParams, SignatureInformation, Uri,
+};
+
+/// The call the cursor is in, as written in the source.
+#[derive(Debug, PartialEq, Eq)]
+struct CallSite {
+    /// The name of the function called, as written, e.g. `Array::fold`.
+    callee: String,
+    /// Whether the function is called in method-call syntax, `x.f(...)`.
+    method_call: bool,
+    /// The index of the argument the cursor is in, counted from the first argument written.
+    active_argument: usize,
+}
+
+// Handle "textDocument/signatureHelp" method.
+pub(super) fn handle_signature_help(
+    id: u32,
+    params: &SignatureHelpParams,
+    program: &Program,
+    uri_to_content: &mut Map<Uri, LatestContent>,
+) {
+    let text_position = &params.text_document_position_params;
+    let Some(latest) = uri_to_content.get_mut(&text_position.text_document.uri) else {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    };
+    let cursor = position_to_bytes(&latest.content, text_position.position);
+    let Some(call) = call_site_before(&latest.content, cursor) else {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    };
+    let Some(written) = FullName::parse(&call.callee) else {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    };
+
+    // Collect the global values the written name can refer to.
+    let mut callees: Vec<&FullName> = program
+        .global_values
+        .keys()
+        .filter(|name| written.is_suffix_of(name))
+        .filter(|name| {
+            let rendered = name.to_string();
+            !rendered.contains('#') && !rendered.contains('?')
+        })
+        .collect();
+
+    // Keep those the module can access, unless the imports cannot be read or none is accessible.
+    let module_name = latest.get_module_info().as_ref().map(|mi| mi.name.clone());
+    if let Some(import_stmts) = latest.get_import_stmts() {
+        let accessible: Vec<&FullName> = callees
+            .iter()
+            .copied()
+            .filter(|name| Some(name.module()) == module_name || is_accessible(import_stmts, name))
+            .collect();
+        if !accessible.is_empty() {
+            callees = accessible;
+        }
+    }
+    if callees.is_empty() {
+        send_response(id, Ok::<_, ()>(None::<()>));
+        return;
+    }
+    callees.sort_by_key(|name| name.to_string());
+
+    let signatures = callees
+        .into_iter()
+        .map(|name| signature_of_global_value(name, &call, program))
+        .collect::<Vec<_>>();
+
+    // Keep the signature the user has chosen while the call is being typed.
+    let active_signature = params
+        .context
+        .as_ref()
+        .and_then(|ctx| ctx.active_signature_help.as_ref())
+        .and_then(|help| help.active_signature)
+        .filter(|idx| (*idx as usize) < signatures.len())
+        .unwrap_or(0);
+
+    let help = SignatureHelp {
+        signatures,
+        active_signature: Some(active_signature),
+        active_parameter: None,
+    };
+    send_response(id, Ok::<_, ()>(help))
+}
+
+/// The signature of the global value `name` called at `call`: its type scheme split at the
+/// parameters, each parameter carrying its item of the `# Parameters` section of the
+/// documentation, if any.
+fn signature_of_global_value(
+    name: &FullName,
+    call: &CallSite,
+    program: &Program,
+) -> SignatureInformation {
+    let gv = program.global_values.get(name).unwrap();
+    let scheme = gv.syn_scm.clone().unwrap_or(gv.scm.clone());
+    let (constraints, param_tys, result_ty) = scheme.to_signature_strings_normalize();
+    let section = parameters_section_of_global_value(name, program);
+
+    // Write the label, recording where each parameter type stands in it. The offsets are counted
+    // in UTF-16 code units, as the positions of the language server are.
+    let mut label = format!("{} : {}", name.to_string(), constraints);
+    let mut parameters = vec![];
+    for (i, param_ty) in param_tys.iter().enumerate() {
+        let start = label.encode_utf16().count() as u32;
+        label += param_ty;
+        let end = label.encode_utf16().count() as u32;
+        label += " -> ";
+        let documentation = section
+            .as_ref()
+            .and_then(|section| section.params.get(i))
+            .map(|(_, item)| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: item.clone(),
+                })
+            });
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation,
+        });
+    }
+    label += &result_ty;
+
+    // In method-call syntax the last parameter is taken by the receiver, so an argument written
+    // at or past it in the parentheses matches no parameter, and none is highlighted.
+    let mut active_parameter = call.active_argument as u32;
+    if call.method_call && call.active_argument + 1 >= parameters.len() {
+        active_parameter = parameters.len() as u32;
+    }
+
+    let documentation = section.map(|section| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: section.text,
+        })
+    });
+    SignatureInformation {
+        label,
+        documentation,
+        parameters: Some(parameters),
+        active_parameter: Some(active_parameter),
+    }
+}
+
+/// Finds the call whose argument list the byte offset `cursor` of `content` is in. Returns `None`
+/// when the cursor is in a comment or a literal, or not in the parentheses of a call.
+fn call_site_before(content: &str, cursor: usize) -> Option<CallSite> {
+    // The code before the cursor, with each comment replaced by a space and the contents of the
+    // literals blanked out, so that brackets and commas in them are not counted.
+    let mut code = vec![];
+    let state = scan_outside_comments(content, cursor, &mut |byte, in_literal| {
+        code.push(if in_literal && byte != b'"' && byte != b'\'' {
+            b' '
+        } else {
+            byte
+        })
+    });
+    if state != ScanState::Normal {
+        return None;
+    }
+    let in_lambda_params = lambda_param_mask(&code);
+
+    // Find the innermost unclosed `(`, counting the commas before the cursor at its level.
+    let mut active_argument = 0;
+    let mut depth = 0;
+    let mut open = None;
+    for (i, byte) in code.iter().enumerate().rev() {
+        match byte {
+            b')' | b']' | b'}' => depth += 1,
+            b'(' | b'[' | b'{' if depth > 0 => depth -= 1,
+            b'(' => {
+                open = Some(i);
+                break;
+            }
+            // The cursor is in an array literal or a block, not in an argument list.
+            b'[' | b'{' => return None,
+            b',' if depth == 0 && !in_lambda_params[i] => active_argument += 1,
+            _ => {}
+        }
+    }
+    let mut end = open?;
+
+    // Count the arguments given in the earlier parentheses of the same call.
+    loop {
+        while end > 0 && code[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+        if end == 0 || code[end - 1] != b')' {
+            break;
+        }
+        let mut depth = 0;
+        let mut commas = 0;
+        let mut group_open = None;
+        for i in (0..end - 1).rev() {
+            match code[i] {
+                b')' | b']' | b'}' => depth += 1,
+                b'(' if depth == 0 => {
+                    group_open = Some(i);
+                    break;
+                }
+                b'(' | b'[' | b'{' => depth -= 1,
+                b',' if depth == 0 && !in_lambda_params[i] => commas += 1,
+                _ => {}
+            }
+        }
+        active_argument += commas + 1;
+        end = group_open?;
+    }
+
+    // Take the name written before the parentheses.
+    let name_chars = chars_allowed_in_identifiers();
+    let mut start = end;
+    while start > 0 && (name_chars.contains(code[start - 1] as char) || code[start - 1] == b':') {
+        start -= 1;
+    }
+    let callee = String::from_utf8(code[start..end].to_vec()).ok()?;
+    let first_of_name = callee.rsplit("::").next()?.chars().next()?;
+    if !(first_of_name.is_ascii_lowercase() || first_of_name == '_') {
+        return None;
+    }
+    let method_call = start > 0 && code[start - 1] == b'.';
+    Some(CallSite {
+        callee,
+        method_call,
+        active_argument,
+    })
+}
+
+/// Which bytes of `code` are in the parameter list of a lambda, between its two `|`, so that the
+/// commas separating the parameters are not taken for those separating arguments. A lambda takes
+/// at least one parameter, so `||` outside a parameter list is the operator.
+fn lambda_param_mask(code: &[u8]) -> Vec<bool> {
+    let mut mask = vec![false; code.len()];
+    let mut in_params = false;
+    let mut i = 0;
+    while i < code.len() {
+        if code[i] == b'|' {
+            if in_params {
+                in_params = false;
+            } else if code.get(i + 1) == Some(&b'|') {
+                i += 2;
+                continue;
+            } else {
+                in_params = true;
+            }
+        }
+        mask[i] = in_params;
+        i += 1;
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_site(source_before_cursor: &str) -> Option<CallSite> {
+        call_site_before(source_before_cursor, source_before_cursor.len())
+    }
+
+    #[test]
+    fn finds_callee_and_argument_index() {
+        assert_eq!(
+            call_site("let x = Array::fold(0, |acc, "),
+            Some(CallSite {
+                callee: "Array::fold".to_string(),
+                method_call: false,
+                active_argument: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn skips_commas_of_lambda_parameters() {
+        assert_eq!(
+            call_site("f(|a, b| a || b, g(|(x, y), z| x)(1, "),
+            Some(CallSite {
+                callee: "g".to_string(),
+                method_call: false,
+                active_argument: 2,
+            })
+        );
+        assert_eq!(
+            call_site("f(|a, b| a + b, "),
+            Some(CallSite {
+                callee: "f".to_string(),
+                method_call: false,
+                active_argument: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn finds_method_call() {
+        assert_eq!(
+            call_site("arr.sort_by("),
+            Some(CallSite {
+                callee: "sort_by".to_string(),
+                method_call: true,
+                active_argument: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn counts_arguments_of_earlier_parentheses() {
+        assert_eq!(
+            call_site("f(a, g(b, c))(d, "),
+            Some(CallSite {
+                callee: "f".to_string(),
+                method_call: false,
+                active_argument: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_brackets_in_literals_and_comments() {
+        assert_eq!(
+            call_site("f(\"(,\", /* ) */ 'a', "),
+            Some(CallSite {
+                callee: "f".to_string(),
+                method_call: false,
+                active_argument: 2,
+            })
+        );
+        assert_eq!(call_site("f(\"abc"), None);
+    }
+
+    #[test]
+    fn rejects_non_call_parentheses() {
+        assert_eq!(call_site("let x = (1, "), None);
+        assert_eq!(call_site("f([1, "), None);
+        assert_eq!(call_site("Some(1, "), None);
+    }
+}
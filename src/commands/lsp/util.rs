@@ -731,6 +731,23 @@ pub(super) fn parameters_of_global_value(
     full_name: &FullName,
     program: &Program,
 ) -> Option<Vec<String>> {
+    let section = parameters_section_of_global_value(full_name, program)?;
+    Some(section.params.into_iter().map(|(name, _)| name).collect())
+}
+
+// The "Parameters" section of the documentation of a global value.
+pub(super) struct ParametersSection {
+    // The text of the section, without its heading line.
+    pub text: String,
+    // The parameters listed in the section, in order, each with the text of its list item.
+    pub params: Vec<(String, String)>,
+}
+
+// Get the "Parameters" section from the documentation of a global value.
+pub(super) fn parameters_section_of_global_value(
+    full_name: &FullName,
+    program: &Program,
+) -> Option<ParametersSection> {
     // Get the document of the global value, which is a markdown string.
     let opt_gv = program.global_values.get(full_name);
     if opt_gv.is_none() {
@@ -745,7 +762,6 @@ pub(super) fn parameters_of_global_value(
     let sections = MarkdownSection::parse_many(docs.lines().collect());
 
     // Find the first top-level or second-level section named "Parameters".
-    // let param_section = sections.iter().find(|sec| sec.title.trim() == "Parameters");
     let param_section = sections.iter().find_map(|sec| {
         if sec.title.trim() == "Parameters" {
             Some(sec)
@@ -802,12 +818,15 @@ pub(super) fn parameters_of_global_value(
                     continue;
                 }
 
-                params.push(param.to_string());
+                params.push((param.to_string(), line[2..].trim().to_string()));
             }
         }
     }
 
-    Some(params)
+    Some(ParametersSection {
+        text: param_section.paragraphs.join("\n\n"),
+        params,
+    })
 }
 
 pub(super) fn find_trait_or_alias_def_src(program: &Program, trait_: TraitId) -> Option<Span> {
@@ -849,7 +868,7 @@ pub(super) fn find_field_def_src(program: &Program, tc: &TyCon, name: &Name) ->
     field.name_src.clone()
 }
 
-pub(super) fn document_from_endnode(node: &EndNode, program: &Program) -> MarkupContent {
+pub(crate) fn document_from_endnode(node: &EndNode, program: &Program) -> MarkupContent {
     fn document_tycon_or_alias(program: &Program, docs: &mut String, tycon: &TyCon) {
         *docs += &format!("```\n{}\n```", tycon.to_string());
         if let Some(ti) = program.type_env.tycons().get(&tycon) {
@@ -1,7 +1,15 @@
+pub mod bench;
 pub mod build;
 pub mod check;
 pub mod clean;
 pub mod deps;
 pub mod docs;
+pub mod docs_html;
+pub mod docs_json;
+pub mod doctest;
+pub mod fmt;
 pub mod lsp;
+pub mod repl;
 pub mod run;
+pub mod semver_check;
+pub mod watch;
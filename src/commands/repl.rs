@@ -0,0 +1,404 @@
+use crate::ast::name::FullName;
+use crate::ast::program::{EndNode, Program};
+use crate::ast::types::TypeNode;
+use crate::build::build::compile_runtime;
+use crate::build::build_object_files::build_jit_module;
+use crate::commands::lsp::util::document_from_endnode;
+use crate::configuration::{Configuration, DiagnosticsConfig, Sanitizer, SubCommand};
+use crate::constants::{C_ENTRY_POINT_NAME, REPL_ENTRY_NAME, REPL_MODULE_NAME, REPL_PATH};
+use crate::elaboration::elaborate_via_config;
+use crate::error::{Errors, MessageFormat};
+use crate::parse::sourcefile::{SourceFile, SourcePos};
+use inkwell::context::Context;
+use inkwell::execution_engine::ExecutionEngine;
+use inkwell::support::load_library_permanently;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const HELP: &str = "\
+Enter an expression to evaluate it and print its value. An expression of type `IO a` is run, and the
+value it results in is printed unless it is `()`.
+
+  let x = <expr>;    Bind `x` for the inputs that follow. The bindings are evaluated again
+                     for each input.
+  import <module>;   Import a module for the inputs that follow.
+  :type <expr>       Print the type of an expression.
+  :doc <name>        Print the documentation of a value, a type or a trait.
+  :help              Print this help.
+  :quit              End the session.
+
+An input continues on the next line while one of its brackets is left open.";
+
+/// Runs `fix repl`: reads inputs from the standard input one by one, and evaluates each in the
+/// program the configuration describes, with the names of the project in scope.
+///
+/// Each input is written into the module `Repl` under `REPL_PATH`, together with the imports and
+/// the `let` bindings entered so far, compiled into code generated in memory, and run in a process
+/// forked from this one. An input that fails to compile is reported and forgotten.
+pub fn repl(mut config: Configuration) -> Result<(), Errors> {
+    if config.sanitizer != Sanitizer::None {
+        return Err(Errors::from_msg(
+            "`fix repl` does not support `--sanitize`.".to_string(),
+        ));
+    }
+    if config.subcommand.run_preliminary_commands() {
+        config.run_preliminary_commands()?;
+    }
+
+    // The code the session generates calls into the runtime, which is loaded into this process.
+    let runtime_path = compile_runtime(&config, true)?;
+    load_library_permanently(&runtime_path).map_err(|e| {
+        Errors::from_msg_err(
+            &format!("Failed to load the runtime \"{}\"", runtime_path.display()),
+            e,
+        )
+    })?;
+    ExecutionEngine::link_in_mc_jit();
+
+    fs::create_dir_all(REPL_PATH)
+        .map_err(|e| Errors::from_msg(format!("Failed to create \"{}\": {}", REPL_PATH, e)))?;
+    let repl_file = PathBuf::from(REPL_PATH).join("repl.fix");
+    // The file is not one of the user's, so no lint runs on it.
+    config.extra_source_files.push(repl_file.clone());
+
+    let mut session = Session {
+        config,
+        repl_file,
+        imports: vec![],
+        lets: vec![],
+    };
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        println!("Fix {}. Enter `:help` for help.", env!("CARGO_PKG_VERSION"));
+    }
+    while let Some(input) = read_input(interactive) {
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        let res = match input.split_once(char::is_whitespace).unwrap_or((input, "")) {
+            (":quit" | ":q", _) => break,
+            (":help" | ":h", _) => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            (":type" | ":t", expr) => session.print_type(expr.trim()),
+            (":doc" | ":d", name) => session.print_doc(name.trim()),
+            (cmd, _) if cmd.starts_with(':') => Err(Errors::from_msg(format!(
+                "Unknown command `{}`. Enter `:help` for the commands.",
+                cmd
+            ))),
+            ("import", _) => session.add_import(input),
+            // `let x = 1; x + 1` is an expression; a binding has nothing after its `;`.
+            ("let", _) if !input.trim_end_matches(';').contains(';') => session.add_let(input),
+            _ => session.evaluate(input),
+        };
+        if let Err(errs) = res {
+            errs.report(MessageFormat::Human);
+        }
+    }
+    Ok(())
+}
+
+/// Read one input: a line, and the lines after it while a bracket of the input is left open. The
+/// prompt is printed only to a terminal. `None` at the end of the standard input.
+fn read_input(interactive: bool) -> Option<String> {
+    let mut input = String::new();
+    loop {
+        if interactive {
+            print!("{}", if input.is_empty() { "fix> " } else { "...> " });
+            io::stdout().flush().ok()?;
+        }
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line).ok()? == 0 {
+            return if input.is_empty() { None } else { Some(input) };
+        }
+        input += &line;
+        if open_brackets(&input) <= 0 {
+            return Some(input);
+        }
+    }
+}
+
+/// The number of brackets `(`, `[` and `{` that `input` leaves open, outside string and character
+/// literals and line comments.
+fn open_brackets(input: &str) -> i64 {
+    let mut depth = 0;
+    let mut chars = input.chars();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                if c == '\\' {
+                    chars.next();
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                '/' if chars.clone().next() == Some('/') => {
+                    // Skip the rest of the line.
+                    while let Some(c) = chars.next() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    depth
+}
+
+/// What the session keeps of the inputs entered so far.
+struct Session {
+    /// The configuration of the program each input is compiled into.
+    config: Configuration,
+    /// The file the module `Repl` is written to.
+    repl_file: PathBuf,
+    /// The import statements entered so far.
+    imports: Vec<String>,
+    /// The `let` bindings entered so far, each ending with `;`. They are evaluated again for every
+    /// input.
+    lets: Vec<String>,
+}
+
+impl Session {
+    /// The source of the module `Repl` whose entry point binds `expr` to `it` after the bindings
+    /// entered so far and then runs `body`, and the position of the binder `it` in it.
+    fn source(
+        &self,
+        imports: &[String],
+        lets: &[String],
+        expr: &str,
+        body: &str,
+    ) -> (String, usize) {
+        let mut source = format!("module {};\n", REPL_MODULE_NAME);
+        for import in imports {
+            source += &format!("{}\n", import);
+        }
+        source += &format!("\n{} : IO ();\n{} = (\n", REPL_ENTRY_NAME, REPL_ENTRY_NAME);
+        for let_ in lets {
+            source += &format!("    {}\n", let_);
+        }
+        source += "    let ";
+        let it_pos = source.len();
+        source += &format!("it = (\n{}\n    );\n    {}\n);\n", expr, body);
+        (source, it_pos)
+    }
+
+    /// Write `source` into the file of the module `Repl`.
+    fn write(&self, source: &str) -> Result<(), Errors> {
+        fs::write(&self.repl_file, source).map_err(|e| {
+            Errors::from_msg(format!(
+                "Failed to write \"{}\": {}",
+                self.repl_file.display(),
+                e
+            ))
+        })
+    }
+
+    /// Type-check the program with `source` as the module `Repl`, as `fix check` does.
+    fn check(&self, source: &str) -> Result<Program, Errors> {
+        self.write(source)?;
+        let mut config = self.config.clone();
+        config.subcommand = SubCommand::Diagnostics(DiagnosticsConfig {
+            files: vec![self.repl_file.clone()],
+            ..DiagnosticsConfig::default()
+        });
+        let program = elaborate_via_config(&config)?;
+        if program.deferred_errors.has_error() {
+            return Err(program.deferred_errors);
+        }
+        Ok(program)
+    }
+
+    /// The type `program` infers for the binder at `it_pos` of the module `Repl`.
+    fn type_of_it(&self, program: &Program, it_pos: usize) -> Result<Arc<TypeNode>, Errors> {
+        let pos = SourcePos {
+            input: SourceFile::from_file_path(self.repl_file.clone()),
+            pos: it_pos,
+        };
+        match program.find_node_at(&pos) {
+            Some(EndNode::Pattern(_, Some(ty))) => Ok(ty),
+            _ => Err(Errors::from_msg(
+                "Failed to infer the type of the input.".to_string(),
+            )),
+        }
+    }
+
+    /// Compile the program with `source` as the module `Repl` into code generated in memory, and run
+    /// its entry point.
+    fn run(&self, source: &str) -> Result<(), Errors> {
+        self.write(source)?;
+        let program = elaborate_via_config(&self.config)?;
+        if program.deferred_errors.has_error() {
+            return Err(program.deferred_errors);
+        }
+        program.check_multi_threading_requirement(&self.config)?;
+
+        let context = Context::create();
+        let module = build_jit_module(&context, program, &self.config)?;
+        let engine = module
+            .create_jit_execution_engine(self.config.get_llvm_opt_level())
+            .map_err(|e| {
+                Errors::from_msg(format!(
+                    "Failed to create the execution engine: {}",
+                    e.to_string()
+                ))
+            })?;
+        let main = engine
+            .get_function_value(C_ENTRY_POINT_NAME)
+            .map_err(|e| Errors::from_msg(format!("Failed to find the entry point: {:?}", e)))?;
+        let mut args = vec!["repl"];
+        args.extend(self.config.run_program_args.iter().map(|arg| arg.as_str()));
+        let status = unsafe { engine.run_function_as_main(main, &args) };
+        if status != 0 {
+            return Err(Errors::from_msg(format!(
+                "The input ended with exit status {}.",
+                status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Evaluate `expr` and print its value, or, if it is an `IO` action, run it and print the value
+    /// it results in.
+    fn evaluate(&self, expr: &str) -> Result<(), Errors> {
+        let (source, it_pos) = self.source(&self.imports, &self.lets, expr, "pure()");
+        let program = self.check(&source)?;
+        let ty = self.type_of_it(&program, it_pos)?;
+
+        // How `it` is shown, and how it is when its type has no `ToString` instance.
+        let (shown, opaque) = if ty.is_io() {
+            let result_ty = ty.collect_type_arguments().pop().unwrap();
+            if result_ty.is_unit() {
+                ("it".to_string(), "it".to_string())
+            } else {
+                (
+                    "it.bind(|x| println(x.to_string))".to_string(),
+                    format!(
+                        "it.bind(|_| println(\"<a value of type {}>\"))",
+                        result_ty.to_string_normalize()
+                    ),
+                )
+            }
+        } else {
+            (
+                "println(it.to_string)".to_string(),
+                format!(
+                    "println(\"<a value of type {}>\")",
+                    ty.to_string_normalize()
+                ),
+            )
+        };
+        let (source, _) = self.source(&self.imports, &self.lets, expr, &shown);
+        if self.check(&source).is_ok() {
+            return self.run(&source);
+        }
+        let (source, _) = self.source(&self.imports, &self.lets, expr, &opaque);
+        self.run(&source)
+    }
+
+    /// Print the type of `expr`.
+    fn print_type(&self, expr: &str) -> Result<(), Errors> {
+        let (source, it_pos) = self.source(&self.imports, &self.lets, expr, "pure()");
+        let program = self.check(&source)?;
+        let ty = self.type_of_it(&program, it_pos)?;
+        println!("{} : {}", expr, ty.to_string_normalize());
+        Ok(())
+    }
+
+    /// Print the documentation of the value, type or trait `name`.
+    fn print_doc(&self, name: &str) -> Result<(), Errors> {
+        // A value is documented through the node of its name in an expression.
+        let (source, it_pos) = self.source(&self.imports, &self.lets, name, "pure()");
+        let expr_pos = it_pos + "it = (\n".len();
+        let node = self.check(&source).ok().and_then(|program| {
+            let pos = SourcePos {
+                input: SourceFile::from_file_path(self.repl_file.clone()),
+                pos: expr_pos,
+            };
+            match program.find_node_at(&pos) {
+                Some(node @ EndNode::Expr(_, _)) => Some((node, program)),
+                _ => None,
+            }
+        });
+        // A type or a trait is looked up by its name, which may be qualified only partially.
+        let node = match node {
+            Some(node) => Some(node),
+            None => {
+                let (source, _) = self.source(&self.imports, &self.lets, "()", "pure()");
+                let program = self.check(&source)?;
+                find_type_or_trait(&program, name).map(|node| (node, program))
+            }
+        };
+        let Some((node, program)) = node else {
+            return Err(Errors::from_msg(format!(
+                "No value, type or trait named `{}` is in scope.",
+                name
+            )));
+        };
+        let docs = document_from_endnode(&node, &program).value;
+        // The documentation is markdown; the fences around code are left out on a terminal.
+        for line in docs.lines().filter(|line| !line.starts_with("```")) {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Add the import statement `import` for the inputs that follow, if the program compiles with it.
+    fn add_import(&mut self, import: &str) -> Result<(), Errors> {
+        let mut imports = self.imports.clone();
+        imports.push(with_semicolon(import));
+        let (source, _) = self.source(&imports, &self.lets, "()", "pure()");
+        self.check(&source)?;
+        self.imports = imports;
+        Ok(())
+    }
+
+    /// Add the `let` binding `let_` for the inputs that follow, if the program compiles with it.
+    fn add_let(&mut self, let_: &str) -> Result<(), Errors> {
+        let mut lets = self.lets.clone();
+        lets.push(with_semicolon(let_));
+        let (source, _) = self.source(&self.imports, &lets, "()", "pure()");
+        self.check(&source)?;
+        self.lets = lets;
+        Ok(())
+    }
+}
+
+/// `stmt`, ending with `;`.
+fn with_semicolon(stmt: &str) -> String {
+    let stmt = stmt.trim_end();
+    if stmt.ends_with(';') {
+        stmt.to_string()
+    } else {
+        format!("{};", stmt)
+    }
+}
+
+/// The node of the type or the trait of `program` that `name` names, as a suffix of its full name.
+fn find_type_or_trait(program: &Program, name: &str) -> Option<EndNode> {
+    let name = FullName::parse(name)?;
+    let tycons = program
+        .type_env
+        .tycons()
+        .keys()
+        .chain(program.type_env.aliases.keys())
+        .filter(|tycon| name.is_suffix_of(&tycon.name))
+        .map(|tycon| EndNode::Type(tycon.clone()));
+    let traits = program
+        .trait_env
+        .traits
+        .keys()
+        .filter(|trait_id| name.is_suffix_of(&trait_id.name))
+        .map(|trait_id| EndNode::Trait(trait_id.clone()));
+    tycons.chain(traits).next()
+}
@@ -58,6 +58,7 @@ pub fn run(
     for arg in &config.run_program_args {
         com.arg(arg);
     }
+    com.envs(config.run_program_env.iter().cloned());
     if inherit_streams {
         com.stdout(Stdio::inherit())
             .stdin(Stdio::inherit())
@@ -88,6 +89,40 @@ pub fn run(
     Ok(output)
 }
 
+/// Builds the program and runs it with the terminal's streams attached, for one run of
+/// `fix run --watch` or `fix test --watch`. A program that ends with a non-zero status or by a
+/// signal is reported as an error, which the watch prints before it waits for the next change.
+pub fn run_watched(config: &Configuration) -> Result<(), Errors> {
+    let output = match run(config.clone(), true)? {
+        Ok(output) => output,
+        Err(e) => {
+            return Err(Errors::from_msg(format!(
+                "Failed to run the program: {}",
+                e
+            )))
+        }
+    };
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(code) => Err(Errors::from_msg(format!(
+            "The program exited with status {}.",
+            code
+        ))),
+        None => {
+            #[cfg(unix)]
+            {
+                if let Some(signal) = output.status.signal() {
+                    return Err(Errors::from_msg(format!(
+                        "Program terminated by signal {}",
+                        signal
+                    )));
+                }
+            }
+            Err(Errors::from_msg("Program terminated by signal".to_string()))
+        }
+    }
+}
+
 /// Builds the program, runs it with the terminal's streams attached, and exits the `fix` process
 /// with the status the program returned. A program that a signal ends aborts `fix` instead.
 pub fn run_command(config: &Configuration) {
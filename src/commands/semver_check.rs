@@ -0,0 +1,576 @@
+//! `fix semver-check`: compares the public API of the project with the one of an earlier revision,
+//! and checks that `[general] version` has been raised as far as the changes need.
+//!
+//! The revision is checked out from a clone of the git repository holding the project, and the API
+//! of each side is what `fix docs --format json` describes of the modules of its `[build]` section,
+//! so that a change is seen exactly where the documentation would show one. The two descriptions
+//! are matched by full name and each difference is classified as breaking or as an addition. The
+//! version bump the differences need is judged as Cargo judges compatibility: in a version `0.y.z`,
+//! `y` plays the part of the major version and `z` the one of the minor version.
+
+use crate::commands::docs_json::{
+    JsonField, JsonImpl, JsonModule, JsonTrait, JsonTraitAlias, JsonTyVar, JsonType, JsonTypeAlias,
+    JsonValue,
+};
+use crate::constants::{PROJECT_FILE_PATH, SEMVER_CHECK_PATH};
+use crate::dependency::lockfile::clone_git_repo;
+use crate::error::{panic_if_err, Errors};
+use crate::metafiles::project_file::ProjectFile;
+use crate::misc::{info_msg, to_absolute_path};
+use git2::build::CheckoutBuilder;
+use git2::Repository;
+use semver::Version;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// How far a change to the API moves away from the baseline.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    /// The change adds to the API, and code written against the baseline still compiles.
+    Minor,
+    /// The change may break code written against the baseline.
+    Major,
+}
+
+impl Level {
+    fn to_str(&self) -> &'static str {
+        match self {
+            Level::Minor => "minor",
+            Level::Major => "major",
+        }
+    }
+}
+
+/// A difference between the API of the baseline and the current one.
+struct Change {
+    level: Level,
+    message: String,
+}
+
+/// The differences found so far.
+#[derive(Default)]
+struct Changes {
+    changes: Vec<Change>,
+}
+
+impl Changes {
+    fn major(&mut self, message: String) {
+        self.changes.push(Change {
+            level: Level::Major,
+            message,
+        });
+    }
+
+    fn minor(&mut self, message: String) {
+        self.changes.push(Change {
+            level: Level::Minor,
+            message,
+        });
+    }
+}
+
+pub fn semver_check_command(baseline: &str) {
+    panic_if_err(semver_check(baseline));
+}
+
+/// Compares the API of the project in the current directory with the one at the git revision
+/// `baseline`, prints the differences, and fails when `[general] version` is not raised far enough
+/// for them.
+pub fn semver_check(baseline: &str) -> Result<(), Errors> {
+    let proj_file = ProjectFile::read_root_file()?;
+
+    info_msg(&format!("Checking out \"{}\"...", baseline));
+    let (_temp_dir, baseline_dir) = checkout_baseline(baseline)?;
+    let baseline_proj_file = ProjectFile::read_file(&baseline_dir.join(PROJECT_FILE_PATH))?;
+
+    let out_dir = env::current_dir()
+        .map_err(|e| Errors::from_msg_err("Failed to get the current directory", e))?
+        .join(SEMVER_CHECK_PATH);
+    info_msg("Describing the API of the baseline...");
+    let old_api = describe_api(&baseline_dir, &out_dir.join("baseline"))?;
+    info_msg("Describing the current API...");
+    let new_api = describe_api(Path::new("."), &out_dir.join("current"))?;
+
+    let mut changes = Changes::default();
+    diff_modules(&old_api, &new_api, &mut changes);
+    let mut changes = changes.changes;
+    changes.sort_by(|a, b| b.level.cmp(&a.level));
+    for change in &changes {
+        println!("{}: {}", change.level.to_str(), change.message);
+    }
+
+    let old_version = baseline_proj_file.general.version();
+    let new_version = proj_file.general.version();
+    let required = changes.iter().map(|change| change.level).max();
+    let majors = changes.iter().filter(|c| c.level == Level::Major).count();
+    let minors = changes.len() - majors;
+    println!(
+        "semver-check: {} breaking change(s) and {} addition(s) since \"{}\" (version {}).",
+        majors, minors, baseline, old_version
+    );
+    if new_version < old_version {
+        return Err(Errors::from_msg(format!(
+            "The version {} in the project file is older than the version {} of \"{}\".",
+            new_version, old_version, baseline
+        )));
+    }
+    let Some(required) = required else {
+        return Ok(());
+    };
+    let least = least_version(&old_version, required);
+    if new_version < least {
+        return Err(Errors::from_msg(format!(
+            "The changes since \"{}\" need a {} version bump from {}, but the version in the project file is {}. Raise `[general] version` to {} or later.",
+            baseline,
+            required.to_str(),
+            old_version,
+            new_version,
+            least
+        )));
+    }
+    println!(
+        "semver-check: the version {} is a {} version bump from {}, as the changes need.",
+        new_version,
+        required.to_str(),
+        old_version
+    );
+    Ok(())
+}
+
+/// The earliest version after `old` that a change of `level` may be released in: the earliest one
+/// Cargo takes to be incompatible with `old` for a major change, and for a minor change the earliest
+/// one raising the number that plays the part of the minor version.
+fn least_version(old: &Version, level: Level) -> Version {
+    match (level, old.major, old.minor) {
+        (_, 0, 0) => Version::new(0, 0, old.patch + 1),
+        (Level::Major, 0, minor) => Version::new(0, minor + 1, 0),
+        (Level::Major, major, _) => Version::new(major + 1, 0, 0),
+        (Level::Minor, 0, minor) => Version::new(0, minor, old.patch + 1),
+        (Level::Minor, major, minor) => Version::new(major, minor + 1, 0),
+    }
+}
+
+/// Checks out the revision `rev` of the git repository holding the current directory into a clone
+/// of it, and returns the clone together with the directory of the project within it.
+fn checkout_baseline(rev: &str) -> Result<(TempDir, PathBuf), Errors> {
+    let repo = Repository::discover(".")
+        .map_err(|e| Errors::from_msg_err("The project is not in a git repository", e))?;
+    let Some(workdir) = repo.workdir() else {
+        return Err(Errors::from_msg(
+            "The git repository of the project has no working directory.".to_string(),
+        ));
+    };
+    let workdir = to_absolute_path(workdir)?;
+    let project_dir = to_absolute_path(Path::new("."))?;
+    let subdir = project_dir
+        .strip_prefix(&workdir)
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+
+    let (temp_dir, clone) = clone_git_repo(&workdir.to_string_lossy())?;
+    // A branch other than the checked-out one is a remote branch of the clone.
+    let commit = clone
+        .revparse_single(rev)
+        .or_else(|_| clone.revparse_single(&format!("origin/{}", rev)))
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| Errors::from_msg(format!("Failed to find rev \"{}\": {}", rev, e)))?;
+    let mut checkout_opts = CheckoutBuilder::default();
+    checkout_opts.force();
+    clone
+        .checkout_tree(commit.as_object(), Some(&mut checkout_opts))
+        .map_err(|e| Errors::from_msg_err("Failed to checkout commit", e))?;
+    clone
+        .set_head_detached(commit.id())
+        .map_err(|e| Errors::from_msg_err("Failed to checkout commit", e))?;
+
+    let baseline_dir = temp_dir.path().join(subdir);
+    if !baseline_dir.join(PROJECT_FILE_PATH).exists() {
+        return Err(Errors::from_msg(format!(
+            "The project has no \"{}\" at \"{}\".",
+            PROJECT_FILE_PATH, rev
+        )));
+    }
+    Ok((temp_dir, baseline_dir))
+}
+
+/// Runs `fix docs --format json` on the project in `project_dir`, writing into `out_dir`, and reads
+/// what it wrote.
+fn describe_api(project_dir: &Path, out_dir: &Path) -> Result<Vec<JsonModule>, Errors> {
+    let _ = fs::remove_dir_all(out_dir);
+    let fix = env::current_exe()
+        .map_err(|e| Errors::from_msg_err("Failed to find the `fix` executable", e))?;
+    let output = Command::new(fix)
+        .args(["docs", "--format", "json", "--out-dir"])
+        .arg(out_dir)
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| Errors::from_msg_err("Failed to run `fix docs`", e))?;
+    if !output.status.success() {
+        return Err(Errors::from_msg(format!(
+            "Failed to describe the API of the project in \"{}\":\n{}{}",
+            project_dir.display(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    JsonModule::read_dir(out_dir)
+}
+
+/// The entries `old` and `new` both have, matched by `name`, after the ones only `old` has and the
+/// ones only `new` has.
+fn match_by_name<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+    name: impl Fn(&T) -> &str,
+) -> (Vec<&'a T>, Vec<&'a T>, Vec<(&'a T, &'a T)>) {
+    let removed = old
+        .iter()
+        .filter(|o| !new.iter().any(|n| name(n) == name(o)))
+        .collect();
+    let added = new
+        .iter()
+        .filter(|n| !old.iter().any(|o| name(o) == name(n)))
+        .collect();
+    let both = old
+        .iter()
+        .filter_map(|o| new.iter().find(|n| name(n) == name(o)).map(|n| (o, n)))
+        .collect();
+    (removed, added, both)
+}
+
+/// Splits a signature written as `[c1, c2] body` into its constraints and its body.
+fn split_constraints(signature: &str) -> (Vec<String>, String) {
+    if !signature.starts_with('[') {
+        return (vec![], signature.to_string());
+    }
+    let mut constraints = vec![];
+    let mut depth = 0;
+    let mut current = String::new();
+    for (i, c) in signature.char_indices() {
+        match c {
+            '[' | '(' | '{' => {
+                depth += 1;
+                if depth == 1 {
+                    continue;
+                }
+            }
+            ']' | ')' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    constraints.push(current.trim().to_string());
+                    return (constraints, signature[i + 1..].trim().to_string());
+                }
+            }
+            ',' if depth == 1 => {
+                constraints.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    (vec![], signature.to_string())
+}
+
+/// Compares the signatures `old` and `new` of `what`: a changed body breaks its users, and so do
+/// new constraints, while dropped constraints only relax it.
+fn diff_signatures(what: &str, old: &str, new: &str, changes: &mut Changes) {
+    let (old_constraints, old_body) = split_constraints(old);
+    let (new_constraints, new_body) = split_constraints(new);
+    if old_body != new_body {
+        changes.major(format!(
+            "the type of {} changed from `{}` to `{}`.",
+            what, old, new
+        ));
+        return;
+    }
+    let added = new_constraints
+        .iter()
+        .filter(|c| !old_constraints.contains(c))
+        .map(|c| format!("`{}`", c))
+        .collect::<Vec<_>>();
+    let removed = old_constraints
+        .iter()
+        .filter(|c| !new_constraints.contains(c))
+        .map(|c| format!("`{}`", c))
+        .collect::<Vec<_>>();
+    if !added.is_empty() {
+        changes.major(format!(
+            "{} has the new constraints {}.",
+            what,
+            added.join(", ")
+        ));
+    }
+    if !removed.is_empty() {
+        changes.minor(format!(
+            "{} no longer has the constraints {}.",
+            what,
+            removed.join(", ")
+        ));
+    }
+}
+
+/// The type variables `tyvars` written as a declaration writes them, with their kinds.
+fn tyvars_to_string(tyvars: &[JsonTyVar]) -> String {
+    tyvars
+        .iter()
+        .map(|tyvar| format!("({} : {})", tyvar.name, tyvar.kind))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn diff_modules(old: &[JsonModule], new: &[JsonModule], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |m| m.name.as_str());
+    for module in removed {
+        changes.major(format!("module `{}` was removed.", module.name));
+    }
+    for module in added {
+        changes.minor(format!("module `{}` was added.", module.name));
+    }
+    for (old, new) in both {
+        diff_values(&old.values, &new.values, changes);
+        diff_types(&old.types, &new.types, changes);
+        diff_type_aliases(&old.type_aliases, &new.type_aliases, changes);
+        diff_traits(&old.traits, &new.traits, changes);
+        diff_trait_aliases(&old.trait_aliases, &new.trait_aliases, changes);
+        diff_impls(&old.impls, &new.impls, changes);
+    }
+}
+
+fn diff_values(old: &[JsonValue], new: &[JsonValue], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |v| v.name.as_str());
+    for value in removed {
+        // A value added with the same type in the same namespace is likely the removed one renamed.
+        let namespace = value.name.rsplit_once("::").map(|(ns, _)| ns);
+        let renamed = added
+            .iter()
+            .find(|a| a.ty == value.ty && a.name.rsplit_once("::").map(|(ns, _)| ns) == namespace);
+        match renamed {
+            Some(renamed) => changes.major(format!(
+                "value `{}` was removed (renamed to `{}`?).",
+                value.name, renamed.name
+            )),
+            None => changes.major(format!("value `{}` was removed.", value.name)),
+        }
+    }
+    for value in added {
+        changes.minor(format!("value `{}` was added.", value.name));
+    }
+    for (old, new) in both {
+        let what = format!("value `{}`", new.name);
+        diff_signatures(&what, &old.ty, &new.ty, changes);
+        if old.deprecated.is_none() && new.deprecated.is_some() {
+            changes.minor(format!("{} was deprecated.", what));
+        }
+    }
+}
+
+fn diff_fields(
+    type_name: &str,
+    kind: &str,
+    old: &[JsonField],
+    new: &[JsonField],
+    changes: &mut Changes,
+) {
+    let (removed, added, both) = match_by_name(old, new, |f| f.name.as_str());
+    for field in removed {
+        changes.major(format!(
+            "{} `{}` of type `{}` was removed.",
+            kind, field.name, type_name
+        ));
+    }
+    // A new field has to be given where a value of the struct is written, and a new variant
+    // handled where one of the union is matched.
+    for field in added {
+        changes.major(format!(
+            "{} `{}` was added to type `{}`.",
+            kind, field.name, type_name
+        ));
+    }
+    for (old, new) in both {
+        if old.ty != new.ty {
+            changes.major(format!(
+                "the type of {} `{}` of type `{}` changed from `{}` to `{}`.",
+                kind, new.name, type_name, old.ty, new.ty
+            ));
+        }
+    }
+}
+
+fn diff_types(old: &[JsonType], new: &[JsonType], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |t| t.name.as_str());
+    for ty in removed {
+        changes.major(format!("type `{}` was removed.", ty.name));
+    }
+    for ty in added {
+        changes.minor(format!("type `{}` was added.", ty.name));
+    }
+    for (old, new) in both {
+        if old.variant != new.variant {
+            changes.major(format!(
+                "type `{}` changed from a {} to a {}.",
+                new.name, old.variant, new.variant
+            ));
+            continue;
+        }
+        if old.is_unbox != new.is_unbox {
+            changes.major(format!(
+                "type `{}` changed from {} to {}.",
+                new.name,
+                if old.is_unbox { "unboxed" } else { "boxed" },
+                if new.is_unbox { "unboxed" } else { "boxed" }
+            ));
+        }
+        let (old_params, new_params) =
+            (tyvars_to_string(&old.tyvars), tyvars_to_string(&new.tyvars));
+        if old_params != new_params {
+            changes.major(format!(
+                "the parameters of type `{}` changed from `{}` to `{}`.",
+                new.name, old_params, new_params
+            ));
+        }
+        diff_fields(&new.name, "field", &old.fields, &new.fields, changes);
+        diff_fields(&new.name, "variant", &old.variants, &new.variants, changes);
+    }
+}
+
+fn diff_type_aliases(old: &[JsonTypeAlias], new: &[JsonTypeAlias], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |t| t.name.as_str());
+    for alias in removed {
+        changes.major(format!("type alias `{}` was removed.", alias.name));
+    }
+    for alias in added {
+        changes.minor(format!("type alias `{}` was added.", alias.name));
+    }
+    for (old, new) in both {
+        let (old_params, new_params) =
+            (tyvars_to_string(&old.tyvars), tyvars_to_string(&new.tyvars));
+        if old_params != new_params || old.value != new.value {
+            changes.major(format!(
+                "type alias `{}` changed from `{} = {}` to `{} = {}`.",
+                new.name, old_params, old.value, new_params, new.value
+            ));
+        }
+    }
+}
+
+fn diff_traits(old: &[JsonTrait], new: &[JsonTrait], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |t| t.name.as_str());
+    for trait_ in removed {
+        changes.major(format!("trait `{}` was removed.", trait_.name));
+    }
+    for trait_ in added {
+        changes.minor(format!("trait `{}` was added.", trait_.name));
+    }
+    for (old, new) in both {
+        if old.type_var.kind != new.type_var.kind {
+            changes.major(format!(
+                "the kind of the types implementing trait `{}` changed from `{}` to `{}`.",
+                new.name, old.type_var.kind, new.type_var.kind
+            ));
+        }
+
+        // Every implementation has to define a new member or associated type.
+        let (removed, added, both) = match_by_name(&old.members, &new.members, |m| m.name.as_str());
+        for member in removed {
+            changes.major(format!(
+                "member `{}` of trait `{}` was removed.",
+                member.name, new.name
+            ));
+        }
+        for member in added {
+            changes.major(format!(
+                "member `{}` was added to trait `{}`.",
+                member.name, new.name
+            ));
+        }
+        for (old_member, new_member) in both {
+            if old_member.ty != new_member.ty {
+                changes.major(format!(
+                    "the type of member `{}` of trait `{}` changed from `{}` to `{}`.",
+                    new_member.name, new.name, old_member.ty, new_member.ty
+                ));
+            }
+        }
+
+        let (removed, added, both) =
+            match_by_name(&old.assoc_types, &new.assoc_types, |a| a.name.as_str());
+        for assoc_type in removed {
+            changes.major(format!(
+                "associated type `{}` of trait `{}` was removed.",
+                assoc_type.name, new.name
+            ));
+        }
+        for assoc_type in added {
+            changes.major(format!(
+                "associated type `{}` was added to trait `{}`.",
+                assoc_type.name, new.name
+            ));
+        }
+        for (old_assoc, new_assoc) in both {
+            let old_sign = format!(
+                "{} : {}",
+                tyvars_to_string(&old_assoc.params),
+                old_assoc.kind
+            );
+            let new_sign = format!(
+                "{} : {}",
+                tyvars_to_string(&new_assoc.params),
+                new_assoc.kind
+            );
+            if old_sign != new_sign {
+                changes.major(format!(
+                    "associated type `{}` of trait `{}` changed from `{}` to `{}`.",
+                    new_assoc.name, new.name, old_sign, new_sign
+                ));
+            }
+        }
+    }
+}
+
+fn diff_trait_aliases(old: &[JsonTraitAlias], new: &[JsonTraitAlias], changes: &mut Changes) {
+    let (removed, added, both) = match_by_name(old, new, |t| t.name.as_str());
+    for alias in removed {
+        changes.major(format!("trait alias `{}` was removed.", alias.name));
+    }
+    for alias in added {
+        changes.minor(format!("trait alias `{}` was added.", alias.name));
+    }
+    for (old, new) in both {
+        if old.traits != new.traits {
+            changes.major(format!(
+                "trait alias `{}` changed from `{}` to `{}`.",
+                new.name,
+                old.traits.join(" + "),
+                new.traits.join(" + ")
+            ));
+        }
+    }
+}
+
+fn diff_impls(old: &[JsonImpl], new: &[JsonImpl], changes: &mut Changes) {
+    // An implementation is known by its head, `type : trait`, whatever its constraints are.
+    let head = |impl_: &JsonImpl| split_constraints(&impl_.predicate).1;
+    let old_heads = old.iter().map(head).collect::<Vec<_>>();
+    let new_heads = new.iter().map(head).collect::<Vec<_>>();
+    for (old_impl, old_head) in old.iter().zip(&old_heads) {
+        match new_heads.iter().position(|h| h == old_head) {
+            None => changes.major(format!("impl `{}` was removed.", old_head)),
+            Some(i) => diff_signatures(
+                &format!("impl `{}`", old_head),
+                &old_impl.predicate,
+                &new[i].predicate,
+                changes,
+            ),
+        }
+    }
+    for new_head in &new_heads {
+        if !old_heads.contains(new_head) {
+            changes.minor(format!("impl `{}` was added.", new_head));
+        }
+    }
+}
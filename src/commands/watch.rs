@@ -0,0 +1,135 @@
+//! `--watch` for `fix build`, `fix run`, `fix test`, `fix bench` and `fix check`: the command runs,
+//! and runs again each time a file it read changes, until the user interrupts it.
+//!
+//! The files are polled for their modification times, and a burst of changes — an editor saving
+//! several files, a `git checkout` — is debounced into one run the way the language server
+//! debounces edits into one diagnostics run. The runs share one type-check cache held in memory in
+//! front of the project's file cache, so a run after an edit checks only what the edit reached; the
+//! object files of the units an edit left alone are taken from the disk as in any build.
+
+use crate::commands::lsp::server::DEFAULT_ANALYZE_DELAY_MS;
+use crate::configuration::Configuration;
+use crate::constants::PROJECT_FILE_PATH;
+use crate::elaboration::typecheckcache::{FileCache, MemoryAndFileCache, SharedTypeCheckCache};
+use crate::error::{Errors, MessageFormat};
+use crate::misc::{info_msg, Map};
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the watched files are polled for changes.
+const POLL_INTERVAL_MS: u64 = 100;
+
+/// What a watched file looked like when it was polled: when it was last modified and how long it
+/// was, or `None` while it does not exist. A file that is removed, or created, counts as changed.
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn stamp_of(path: &PathBuf) -> FileStamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn stamps_of(paths: &[PathBuf]) -> Map<PathBuf, FileStamp> {
+    paths
+        .iter()
+        .map(|path| (path.clone(), stamp_of(path)))
+        .collect()
+}
+
+/// The files a command run under `config` reads: the project file, and the sources of every
+/// project the program is built from — the dependencies' among them — together with the source
+/// files the command line names.
+fn watched_files(config: &Configuration) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(PROJECT_FILE_PATH)];
+    for sources in &config.project_sources {
+        files.extend(sources.files.iter().cloned());
+    }
+    files.extend(config.source_files().cloned());
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Runs `f`, turning a panic into `None`. The compiler reports a fatal error by printing it
+/// through the panic hook and unwinding, so the message has reached the user by then; the watch
+/// goes on to wait for the edit that fixes it.
+fn run_guarded<T>(f: impl FnOnce() -> Result<T, Errors>) -> Option<Result<T, Errors>> {
+    catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Waits until a file of `files` differs from `stamps`, and then until none of them has changed for
+/// the debounce window.
+fn wait_for_change(files: &[PathBuf], stamps: Map<PathBuf, FileStamp>) {
+    let poll_interval = Duration::from_millis(POLL_INTERVAL_MS);
+    let mut last = loop {
+        thread::sleep(poll_interval);
+        let current = stamps_of(files);
+        if current != stamps {
+            break current;
+        }
+    };
+    let debounce = Duration::from_millis(DEFAULT_ANALYZE_DELAY_MS);
+    let mut quiet_since = Instant::now();
+    while quiet_since.elapsed() < debounce {
+        thread::sleep(poll_interval);
+        let current = stamps_of(files);
+        if current != last {
+            last = current;
+            quiet_since = Instant::now();
+        }
+    }
+}
+
+/// Runs a command over and over, once at the start and once after each change to the files it
+/// read, and never returns.
+///
+/// # Arguments
+///
+/// * `create_config` - Creates the configuration of a run, reading the project file and installing
+///   the dependencies it names, so an edit to the project file takes effect in the next run.
+/// * `run_once` - Runs the command under a configuration. What it fails with is reported, and the
+///   watch goes on.
+/// * `format` - The form diagnostics are reported in when no configuration could be created.
+pub fn watch(
+    mut create_config: impl FnMut() -> Result<Configuration, Errors>,
+    mut run_once: impl FnMut(&Configuration) -> Result<(), Errors>,
+    format: MessageFormat,
+) -> ! {
+    let typecheck_cache: SharedTypeCheckCache = Arc::new(MemoryAndFileCache::new());
+    // Remove the type-check cache entries the sessions before this one left over, once: pruning
+    // after each run would list the whole cache on every edit.
+    FileCache::new().prune_current_build();
+    // A run that fails before it knows the project's sources waits on the files the last run read,
+    // and on the project file, whose edit is the likely fix.
+    let mut files = vec![PathBuf::from(PROJECT_FILE_PATH)];
+    loop {
+        let config = match run_guarded(&mut create_config) {
+            Some(Ok(mut config)) => {
+                config.type_check_cache = typecheck_cache.clone();
+                files = watched_files(&config);
+                Some(config)
+            }
+            Some(Err(errs)) => {
+                errs.report(format);
+                None
+            }
+            None => None,
+        };
+        // The files are stamped before the run, so that an edit made while it runs starts the next.
+        let stamps = stamps_of(&files);
+        if let Some(config) = config {
+            if let Some(Err(errs)) = run_guarded(|| run_once(&config)) {
+                errs.report(config.message_format);
+            }
+        }
+        info_msg(&format!(
+            "Watching {} files for changes. Press Ctrl-C to stop.",
+            files.len()
+        ));
+        wait_for_change(&files, stamps);
+        info_msg("A file changed; running again.");
+    }
+}
@@ -1,15 +1,17 @@
 use crate::ast::name::FullName;
 use crate::build::cpu_features::CpuFeatures;
 use crate::constants::{
-    CHECK_C_TYPES_PATH, C_CHAR_NAME, C_DOUBLE_NAME, C_FLOAT_NAME, C_INT_NAME, C_LONG_LONG_NAME,
-    C_LONG_NAME, C_SHORT_NAME, C_SIZE_T_NAME, C_TYPES_JSON_PATH, C_UNSIGNED_CHAR_NAME,
-    C_UNSIGNED_INT_NAME, C_UNSIGNED_LONG_LONG_NAME, C_UNSIGNED_LONG_NAME, C_UNSIGNED_SHORT_NAME,
-    DEFAULT_COMPILATION_UNIT_MAX_SIZE, MAX_SPLIT_SCALARS, OPTIMIZATION_LEVEL_BASIC,
-    OPTIMIZATION_LEVEL_EXPERIMENTAL, OPTIMIZATION_LEVEL_MAX, OPTIMIZATION_LEVEL_NONE,
+    BENCH_BASELINE_PATH, CHECK_C_TYPES_PATH, C_CHAR_NAME, C_DOUBLE_NAME, C_FLOAT_NAME, C_INT_NAME,
+    C_LONG_LONG_NAME, C_LONG_NAME, C_SHORT_NAME, C_SIZE_T_NAME, C_TYPES_JSON_PATH,
+    C_UNSIGNED_CHAR_NAME, C_UNSIGNED_INT_NAME, C_UNSIGNED_LONG_LONG_NAME, C_UNSIGNED_LONG_NAME,
+    C_UNSIGNED_SHORT_NAME, DEFAULT_COMPILATION_UNIT_MAX_SIZE, GDB_SCRIPT_SUFFIX,
+    LLDB_SCRIPT_FILE_NAME, MAIN_FUNCTION_NAME, MAIN_MODULE_NAME, MAX_SPLIT_SCALARS,
+    OPTIMIZATION_LEVEL_BASIC, OPTIMIZATION_LEVEL_EXPERIMENTAL, OPTIMIZATION_LEVEL_MAX,
+    OPTIMIZATION_LEVEL_NONE, REPL_ENTRY_NAME, REPL_MODULE_NAME,
 };
 use crate::elaboration::typecheckcache::{FileCache, TypeCheckCache};
 use crate::env_vars;
-use crate::error::{panic_if_err, panic_with_msg, Errors};
+use crate::error::{panic_if_err, panic_with_msg, Errors, MessageFormat};
 use crate::hash::HashSource;
 use crate::metafiles::project_file::{ProjectName, ProjectOrigin};
 use crate::misc::{
@@ -88,6 +90,8 @@ pub enum OutputFileType {
     Executable,
     /// A shared library other programs link against.
     DynamicLibrary,
+    /// An archive of object files that other programs link into themselves.
+    StaticLibrary,
 }
 
 impl OutputFileType {
@@ -96,6 +100,7 @@ impl OutputFileType {
         match file_type {
             "exe" => Ok(OutputFileType::Executable),
             "dylib" => Ok(OutputFileType::DynamicLibrary),
+            "staticlib" => Ok(OutputFileType::StaticLibrary),
             _ => Err(Errors::from_msg(format!(
                 "Unknown output file type: `{}`",
                 file_type
@@ -108,6 +113,16 @@ impl OutputFileType {
         match self {
             OutputFileType::Executable => "exe",
             OutputFileType::DynamicLibrary => "dylib",
+            OutputFileType::StaticLibrary => "staticlib",
+        }
+    }
+
+    /// Whether this kind is a library, which carries the functions `FFI_EXPORT` defines for another
+    /// program to call, and no entry point of its own.
+    pub fn is_library(&self) -> bool {
+        match self {
+            OutputFileType::Executable => false,
+            OutputFileType::DynamicLibrary | OutputFileType::StaticLibrary => true,
         }
     }
 
@@ -131,6 +146,13 @@ impl OutputFileType {
                     "lib.so"
                 }
             }
+            OutputFileType::StaticLibrary => {
+                if env::consts::OS == "windows" {
+                    "lib.lib"
+                } else {
+                    "lib.a"
+                }
+            }
         }
     }
 }
@@ -215,6 +237,42 @@ impl Sanitizer {
     }
 }
 
+/// What the generated program counts about itself while it runs, to report when it exits.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Profile {
+    /// Generate the program as it is built for use.
+    None,
+    /// Count the allocations, retains, releases and force-unique clones the program performs, by
+    /// the source location each is performed at, and write a report ranking those locations when
+    /// the program exits. See `RcProfileEvent`.
+    Rc,
+}
+
+impl fmt::Display for Profile {
+    /// Writes the name a `profile` setting spells this profile with, which `Profile::from_str`
+    /// reads back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Profile::None => write!(f, "none"),
+            Profile::Rc => write!(f, "rc"),
+        }
+    }
+}
+
+impl Profile {
+    /// Reads the value a `profile` setting names, or reports the names there are.
+    pub fn from_str(name: &str) -> Result<Profile, Errors> {
+        match name {
+            "none" => Ok(Profile::None),
+            "rc" => Ok(Profile::Rc),
+            _ => Err(Errors::from_msg(format!(
+                "Unknown profile \"{}\". Available profiles are \"none\" and \"rc\".",
+                name
+            ))),
+        }
+    }
+}
+
 /// The subcommand of the `fix` command that the invocation selected, carrying the settings that
 /// belong to that subcommand alone.
 #[derive(Clone)]
@@ -225,11 +283,17 @@ pub enum SubCommand {
     Run,
     /// Build the test program and run it.
     Test,
+    /// Build the benchmark program at the highest optimization level, run it, and report how long
+    /// each benchmark took against the saved baseline.
+    Bench(BenchConfig),
     /// Elaborate the source files and report the errors and warnings found in them, for the
     /// language server.
     Diagnostics(DiagnosticsConfig),
     /// Generate documentation for the modules.
     Docs(DocsConfig),
+    /// Build each input of the interactive session, whose entry point is the module `fix repl`
+    /// writes, into code generated in memory and run in the compiler's process.
+    Repl,
 }
 
 /// Which section of the project file a build reads its settings from.
@@ -256,20 +320,25 @@ impl SubCommand {
             SubCommand::Build => true,
             SubCommand::Run => true,
             SubCommand::Test => true,
+            SubCommand::Bench(_) => true,
             SubCommand::Diagnostics(_) => false,
             SubCommand::Docs(_) => false,
+            SubCommand::Repl => true,
         }
     }
 
     /// Whether the build goes on to generate code and link a binary. Reporting diagnostics and
-    /// generating documentation stop with the elaborated program in hand.
+    /// generating documentation stop with the elaborated program in hand, and the interactive session
+    /// generates code in memory, linking nothing.
     pub fn build_binary(&self) -> bool {
         match self {
             SubCommand::Build => true,
             SubCommand::Run => true,
             SubCommand::Test => true,
+            SubCommand::Bench(_) => true,
             SubCommand::Diagnostics(_) => false,
             SubCommand::Docs(_) => false,
+            SubCommand::Repl => false,
         }
     }
 
@@ -281,8 +350,10 @@ impl SubCommand {
             SubCommand::Build => true,
             SubCommand::Run => false,
             SubCommand::Test => false,
+            SubCommand::Bench(_) => false,
             SubCommand::Diagnostics(_) => false,
             SubCommand::Docs(_) => false,
+            SubCommand::Repl => false,
         }
     }
 
@@ -292,8 +363,10 @@ impl SubCommand {
             SubCommand::Build => BuildConfigType::Build,
             SubCommand::Run => BuildConfigType::Build,
             SubCommand::Test => BuildConfigType::Test,
+            SubCommand::Bench(_) => BuildConfigType::Build,
             SubCommand::Diagnostics(_) => BuildConfigType::Test,
             SubCommand::Docs(docs_config) => docs_config.mode,
+            SubCommand::Repl => BuildConfigType::Build,
         }
     }
 
@@ -304,8 +377,10 @@ impl SubCommand {
             SubCommand::Build => true,
             SubCommand::Run => true,
             SubCommand::Test => true,
+            SubCommand::Bench(_) => true,
             SubCommand::Diagnostics(_) => true,
             SubCommand::Docs(_) => false,
+            SubCommand::Repl => true,
         }
     }
 
@@ -315,8 +390,10 @@ impl SubCommand {
             SubCommand::Build => "build",
             SubCommand::Run => "run",
             SubCommand::Test => "test",
+            SubCommand::Bench(_) => "bench",
             SubCommand::Diagnostics(_) => "diagnostics",
             SubCommand::Docs(_) => "docs",
+            SubCommand::Repl => "repl",
         }
     }
 }
@@ -349,6 +426,76 @@ pub struct DiagnosticsConfig {
     /// error in part of a body (e.g. an `if` condition) does not
     /// blank out inferred types elsewhere in the same body.
     pub error_tolerant: bool,
+    /// Go on past type-checking to instantiate the program as `fix build` does, for a report made
+    /// of the code the optimizations see, such as the one of `--explain-uniqueness`. The
+    /// diagnostics of type-checking are collected as in a build: the first error stops it.
+    pub instantiate: bool,
+}
+
+/// How the `bench` subcommand measures the benchmarks, and the baseline it compares them with.
+#[derive(Clone)]
+pub struct BenchConfig {
+    /// How long each benchmark is run before it is measured, in seconds. The measurements are
+    /// spread over what the warm-up shows one run of the benchmark to take.
+    pub warm_up_time: f64,
+    /// How long each benchmark is measured for, in seconds, over all of its samples together.
+    pub measurement_time: f64,
+    /// The number of samples taken of each benchmark. A sample times as many runs of the benchmark
+    /// as fill its share of the measurement time.
+    pub samples: u64,
+    /// The file the results are compared with, and saved to as the new baseline.
+    pub baseline_path: PathBuf,
+    /// Whether to save the results as the baseline even when one is saved already.
+    pub save_baseline: bool,
+}
+
+impl Default for BenchConfig {
+    /// One second of warm-up and three of measurement over 30 samples, compared with the baseline
+    /// under `.fixlang`.
+    fn default() -> Self {
+        BenchConfig {
+            warm_up_time: 1.0,
+            measurement_time: 3.0,
+            samples: 30,
+            baseline_path: PathBuf::from(BENCH_BASELINE_PATH),
+            save_baseline: false,
+        }
+    }
+}
+
+/// The form `fix docs` writes the documentation in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    /// A Markdown file for each module.
+    Markdown,
+    /// A page for each module, in which the names in signatures link to the pages documenting
+    /// them, together with an index page and a search index read by a script on the pages, so
+    /// that the documentation is browsed from the disk without a server.
+    Html,
+    /// A JSON file for each module describing its entries, for tools to read. The layout is
+    /// versioned by `DOCS_JSON_SCHEMA_VERSION`.
+    Json,
+}
+
+impl Default for DocsFormat {
+    fn default() -> Self {
+        DocsFormat::Markdown
+    }
+}
+
+impl DocsFormat {
+    /// Reads the value a `--format` option of `fix docs` names, or reports the names there are.
+    pub fn from_str(name: &str) -> Result<DocsFormat, Errors> {
+        match name {
+            "markdown" => Ok(DocsFormat::Markdown),
+            "html" => Ok(DocsFormat::Html),
+            "json" => Ok(DocsFormat::Json),
+            _ => Err(Errors::from_msg(format!(
+                "Unknown documentation format \"{}\". Available formats are \"markdown\", \"html\" and \"json\".",
+                name
+            ))),
+        }
+    }
 }
 
 /// What the `docs` subcommand documents, and where it writes the documentation.
@@ -361,8 +508,12 @@ pub struct DocsConfig {
     pub include_compiler_defined_methods: bool,
     /// Whether the documentation also covers the private items, those whose names begin with `_`.
     pub include_private: bool,
-    /// The directory the documentation is written into, as one `<module>.md` file per module.
+    /// The directory the documentation is written into, as one `<module>.md` file per module, or
+    /// one `<module>.html` file per module beside the index and the search files, or one
+    /// `<module>.json` file per module.
     pub out_dir: PathBuf,
+    /// The form the documentation is written in.
+    pub format: DocsFormat,
     /// Which section of the project file the settings for assembling the documented program are
     /// taken from.
     pub mode: BuildConfigType,
@@ -447,6 +598,14 @@ pub struct Configuration {
     ///
     /// Every one of these is compiled, so `source_files` covers them.
     pub root_source_files: Vec<PathBuf>,
+    /// The source files the `[build.test]` section of the root project adds to its `build` section.
+    /// `fix test` runs the values named `test_*` that these files define; `test_source_files`
+    /// answers with them and the files no project supplies.
+    pub root_test_only_source_files: Vec<PathBuf>,
+    /// The source files the `[build.bench]` section of the root project adds to its `build` section
+    /// in a benchmark build. `fix bench` measures the values named `bench_*` that these files
+    /// define; `bench_source_files` answers with them and the files no project supplies.
+    pub root_bench_only_source_files: Vec<PathBuf>,
     /// The sources every project contributes to the build, beside what that project declares for
     /// them, the root project and every dependency alike. `ProjectFile::set_config` adds them as it
     /// configures each project.
@@ -478,10 +637,13 @@ pub struct Configuration {
     /// Where the build writes what it produces. `None` leaves the name to the build, which forms
     /// it from the kind of file it produces.
     pub out_file_path: Option<PathBuf>,
-    /// The kind of file the build produces, an executable or a dynamic library. It reaches the
-    /// generated code as well: a dynamic library is compiled with position-independent
+    /// The kind of file the build produces, an executable or a dynamic or static library. It
+    /// reaches the generated code as well: a library is compiled with position-independent
     /// relocations, and an executable is the kind that carries the entry point.
     pub output_file_type: OutputFileType,
+    /// Where a build of a library writes the C header declaring the functions it exports. `None`
+    /// puts it beside the library, under the library's name with the extension `.h`.
+    pub c_header_path: Option<PathBuf>,
     /// Whether the program is built to run several threads, which makes its reference counting
     /// safe to share between them at the cost of speed. Turn it on through `set_threaded`, which
     /// also links the pthread library.
@@ -527,6 +689,9 @@ pub struct Configuration {
     pub num_worker_thread: usize,
     /// The arguments the built program is given in `run` mode.
     pub run_program_args: Vec<String>,
+    /// The environment variables the built program is given in `run` mode, beside the ones the
+    /// `fix` process has.
+    pub run_program_env: Vec<(String, String)>,
     /// LLVM passes to run in place of the ones the optimization level implies.
     /// Used only for compiler development.
     pub llvm_passes_override: Option<Vec<String>>,
@@ -536,11 +701,16 @@ pub struct Configuration {
     /// Dump the RC IR of the named module's symbols (`all` = every module) to a file under
     /// `.fixlang/`. `None` dumps nothing. Used only for compiler development.
     pub emit_rc_ir: Option<String>,
+    /// Report each operation in the user's sources that keeps its run-time uniqueness check, and
+    /// why. Set by `--explain-uniqueness`.
+    pub explain_uniqueness: bool,
     /// Run the compiler's own consistency checks — the RC IR validator and the assertions in the
     /// code generator — and turn an internal error into a panic.
     pub develop_mode: bool,
     /// Enable backtrace support: keep frame pointers and link the backtrace library.
     pub backtrace: bool,
+    /// What the generated program counts about itself and reports when it exits.
+    pub profile: Profile,
     /// Leave the run-time checks, such as the array bounds check, out of the program.
     pub no_runtime_check: bool,
     /// Compile `eval {side}; {main}` as `{main}`, so that the effect of `{side}` is left out of the
@@ -548,6 +718,13 @@ pub struct Configuration {
     pub skip_eval: bool,
     /// How `DEPRECATED` warnings are handled. See `DeprecationMode`.
     pub deprecation_mode: DeprecationMode,
+    /// The form in which diagnostics are reported: text for a person, or JSON for a tool. Set by
+    /// `--message-format`.
+    pub message_format: MessageFormat,
+    /// The level each lint is reported at, keyed by the lint's diagnostic code, as the `lints`
+    /// section of the root project file sets it. A lint left out is reported as a warning. See
+    /// `Configuration::lint_level`.
+    pub lint_levels: Map<String, LintLevel>,
 }
 
 /// How the compiler reacts to a use of a deprecated item.
@@ -568,6 +745,38 @@ impl Default for DeprecationMode {
     }
 }
 
+/// How a lint's findings are reported, as the `lints` section of the project file sets it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// Report nothing.
+    Allow,
+    /// Report each finding as a warning (default).
+    Warn,
+    /// Report each finding as an error, failing the compilation.
+    Deny,
+}
+
+impl Default for LintLevel {
+    fn default() -> Self {
+        LintLevel::Warn
+    }
+}
+
+impl LintLevel {
+    /// Reads the level a `lints` entry names, or reports the names there are.
+    pub fn from_str(name: &str) -> Result<LintLevel, Errors> {
+        match name {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(Errors::from_msg(format!(
+                "Unknown lint level \"{}\". Available levels are \"allow\", \"warn\" and \"deny\".",
+                name
+            ))),
+        }
+    }
+}
+
 /// How hard the compiler works to make the program fast, trading compile time for run time. The
 /// variants are ordered, so a pass can turn itself on from a given level up.
 #[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
@@ -612,6 +821,8 @@ impl Configuration {
             subcommand,
             extra_source_files: vec![],
             root_source_files: vec![],
+            root_test_only_source_files: vec![],
+            root_bench_only_source_files: vec![],
             project_sources: vec![],
             object_files: vec![],
             fix_opt_level: env_vars::get_max_opt_level(),
@@ -624,6 +835,7 @@ impl Configuration {
             emit_llvm: false,
             out_file_path: None,
             output_file_type: OutputFileType::Executable,
+            c_header_path: None,
             threaded: false,
             runtime_c_macro: vec![],
             show_build_times: false,
@@ -641,13 +853,18 @@ impl Configuration {
             num_worker_thread: 0,
             llvm_passes_override: None,
             run_program_args: vec![],
+            run_program_env: vec![],
             emit_symbols: false,
             emit_rc_ir: None,
+            explain_uniqueness: false,
             develop_mode: false,
             backtrace: false,
+            profile: Profile::None,
             no_runtime_check: false,
             skip_eval: false,
             deprecation_mode: DeprecationMode::default(),
+            message_format: MessageFormat::default(),
+            lint_levels: Map::default(),
         })
     }
 }
@@ -749,6 +966,26 @@ impl Configuration {
             .chain(self.extra_source_files.iter())
     }
 
+    /// The source files `fix test` looks for tests named `test_*` in: the ones the `[build.test]`
+    /// section of the root project adds, and the ones no project supplies.
+    pub fn test_source_files(&self) -> Vec<PathBuf> {
+        self.root_test_only_source_files
+            .iter()
+            .chain(self.extra_source_files.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// The source files `fix bench` looks for benchmarks named `bench_*` in: the ones the
+    /// `[build.bench]` section of the root project adds, and the ones no project supplies.
+    pub fn bench_source_files(&self) -> Vec<PathBuf> {
+        self.root_bench_only_source_files
+            .iter()
+            .chain(self.extra_source_files.iter())
+            .cloned()
+            .collect()
+    }
+
     /// Where `--emit-llvm` writes one compilation unit's LLVM IR: a `.ll` file beside the output
     /// file, or in the working directory where the build names no output file.
     ///
@@ -787,6 +1024,20 @@ impl Configuration {
         }
     }
 
+    /// The level the lint of diagnostic code `code` is reported at.
+    pub fn lint_level(&self, code: &str) -> LintLevel {
+        self.lint_levels.get(code).copied().unwrap_or_default()
+    }
+
+    /// The `IO ()` value the entry point of an executable runs: `Main::main`, or, in the interactive
+    /// session, the value running the input.
+    pub fn entry_io_value_name(&self) -> FullName {
+        match self.subcommand {
+            SubCommand::Repl => FullName::from_strs(&[REPL_MODULE_NAME], REPL_ENTRY_NAME),
+            _ => FullName::from_strs(&[MAIN_MODULE_NAME], MAIN_FUNCTION_NAME),
+        }
+    }
+
     /// Where the build writes what it produces: the path the settings name, or the default name of
     /// the kind of file being produced.
     pub fn get_output_file_path(&self) -> PathBuf {
@@ -796,6 +1047,34 @@ impl Configuration {
         }
     }
 
+    /// Where a build of a library writes the C header declaring the functions it exports: the path
+    /// the settings name, or the path of the library with the extension `.h`.
+    pub fn get_c_header_path(&self) -> PathBuf {
+        match &self.c_header_path {
+            None => self.get_output_file_path().with_extension("h"),
+            Some(c_header_path) => c_header_path.clone(),
+        }
+    }
+
+    /// Where a build with debug information writes the gdb pretty-printers of its values: beside the
+    /// output, under the output's file name followed by `-gdb.py`, which is the name gdb loads the
+    /// script of a program by when it loads the program.
+    pub fn get_gdb_script_path(&self) -> PathBuf {
+        let out_path = self.get_output_file_path();
+        let mut file_name = out_path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(GDB_SCRIPT_SUFFIX);
+        out_path.with_file_name(file_name)
+    }
+
+    /// Where a build with debug information writes the lldb formatters of its values: the file
+    /// `LLDB_SCRIPT_FILE_NAME` in the directory of the output. lldb names the module a script
+    /// defines after the file, so the file is named by a Python identifier rather than after the
+    /// output.
+    pub fn get_lldb_script_path(&self) -> PathBuf {
+        self.get_output_file_path()
+            .with_file_name(LLDB_SCRIPT_FILE_NAME)
+    }
+
     /// Builds the program to run several threads, and links the pthread library its runtime then
     /// calls into.
     pub fn set_threaded(&mut self) {
@@ -850,6 +1129,10 @@ impl Configuration {
     /// is externally visible, since another unit calls it. Runs at `Basic` and below; above that the
     /// program is one unit, which is what lets a pass see all of it at once.
     pub fn enable_separated_compilation(&self) -> bool {
+        // The interactive session compiles the whole program into the one module it runs.
+        if matches!(self.subcommand, SubCommand::Repl) {
+            return false;
+        }
         !self.force_all_optimizations() && self.fix_opt_level <= FixOptimizationLevel::Basic
     }
 
@@ -955,6 +1238,16 @@ impl Configuration {
         }
     }
 
+    /// Build the program so that it counts what `profile` names and reports it when it exits: for
+    /// `Profile::Rc`, define the runtime's `PROFILE_RC` macro, under which the runtime keeps the
+    /// counts and writes the report.
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = profile;
+        if profile == Profile::Rc {
+            self.runtime_c_macro.push("PROFILE_RC".to_string());
+        }
+    }
+
     /// Whether the generated code must keep its frame pointers. macOS's `backtrace()` walks them, so
     /// a build that prints a backtrace there keeps them.
     pub fn no_elim_frame_pointers(&self) -> bool {
@@ -1025,12 +1318,15 @@ impl Configuration {
         // uninstrumented objects and report a clean run of a program nothing was checking.
         hash_source.push_text(&self.sanitizer.to_string());
         hash_source.push_text(&self.backtrace.to_string());
+        // A profiled program calls into the runtime at every event it counts, so an object built
+        // without the calls would report a program that performed nothing.
+        hash_source.push_text(&self.profile.to_string());
         hash_source.push_text(&self.no_runtime_check.to_string());
         hash_source.push_text(&self.skip_eval.to_string());
         hash_source.push_text(&self.c_type_sizes.to_string());
         hash_source.push_text(&self.max_split_scalars.to_string());
-        // The kind of the output file reaches the code in two ways: a dynamic library is generated
-        // with position-independent relocations (`get_target_machine`), and an executable is the
+        // The kind of the output file reaches the code in two ways: a library is generated with
+        // position-independent relocations (`get_target_machine`), and an executable is the
         // only kind that carries the entry point (`elaborate_via_config`). An object built for one
         // kind therefore fails to link into the other.
         hash_source.push_text(self.output_file_type.to_str());
@@ -1045,6 +1341,21 @@ impl Configuration {
         // Command type.
         // The implementation of the entry point function differs depending on the command type.
         hash_source.push_text(self.subcommand.command_type_string());
+        // In test mode, the entry point runs the tests the test source files define, and in bench
+        // mode the benchmarks the benchmark source files define.
+        let case_source_files = match self.subcommand {
+            SubCommand::Test => Some(self.test_source_files()),
+            SubCommand::Bench(_) => Some(self.bench_source_files()),
+            _ => None,
+        };
+        if let Some(case_source_files) = case_source_files {
+            hash_source.push_list(
+                &case_source_files
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect::<Vec<_>>(),
+            );
+        }
 
         // Build time of the compiler.
         hash_source.push_text(build_time_utc!());
@@ -1436,8 +1747,8 @@ int main() {
 #[cfg(test)]
 mod tests {
     use super::{
-        llvm_passes_for_speed, Configuration, FixOptimizationLevel, OutputFileType, Sanitizer,
-        SubCommand,
+        llvm_passes_for_speed, Configuration, FixOptimizationLevel, OutputFileType, Profile,
+        Sanitizer, SubCommand,
     };
     use crate::misc::Map;
     use std::fs;
@@ -1610,6 +1921,10 @@ mod tests {
                 "backtrace",
                 Box::new(|config: &mut Configuration| config.backtrace = true),
             ),
+            (
+                "profile",
+                Box::new(|config: &mut Configuration| config.profile = Profile::Rc),
+            ),
             (
                 "no_runtime_check",
                 Box::new(|config: &mut Configuration| config.no_runtime_check = true),
@@ -7,11 +7,16 @@ pub const MODULE_SEPARATOR: &str = ".";
 pub const STD_NAME: &str = "Std";
 pub const FFI_NAME: &str = "FFI";
 pub const IO_NAME: &str = "IO";
+/// The name of `Std::IO::IOFail`, the type of I/O actions which may fail, and of its namespace.
+pub const IO_FAIL_NAME: &str = "IOFail";
+/// The name of `Std::Result`.
+pub const RESULT_NAME: &str = "Result";
 /// The field of `Std::IO` holding the action, a function taking an `IOState` to the state after the
 /// action and the action's result.
 pub const IO_DATA_NAME: &str = "runner";
 /// The `Std` value that puts the values reachable from a value into multi-threaded mode.
 pub const MARK_THREADED_NAME: &str = "mark_threaded";
+pub const GET_FUNPTR_CALLBACK_NAME: &str = "get_funptr_callback";
 pub const PTR_NAME: &str = "Ptr";
 pub const U8_NAME: &str = "U8";
 pub const I8_NAME: &str = "I8";
@@ -47,6 +52,7 @@ pub const LAZY_NAME: &str = "Lazy";
 pub const FUNCTOR_NAME: &str = "Functor";
 pub const TUPLE_NAME: &str = "Tuple";
 pub const DESTRUCTOR_NAME: &str = "Destructor";
+pub const BOX_NAME: &str = "Box";
 pub const DESTRUCTOR_OBJECT_VALUE_FIELD_IDX: u32 = 0;
 pub const DESTRUCTOR_OBJECT_DTOR_FIELD_IDX: u32 = 1;
 pub const STRING_NAME: &str = "String";
@@ -63,10 +69,25 @@ pub const MAIN_MODULE_NAME: &str = "Main";
 /// The name of the entry point in the object file: the function the C runtime calls once it has set
 /// the process up, which the compiler generates to run the program's `IO` action.
 pub const C_ENTRY_POINT_NAME: &str = "main";
-/// The name of the Fix value `fix test` starts from: `Test::test`, of type `IO ()`.
+/// The name of the Fix value `fix test` runs as a test wherever it is defined: `Test::test`, of
+/// type `IO ()` or `IO (Result ErrMsg ())`.
 pub const TEST_FUNCTION_NAME: &str = "test";
-/// The name of the module whose namespace holds the test entry point `Test::test`.
+/// The name of the module whose namespace holds the test `Test::test`.
 pub const TEST_MODULE_NAME: &str = "Test";
+/// The prefix of the names of the values `fix test` finds in the test source files and runs as
+/// tests.
+pub const TEST_CASE_PREFIX: &str = "test_";
+/// The prefix of the names of the values `fix bench` finds in the benchmark source files and
+/// measures as benchmarks.
+pub const BENCH_CASE_PREFIX: &str = "bench_";
+/// The name of the module `fix repl` writes each input into.
+pub const REPL_MODULE_NAME: &str = "Repl";
+/// The name of the `IO ()` value of the module `REPL_MODULE_NAME` that runs an input of `fix repl`,
+/// which the program built for the input starts from in place of `Main::main`.
+pub const REPL_ENTRY_NAME: &str = "repl_main";
+/// The `Std::IO::IOFail` value that turns a test of type `IO (Result ErrMsg ())` into an `IO ()`,
+/// which writes the message of an `err` to stderr and exits with status 1.
+pub const TEST_RESULT_RUNNER_NAME: &str = "_run_test_result";
 pub const BOXED_TRAIT_NAME: &str = "Boxed";
 pub const WITH_RETAINED_NAME: &str = "with_retained";
 #[allow(unused)]
@@ -265,6 +286,10 @@ pub const CTRL_BLK_ALLOC_OFFSET_IDX: u32 = 2;
 // Paths
 pub const DOT_FIXLANG: &str = ".fixlang";
 pub const RUN_PATH: &str = ".fixlang/run";
+pub const REPL_PATH: &str = ".fixlang/repl";
+pub const DOCTEST_PATH: &str = ".fixlang/doctest";
+pub const BENCH_BASELINE_PATH: &str = ".fixlang/bench/baseline.json";
+pub const SEMVER_CHECK_PATH: &str = ".fixlang/semver-check";
 pub const TYPE_CHECK_CACHE_PATH: &str = ".fixlang/cache/typecheck";
 pub const UNITS_CACHE_PATH: &str = ".fixlang/cache/units";
 pub const INTERMEDIATE_PATH: &str = ".fixlang/intermediate";
@@ -279,6 +304,10 @@ pub const PROJECT_FILE_PATH: &str = "fixproj.toml";
 pub const SAMPLE_MAIN_FILE_PATH: &str = "main.fix";
 pub const SAMPLE_TEST_FILE_PATH: &str = "test.fix";
 pub const LOCK_FILE_PATH: &str = "fixdeps.lock";
+// What a build with debug information writes beside the output: the gdb pretty-printers, under the
+// output's name followed by this suffix, and the lldb formatters, under this file name.
+pub const GDB_SCRIPT_SUFFIX: &str = "-gdb.py";
+pub const LLDB_SCRIPT_FILE_NAME: &str = "fix_lldb.py";
 pub const LOCK_FILE_TEST_PATH: &str = "fixdeps.test.lock";
 pub const LOCK_FILE_LSP_PATH: &str = ".fixlang/fixdeps.lsp.lock";
 pub const EXTERNAL_PROJ_INSTALL_PATH: &str = ".fixlang/deps";
@@ -396,6 +425,7 @@ pub const ERR_AMBIGUOUS_NAME: &str = "ambiguous-name";
 pub const ERR_NO_VALUE_MATCH: &str = "no-value-match";
 pub const ERR_MISSING_TRAIT_IMPL: &str = "missing-trait-impl";
 pub const ERR_MISSING_STRUCT_FIELD: &str = "missing-struct-field";
+pub const ERR_NON_EXHAUSTIVE_MATCH: &str = "non-exhaustive-match";
 /// Diagnostic code emitted for each `Std::#hole` reference left in the
 /// program after elaboration.
 pub const ERR_HOLE: &str = "missing-expression";
@@ -414,6 +444,10 @@ pub const HOLE_NAME: &str = "#hole";
 /// binder back as `_`.
 pub const PATTERN_WILDCARD_VAR_PREFIX: &str = "#wildcard";
 
+/// The name the parser binds the matched value of a `match` to when it turns guards and string
+/// literal patterns into a chain of `if`s. As `#` cannot start a source name, no arm can read it.
+pub const MATCH_VALUE_VAR_NAME: &str = "#match_value";
+
 /// Prefix of the type-variable names the parser generates for `_` type
 /// wildcards (e.g. `#typewildcard0`). Each `_` in a type annotation gets a
 /// distinct name so that, for example, the two wildcards in `(_, _)` stay
@@ -331,6 +331,27 @@ pub struct DependencyLockFileEntry {
 }
 
 impl DependencyLockFileEntry {
+    /// The name of the project.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version of the project the resolution chose.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The directory the project is installed at, or the one it sits in for a path dependency.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The repository and the commit a git dependency is checked out at, or `None` for a path
+    /// dependency.
+    pub fn git(&self) -> Option<(&str, &str)> {
+        self.git.as_ref().map(|g| (g.repo.as_str(), g.rev.as_str()))
+    }
+
     // Read this dependency's `fixproj.toml` and populate `role = Dependent` plus
     // `source` (Git when the lockfile entry carries git info, Local otherwise) so that
     // the returned `ProjectFile` is ready to be passed into `set_config`.
@@ -0,0 +1,84 @@
+// The search box of the documentation `fix docs --format html` writes.
+//
+// The index is the array `search-index.js` assigns to `FIX_DOCS_SEARCH_INDEX`, loaded as a script
+// before this one, so that the search works on pages opened from the disk.
+(function () {
+    "use strict";
+
+    const MAX_RESULTS = 50;
+
+    const input = document.getElementById("search");
+    const results = document.getElementById("search-results");
+    const index = window.FIX_DOCS_SEARCH_INDEX;
+    if (!input || !results || !Array.isArray(index)) {
+        return;
+    }
+
+    // The rank of `item` for `query`, lower being better, or -1 when it does not match: the last
+    // segment of the name equal to the query, then beginning with it, then containing it, then
+    // the full name containing it.
+    function rank(item, query) {
+        const name = item.name.toLowerCase();
+        const separator = name.lastIndexOf("::");
+        const short = separator < 0 ? name : name.slice(separator + 2);
+        if (short === query) {
+            return 0;
+        }
+        if (short.startsWith(query)) {
+            return 1;
+        }
+        if (short.includes(query)) {
+            return 2;
+        }
+        if (name.includes(query)) {
+            return 3;
+        }
+        return -1;
+    }
+
+    function show(query) {
+        results.replaceChildren();
+        query = query.trim().toLowerCase();
+        if (query === "") {
+            return;
+        }
+        const found = [];
+        for (const item of index) {
+            const r = rank(item, query);
+            if (r >= 0) {
+                found.push([r, item]);
+            }
+        }
+        found.sort((a, b) => a[0] - b[0] || a[1].name.length - b[1].name.length
+            || a[1].name.localeCompare(b[1].name));
+        for (const [, item] of found.slice(0, MAX_RESULTS)) {
+            const li = document.createElement("li");
+            const kind = document.createElement("span");
+            kind.className = "kind";
+            kind.textContent = item.kind;
+            const link = document.createElement("a");
+            link.href = item.href;
+            link.textContent = item.name;
+            li.append(kind, link);
+            if (item.detail) {
+                const detail = document.createElement("span");
+                detail.className = "detail";
+                detail.textContent = item.detail;
+                li.append(detail);
+            }
+            results.append(li);
+        }
+    }
+
+    input.addEventListener("input", () => show(input.value));
+    input.addEventListener("keydown", (event) => {
+        if (event.key === "Enter") {
+            const first = results.querySelector("a");
+            if (first) {
+                window.location.href = first.href;
+            }
+        }
+    });
+    // Keep the query when the page is shown again from the history.
+    show(input.value);
+})();
@@ -52,7 +52,7 @@ pub fn run_explicit_import_command() -> Result<(), Errors> {
 }
 
 // Get the list of source files that belong to the project (not dependencies).
-fn get_user_source_files(proj_file: &ProjectFile) -> Result<Vec<PathBuf>, Errors> {
+pub(crate) fn get_user_source_files(proj_file: &ProjectFile) -> Result<Vec<PathBuf>, Errors> {
     // Use get_files(BuildMode::Test) to get the root project's source files (excluding dependencies).
     // BuildMode::Test includes test files.
     let files = proj_file.get_files(BuildConfigType::Test);
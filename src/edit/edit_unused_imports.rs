@@ -0,0 +1,82 @@
+// Module for the "fix edit unused-imports" command.
+// This command removes, from the source files of a Fix project, the import statements and the items of import
+// statements through which nothing is used; they are what the `unused-import` lint reports.
+
+use crate::ast::program::Program;
+use crate::commands::lsp::edit_import::create_text_edits_to_remove_unused_imports;
+use crate::commands::lsp::server::run_diagnostics;
+use crate::edit::edit_explict_import::get_user_source_files;
+use crate::edit::edit_util::apply_text_edits;
+use crate::elaboration::lint::{unused_imports_of_module, LINT_CODES};
+use crate::elaboration::typecheckcache::MemoryCache;
+use crate::error::Errors;
+use crate::metafiles::project_file::ProjectFile;
+use crate::misc::{info_msg, Map};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Run the "fix edit unused-imports" command.
+// This command:
+// 1. Checks if the project has any errors other than lints (exits if errors exist)
+// 2. For each source file in the project (excluding dependencies):
+//    - Erases the import statements nothing of which is used
+//    - Rewrites the other import statements with their unused items left out
+pub fn run_unused_imports_command() -> Result<(), Errors> {
+    info_msg("Running diagnostics on the program...");
+
+    // Read the project file to get the list of source files.
+    let proj_file = ProjectFile::read_root_file()?;
+
+    // Run diagnostics to check if the project has errors and get the Program.
+    // A lint set to `deny` makes its findings errors; they do not prevent the removal.
+    let typecheck_cache = Arc::new(MemoryCache::new());
+    let result = run_diagnostics(typecheck_cache, Arc::new(Map::default()))?;
+    let program = result.program;
+    if program.deferred_errors.has_error_except(&LINT_CODES) {
+        return Err(program.deferred_errors);
+    }
+
+    for file_path in get_user_source_files(&proj_file)? {
+        remove_unused_imports_of_file(&file_path, &program)?;
+    }
+
+    Ok(())
+}
+
+// Remove the unused imports of a single file.
+fn remove_unused_imports_of_file(file_path: &PathBuf, program: &Program) -> Result<(), Errors> {
+    let module_info = program.module_of_file(file_path).ok_or_else(|| {
+        Errors::from_msg(format!(
+            "Could not find module info for {}",
+            file_path.display()
+        ))
+    })?;
+    let unused_imports = unused_imports_of_module(program, &module_info.name);
+    if unused_imports.is_empty() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(file_path).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to read file {}: {}",
+            file_path.display(),
+            e
+        ))
+    })?;
+    let text_edits = create_text_edits_to_remove_unused_imports(&content, &unused_imports);
+    let new_content = apply_text_edits(&content, &text_edits);
+    fs::write(file_path, new_content).map_err(|e| {
+        Errors::from_msg(format!(
+            "Failed to write file {}: {}",
+            file_path.display(),
+            e
+        ))
+    })?;
+
+    info_msg(&format!(
+        "Removed unused imports from: {}",
+        file_path.display()
+    ));
+    Ok(())
+}
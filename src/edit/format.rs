@@ -0,0 +1,754 @@
+// Module for the source formatter behind the "fix fmt" command and the language server's
+// formatting requests.
+//
+// The formatter re-prints a module in one canonical layout while keeping every token and comment
+// of it. The line breaks stay where the author put them, apart from runs of blank lines, which
+// shrink to one; what the formatter decides is the rest of the layout:
+//
+//  * Indentation. A line is indented four spaces deeper than the line that opened the innermost
+//    bracket still open at its start, and a line that starts by closing brackets is indented as the
+//    line that opened them. A line that continues an unfinished line of the same bracket (the one
+//    before it does not end with `;`, `,`, `in` or a closing bracket), or that starts with a binary
+//    operator, is indented four spaces more.
+//  * Spacing within a line. Binary operators, `:`, `=` and the arrows are surrounded by one space;
+//    commas and semicolons are followed by one; nothing separates `::`, `.`, a prefix operator or a
+//    lambda's `|`s from their operand; `{ ... }` is padded inside and `( ... )` and `[ ... ]` are
+//    not. Whether a `(` or `[` right after a name is separated from it is left as written, since a
+//    type application needs the space and a function application reads better without it.
+//  * Trailing whitespace is removed, and the file ends with exactly one line break.
+//
+// The source is scanned with `lex_layout_tokens`, which reuses the token rules of `grammer.pest`
+// and keeps comments, so that the formatter never has to re-derive what a token looks like. It
+// refuses a source the parser does not accept, and checks its own output: the result has to be
+// accepted by the parser and carry the same tokens as the input, in the same order.
+
+use crate::error::Errors;
+use crate::parse::lexer::{lex_layout_tokens, LayoutTokenKind};
+use crate::parse::parser::validate_source_syntax;
+use crate::parse::sourcefile::SourceFile;
+use std::path::Path;
+
+/// The number of spaces one level of indentation is made of.
+const INDENT_WIDTH: usize = 4;
+
+/// Operators that never start an expression, so that a line starting with one of them continues the
+/// line before it.
+const LEADING_BINARY_OPERATORS: &[&str] = &[
+    ".", "$", ";;", ">>", "<<", "&&", "||", "+", "/", "%", "==", "!=", "<=", ">=", "<", ">", "->",
+    "=>", "=", ":",
+];
+
+/// A stretch of the original source as the formatter lays it out: one line, or several when a
+/// token on it, such as a block comment or a string literal, spans lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedLines {
+    /// The first line of the original source the stretch covers, counted from 0.
+    pub first_line: usize,
+    /// The last line of the original source the stretch covers, counted from 0.
+    pub last_line: usize,
+    /// The text the stretch is laid out as, without its final line break, or `None` for a blank
+    /// line the layout drops.
+    pub text: Option<String>,
+}
+
+/// Formats the Fix source `content` of the file `file_path` into the canonical layout.
+///
+/// Fails with the parse error when the parser does not accept `content`.
+pub fn format_source(file_path: &Path, content: &str) -> Result<String, Errors> {
+    Ok(join_formatted_lines(&format_source_lines(
+        file_path, content,
+    )?))
+}
+
+/// Formats the Fix source `content` of the file `file_path` into the canonical layout, telling
+/// which lines of `content` each line of the result comes from. The stretches cover every line of
+/// `content` once, in order, so that a part of the result replaces the same part of `content`.
+///
+/// Fails with the parse error when the parser does not accept `content`.
+pub fn format_source_lines(file_path: &Path, content: &str) -> Result<Vec<FormattedLines>, Errors> {
+    validate_source_syntax(&SourceFile::from_file_path_and_content(
+        file_path.to_path_buf(),
+        content.to_string(),
+    ))?;
+    let lines = Layout::default().lay_out(content);
+    verify_formatted(file_path, content, &join_formatted_lines(&lines))?;
+    Ok(lines)
+}
+
+/// The text the stretches `lines` are laid out as: the lines kept, each followed by a line break.
+pub fn join_formatted_lines(lines: &[FormattedLines]) -> String {
+    let mut text = String::default();
+    for line in lines {
+        if let Some(line) = &line.text {
+            text += line;
+            text += "\n";
+        }
+    }
+    text
+}
+
+/// Checks that formatting `original` into `formatted` kept its meaning: the parser still accepts
+/// the result, and the two carry the same tokens in the same order. A failure is a bug of the
+/// formatter, so it is reported as one, and the caller leaves the file as it is.
+fn verify_formatted(file_path: &Path, original: &str, formatted: &str) -> Result<(), Errors> {
+    let bug = |what: &str| {
+        Errors::from_msg(format!(
+            "Formatting \"{}\" would {}, so the file is left as it is. This is a bug of the formatter; please report it.",
+            file_path.display(),
+            what
+        ))
+    };
+    let reparsed = validate_source_syntax(&SourceFile::from_file_path_and_content(
+        file_path.to_path_buf(),
+        formatted.to_string(),
+    ));
+    if reparsed.is_err() {
+        return Err(bug("break its syntax"));
+    }
+    let token_texts = |content: &str| -> Vec<String> {
+        scan_lines(content)
+            .into_iter()
+            .flatten()
+            .map(|tok| tok.text.to_string())
+            .collect()
+    };
+    if token_texts(original) != token_texts(formatted) {
+        return Err(bug("change its tokens"));
+    }
+    Ok(())
+}
+
+/// What a token is to the layout: the class its spacing and indentation rules look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenClass {
+    /// A name or a literal other than a string: it needs a space to stay apart from another one.
+    Word,
+    /// A string or character literal.
+    Literal,
+    /// A keyword.
+    Keyword,
+    /// A `//` comment, which runs to the end of the line.
+    LineComment,
+    /// A `/* */` comment.
+    BlockComment,
+    /// `(`, `[` or `{`.
+    Open,
+    /// `)`, `]` or `}`.
+    Close,
+    /// `,` or `;`.
+    Separator,
+    /// Any other operator or punctuation.
+    Symbol,
+}
+
+/// One token of a line, with the whitespace the source puts before it on that line.
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    /// The text of the token. A line comment loses its trailing whitespace.
+    text: &'a str,
+    /// The byte offset of the token in the source.
+    start: usize,
+    /// The class the layout rules look at.
+    class: TokenClass,
+    /// The whitespace between this token and the one before it on the same line, as written.
+    gap: &'a str,
+}
+
+/// Scans `content` into its lines of tokens. The scan merges a run of words written without a gap
+/// into one token, so that `FFI_CALL` stays as written, and splits a negative number literal that
+/// follows an operand into a binary `-` and the number, since that is how the parser reads it.
+fn scan_lines(content: &str) -> Vec<Vec<Token<'_>>> {
+    let mut lines: Vec<Vec<Token>> = vec![vec![]];
+    let mut gap_start: Option<usize> = None;
+    // Whether the last code token before the current position ends an operand.
+    let mut after_operand = false;
+    for tok in lex_layout_tokens(content) {
+        let text = &content[tok.start..tok.end];
+        let class = match tok.kind {
+            LayoutTokenKind::Space => {
+                gap_start.get_or_insert(tok.start);
+                continue;
+            }
+            LayoutTokenKind::Newline => {
+                lines.push(vec![]);
+                gap_start = None;
+                continue;
+            }
+            LayoutTokenKind::LineComment => TokenClass::LineComment,
+            LayoutTokenKind::BlockComment => TokenClass::BlockComment,
+            LayoutTokenKind::Literal => TokenClass::Literal,
+            LayoutTokenKind::Word => TokenClass::Word,
+            LayoutTokenKind::Keyword => TokenClass::Keyword,
+            LayoutTokenKind::Symbol => match text {
+                "(" | "[" | "{" => TokenClass::Open,
+                ")" | "]" | "}" => TokenClass::Close,
+                "," | ";" => TokenClass::Separator,
+                _ => TokenClass::Symbol,
+            },
+        };
+        let gap = match gap_start.take() {
+            Some(start) => &content[start..tok.start],
+            None => "",
+        };
+        let line = lines.last_mut().unwrap();
+
+        // A negative number literal after an operand is a subtraction.
+        if class == TokenClass::Word && after_operand && text.len() > 1 && text.starts_with('-') {
+            line.push(Token {
+                text: &text[..1],
+                start: tok.start,
+                class: TokenClass::Symbol,
+                gap,
+            });
+            line.push(Token {
+                text: &text[1..],
+                start: tok.start + 1,
+                class: TokenClass::Word,
+                gap: "",
+            });
+            after_operand = true;
+            continue;
+        }
+
+        // A run of words written without a gap is one token to the layout.
+        if class == TokenClass::Word && gap.is_empty() {
+            if let Some(prev) = line.last_mut() {
+                if prev.class == TokenClass::Word {
+                    prev.text = &content[prev.start..tok.end];
+                    continue;
+                }
+            }
+        }
+
+        let text = if class == TokenClass::LineComment {
+            text.trim_end()
+        } else {
+            text
+        };
+        match class {
+            TokenClass::LineComment | TokenClass::BlockComment => {}
+            TokenClass::Word | TokenClass::Literal | TokenClass::Close => after_operand = true,
+            TokenClass::Symbol => after_operand = text == "?",
+            _ => after_operand = false,
+        }
+        line.push(Token {
+            text,
+            start: tok.start,
+            class,
+            gap,
+        });
+    }
+
+    // The line break that ends the last line does not start another one.
+    if lines.len() > 1 && lines.last().unwrap().is_empty() {
+        lines.pop();
+    }
+    lines
+}
+
+/// A bracket open at some point of the layout, or the top level of the module.
+#[derive(Debug, Clone)]
+struct Frame {
+    /// The indentation of a line within the bracket that starts a new item.
+    content_indent: usize,
+    /// The indentation of a line that starts by closing the bracket.
+    close_indent: usize,
+    /// Whether the last line of code within the bracket ended its item: `None` before the first
+    /// line of code within it.
+    item_ended: Option<bool>,
+    /// Whether a lambda's `|` has been opened within the bracket and not yet closed.
+    in_lambda_head: bool,
+    /// Whether the bracket is a `{` of an import statement, which is written without padding.
+    tight: bool,
+    /// The number of the line the bracket is opened on, counted from 1 over the lines laid out.
+    /// The top level of the module is opened on no line, which is 0.
+    open_line: usize,
+}
+
+impl Frame {
+    /// The bracket opened on the line numbered `open_line`, whose indentation is
+    /// `open_line_indent`.
+    fn new(open_line: usize, open_line_indent: usize, tight: bool) -> Frame {
+        Frame {
+            content_indent: open_line_indent + INDENT_WIDTH,
+            close_indent: open_line_indent,
+            item_ended: None,
+            in_lambda_head: false,
+            tight,
+            open_line,
+        }
+    }
+}
+
+/// Where the layout is within the head of an `FFI_CALL` or `FFI_EXPORT`, whose C function name is
+/// read verbatim by the parser, spaces included. The whitespace of such a name is kept as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FfiHead {
+    /// Outside such a head.
+    None,
+    /// After `FFI_EXPORT`, before the comma that precedes the C function name.
+    ExportHead,
+    /// After `FFI_CALL`, `FFI_CALL_IO` or `FFI_CALL_IOS`, before its `[`.
+    CallHead,
+    /// After the `[` of an `FFI_CALL`, before the C return type.
+    CallReturnType,
+    /// Within the `()` return type of an `FFI_CALL`.
+    CallUnitReturnType,
+    /// Within a C function name, which runs up to the given token.
+    Verbatim(&'static str),
+}
+
+/// A token the layout has placed, as the rules for the next token see it.
+#[derive(Debug, Clone, Copy)]
+struct Placed<'a> {
+    token: Token<'a>,
+    /// Whether the token is a `-`, `!` or `*` applied to the operand after it.
+    is_prefix: bool,
+    /// Whether the token is the `|` that opens a lambda's parameters.
+    opens_lambda: bool,
+    /// Whether the token is the `|` that closes a lambda's parameters.
+    closes_lambda: bool,
+}
+
+/// The state of the layout as it goes through the source line by line.
+struct Layout {
+    /// The brackets open at the current position, outermost first. The first frame is the top
+    /// level of the module and is never closed.
+    frames: Vec<Frame>,
+    /// Where the layout is within an FFI head.
+    ffi_head: FfiHead,
+    /// Whether the layout is within an import statement.
+    in_import: bool,
+    /// Whether the last code token placed ends an operand.
+    after_operand: bool,
+    /// The number of the line being laid out, counted from 1.
+    line_number: usize,
+    /// The indentation of the line being laid out.
+    line_indent: usize,
+    /// Whether a line other than a blank one has been placed.
+    placed_line: bool,
+    /// Whether the last line placed is a blank one.
+    last_blank: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        let mut top_level = Frame::new(0, 0, false);
+        top_level.content_indent = 0;
+        Layout {
+            frames: vec![top_level],
+            ffi_head: FfiHead::None,
+            in_import: false,
+            after_operand: false,
+            line_number: 0,
+            line_indent: 0,
+            placed_line: false,
+            last_blank: false,
+        }
+    }
+}
+
+impl Layout {
+    /// Lays out the source `content`, line by line.
+    fn lay_out(mut self, content: &str) -> Vec<FormattedLines> {
+        let mut result = vec![];
+        let mut line_no = 0;
+        for line in scan_lines(content) {
+            let first_line = line_no;
+            line_no += line
+                .iter()
+                .map(|tok| tok.text.matches('\n').count())
+                .sum::<usize>();
+            result.push(FormattedLines {
+                first_line,
+                last_line: line_no,
+                text: self.lay_out_line(&line),
+            });
+            line_no += 1;
+        }
+
+        // The file ends right after its last non-blank line.
+        for line in result.iter_mut().rev() {
+            match &line.text {
+                Some(text) if text.is_empty() => line.text = None,
+                Some(_) => break,
+                None => {}
+            }
+        }
+        result
+    }
+
+    /// The innermost bracket open at the current position.
+    fn top(&mut self) -> &mut Frame {
+        self.frames.last_mut().unwrap()
+    }
+
+    /// Lays out one line of the source, or drops it when it is a blank line the layout does not
+    /// keep.
+    fn lay_out_line(&mut self, line: &[Token]) -> Option<String> {
+        self.line_number += 1;
+        if line.is_empty() {
+            if !self.placed_line || self.last_blank {
+                return None;
+            }
+            self.last_blank = true;
+            return Some(String::default());
+        }
+        self.placed_line = true;
+        self.last_blank = false;
+
+        self.line_indent = self.indent_of(line);
+        let mut text = " ".repeat(self.line_indent);
+        let mut prev: Option<Placed> = None;
+        let mut last_code: Option<Token> = None;
+        for tok in line {
+            if let Some(prev) = &prev {
+                text += &self.gap_between(prev, tok);
+            }
+            prev = Some(self.place(*tok));
+            if !matches!(
+                tok.class,
+                TokenClass::LineComment | TokenClass::BlockComment
+            ) {
+                last_code = Some(*tok);
+            }
+        }
+
+        // A line of code tells whether the item it belongs to goes on; a comment does not. The
+        // first line within a bracket starts with the bracket's first item, however the line that
+        // opens the bracket ends.
+        if let Some(last) = last_code {
+            let line_number = self.line_number;
+            let top = self.top();
+            if top.open_line != line_number {
+                let ended = matches!(last.class, TokenClass::Separator | TokenClass::Close)
+                    || (last.class == TokenClass::Keyword && last.text == "in");
+                top.item_ended = Some(ended);
+            }
+        }
+        Some(text.trim_end().to_string())
+    }
+
+    /// The indentation of `line`, given the brackets open at its start.
+    fn indent_of(&mut self, line: &[Token]) -> usize {
+        let leading_closes = line
+            .iter()
+            .take_while(|tok| tok.class == TokenClass::Close)
+            .count();
+        if leading_closes > 0 {
+            let closed = self.frames.len().saturating_sub(leading_closes).max(1);
+            return self.frames[closed].close_indent;
+        }
+        let first = line[0];
+        let continues =
+            first.class == TokenClass::Symbol && LEADING_BINARY_OPERATORS.contains(&first.text);
+        let top = self.top();
+        match top.item_ended {
+            Some(false) => top.content_indent + INDENT_WIDTH,
+            _ if continues => top.content_indent + INDENT_WIDTH,
+            _ => top.content_indent,
+        }
+    }
+
+    /// Places `tok` after the tokens placed before it, updating the brackets, the lambda heads,
+    /// the FFI heads and the import statements the layout is within.
+    fn place<'a>(&mut self, tok: Token<'a>) -> Placed<'a> {
+        let mut placed = Placed {
+            token: tok,
+            is_prefix: false,
+            opens_lambda: false,
+            closes_lambda: false,
+        };
+        if matches!(
+            tok.class,
+            TokenClass::LineComment | TokenClass::BlockComment
+        ) {
+            return placed;
+        }
+
+        self.ffi_head = match (self.ffi_head, tok.text) {
+            (FfiHead::None, "FFI_EXPORT") => FfiHead::ExportHead,
+            (FfiHead::None, "FFI_CALL" | "FFI_CALL_IO" | "FFI_CALL_IOS") => FfiHead::CallHead,
+            (FfiHead::ExportHead, ",") => FfiHead::Verbatim("]"),
+            (FfiHead::CallHead, "[") => FfiHead::CallReturnType,
+            (FfiHead::CallReturnType, "(") => FfiHead::CallUnitReturnType,
+            (FfiHead::CallReturnType, _) | (FfiHead::CallUnitReturnType, _) => {
+                FfiHead::Verbatim("(")
+            }
+            (FfiHead::Verbatim(end), text) if text == end => FfiHead::None,
+            (head, _) => head,
+        };
+
+        match tok.class {
+            TokenClass::Open => {
+                let tight = self.in_import && tok.text == "{";
+                self.frames
+                    .push(Frame::new(self.line_number, self.line_indent, tight));
+            }
+            TokenClass::Close => {
+                if self.frames.len() > 1 {
+                    self.frames.pop();
+                }
+            }
+            TokenClass::Keyword if tok.text == "import" => self.in_import = true,
+            TokenClass::Separator if tok.text == ";" => self.in_import = false,
+            TokenClass::Symbol if tok.text == "|" => {
+                let top = self.top();
+                top.in_lambda_head = !top.in_lambda_head;
+                placed.opens_lambda = top.in_lambda_head;
+                placed.closes_lambda = !top.in_lambda_head;
+            }
+            TokenClass::Symbol if matches!(tok.text, "-" | "!" | "*") => {
+                placed.is_prefix = !self.after_operand;
+            }
+            _ => {}
+        }
+        self.after_operand = match tok.class {
+            TokenClass::Word | TokenClass::Literal | TokenClass::Close => true,
+            TokenClass::Symbol => tok.text == "?",
+            _ => false,
+        };
+        placed
+    }
+
+    /// The whitespace to put between the placed token `prev` and the next token `next` on the
+    /// same line.
+    fn gap_between(&self, prev: &Placed, next: &Token) -> String {
+        // Within a C function name, whitespace belongs to the name.
+        if let FfiHead::Verbatim(_) = self.ffi_head {
+            return next.gap.to_string();
+        }
+        let prev_tok = &prev.token;
+        let keep = || {
+            if next.gap.is_empty() {
+                String::default()
+            } else {
+                " ".to_string()
+            }
+        };
+        let none = String::default;
+        let one = || " ".to_string();
+        let frame = self.frames.last().unwrap();
+
+        // Comments.
+        if next.class == TokenClass::LineComment {
+            return one();
+        }
+        if next.class == TokenClass::BlockComment || prev_tok.class == TokenClass::BlockComment {
+            return keep();
+        }
+
+        // Brackets and separators.
+        if prev_tok.class == TokenClass::Open {
+            return if prev_tok.text == "{" && !frame.tight && next.text != "}" {
+                one()
+            } else {
+                none()
+            };
+        }
+        if next.class == TokenClass::Close {
+            return if next.text == "}" && !frame.tight && prev_tok.text != "{" {
+                one()
+            } else {
+                none()
+            };
+        }
+        if next.class == TokenClass::Separator {
+            return none();
+        }
+        if prev_tok.class == TokenClass::Separator {
+            return one();
+        }
+
+        // Tokens that bind tightly to their neighbors.
+        if matches!(prev_tok.text, "::" | ".") || matches!(next.text, "::" | ".") {
+            return none();
+        }
+        if prev_tok.text == "?" {
+            return none();
+        }
+        if prev.opens_lambda {
+            return none();
+        }
+        if next.text == "|" && frame.in_lambda_head {
+            return none();
+        }
+        if prev.closes_lambda {
+            return one();
+        }
+        if prev.is_prefix {
+            let operand_starts = matches!(
+                next.class,
+                TokenClass::Word | TokenClass::Literal | TokenClass::Open
+            ) || matches!(next.text, "-" | "!" | "*" | "?" | "|");
+            return if operand_starts { none() } else { one() };
+        }
+
+        // Words and brackets.
+        if prev_tok.class == TokenClass::Keyword || next.class == TokenClass::Keyword {
+            return one();
+        }
+        let prev_is_operand = matches!(
+            prev_tok.class,
+            TokenClass::Word | TokenClass::Literal | TokenClass::Close
+        );
+        let next_is_operand = matches!(next.class, TokenClass::Word | TokenClass::Literal);
+        if prev_is_operand && next.class == TokenClass::Open && next.text != "{" {
+            return keep();
+        }
+        if matches!(prev_tok.class, TokenClass::Word | TokenClass::Literal) && next_is_operand {
+            return keep();
+        }
+        if next.class == TokenClass::Open
+            || prev_tok.class == TokenClass::Close
+            || prev_tok.class == TokenClass::Symbol
+            || next.class == TokenClass::Symbol
+        {
+            return one();
+        }
+        keep()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn format(src: &str) -> String {
+        format_source(&PathBuf::from("test.fix"), src)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    }
+
+    /// Verifies that indentation follows the brackets and the items within them, whatever the
+    /// source indented them as.
+    #[test]
+    fn indents_by_brackets() {
+        let src = "module Main;\n\
+                   main : IO ();\n\
+                   main = (\n\
+                   let x = 1;\n\
+                   \t  println $ if x == 1 {\n\
+                   \"one\"\n\
+                   } else {\n\
+                   \"other\"\n\
+                   }\n\
+                   );\n";
+        assert_eq!(
+            format(src),
+            "module Main;\n\
+             main : IO ();\n\
+             main = (\n    \
+                 let x = 1;\n    \
+                 println $ if x == 1 {\n        \
+                     \"one\"\n    \
+                 } else {\n        \
+                     \"other\"\n    \
+                 }\n\
+             );\n"
+        );
+    }
+
+    /// Verifies the spacing rules within a line: operators, commas, lambdas, prefix operators and
+    /// paths.
+    #[test]
+    fn normalizes_spacing() {
+        let src = "module Main;\n\
+                   f:I64->I64->I64=|  a,b  |a+b*-a;\n\
+                   g : Std::Array I64 = [ 1,2 ].map( |x|x.add(1) );\n\
+                   h : Bool = !true||false;\n";
+        assert_eq!(
+            format(src),
+            "module Main;\n\
+             f : I64 -> I64 -> I64 = |a, b| a + b * -a;\n\
+             g : Std::Array I64 = [1, 2].map(|x| x.add(1));\n\
+             h : Bool = !true || false;\n"
+        );
+    }
+
+    /// Verifies that comments are kept, trailing whitespace is removed, and runs of blank lines
+    /// shrink to one.
+    #[test]
+    fn keeps_comments_and_collapses_blank_lines() {
+        let src = "\n\nmodule Main;   \n\n\n\n// The answer.   \nx : I64 = 42; /* inline */ // trailing\n\n\n";
+        assert_eq!(
+            format(src),
+            "module Main;\n\n// The answer.\nx : I64 = 42; /* inline */ // trailing\n"
+        );
+    }
+
+    /// Verifies that the braces of an import statement stay unpadded, and those of a struct are
+    /// padded.
+    #[test]
+    fn import_braces_are_tight() {
+        let src = "module Main;\nimport Std::{ Array,Option };\ntype P = struct {x:I64};\n";
+        assert_eq!(
+            format(src),
+            "module Main;\nimport Std::{Array, Option};\ntype P = struct { x : I64 };\n"
+        );
+    }
+
+    /// Verifies that a subtraction written without spaces is spaced although the scanner reads
+    /// `-1` as a number.
+    #[test]
+    fn spaces_subtraction_of_literal() {
+        let src = "module Main;\nx : I64 = 3-1;\ny : I64 = -1;\n";
+        assert_eq!(
+            format(src),
+            "module Main;\nx : I64 = 3 - 1;\ny : I64 = -1;\n"
+        );
+    }
+
+    /// Verifies that formatting a formatted source changes nothing.
+    #[test]
+    fn formatting_is_idempotent() {
+        let src = "module Main;\n\
+                   loop_sum : I64 -> I64;\n\
+                   loop_sum = |n| (\n\
+                   loop((0, 0), |(i, sum)|\n\
+                   if i == n { break $ sum };\n\
+                   continue $ (i + 1, sum + i)\n\
+                   )\n\
+                   );\n\
+                   type T = union {\n\
+                   a : I64,\n\
+                   b : ()\n\
+                   };\n";
+        let once = format(src);
+        assert_eq!(format(&once), once);
+        assert_eq!(
+            once,
+            "module Main;\n\
+             loop_sum : I64 -> I64;\n\
+             loop_sum = |n| (\n    \
+                 loop((0, 0), |(i, sum)|\n        \
+                     if i == n { break $ sum };\n        \
+                     continue $ (i + 1, sum + i)\n    \
+                 )\n\
+             );\n\
+             type T = union {\n    \
+                 a : I64,\n    \
+                 b : ()\n\
+             };\n"
+        );
+    }
+
+    /// Verifies that the whitespace of a string literal spanning lines is kept, and that the
+    /// stretch it is on covers every line it spans.
+    #[test]
+    fn keeps_multiline_string() {
+        let src = "module Main;\nx : String = \"a\n   b\";\ny : I64 = 1;\n";
+        let lines = format_source_lines(&PathBuf::from("test.fix"), src)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()));
+        assert_eq!(join_formatted_lines(&lines), src);
+        assert_eq!(lines[1].first_line, 1);
+        assert_eq!(lines[1].last_line, 2);
+        assert_eq!(lines[2].first_line, 3);
+    }
+
+    /// Verifies that a source the parser rejects is not formatted.
+    #[test]
+    fn rejects_broken_source() {
+        assert!(format_source(&PathBuf::from("test.fix"), "module Main;\nx = (;\n").is_err());
+    }
+}
@@ -1,4 +1,6 @@
 // Module for various editing commands and utilities.
 
 pub mod edit_explict_import;
+pub mod edit_unused_imports;
 pub mod edit_util;
+pub mod format;
@@ -856,6 +856,10 @@ fn resolve_opaque_tycon_in_pattern(
             ),
             info,
         }),
+        Pattern::Lit(val, ty) => Arc::new(PatternNode {
+            pattern: Pattern::Lit(*val, ty.clone()),
+            info,
+        }),
     }
 }
 
@@ -0,0 +1,383 @@
+//! The lints: checks that report code which compiles but does nothing, run once the program is
+//! type-checked. Each lint has a diagnostic code, and the `lints` section of the project file sets
+//! the level it is reported at (see `LintLevel`).
+//!
+//! - `unused-import` (`WARN_UNUSED_IMPORT`): an import statement, or an item of one, through which
+//!   the module uses no name.
+//! - `unused-variable` (`WARN_UNUSED_VARIABLE`): a binder of a `let`, of a lambda parameter or of
+//!   a `match` arm pattern that its scope never refers to.
+//! - `unused-private-global` (`WARN_UNUSED_PRIVATE_GLOBAL`): a global value whose name begins
+//!   with `_` that no global value of a public name reaches.
+//!
+//! A name beginning with `_` is exempt from `unused-variable`, so that a binder kept for its
+//! documentation value can say so.
+//!
+//! The lints report on the root project's own sources, `Configuration::root_source_files`, and
+//! read what type-checking leaves behind: the names each module resolved and the resolved bodies
+//! of the values. A program with errors leaves both incomplete, so the caller runs the lints on a
+//! program without errors alone.
+
+use crate::ast::expr::{Expr, ExprNode};
+use crate::ast::import::ImportStatement;
+use crate::ast::name::{FullName, Name};
+use crate::ast::pattern::{Pattern, PatternNode};
+use crate::ast::program::{Program, SymbolExpr};
+use crate::configuration::{Configuration, LintLevel};
+use crate::error::{
+    Error, Errors, WARN_UNUSED_IMPORT, WARN_UNUSED_PRIVATE_GLOBAL, WARN_UNUSED_VARIABLE,
+};
+use crate::misc::{to_absolute_path, Map, Set};
+use crate::parse::sourcefile::Span;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The diagnostic codes of the lints, which name them in the `lints` section of the project file.
+pub const LINT_CODES: [&str; 3] = [
+    WARN_UNUSED_IMPORT,
+    WARN_UNUSED_VARIABLE,
+    WARN_UNUSED_PRIVATE_GLOBAL,
+];
+
+/// The findings of every lint on the user's sources, each at the level `config` sets for its lint.
+/// Within a lint, the findings are ordered by their source location, so that one run reports what
+/// the next one does.
+pub fn collect_lint_diagnostics(program: &Program, config: &Configuration) -> Errors {
+    let user_files: Set<PathBuf> = config
+        .root_source_files
+        .iter()
+        .filter_map(|path| to_absolute_path(path).ok())
+        .collect();
+
+    let mut diagnostics = Errors::empty();
+    for code in LINT_CODES {
+        let level = config.lint_level(code);
+        if level == LintLevel::Allow {
+            continue;
+        }
+        let mut findings = match code {
+            WARN_UNUSED_IMPORT => unused_imports(program, &user_files),
+            WARN_UNUSED_VARIABLE => unused_variables(program, &user_files),
+            WARN_UNUSED_PRIVATE_GLOBAL => unused_private_globals(program, &user_files),
+            _ => unreachable!(),
+        };
+        findings.sort_by(|(_, lhs), (_, rhs)| lhs.cmp(rhs));
+        for (msg, span) in findings {
+            let mut err = match level {
+                LintLevel::Allow => unreachable!(),
+                LintLevel::Warn => Error::warning_from_msg_srcs(msg, &[&Some(span)]),
+                LintLevel::Deny => Error::from_msg_srcs(msg, &[&Some(span)]),
+            };
+            err.code = Some(code);
+            diagnostics.append(Errors::from_err(err));
+        }
+    }
+    diagnostics
+}
+
+/// An import statement written in source that imports something its module does not use.
+pub struct UnusedImport<'a> {
+    /// The statement as it is written.
+    pub stmt: &'a ImportStatement,
+    /// The statement with the unused items taken out, or `None` where nothing it imports is used.
+    pub retained: Option<ImportStatement>,
+    /// The leaf items taken out, each as the path it names and the span of the token naming it.
+    pub removed: Vec<(String, Option<Span>)>,
+}
+
+/// The import statements written in `module` that import something the module does not use, in the
+/// order they are written.
+pub fn unused_imports_of_module<'a>(program: &'a Program, module: &Name) -> Vec<UnusedImport<'a>> {
+    let used: Vec<FullName> = program
+        .import_required
+        .get(module)
+        .map(|names| names.iter().cloned().collect())
+        .unwrap_or_default();
+    let Some(stmts) = program.mod_to_import_stmts.get(module) else {
+        return vec![];
+    };
+    let mut unused = vec![];
+    for stmt in stmts {
+        // A statement the compiler added, or one standing for an absolute path, is not written as
+        // an import, so there is nothing to remove.
+        if stmt.implicit || stmt.source.is_none() {
+            continue;
+        }
+        let (retained, removed) = stmt.retain_used_items(&used);
+        if retained.is_some() && removed.is_empty() {
+            continue;
+        }
+        unused.push(UnusedImport {
+            stmt,
+            retained,
+            removed,
+        });
+    }
+    unused
+}
+
+/// The findings of `unused-import`: a statement nothing of which is used is reported as a whole,
+/// and otherwise each unused item of a statement is.
+fn unused_imports(program: &Program, user_files: &Set<PathBuf>) -> Vec<(String, Span)> {
+    let mut findings = vec![];
+    for mod_info in &program.modules {
+        let Ok(path) = mod_info.absolute_source_path() else {
+            continue;
+        };
+        if !user_files.contains(&path) {
+            continue;
+        }
+        for unused in unused_imports_of_module(program, &mod_info.name) {
+            if unused.retained.is_none() {
+                findings.push((
+                    format!(
+                        "Unused import: nothing this statement imports from `{}` is used.",
+                        unused.stmt.module_name
+                    ),
+                    unused.stmt.source.clone().unwrap(),
+                ));
+                continue;
+            }
+            for (path, span) in unused.removed {
+                let Some(span) = span else {
+                    continue;
+                };
+                findings.push((format!("Unused import: `{}` is not used.", path), span));
+            }
+        }
+    }
+    findings
+}
+
+/// The findings of `unused-variable`.
+fn unused_variables(program: &Program, user_files: &Set<PathBuf>) -> Vec<(String, Span)> {
+    let mut collector = BinderCollector {
+        user_files,
+        file_cache: Map::default(),
+        binders: Map::default(),
+    };
+    for (_name, gv) in &program.global_values {
+        if gv.compiler_defined_method {
+            continue;
+        }
+        let roots: Vec<&Arc<ExprNode>> = match &gv.expr {
+            SymbolExpr::Simple(te) => vec![&te.expr],
+            SymbolExpr::Method(impls) => impls.iter().map(|m| &m.expr.expr).collect(),
+        };
+        for root in roots {
+            if let Some(src) = &root.source {
+                if collector.is_in_user_file(src) {
+                    collector.collect_expr(root);
+                }
+            }
+        }
+    }
+    collector
+        .binders
+        .into_values()
+        .filter(|(_, _, used)| !used)
+        .map(|(name, span, _)| {
+            (
+                format!(
+                    "Unused variable `{}`. Remove it, or prefix its name with `_` if it is unused on purpose.",
+                    name
+                ),
+                span,
+            )
+        })
+        .collect()
+}
+
+/// Walks the bodies of the values and records each binder the user wrote, with whether its scope
+/// refers to it.
+struct BinderCollector<'a> {
+    /// The files the user writes; binders elsewhere are not recorded.
+    user_files: &'a Set<PathBuf>,
+    /// Memoizes, per source file path, whether the file is a user file and its content.
+    file_cache: Map<PathBuf, Option<String>>,
+    /// The binders, keyed by where they are written: the name, its span, and whether it is used.
+    ///
+    /// A desugaring may copy a binder into several places, all at the span the user wrote it at,
+    /// so a binder counts as used where any of its copies is.
+    binders: Map<(PathBuf, usize, usize), (Name, Span, bool)>,
+}
+
+impl<'a> BinderCollector<'a> {
+    /// Walk an expression tree, recording the binders in it.
+    fn collect_expr(&mut self, expr: &Arc<ExprNode>) {
+        match &*expr.expr {
+            Expr::Var(_) | Expr::LLVM(_) => {}
+            Expr::App(func, args) => {
+                self.collect_expr(func);
+                for a in args {
+                    self.collect_expr(a);
+                }
+            }
+            // The parameter of a lambda the user writes is bound by the `let` its body starts with.
+            Expr::Lam(_args, body) => self.collect_expr(body),
+            Expr::Let(pat, bound, val) => {
+                self.collect_pattern(pat, val);
+                self.collect_expr(bound);
+                self.collect_expr(val);
+            }
+            Expr::If(cond, then_e, else_e) => {
+                self.collect_expr(cond);
+                self.collect_expr(then_e);
+                self.collect_expr(else_e);
+            }
+            Expr::Match(cond, arms) => {
+                self.collect_expr(cond);
+                for (pat, val) in arms {
+                    self.collect_pattern(pat, val);
+                    self.collect_expr(val);
+                }
+            }
+            Expr::TyAnno(e, _) => self.collect_expr(e),
+            Expr::MakeStruct(_, fields) => {
+                for (_name, _name_span, e) in fields {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::ArrayLit(elems) => {
+                for e in elems {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::FFICall(_, _, _, _, args, _) => {
+                for e in args {
+                    self.collect_expr(e);
+                }
+            }
+            Expr::Eval(side, main) => {
+                self.collect_expr(side);
+                self.collect_expr(main);
+            }
+        }
+    }
+
+    /// Record the variables of `pat`, a pattern whose variables are in scope in `scope`.
+    fn collect_pattern(&mut self, pat: &PatternNode, scope: &Arc<ExprNode>) {
+        match &pat.pattern {
+            Pattern::Var(v, _) => {
+                let name = &v.name.name;
+                if v.name.is_pattern_wildcard() || name.starts_with('#') || name.starts_with('_') {
+                    return;
+                }
+                let Some(span) = &pat.info.source else {
+                    return;
+                };
+                // A binder the compiler made up may carry the span of what the user wrote; it is
+                // told apart by the name not being written there.
+                if !self.is_written_at(name, span) {
+                    return;
+                }
+                let used = scope.has_free_var(&v.name);
+                let key = (span.input.file_path.clone(), span.start, span.end);
+                let entry = self
+                    .binders
+                    .entry(key)
+                    .or_insert_with(|| (name.clone(), span.clone(), false));
+                entry.2 |= used;
+            }
+            Pattern::Struct(_, fields) => {
+                for (_name, _name_span, sub) in fields {
+                    self.collect_pattern(sub, scope);
+                }
+            }
+            Pattern::Union(_, _, sub) => self.collect_pattern(sub, scope),
+            Pattern::Lit(_, _) => {}
+        }
+    }
+
+    /// Whether `span` lies in a user file. Results are cached per source file path.
+    fn is_in_user_file(&mut self, span: &Span) -> bool {
+        self.file_content(span).is_some()
+    }
+
+    /// Whether `span`, in a user file, starts with the name `name` written as a whole word.
+    fn is_written_at(&mut self, name: &str, span: &Span) -> bool {
+        let Some(content) = self.file_content(span) else {
+            return false;
+        };
+        let Some(written) = content.get(span.start..span.end) else {
+            return false;
+        };
+        match written.strip_prefix(name) {
+            Some(rest) => !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'),
+            None => false,
+        }
+    }
+
+    /// The content of the file `span` lies in, or `None` where it is not a user file or cannot be
+    /// read.
+    fn file_content(&mut self, span: &Span) -> Option<&String> {
+        let key = &span.input.file_path;
+        if !self.file_cache.contains_key(key) {
+            let content = to_absolute_path(key)
+                .ok()
+                .filter(|path| self.user_files.contains(path))
+                .and_then(|_| span.input.string().ok());
+            self.file_cache.insert(key.clone(), content);
+        }
+        self.file_cache.get(key).unwrap().as_ref()
+    }
+}
+
+/// The findings of `unused-private-global`: the values defined in user files whose names begin
+/// with `_` and which no value of a public name reaches through the values it refers to.
+fn unused_private_globals(program: &Program, user_files: &Set<PathBuf>) -> Vec<(String, Span)> {
+    let is_private = |name: &FullName| name.name.starts_with('_');
+
+    // Every value of a public name is a root, as is every value an `FFI_EXPORT` names.
+    let mut stack: Vec<&FullName> = program
+        .global_values
+        .keys()
+        .filter(|name| {
+            !is_private(name)
+                || program
+                    .export_statements
+                    .iter()
+                    .any(|stmt| stmt.value_name.is_suffix_of(name))
+        })
+        .collect();
+    let mut reached: Set<&FullName> = stack.iter().copied().collect();
+    while let Some(name) = stack.pop() {
+        let gv = program.global_values.get(name).unwrap();
+        gv.expr.walk_var_uses(&mut |var, _src| {
+            if !var.name.is_global() {
+                return;
+            }
+            if let Some((used_name, _)) = program.global_values.get_key_value(&var.name) {
+                if reached.insert(used_name) {
+                    stack.push(used_name);
+                }
+            }
+        });
+    }
+
+    let mut findings = vec![];
+    for (name, gv) in &program.global_values {
+        if !is_private(name) || reached.contains(name) || gv.compiler_defined_method {
+            continue;
+        }
+        if matches!(gv.expr, SymbolExpr::Method(_)) {
+            continue;
+        }
+        let Some(span) = gv.decl_src.as_ref().or(gv.defn_src.as_ref()) else {
+            continue;
+        };
+        let Ok(path) = to_absolute_path(&span.input.file_path) else {
+            continue;
+        };
+        if !user_files.contains(&path) {
+            continue;
+        }
+        findings.push((
+            format!(
+                "`{}` is never used: it is private, and no public value refers to it.",
+                name.to_string()
+            ),
+            span.clone(),
+        ));
+    }
+    findings
+}
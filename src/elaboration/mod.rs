@@ -1,11 +1,13 @@
 pub mod check_holes;
 pub mod desugar_opaque;
+pub mod lint;
 pub mod name_resolution;
 pub mod typecheck;
 pub mod typecheckcache;
 
 use crate::ast::program::Program;
-use crate::configuration::{Configuration, OutputFileType, SubCommand};
+use crate::configuration::{Configuration, DiagnosticsConfig, OutputFileType, SubCommand};
+use crate::elaboration::lint::collect_lint_diagnostics;
 use crate::error::Errors;
 use crate::fixstd::stdlib::{make_std_mod, make_tuple_traits_mod};
 use crate::parse::parser::parse_file_path;
@@ -62,6 +64,9 @@ fn elaborate(mut program: Program, config: &Configuration) -> Result<Program, Er
     // Validate the traits, the trait aliases and the trait implementations, structurally.
     program.validate_trait_env_structure()?;
 
+    // Check that the field types of each type named by `DERIVE` implement the derived traits.
+    program.validate_derive_statements()?;
+
     // Create symbols.
     program.create_trait_member_symbols()?;
 
@@ -93,8 +98,14 @@ fn elaborate(mut program: Program, config: &Configuration) -> Result<Program, Er
 
     let typechecker = program.create_typechecker(config);
 
-    // When running diagnostics, perform type checking of target modules and return here.
-    if let SubCommand::Diagnostics(diag_config) = &config.subcommand {
+    // When running diagnostics, perform type checking of target modules and return here, unless the
+    // diagnostics ask for the instantiated program.
+    if let SubCommand::Diagnostics(
+        diag_config @ DiagnosticsConfig {
+            instantiate: false, ..
+        },
+    ) = &config.subcommand
+    {
         let _sw = StopWatch::new("typecheck", config.show_build_times);
         let target_module_names = program.modules_from_files(&diag_config.files)?;
         let mut errors = Errors::empty();
@@ -108,6 +119,13 @@ fn elaborate(mut program: Program, config: &Configuration) -> Result<Program, Er
         program
             .deferred_errors
             .append(program.collect_deprecation_diagnostics(config));
+        // The lints read the names every value of a module resolved, which a check restricted to
+        // some of the values leaves incomplete.
+        if diag_config.target_symbols.is_none() && !program.deferred_errors.has_error() {
+            program
+                .deferred_errors
+                .append(collect_lint_diagnostics(&program, config));
+        }
         return Ok(program);
     }
 
@@ -130,13 +148,26 @@ fn elaborate(mut program: Program, config: &Configuration) -> Result<Program, Er
         .deferred_errors
         .append(program.collect_deprecation_diagnostics(config));
 
-    // Instantiate Main::main (or Test::test).
+    // Report unused imports, binders and private values, once every module is type-checked.
+    if !program.deferred_errors.has_error() {
+        program
+            .deferred_errors
+            .append(collect_lint_diagnostics(&program, config));
+    }
+
+    // Instantiate Main::main (or the value running the input of `fix repl`), the tests in test
+    // mode, or the benchmarks in bench mode.
     match config.output_file_type {
-        OutputFileType::Executable => program.instantiate_entry_io_value(
-            &typechecker,
-            matches!(config.subcommand, SubCommand::Test),
-        )?,
-        OutputFileType::DynamicLibrary => {}
+        OutputFileType::Executable => match config.subcommand {
+            SubCommand::Test => {
+                program.instantiate_test_cases(&typechecker, &config.test_source_files())?
+            }
+            SubCommand::Bench(_) => {
+                program.instantiate_bench_cases(&typechecker, &config.bench_source_files())?
+            }
+            _ => program.instantiate_entry_io_value(&config.entry_io_value_name(), &typechecker)?,
+        },
+        OutputFileType::DynamicLibrary | OutputFileType::StaticLibrary => {}
     };
 
     // Instantiate all exported values and values called from them.
@@ -146,6 +177,10 @@ fn elaborate(mut program: Program, config: &Configuration) -> Result<Program, Er
     // the type it is exported at, and before code generation puts one function under the name.
     program.validate_c_function_calls()?;
 
+    // Reject a closure turned into a C function pointer at a type no C function can stand for, the
+    // same way an exported value is.
+    program.validate_c_callbacks()?;
+
     // Reject a value whose type has no layout, now that the program's types are instantiated and
     // before code generation walks the fields of any of them.
     program.validate_layouts()?;
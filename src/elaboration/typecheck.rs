@@ -786,7 +786,8 @@ impl TypeCheckContext {
 
     /// Run `Pattern::validate_match_cases_exhaustiveness` on the
     /// arms of a typed `Match` when at least one arm was a union
-    /// variant (signalled by `cond_tc_info.is_some()`). In
+    /// variant (signalled by `cond_tc_info.is_some()`), and
+    /// `Pattern::validate_lit_match_cases` when an arm is a literal. In
     /// `error_tolerant` mode a non-exhaustive match is swallowed
     /// so the typed tree still surfaces to downstream LSP consumers.
     fn validate_match_exhaustiveness_if_needed(
@@ -794,16 +795,18 @@ impl TypeCheckContext {
         typed: &Arc<ExprNode>,
         cond_tc_info: Option<(Arc<TyCon>, TyConInfo)>,
     ) -> Result<(), Errors> {
-        let Some((cond_tycon, cond_ti)) = cond_tc_info else {
+        let pats = typed.get_match_pat_vals().into_iter().map(|(pat, _)| pat);
+        let res = if let Some((cond_tycon, cond_ti)) = cond_tc_info {
+            Pattern::validate_match_cases_exhaustiveness(&cond_tycon, &cond_ti, &typed.source, pats)
+        } else if typed
+            .get_match_pat_vals()
+            .iter()
+            .any(|(pat, _)| pat.is_lit())
+        {
+            Pattern::validate_lit_match_cases(&typed.source, pats)
+        } else {
             return Ok(());
         };
-        let pats = typed.get_match_pat_vals().into_iter().map(|(pat, _)| pat);
-        let res = Pattern::validate_match_cases_exhaustiveness(
-            &cond_tycon,
-            &cond_ti,
-            &typed.source,
-            pats,
-        );
         if self.error_tolerant {
             Ok(())
         } else {
@@ -1431,6 +1434,9 @@ impl TypeCheckContext {
                         let validated =
                             self.validate_union_arm(&cond, &cond_ty, pat, &mut cond_tc_info);
                         self.tolerate(validated)?.unwrap_or_else(|| pat.clone())
+                    } else if pat.is_lit() {
+                        // A literal matches one value, leaving the rest to the arms after it.
+                        pat.clone()
                     } else {
                         // `pat` is not a union pattern, so we can use it as is.
                         otherwise = Some(pat.clone());
@@ -1688,6 +1694,7 @@ impl TypeCheckContext {
                     }
                 }
             }
+            Pattern::Lit(_, _) => {}
         }
         if pat.pattern.has_duplicate_vars() && !tolerate {
             return Err(Errors::from_msg_srcs(
@@ -2436,6 +2443,7 @@ impl TypeCheckContext {
                 }
                 pat.set_struct_field_to_pat(field_to_pat)
             }
+            Pattern::Lit(_, _) => pat,
         })
     }
 
@@ -2664,7 +2672,7 @@ impl TypeCheckContext {
     /// into sub-patterns, then validates the type of `pat` itself.
     fn check_pattern_types_are_fixed(&self, pat: &Arc<PatternNode>) -> Result<(), Errors> {
         match &pat.pattern {
-            Pattern::Var(_, _) => {}
+            Pattern::Var(_, _) | Pattern::Lit(_, _) => {}
             Pattern::Union(_, _, subpat) => self.check_pattern_types_are_fixed(subpat)?,
             Pattern::Struct(_, fields) => {
                 for (_, _, subpat) in fields {
@@ -2757,7 +2765,7 @@ impl TypeCheckContext {
             ));
         }
         match &pat.pattern {
-            Pattern::Var(_, _) => {}
+            Pattern::Var(_, _) | Pattern::Lit(_, _) => {}
             Pattern::Union(_, _, subpat) => self.check_all_pattern_typed(subpat)?,
             Pattern::Struct(_, fields) => {
                 for (_, _, subpat) in fields {
@@ -2,13 +2,16 @@ use crate::{
     ast::name::FullName, ast::program::TypedExpr, ast::types::Scheme,
     constants::TYPE_CHECK_CACHE_PATH, elaboration::touch_directory, misc::warn_msg,
 };
+use build_time::build_time_utc;
+use rand::{thread_rng, Rng};
 use std::{
     collections::{BTreeMap, VecDeque},
-    fs::File,
+    fs::{self, File},
     io::{Read, Write},
     panic::RefUnwindSafe,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
 };
 
 /// A type-check cache held by the threads that check a program together.
@@ -61,48 +64,185 @@ fn entity_identity(name: &FullName, type_: &Arc<Scheme>) -> EntityIdentity {
     format!("{}\n{}", name, type_)
 }
 
+/// The directory, relative to the working directory, that holds the entries `FileCache` files for
+/// this build of the compiler.
+///
+/// The hash an entry is filed under already covers the build of the compiler, so an entry another
+/// build wrote is never read. Filing the entries of each build in a directory of its own is what
+/// lets `FileCache::collect_garbage` find them and remove them at once.
+///
+/// # Examples
+///
+/// A compiler of version `1.2.0` files its entries under `.fixlang/cache/typecheck/1.2.0-<digest>`,
+/// where the digest is taken over the time the compiler was built.
+pub fn type_check_cache_dir() -> PathBuf {
+    let build_digest = format!("{:x}", md5::compute(build_time_utc!()));
+    PathBuf::from(TYPE_CHECK_CACHE_PATH).join(format!(
+        "{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        &build_digest[..16]
+    ))
+}
+
+/// The extension of the file an entry is written to before it is renamed into place.
+const TEMPORARY_ENTRY_EXTENSION: &str = "tmp";
+
+/// How long a temporary file has gone unwritten before `FileCache::prune_current_build` takes its
+/// writing for cut short. A younger one may be an entry another process is writing right now.
+const STALE_TEMPORARY_ENTRY_AGE: Duration = Duration::from_secs(10 * 60);
+
 /// A cache that gives every entry a file of its own, so the entries outlive the run that wrote
-/// them.
+/// them. Several processes — builds, checks, and the language server — share the files of a
+/// project, so an entry is written to a file of its own first and renamed into place: a reader
+/// sees either the whole entry or none.
 pub struct FileCache {}
 
 impl FileCache {
     /// Creates a handle to the cache. Every handle reaches the same entries, which live under
-    /// `TYPE_CHECK_CACHE_PATH` relative to the working directory.
+    /// `type_check_cache_dir()`.
     pub fn new() -> Self {
         FileCache {}
     }
 
     /// The name of the file that holds the cache entry for a value.
     ///
-    /// The digest is what identifies the entry: it is taken over the entity and the version hash at
-    /// once, so two entries meet in one file only when both agree. The part in front of it names
-    /// the value for someone reading the cache directory, and is filename-safe because it keeps
-    /// only alphanumeric characters; several values can wear the same one.
+    /// The last digest is what identifies the entry: it is taken over the entity and the version
+    /// hash at once, so two entries meet in one file only when both agree. The digest before it is
+    /// taken over the entity alone, so that the entries of one entity, which differ in the version
+    /// hash, can be told to belong together. The part in front names the value for someone reading
+    /// the cache directory, and is filename-safe because it keeps only alphanumeric characters;
+    /// several values can wear the same one.
     ///
     /// # Examples
     ///
-    /// The entries of `Main::hole_val` are filed under names of the form `Main__hole_val_<digest>`.
+    /// The entries of `Main::hole_val` are filed under names of the form
+    /// `Main__hole_val_<entity digest>_<digest>`.
     fn cache_file_name(&self, name: &FullName, type_: &Arc<Scheme>, version_hash: &str) -> String {
-        let key = format!("{}\n{}", entity_identity(name, type_), version_hash);
+        let entity = entity_identity(name, type_);
+        let entity_digest = format!("{:x}", md5::compute(&entity));
+        let key = format!("{}\n{}", entity, version_hash);
         let digest = format!("{:x}", md5::compute(key));
 
         let readable_name = name
             .to_string()
             .replace(|c: char| !c.is_alphanumeric(), "_");
-        format!("{}_{}", readable_name, digest)
+        format!("{}_{}_{}", readable_name, entity_digest, digest)
     }
 
     /// The path of the file that holds the cache entry for a value, creating the cache directory if
     /// it is absent.
     fn cache_file_path(&self, name: &FullName, type_: &Arc<Scheme>, version_hash: &str) -> PathBuf {
         let cache_file_name = self.cache_file_name(name, type_, version_hash);
-        touch_directory(TYPE_CHECK_CACHE_PATH).join(cache_file_name)
+        touch_directory(type_check_cache_dir()).join(cache_file_name)
+    }
+
+    /// Removes the entries no run of this compiler can make use of, and returns how many files it
+    /// removed:
+    /// - the entries of every other build of the compiler, whose hashes no longer come up;
+    /// - of the entries of one value checked against one type, all but the `CACHE_GENERATION`
+    ///   written last, as `MemoryCache` does: the others were filed under sources that have since
+    ///   been edited, and come up again only if the edits are undone;
+    /// - the files of entries whose writing was cut short, which are told from those still being
+    ///   written by having been left alone for `STALE_TEMPORARY_ENTRY_AGE`.
+    ///
+    /// A file that cannot be removed is reported as a warning and left in place.
+    pub fn collect_garbage(&self) -> usize {
+        let mut removed = 0;
+        let current_dir = type_check_cache_dir();
+        let Ok(builds) = fs::read_dir(TYPE_CHECK_CACHE_PATH) else {
+            return 0;
+        };
+        for build in builds.flatten() {
+            let path = build.path();
+            if path == current_dir {
+                continue;
+            }
+            let result = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            removed += remove_or_warn(result, &path);
+        }
+        removed + self.prune_current_build()
+    }
+
+    /// Removes the entries of this build of the compiler that `collect_garbage` removes, leaving
+    /// the directories of the other builds alone, and returns how many files it removed. A
+    /// process that keeps running, as the language server does, calls this rather than
+    /// `collect_garbage`: a compiler of another build, such as the one of the terminal beside an
+    /// editor, may be using the other directories.
+    pub fn prune_current_build(&self) -> usize {
+        let mut removed = 0;
+        let Ok(entries) = fs::read_dir(type_check_cache_dir()) else {
+            return removed;
+        };
+        let mut entries_of_entity: BTreeMap<String, Vec<(SystemTime, PathBuf)>> =
+            BTreeMap::default();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let mut parts = file_name.rsplitn(3, '_');
+            let (Some(_digest), Some(entity_digest), Some(_readable_name)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                removed += remove_or_warn(fs::remove_file(&path), &path);
+                continue;
+            };
+            let written = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            if path
+                .extension()
+                .map_or(false, |ext| ext == TEMPORARY_ENTRY_EXTENSION)
+            {
+                let stale = written
+                    .elapsed()
+                    .map_or(false, |age| age >= STALE_TEMPORARY_ENTRY_AGE);
+                if stale {
+                    removed += remove_or_warn(fs::remove_file(&path), &path);
+                }
+                continue;
+            }
+            entries_of_entity
+                .entry(entity_digest.to_string())
+                .or_default()
+                .push((written, path));
+        }
+        for (_, mut entries) in entries_of_entity {
+            // The entries written last come first; the path breaks a tie, so that which entries
+            // survive does not depend on the order the directory is listed in.
+            entries.sort_by(|(a_time, a_path), (b_time, b_path)| {
+                b_time.cmp(a_time).then_with(|| a_path.cmp(b_path))
+            });
+            for (_, path) in entries.into_iter().skip(CACHE_GENERATION as usize) {
+                removed += remove_or_warn(fs::remove_file(&path), &path);
+            }
+        }
+        removed
+    }
+}
+
+/// 1 if `result` says `path` was removed, or 0 after warning that it was not.
+fn remove_or_warn(result: std::io::Result<()>, path: &Path) -> usize {
+    match result {
+        Ok(()) => 1,
+        Err(why) => {
+            warn_msg(&format!(
+                "Failed to remove cache file \"{}\": {}.",
+                path.to_string_lossy(),
+                why
+            ));
+            0
+        }
     }
 }
 
 impl TypeCheckCache for FileCache {
-    /// Writes the expression into the entry's own file. A file that cannot be created or written
-    /// is reported as a warning and leaves the entry absent.
+    /// Writes the expression into a temporary file beside the entry's own, and renames it into
+    /// place, so a process reading the entry meanwhile finds it either absent or whole. A file that
+    /// cannot be created, written or renamed is reported as a warning and leaves the entry absent.
     fn save_cache(
         &self,
         expr: &TypedExpr,
@@ -112,7 +252,12 @@ impl TypeCheckCache for FileCache {
     ) {
         let cache_file_path = self.cache_file_path(name, type_, version_hash);
         let cache_file_path_str = cache_file_path.to_string_lossy().to_string();
-        let mut cache_file = match File::create(&cache_file_path) {
+        let tmp_file_path = cache_file_path.with_extension(format!(
+            "{}.{}",
+            thread_rng().gen::<u64>(),
+            TEMPORARY_ENTRY_EXTENSION
+        ));
+        let mut tmp_file = match File::create(&tmp_file_path) {
             Err(_) => {
                 warn_msg(&format!(
                     "Failed to create cache file \"{}\".",
@@ -123,14 +268,20 @@ impl TypeCheckCache for FileCache {
             Ok(file) => file,
         };
         let serialized = postcard::to_allocvec(expr).unwrap();
-        match cache_file.write_all(&serialized) {
-            Ok(_) => {}
-            Err(_) => {
-                warn_msg(&format!(
-                    "Failed to write cache file \"{}\".",
-                    cache_file_path_str
-                ));
-            }
+        if tmp_file.write_all(&serialized).is_err() {
+            warn_msg(&format!(
+                "Failed to write cache file \"{}\".",
+                cache_file_path_str
+            ));
+            let _ = fs::remove_file(&tmp_file_path);
+            return;
+        }
+        if let Err(why) = fs::rename(&tmp_file_path, &cache_file_path) {
+            warn_msg(&format!(
+                "Failed to write cache file \"{}\": {}.",
+                cache_file_path_str, why
+            ));
+            let _ = fs::remove_file(&tmp_file_path);
         }
     }
 
@@ -250,6 +401,59 @@ impl TypeCheckCache for MemoryCache {
     }
 }
 
+/// A cache that holds its entries in memory and in files at once: an entry is read from memory
+/// when it is there and from its file otherwise, and stored into both. The language server checks
+/// the program again after every edit, which memory answers fastest, and starts out with the
+/// entries the builds and checks of the project have filed, rather than checking every
+/// dependency afresh.
+///
+/// Every value checked afresh is filed, so the files pile up with each edit. The language server
+/// and `fix watch` run `FileCache::prune_current_build` once as a session starts, which removes
+/// what the sessions before it left over.
+pub struct MemoryAndFileCache {
+    memory: MemoryCache,
+    file: FileCache,
+}
+
+impl MemoryAndFileCache {
+    /// Creates a cache holding no entries in memory, reaching the entries `FileCache` does.
+    pub fn new() -> Self {
+        MemoryAndFileCache {
+            memory: MemoryCache::new(),
+            file: FileCache::new(),
+        }
+    }
+}
+
+impl TypeCheckCache for MemoryAndFileCache {
+    fn save_cache(
+        &self,
+        expr: &TypedExpr,
+        name: &FullName,
+        type_: &Arc<Scheme>,
+        version_hash: &str,
+    ) {
+        self.memory.save_cache(expr, name, type_, version_hash);
+        self.file.save_cache(expr, name, type_, version_hash);
+    }
+
+    /// Reads memory first. An entry found in its file alone is kept in memory, so the next read of
+    /// it stays off the disk.
+    fn load_cache(
+        &self,
+        name: &FullName,
+        type_: &Arc<Scheme>,
+        version_hash: &str,
+    ) -> Option<TypedExpr> {
+        if let Some(expr) = self.memory.load_cache(name, type_, version_hash) {
+            return Some(expr);
+        }
+        let expr = self.file.load_cache(name, type_, version_hash)?;
+        self.memory.save_cache(&expr, name, type_, version_hash);
+        Some(expr)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{entity_identity, FileCache};
@@ -1,12 +1,13 @@
 use crate::misc::{insert_to_map_vec, Map, Set};
 use crate::parse::sourcefile::Span;
 use colored::{Color, Colorize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::{
     any::Any,
     fmt::{self, Display, Formatter},
     mem, panic,
     path::{Path, PathBuf},
+    process,
 };
 
 /// Diagnostic code for "use of a deprecated item".
@@ -15,16 +16,31 @@ pub const WARN_DEPRECATED: &'static str = "deprecated";
 /// Diagnostic code for "import of a module whose project is not a declared dependency".
 pub const WARN_UNDECLARED_DEPENDENCY: &'static str = "undeclared-dependency";
 
+/// Diagnostic code for "import through which nothing is used" (a lint).
+pub const WARN_UNUSED_IMPORT: &'static str = "unused-import";
+
+/// Diagnostic code for "local binder that is never referred to" (a lint).
+pub const WARN_UNUSED_VARIABLE: &'static str = "unused-variable";
+
+/// Diagnostic code for "private global value no public one reaches" (a lint).
+pub const WARN_UNUSED_PRIVATE_GLOBAL: &'static str = "unused-private-global";
+
+/// Diagnostic code for "update that kept its run-time uniqueness check" (`--explain-uniqueness`).
+pub const NOTE_KEPT_UNIQUE_CHECK: &'static str = "kept-unique-check";
+
 /// Severity of a diagnostic.
 ///
 /// Errors are fatal and cause compilation to fail. Warnings are non-fatal:
 /// they are reported to the user but do not by themselves block compilation.
+/// Notes are reports the user asked for, such as `--explain-uniqueness` gives.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Severity {
     /// Fatal diagnostic; compilation fails when any error is present.
     Error,
     /// Non-fatal diagnostic; reported but does not block compilation.
     Warning,
+    /// Information the user asked for; it points at nothing wrong.
+    Note,
 }
 
 impl Severity {
@@ -34,6 +50,48 @@ impl Severity {
         match self {
             Severity::Error => ("error".red().bold().to_string(), Color::Red),
             Severity::Warning => ("warning".yellow().bold().to_string(), Color::Yellow),
+            Severity::Note => ("note".cyan().bold().to_string(), Color::Cyan),
+        }
+    }
+}
+
+impl Display for Severity {
+    /// Writes the name of this severity without color, as the JSON message format spells it.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// The form in which the compiler reports diagnostics on the command line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Colored text with the source quoted, written to stderr.
+    Human,
+    /// One JSON object per diagnostic and per line, written to stdout, for tools to read. See
+    /// `Error::to_json` for the shape of an object.
+    Json,
+}
+
+impl Default for MessageFormat {
+    fn default() -> Self {
+        MessageFormat::Human
+    }
+}
+
+impl MessageFormat {
+    /// Reads the value a `--message-format` option names, or reports the names there are.
+    pub fn from_str(name: &str) -> Result<MessageFormat, Errors> {
+        match name {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(Errors::from_msg(format!(
+                "Unknown message format \"{}\". Available message formats are \"human\" and \"json\".",
+                name
+            ))),
         }
     }
 }
@@ -65,6 +123,14 @@ impl Errors {
         self.errs.iter().any(|e| e.severity == Severity::Error)
     }
 
+    /// Whether this collection contains an item with `Severity::Error` whose code is none of
+    /// `codes`, such as an error other than a lint promoted to one.
+    pub fn has_error_except(&self, codes: &[&str]) -> bool {
+        self.errs.iter().any(|e| {
+            e.severity == Severity::Error && !e.code.map_or(false, |code| codes.contains(&code))
+        })
+    }
+
     /// Whether this collection contains any diagnostic at all (errors or warnings).
     pub fn has_diagnostics(&self) -> bool {
         !self.errs.is_empty()
@@ -95,6 +161,19 @@ impl Errors {
         self.errs.append(&mut other.errs);
     }
 
+    /// Replaces each source location of the diagnostics by the one `relocate` gives for it, as where
+    /// code was compiled from a file other than the one it is written in. A location for which
+    /// `relocate` answers `None` stays where it is.
+    pub fn relocate_srcs(&mut self, relocate: impl Fn(&Span) -> Option<Span>) {
+        for err in &mut self.errs {
+            for (_, span) in &mut err.srcs {
+                if let Some(relocated) = relocate(span) {
+                    *span = relocated;
+                }
+            }
+        }
+    }
+
     /// Appends the diagnostics of a failed `res` to this collection, so that the caller carries on
     /// and reports them together with whatever it finds afterwards.
     pub fn eat_err(&mut self, res: Result<(), Errors>) {
@@ -163,6 +242,36 @@ impl Errors {
         rendered
     }
 
+    /// Renders every diagnostic as a line holding its JSON object (see `Error::to_json`), in the
+    /// order they were reported. A diagnostic whose object repeats one already written is left
+    /// out, as in `to_string`.
+    pub fn to_json_lines(&self) -> String {
+        let mut line_set = Set::default();
+        let mut rendered = String::default();
+        for err in &self.errs {
+            let line = err.to_json().to_string();
+            if line_set.contains(&line) {
+                continue;
+            }
+            line_set.insert(line.clone());
+            rendered += &line;
+            rendered += "\n";
+        }
+        rendered
+    }
+
+    /// Writes every diagnostic in the given format: to stderr as text, or to stdout as JSON lines.
+    /// Nothing is written for an empty collection.
+    pub fn report(&self, format: MessageFormat) {
+        if !self.has_diagnostics() {
+            return;
+        }
+        match format {
+            MessageFormat::Human => eprint!("{}", self.to_string()),
+            MessageFormat::Json => print!("{}", self.to_json_lines()),
+        }
+    }
+
     /// Groups the diagnostics by the file of their first source location, ordered by path.
     ///
     /// # Arguments
@@ -206,7 +315,7 @@ pub struct Error {
     /// shape is decided by `code`.
     pub data: Option<Value>,
     /// Severity of this diagnostic: an error fails the compilation, a warning accompanies a
-    /// compilation that still succeeds.
+    /// compilation that still succeeds, and a note reports what the user asked to be told.
     pub severity: Severity,
 }
 
@@ -248,6 +357,13 @@ impl Error {
         err
     }
 
+    /// Build a note-severity diagnostic.
+    pub fn note_from_msg_srcs(msg: String, srcs: &[&Option<Span>]) -> Error {
+        let mut err = Error::from_msg_srcs(msg, srcs);
+        err.severity = Severity::Note;
+        err
+    }
+
     /// Attaches one more source location to this diagnostic, shown after the ones already
     /// attached.
     ///
@@ -285,6 +401,60 @@ impl Error {
         }
         rendered
     }
+
+    /// This diagnostic as the JSON object the `--message-format=json` option writes for it:
+    ///
+    /// ```json
+    /// {
+    ///   "severity": "error" | "warning" | "note",
+    ///   "code": string | null,
+    ///   "message": string,
+    ///   "spans": [
+    ///     {
+    ///       "file": string,
+    ///       "line_start": number, "column_start": number,
+    ///       "line_end": number, "column_end": number,
+    ///       "byte_start": number, "byte_end": number,
+    ///       "is_primary": bool,
+    ///       "label": string | null
+    ///     }, ...
+    ///   ],
+    ///   "data": any | null
+    /// }
+    /// ```
+    ///
+    /// Lines and columns count from 1, and a column counts the characters of its line; the end is
+    /// exclusive. The first span is the primary one, and the others are where the diagnostic
+    /// points the reader to as well, each labelled with the line the text form prints above it.
+    pub fn to_json(&self) -> Value {
+        let spans = self
+            .srcs
+            .iter()
+            .enumerate()
+            .map(|(i, (desc, span))| {
+                let (line_start, column_start) = span.start_line_col();
+                let (line_end, column_end) = span.end_line_col();
+                json!({
+                    "file": span.input.file_path.to_string_lossy(),
+                    "line_start": line_start,
+                    "column_start": column_start,
+                    "line_end": line_end,
+                    "column_end": column_end,
+                    "byte_start": span.start,
+                    "byte_end": span.end,
+                    "is_primary": i == 0,
+                    "label": if desc.is_empty() { None } else { Some(desc) },
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "severity": self.severity.to_string(),
+            "code": self.code,
+            "message": self.msg,
+            "spans": spans,
+            "data": self.data,
+        })
+    }
 }
 
 /// Panics with `msg`, having installed a panic hook that prints the message alone, so that the
@@ -319,3 +489,15 @@ pub fn panic_with_msg(msg: &str) -> ! {
 pub fn panic_if_err<T>(res: Result<T, Errors>) -> T {
     res.unwrap_or_else(|errs| panic_notrace(&errs.to_string()))
 }
+
+/// The value of a successful `res`. A failed one ends the process with its diagnostics reported in
+/// the given format; as JSON, they go to stdout and the process exits with status 1.
+pub fn report_if_err<T>(res: Result<T, Errors>, format: MessageFormat) -> T {
+    match format {
+        MessageFormat::Human => panic_if_err(res),
+        MessageFormat::Json => res.unwrap_or_else(|errs| {
+            errs.report(MessageFormat::Json);
+            process::exit(1)
+        }),
+    }
+}
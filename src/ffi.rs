@@ -4,20 +4,29 @@
 //! `FFI_EXPORT` defines one it offers — and the compiler describes the entry point it writes. All
 //! three reach the module as one function, so `CSignature` is what they have to agree on and
 //! `CSignature::get_or_declare_in_module` is where they meet.
+//!
+//! An `FFI_CALL` may also pass and return a struct by value. How such a struct is laid down and how
+//! the C ABI carries it is the business of `crate::c_struct`; a signature applies it to the
+//! declaration of the function and to each call of it.
 
 use crate::ast::export_statement::ExportedFunctionType;
 use crate::ast::name::{FullName, Name};
 use crate::ast::program::TypeEnv;
-use crate::ast::types::{tycon, TyCon, TypeNode};
+use crate::ast::types::{tycon, type_tycon, TyCon, TypeNode};
+use crate::c_struct::{
+    c_struct_layout, pass_structs, CPosition, CRegisterPiece, CStructAbi, CStructLayout,
+    CStructPassing,
+};
 use crate::constants::{
     F32_NAME, F64_NAME, I16_NAME, I32_NAME, I64_NAME, I8_NAME, PTR_NAME, STD_NAME, U16_NAME,
     U32_NAME, U64_NAME, U8_NAME,
 };
-use crate::generator::Generator;
+use crate::error::panic_with_msg;
+use crate::generator::{enum_attribute_kind_id, Generator, Object};
 use inkwell::attributes::AttributeLoc;
 use inkwell::context::Context;
-use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
-use inkwell::values::FunctionValue;
+use inkwell::types::{AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue};
 use inkwell::AddressSpace;
 use std::sync::Arc;
 
@@ -71,6 +80,13 @@ impl CIntegerExtension {
 
 /// How a Fix type constructor crosses to C.
 impl TyCon {
+    /// Whether this type constructor, written in a C signature, is a struct passed by value: it is
+    /// neither a C scalar nor `()`. Which structs a signature may name is checked where its names
+    /// are resolved, by `c_struct_layout`.
+    pub fn is_c_struct(self: &TyCon) -> bool {
+        !self.is_unit() && !self.is_c_scalar()
+    }
+
     /// The shape of the C type this type constructor stands for.
     /// `()` is C's `void`, which carries no value, so it has no shape.
     ///
@@ -130,12 +146,19 @@ impl TyCon {
     /// the Fix type fixes and the spelling C would otherwise use — `int`, `long` — is a different
     /// width on a different target.
     ///
+    /// A struct is written with the name of its Fix declaration, which a C declaration of it takes as
+    /// its tag.
+    ///
     /// # Examples
-    /// `I32` is written `int32_t`, `U8` is `uint8_t`, `Ptr` is `void *`, and `()` is `void`.
+    /// `I32` is written `int32_t`, `U8` is `uint8_t`, `Ptr` is `void *`, `()` is `void`, and the
+    /// struct `Main::Point` is `struct Point`.
     pub fn c_type_name(self: &TyCon) -> String {
         if self.is_unit() {
             return "void".to_string();
         }
+        if self.is_c_struct() {
+            return format!("struct {}", self.name.name);
+        }
         assert!(
             self.is_c_scalar(),
             "call c_type_name for {}",
@@ -236,19 +259,79 @@ impl CSignature {
 
     /// Whether this signature and `other` declare the same C function: the two describe every
     /// position the same way, down to what a declaration of it carries — `CTypeShape` holds which
-    /// differences between two Fix types that is, and which it is not.
+    /// differences between two Fix types that is, and which it is not. A struct is described the
+    /// same way by itself alone.
     pub fn agrees_with(&self, other: &CSignature) -> bool {
+        let alike = |a: &Arc<TyCon>, b: &Arc<TyCon>| {
+            if a.is_c_struct() || b.is_c_struct() {
+                a == b
+            } else {
+                a.c_type_shape() == b.c_type_shape()
+            }
+        };
         self.is_var_args == other.is_var_args
             && self.param_tys.len() == other.param_tys.len()
-            && self.ret_tycon.c_type_shape() == other.ret_tycon.c_type_shape()
+            && alike(&self.ret_tycon, &other.ret_tycon)
             && (self.param_tys.iter())
                 .zip(other.param_tys.iter())
-                .all(|(a, b)| a.c_type_shape() == b.c_type_shape())
+                .all(|(a, b)| alike(a, b))
+    }
+
+    /// The layout and the passing of each struct this signature exchanges by value, on the target
+    /// of the module `gc` builds.
+    fn struct_positions<'c, 'm>(&self, gc: &Generator<'c, 'm>) -> CStructPositions {
+        let layout_of = |tc: &Arc<TyCon>| {
+            tc.is_c_struct().then(|| {
+                c_struct_layout(tc, gc.type_env()).unwrap_or_else(|msg| {
+                    panic!("`{}` reached a C signature: {}", tc.to_string(), msg)
+                })
+            })
+        };
+        let ret_layout = layout_of(&self.ret_tycon);
+        let param_layouts = self.param_tys.iter().map(layout_of).collect::<Vec<_>>();
+        if ret_layout.is_none() && param_layouts.iter().all(Option::is_none) {
+            return CStructPositions {
+                ret: None,
+                params: vec![None; self.param_tys.len()],
+            };
+        }
+        let triple = gc.module.get_triple();
+        let triple = triple.as_str().to_string_lossy();
+        let Some(abi) = CStructAbi::of_target(&triple) else {
+            panic_with_msg(&format!(
+                "Passing a struct by value to a C function is not supported on the target `{}`.",
+                triple
+            ));
+        };
+        let positions = (self.param_tys.iter())
+            .zip(param_layouts.iter())
+            .map(|(param_ty, layout)| match layout {
+                Some(layout) => CPosition::Struct(layout),
+                None => CPosition::Scalar(param_ty.c_type_shape().unwrap()),
+            })
+            .collect::<Vec<_>>();
+        let (ret_passing, param_passings) = pass_structs(abi, ret_layout.as_ref(), &positions);
+        let position = |layout: Option<CStructLayout>, passing: Option<CStructPassing>| {
+            Some(CStructPosition {
+                layout: layout?,
+                passing: passing.unwrap(),
+            })
+        };
+        CStructPositions {
+            ret: position(ret_layout, ret_passing),
+            params: (param_layouts.into_iter())
+                .zip(param_passings)
+                .map(|(layout, passing)| position(layout, passing))
+                .collect(),
+        }
     }
 
     /// The function `name` of this signature in the module, declaring it where nothing declares it
     /// yet. Every description of one C name goes through here, which is what puts the calls a
     /// program makes and the definition it exports on one function.
+    ///
+    /// A struct takes the parameters `CStructPassing` says: one per register it travels in, or a
+    /// pointer, and a result returned in memory takes a pointer ahead of the parameters.
     pub fn get_or_declare_in_module<'c, 'm>(
         &self,
         name: &Name,
@@ -257,16 +340,38 @@ impl CSignature {
         if let Some(declared) = gc.module.get_function(name) {
             return declared;
         }
-        let param_c_tys = self
-            .param_tys
-            .iter()
-            .map(|param_ty| {
+        let structs = self.struct_positions(gc);
+        let ptr_ty = gc.context.ptr_type(AddressSpace::from(0));
+        let mut param_c_tys: Vec<BasicMetadataTypeEnum> = vec![];
+        let returns_through_out_pointer = structs
+            .ret
+            .as_ref()
+            .is_some_and(|ret| ret.passing == CStructPassing::ThroughOutPointer);
+        if returns_through_out_pointer {
+            param_c_tys.push(ptr_ty.into());
+        }
+        // The first LLVM parameter of each parameter of the signature.
+        let mut first_llvm_params = vec![];
+        for (param_ty, position) in self.param_tys.iter().zip(structs.params.iter()) {
+            first_llvm_params.push(param_c_tys.len() as u32);
+            match position.as_ref().map(|position| &position.passing) {
                 // A parameter of type `()` is rejected where the signature is written: `void` is a
                 // result alone.
-                param_ty.get_c_type(gc.context).unwrap().into()
-            })
-            .collect::<Vec<BasicMetadataTypeEnum>>();
-        let fn_ty = match self.ret_tycon.get_c_type(gc.context) {
+                None => param_c_tys.push(param_ty.get_c_type(gc.context).unwrap().into()),
+                Some(CStructPassing::Direct(pieces)) => {
+                    param_c_tys.extend((pieces.iter()).map(|piece| -> BasicMetadataTypeEnum {
+                        register_piece_type(*piece, gc.context).into()
+                    }))
+                }
+                Some(_) => param_c_tys.push(ptr_ty.into()),
+            }
+        }
+        let ret_c_ty = match structs.ret.as_ref().map(|ret| &ret.passing) {
+            None => self.ret_tycon.get_c_type(gc.context),
+            Some(CStructPassing::Direct(pieces)) => Some(direct_struct_type(pieces, gc.context)),
+            Some(_) => None,
+        };
+        let fn_ty = match ret_c_ty {
             None => gc
                 .context
                 .void_type()
@@ -279,13 +384,100 @@ impl CSignature {
             name,
             "the C function enters the module under the name it was given"
         );
-        gc.add_c_integer_extension_attribute(func, AttributeLoc::Return, &self.ret_tycon);
-        for (i, param_ty) in self.param_tys.iter().enumerate() {
-            gc.add_c_integer_extension_attribute(func, AttributeLoc::Param(i as u32), param_ty);
+        match &structs.ret {
+            None => {
+                gc.add_c_integer_extension_attribute(func, AttributeLoc::Return, &self.ret_tycon)
+            }
+            Some(ret) if returns_through_out_pointer => {
+                add_memory_attributes(func, AttributeLoc::Param(0), "sret", &ret.layout, gc)
+            }
+            Some(_) => {}
+        }
+        for (i, position) in structs.params.iter().enumerate() {
+            let loc = AttributeLoc::Param(first_llvm_params[i]);
+            match position {
+                None => gc.add_c_integer_extension_attribute(func, loc, &self.param_tys[i]),
+                Some(CStructPosition {
+                    layout,
+                    passing: CStructPassing::ByValue,
+                }) => add_memory_attributes(func, loc, "byval", layout, gc),
+                Some(_) => {}
+            }
         }
         func
     }
 
+    /// Call `func`, the function `get_or_declare_in_module` gives for this signature, with the
+    /// values of `args`, and return what it returns as Fix lays it down: the one scalar of a scalar
+    /// result, the value of a struct result, and `None` for `void`.
+    ///
+    /// An argument past the parameters of a variadic signature is a scalar, passed as it stands.
+    pub fn build_call<'c, 'm>(
+        &self,
+        func: FunctionValue<'c>,
+        args: &[Object<'c>],
+        gc: &mut Generator<'c, 'm>,
+    ) -> Option<BasicValueEnum<'c>> {
+        let structs = self.struct_positions(gc);
+        let fun_name = func.get_name().to_str().unwrap().to_string();
+        let mut arg_vals: Vec<BasicMetadataValueEnum> = vec![];
+        let ret_ty = structs.ret.as_ref().map(|ret| {
+            let ret_ty = c_struct_value_type(&self.ret_tycon, gc.type_env()).get_embedded_type(gc);
+            assert_laid_out_as_c_struct(&self.ret_tycon, ret_ty, &ret.layout, gc);
+            ret_ty
+        });
+        let out_ptr = match &structs.ret {
+            Some(ret) if ret.passing == CStructPassing::ThroughOutPointer => {
+                let out_ptr = gc.build_alloca_at_entry(ret_ty.unwrap(), "out_ptr@FFI_CALL");
+                arg_vals.push(out_ptr.into());
+                Some(out_ptr)
+            }
+            _ => None,
+        };
+        for (i, arg) in args.iter().enumerate() {
+            let Some(Some(position)) = structs.params.get(i) else {
+                arg_vals.push(arg.extract_field(gc, 0).into());
+                continue;
+            };
+            let val = arg.value(gc);
+            assert_laid_out_as_c_struct(&self.param_tys[i], val.get_type(), &position.layout, gc);
+            match &position.passing {
+                CStructPassing::Direct(pieces) => {
+                    let coerced = reinterpret(val, direct_struct_type(pieces, gc.context), gc);
+                    if pieces.len() == 1 {
+                        arg_vals.push(coerced.into());
+                    } else {
+                        for j in 0..pieces.len() {
+                            let piece = gc
+                                .builder()
+                                .build_extract_value(coerced.into_struct_value(), j as u32, "")
+                                .unwrap();
+                            arg_vals.push(piece.into());
+                        }
+                    }
+                }
+                CStructPassing::ByValue | CStructPassing::ByReference => {
+                    arg_vals.push(spill(val, gc).into());
+                }
+                CStructPassing::ThroughOutPointer => unreachable!("an argument is not returned"),
+            }
+        }
+        let call_site = gc
+            .builder()
+            .build_call(func, &arg_vals, &format!("FFI_CALL({})", fun_name))
+            .unwrap();
+        let ret_val = call_site.try_as_basic_value().left();
+        match (structs.ret, out_ptr) {
+            (None, _) => ret_val,
+            (Some(_), Some(out_ptr)) => Some(
+                gc.builder()
+                    .build_load(ret_ty.unwrap(), out_ptr, "ret@FFI_CALL")
+                    .unwrap(),
+            ),
+            (Some(_), None) => Some(reinterpret(ret_val.unwrap(), ret_ty.unwrap(), gc)),
+        }
+    }
+
     /// The signature as a C declaration of the function `name` reads.
     ///
     /// # Examples
@@ -360,3 +552,119 @@ pub fn assert_crosses_as_c_type<'c, 'm>(
         c_ty.to_string()
     );
 }
+
+/// A struct a signature exchanges by value: how it is laid down, and how the call carries it.
+#[derive(Clone)]
+struct CStructPosition {
+    layout: CStructLayout,
+    passing: CStructPassing,
+}
+
+/// The struct positions of a signature: the result, and each parameter in order, with `None` where
+/// the position holds a scalar or `void`.
+struct CStructPositions {
+    ret: Option<CStructPosition>,
+    params: Vec<Option<CStructPosition>>,
+}
+
+/// The type a value of the struct `tc` has by the time code is generated for it. A struct of one
+/// field is then its field, so the value is laid down as the field alone, which is the layout C gives
+/// it too.
+pub fn c_struct_value_type(tc: &Arc<TyCon>, type_env: &TypeEnv) -> Arc<TypeNode> {
+    type_tycon(tc).unwrap_newtypes(type_env)
+}
+
+/// The LLVM type of a register piece.
+fn register_piece_type<'c>(piece: CRegisterPiece, ctx: &'c Context) -> BasicTypeEnum<'c> {
+    match piece {
+        CRegisterPiece::Integer { bits } => ctx.custom_width_int_type(bits).into(),
+        CRegisterPiece::Float => ctx.f32_type().into(),
+        CRegisterPiece::FloatPair => ctx.f32_type().vec_type(2).into(),
+        CRegisterPiece::Double => ctx.f64_type().into(),
+        CRegisterPiece::IntegerArray { count } => ctx.i64_type().array_type(count).into(),
+        CRegisterPiece::FloatArray { count } => ctx.f32_type().array_type(count).into(),
+        CRegisterPiece::DoubleArray { count } => ctx.f64_type().array_type(count).into(),
+    }
+}
+
+/// The LLVM type a struct passed directly is returned as: its one piece, or a literal struct of its
+/// pieces, which is also the value its pieces as arguments are extracted from.
+fn direct_struct_type<'c>(pieces: &[CRegisterPiece], ctx: &'c Context) -> BasicTypeEnum<'c> {
+    let piece_tys = (pieces.iter())
+        .map(|piece| register_piece_type(*piece, ctx))
+        .collect::<Vec<_>>();
+    if piece_tys.len() == 1 {
+        return piece_tys[0];
+    }
+    ctx.struct_type(&piece_tys, false).into()
+}
+
+/// Mark the pointer parameter at `loc` of `func` as one the struct of `layout` is passed or returned
+/// in memory through, with the type attribute `name` (`byval` or `sret`) and the alignment the
+/// memory has.
+///
+/// The copy `byval` makes on the stack takes a slot of eight bytes at least, the unit arguments on
+/// the stack are pushed in.
+fn add_memory_attributes<'c, 'm>(
+    func: FunctionValue<'c>,
+    loc: AttributeLoc,
+    name: &str,
+    layout: &CStructLayout,
+    gc: &Generator<'c, 'm>,
+) {
+    let memory_ty = gc.context.i8_type().array_type(layout.size as u32);
+    func.add_attribute(
+        loc,
+        gc.context
+            .create_type_attribute(enum_attribute_kind_id(name), memory_ty.as_any_type_enum()),
+    );
+    let align = if name == "byval" {
+        layout.align.max(8)
+    } else {
+        layout.align
+    };
+    func.add_attribute(
+        loc,
+        gc.context
+            .create_enum_attribute(enum_attribute_kind_id("align"), align),
+    );
+}
+
+/// The bytes of `val` read as a value of `to_ty`, through a stack slot that holds either and is
+/// aligned for both: the pieces a struct travels in cover its bytes as they lie in memory, not its
+/// fields.
+fn reinterpret<'c, 'm>(
+    val: BasicValueEnum<'c>,
+    to_ty: BasicTypeEnum<'c>,
+    gc: &mut Generator<'c, 'm>,
+) -> BasicValueEnum<'c> {
+    let size = gc.sizeof(&val.get_type()).max(gc.sizeof(&to_ty));
+    let slot_ty = gc.context.i64_type().array_type(size.div_ceil(8) as u32);
+    let slot = gc.build_alloca_at_entry(slot_ty, "slot@FFI_CALL");
+    gc.builder().build_store(slot, val).unwrap();
+    gc.builder().build_load(to_ty, slot, "").unwrap()
+}
+
+/// `val` stored in a stack slot of its own, for a callee that takes the struct behind a pointer.
+fn spill<'c, 'm>(val: BasicValueEnum<'c>, gc: &mut Generator<'c, 'm>) -> PointerValue<'c> {
+    let slot = gc.build_alloca_at_entry(val.get_type(), "arg@FFI_CALL");
+    gc.builder().build_store(slot, val).unwrap();
+    slot
+}
+
+/// Assert that Fix lays a value of the struct `tc` down in the LLVM type `ty` as `layout` says C
+/// does, which is what lets its bytes reach C as they stand. This is the check `CTypeSizes` makes of
+/// a scalar, made of a struct.
+fn assert_laid_out_as_c_struct<'c, 'm>(
+    tc: &Arc<TyCon>,
+    ty: BasicTypeEnum<'c>,
+    layout: &CStructLayout,
+    gc: &mut Generator<'c, 'm>,
+) {
+    assert_eq!(
+        (gc.sizeof(&ty), gc.abi_alignment(&ty)),
+        (layout.size, layout.align),
+        "Fix lays the struct `{}` down in the size and alignment C gives it",
+        tc.to_string()
+    );
+}
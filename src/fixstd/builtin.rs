@@ -1,3 +1,4 @@
+use crate::ast::export_statement::ExportedFunctionType;
 use crate::ast::{
     expr::{
         expr_abs, expr_abs_many, expr_app, expr_if, expr_let, expr_llvm, expr_make_struct,
@@ -20,17 +21,19 @@ use crate::ast::{
 use crate::constants::{
     TraverserWorkType, ARRAY_CAP_IDX, ARRAY_NAME, ARRAY_SIZE_IDX, ARRAY_STORAGE_ALLOC_SLACK,
     ARRAY_STORAGE_IDX, ARRAY_STORAGE_NAME, ARRAY_UNSAFE_EMPTY_NAME, ARROW_NAME, BOOL_NAME,
-    BOXED_TRAIT_NAME, BOXED_TYPE_DATA_IDX, CAP_NAME, CLOSURE_CAPTURE_IDX, CLOSURE_FUNPTR_IDX,
-    CONST_NAME, DESTRUCTOR_NAME, DESTRUCTOR_OBJECT_DTOR_FIELD_IDX,
+    BOXED_TRAIT_NAME, BOXED_TYPE_DATA_IDX, BOX_NAME, CAP_NAME, CLOSURE_CAPTURE_IDX,
+    CLOSURE_FUNPTR_IDX, CONST_NAME, DESTRUCTOR_NAME, DESTRUCTOR_OBJECT_DTOR_FIELD_IDX,
     DESTRUCTOR_OBJECT_VALUE_FIELD_IDX, DYNAMIC_OBJECT_NAME, F32_NAME, F64_NAME, FFI_NAME,
     FUNCTOR_NAME, FUNPTR_ARGS_MAX, FUNPTR_NAME, I16_NAME, I32_NAME, I64_NAME, I8_NAME,
     IDENTITY_NAME, IOSTATE_NAME, IO_NAME, IS_UNIQUE_VALUE_FIELD, LAZY_NAME, PTR_NAME,
-    PUNCHED_ARRAY_ARRAY_IDX, PUNCHED_ARRAY_HOLE_IDX, PUNCHED_ARRAY_NAME, STD_NAME, STORAGE_BUF_IDX,
-    STRING_NAME, STRUCT_GETTER_SYMBOL, STRUCT_PLUG_IN_FORCE_UNIQUE_SYMBOL, STRUCT_PLUG_IN_SYMBOL,
-    STRUCT_PUNCH_FORCE_UNIQUE_SYMBOL, STRUCT_PUNCH_SYMBOL, STRUCT_SETTER_SYMBOL, TUPLE_NAME,
-    TUPLE_UNBOX, U16_NAME, U32_NAME, U64_NAME, U8_NAME, UNION_DATA_IDX,
+    PUNCHED_ARRAY_ARRAY_IDX, PUNCHED_ARRAY_HOLE_IDX, PUNCHED_ARRAY_NAME, RESULT_NAME, STD_NAME,
+    STORAGE_BUF_IDX, STRING_NAME, STRUCT_GETTER_SYMBOL, STRUCT_PLUG_IN_FORCE_UNIQUE_SYMBOL,
+    STRUCT_PLUG_IN_SYMBOL, STRUCT_PUNCH_FORCE_UNIQUE_SYMBOL, STRUCT_PUNCH_SYMBOL,
+    STRUCT_SETTER_SYMBOL, TUPLE_NAME, TUPLE_UNBOX, U16_NAME, U32_NAME, U64_NAME, U8_NAME,
+    UNION_DATA_IDX,
 };
-use crate::fixstd::runtime::{RUNTIME_ABORT, RUNTIME_EPRINTLN, RUNTIME_REALLOC};
+use crate::ffi::CSignature;
+use crate::fixstd::runtime::{RcProfileEvent, RUNTIME_ABORT, RUNTIME_EPRINTLN, RUNTIME_REALLOC};
 use crate::generator::{Generator, Object};
 use crate::misc::{make_map, Map, Set};
 use crate::object::{
@@ -656,6 +659,14 @@ pub fn make_io_unit_ty() -> Arc<TypeNode> {
     type_tyapp(make_io_ty(), make_unit_ty())
 }
 
+// Make type `IO (Result ErrMsg ())`, with the alias `ErrMsg` resolved to `String`.
+pub fn make_io_result_unit_ty() -> Arc<TypeNode> {
+    let result_ty = type_tycon(&tycon(FullName::from_strs(&[STD_NAME], RESULT_NAME)));
+    let string_ty = type_tycon(&make_string_tycon());
+    let result_ty = type_tyapp(type_tyapp(result_ty, string_ty), make_unit_ty());
+    type_tyapp(make_io_ty(), result_ty)
+}
+
 // Check if given name has form `TupleN` and returns N.
 pub fn get_tuple_n(name: &FullName) -> Option<u32> {
     if name.namespace != NameSpace::from_strs(&[STD_NAME]) {
@@ -701,6 +712,13 @@ pub struct InlineLLVMIntLit {
     val: u64,
 }
 
+impl InlineLLVMIntLit {
+    /// The bits of the value, as `expr_int_lit` takes them.
+    pub fn val(&self) -> u64 {
+        self.val
+    }
+}
+
 #[typetag::serde]
 impl LLVMGen for InlineLLVMIntLit {
     fn generate<'c, 'm>(&self, gc: &mut Generator<'c, 'm>, ty: &Arc<TypeNode>) -> Object<'c> {
@@ -2524,6 +2542,7 @@ impl LLVMGen for InlineLLVMArraySetCapacityBoundsUnchecked {
         // Shared: allocate a new storage of `new_cap`, retain-copy the live elements, then drop the
         // reference to the old shared storage.
         gc.builder().position_at_end(shared_bb);
+        gc.build_profile_rc_event(RcProfileEvent::Clone);
         let len = array.extract_field(gc, ARRAY_SIZE_IDX).into_int_value();
         let new_storage = alloc_array_storage(gc, elem_ty.clone(), new_cap, CapacityCheck::Skip);
         let dst_buf = new_storage.gep_boxed(gc, STORAGE_BUF_IDX);
@@ -3278,6 +3297,7 @@ fn make_array_unique_with_hole<'c, 'm>(
     // Implement shared_bb: allocate a new storage, copy the elements into it, and drop the reference
     // to the old shared storage.
     gc.builder().position_at_end(shared_bb);
+    gc.build_profile_rc_event(RcProfileEvent::Clone);
     let size = array.extract_field(gc, ARRAY_SIZE_IDX).into_int_value();
     let cap = array.extract_field(gc, ARRAY_CAP_IDX).into_int_value();
     let new_storage = alloc_array_storage(gc, elem_ty.clone(), cap, CapacityCheck::Skip);
@@ -5916,6 +5936,7 @@ fn make_struct_union_unique<'c, 'm>(
 
     // Implement shared_bb.
     gc.builder().position_at_end(shared_bb);
+    gc.build_profile_rc_event(RcProfileEvent::Clone);
 
     // Create new object and clone fields.
     let cloned_obj = create_obj(obj.ty.clone(), &vec![], None, gc, Some("cloned_obj"));
@@ -7740,6 +7761,121 @@ pub fn get_retain_function_of_boxed_value() -> (Arc<ExprNode>, Arc<Scheme>) {
     (expr, scm)
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InlineLLVMGetCallbackFunctionOfClosureFunctionBody {
+    var_name: FullName,
+}
+
+#[typetag::serde]
+impl LLVMGen for InlineLLVMGetCallbackFunctionOfClosureFunctionBody {
+    fn generate<'c, 'm>(&self, gc: &mut Generator<'c, 'm>, _ret_ty: &Arc<TypeNode>) -> Object<'c> {
+        // Get argument
+        let arg = gc.get_scoped_obj_noretain(&self.var_name);
+
+        // Get the type of the closure, and the type of the box the context pointer points to.
+        let fun_ty = arg.ty.get_lambda_dst();
+        let box_ty = type_tyapp(
+            type_tycon(&tycon(FullName::from_strs(&[STD_NAME], BOX_NAME))),
+            fun_ty.clone(),
+        );
+        // `Program::validate_c_callbacks` has admitted the closure's type as one of an exported value.
+        let function_type = ExportedFunctionType::of_unwrapped(&fun_ty);
+
+        // Get function pointer to callback function.
+        let callback_function_name = format!("callback#{}", arg.ty.to_string_normalize());
+        let func = if let Some(func) = gc.module.get_function(&callback_function_name) {
+            func
+        } else {
+            // Define callback function, which takes the context pointer after the closure's
+            // arguments.
+            let mut signature = CSignature::of_ffi_export(&function_type, gc.type_env());
+            signature
+                .param_tys
+                .push(tycon(FullName::from_strs(&[STD_NAME], PTR_NAME)));
+            let callback_function = signature.get_or_declare_in_module(&callback_function_name, gc);
+            callback_function.set_linkage(Linkage::Internal);
+            let bb = gc.context.append_basic_block(callback_function, "entry");
+            let _builder_guard = gc.push_builder();
+            gc.builder().position_at_end(bb);
+
+            // Get the closure from the box the context pointer points to. The caller owns the box,
+            // so it is retained before its field is taken out.
+            let mut params = callback_function.get_params();
+            let ctx_ptr = params.pop().unwrap();
+            let box_obj = Object::new(ctx_ptr, box_ty, gc);
+            gc.retain(box_obj.clone(), RcState::Unknown);
+            let fun_obj =
+                ObjectFieldType::get_struct_fields(gc, &box_obj, &[0], RcState::Unknown)[0].clone();
+
+            // Call the closure and return its result.
+            function_type.build_call_and_return(fun_obj, &params, gc);
+
+            callback_function
+        };
+        let func_ptr = func.as_global_value().as_pointer_value();
+
+        let ret = create_obj(
+            make_ptr_ty(),
+            &vec![],
+            None,
+            gc,
+            Some("ret_val@get_funptr_callback"),
+        );
+        ret.insert_field(gc, 0, func_ptr)
+    }
+
+    fn name(&self) -> String {
+        format!("closure_callback_func_ptr({})", self.var_name.to_string())
+    }
+
+    fn free_vars_mut(&mut self) -> Vec<&mut FullName> {
+        vec![&mut self.var_name]
+    }
+
+    fn borrows_operand(&self, i: usize, _arg_tys: &[Arc<TypeNode>], _type_env: &TypeEnv) -> bool {
+        i == 0
+    }
+
+    fn result_locality(
+        &self,
+        result_ty: &Arc<TypeNode>,
+        arg_tys: &[Arc<TypeNode>],
+        type_env: &TypeEnv,
+    ) -> ExtShape {
+        ExtShape::fresh_holding(result_ty, arg_tys, type_env)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub fn get_callback_function_of_closure() -> (Arc<ExprNode>, Arc<Scheme>) {
+    const TARGET_TYPE_NAME: &str = "f";
+    const VAR_NAME: &str = "x";
+    let target_type = type_tyvar_star(TARGET_TYPE_NAME);
+    let arg_type = type_tyapp(make_lazy_ty(), target_type.clone());
+    let ret_type = make_ptr_ty();
+    let scm = Scheme::generalize(
+        &[],
+        vec![],
+        vec![],
+        type_fun(arg_type.clone(), ret_type.clone()),
+    );
+    let expr = expr_abs(
+        vec![var_local(VAR_NAME)],
+        expr_llvm(
+            Box::new(InlineLLVMGetCallbackFunctionOfClosureFunctionBody {
+                var_name: FullName::local(VAR_NAME),
+            }),
+            ret_type,
+            None,
+        ),
+        None,
+    );
+    (expr, scm)
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct InlineLLVMGetBoxedDataPtrFunctionBody {
     var_name: FullName,
@@ -0,0 +1,159 @@
+# Pretty-printers for the values of a Fix program, written by `fix build -g` beside the program as
+# `<program>-gdb.py`, which gdb loads with the program once its directory is on the auto-load safe
+# path (`add-auto-load-safe-path <directory>`), or by `source <program>-gdb.py`.
+#
+# The printers read the layouts the debug information describes:
+# - an `Array` value is `{_storage, <array size>, <array capacity>}`, its elements in the
+#   `<array elements>` member of the `#ArrayStorage` `_storage` points to;
+# - a `String` is an `Array U8` in its `_data` field, ending in a null terminator;
+# - a union is `{<union tag>, <union value>}`, the tag being the index of the active member of the
+#   `<union value>`, whose members are named after the variants;
+# - a boxed value is a pointer to a struct headed by a `<control block>`.
+
+import gdb
+
+CONTROL_BLOCK_NAME = "<control block>"
+ARRAY_STORAGE_NAME = "_storage"
+ARRAY_SIZE_NAME = "<array size>"
+ARRAY_ELEMENTS_NAME = "<array elements>"
+UNION_TAG_NAME = "<union tag>"
+UNION_VALUE_NAME = "<union value>"
+STRING_NAME = "Std::String"
+
+
+def field_names(ty):
+    return [field.name for field in ty.fields()]
+
+
+def is_unit(val):
+    ty = val.type.strip_typedefs()
+    return ty.code == gdb.TYPE_CODE_STRUCT and len(ty.fields()) == 0
+
+
+def is_boxed_struct(ty):
+    """Whether `ty` is the layout of a boxed value, other than the storage of an array, which is
+    shown through the `Array` value owning it."""
+    if ty.code != gdb.TYPE_CODE_STRUCT:
+        return False
+    names = field_names(ty)
+    return bool(names) and names[0] == CONTROL_BLOCK_NAME and ARRAY_ELEMENTS_NAME not in names
+
+
+def array_elements(val):
+    """The elements of the `Array` value `val`, as a list of `gdb.Value`."""
+    size = int(val[ARRAY_SIZE_NAME])
+    if size <= 0:
+        return []
+    storage = val[ARRAY_STORAGE_NAME]
+    if int(storage) == 0:
+        return []
+    buffer = storage.dereference()[ARRAY_ELEMENTS_NAME]
+    # The debug type of the buffer claims a fixed number of elements; walk the real ones by address.
+    first = buffer[0].address
+    return [(first + i).dereference() for i in range(size)]
+
+
+class ArrayPrinter:
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        return "size=%d" % int(self.val[ARRAY_SIZE_NAME])
+
+    def children(self):
+        for i, elem in enumerate(array_elements(self.val)):
+            yield ("[%d]" % i, elem)
+
+    def display_hint(self):
+        return "array"
+
+
+class StringPrinter:
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        data = self.val["_data"]
+        # The last byte of the data is the null terminator, which the string does not include.
+        size = int(data[ARRAY_SIZE_NAME]) - 1
+        if size <= 0:
+            return '""'
+        first = data[ARRAY_STORAGE_NAME].dereference()[ARRAY_ELEMENTS_NAME][0].address
+        return first.lazy_string(encoding="utf-8", length=size)
+
+    def display_hint(self):
+        return "string"
+
+
+class UnionPrinter:
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        tag = int(self.val[UNION_TAG_NAME])
+        payload = self.val[UNION_VALUE_NAME]
+        variants = payload.type.strip_typedefs().fields()
+        if tag < 0 or tag >= len(variants):
+            return "<invalid union tag %d>" % tag
+        variant = variants[tag].name
+        value = payload[variant]
+        if is_unit(value):
+            return variant
+        return "%s(%s)" % (variant, value.format_string())
+
+
+class BoxedStructPrinter:
+    """A struct behind a box, shown by its fields without its control block."""
+
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        return None
+
+    def children(self):
+        for name in field_names(self.val.type.strip_typedefs()):
+            if name != CONTROL_BLOCK_NAME:
+                yield (name, self.val[name])
+
+
+class BoxPrinter:
+    """A pointer to a boxed value, shown by the value it points to."""
+
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        if int(self.val) == 0:
+            return "<null box>"
+        return self.val.dereference().format_string()
+
+
+def lookup(val):
+    ty = val.type.strip_typedefs()
+    if ty.code == gdb.TYPE_CODE_PTR:
+        if is_boxed_struct(ty.target().strip_typedefs()):
+            return BoxPrinter(val)
+        return None
+    if ty.code != gdb.TYPE_CODE_STRUCT:
+        return None
+    names = field_names(ty)
+    if ty.name == STRING_NAME and "_data" in names:
+        return StringPrinter(val)
+    if ARRAY_STORAGE_NAME in names and ARRAY_SIZE_NAME in names:
+        return ArrayPrinter(val)
+    if UNION_TAG_NAME in names and UNION_VALUE_NAME in names:
+        return UnionPrinter(val)
+    if is_boxed_struct(ty):
+        return BoxedStructPrinter(val)
+    return None
+
+
+def register(objfile):
+    if objfile is None:
+        gdb.pretty_printers.append(lookup)
+    else:
+        objfile.pretty_printers.append(lookup)
+
+
+register(gdb.current_objfile())
@@ -0,0 +1,179 @@
+# Formatters for the values of a Fix program, written by `fix build -g` beside the program as
+# `fix_lldb.py`, which lldb loads by `command script import <directory>/fix_lldb.py`.
+#
+# The formatters read the layouts the debug information describes; see the gdb pretty-printers
+# written beside the program as `<program>-gdb.py`, which read the same ones. Selecting a Fix type
+# by its layout rather than by its name takes the recognizer functions of lldb 16 and later.
+
+import lldb
+
+CONTROL_BLOCK_NAME = "<control block>"
+ARRAY_STORAGE_NAME = "_storage"
+ARRAY_SIZE_NAME = "<array size>"
+ARRAY_ELEMENTS_NAME = "<array elements>"
+UNION_TAG_NAME = "<union tag>"
+UNION_VALUE_NAME = "<union value>"
+STRING_NAME = "Std::String"
+
+
+def field_names(sbtype):
+    return [sbtype.GetFieldAtIndex(i).GetName() for i in range(sbtype.GetNumberOfFields())]
+
+
+def is_struct(sbtype):
+    return sbtype.GetTypeClass() in (lldb.eTypeClassStruct, lldb.eTypeClassClass)
+
+
+def is_array(sbtype, internal_dict):
+    names = field_names(sbtype.GetCanonicalType())
+    return ARRAY_STORAGE_NAME in names and ARRAY_SIZE_NAME in names
+
+
+def is_union(sbtype, internal_dict):
+    names = field_names(sbtype.GetCanonicalType())
+    return UNION_TAG_NAME in names and UNION_VALUE_NAME in names
+
+
+def is_boxed_struct_type(sbtype):
+    if not is_struct(sbtype):
+        return False
+    names = field_names(sbtype)
+    return bool(names) and names[0] == CONTROL_BLOCK_NAME and ARRAY_ELEMENTS_NAME not in names
+
+
+def is_boxed_struct(sbtype, internal_dict):
+    return is_boxed_struct_type(sbtype.GetCanonicalType())
+
+
+def array_size(valobj):
+    return valobj.GetNonSyntheticValue().GetChildMemberWithName(ARRAY_SIZE_NAME).GetValueAsSigned()
+
+
+def array_first_element(valobj):
+    storage = valobj.GetNonSyntheticValue().GetChildMemberWithName(ARRAY_STORAGE_NAME)
+    if storage.GetValueAsUnsigned() == 0:
+        return None
+    buffer = storage.Dereference().GetChildMemberWithName(ARRAY_ELEMENTS_NAME)
+    return buffer.GetChildAtIndex(0)
+
+
+class ArraySynthProvider:
+    def __init__(self, valobj, internal_dict):
+        self.valobj = valobj
+        self.update()
+
+    def update(self):
+        self.size = max(array_size(self.valobj), 0)
+        self.first = array_first_element(self.valobj) if self.size > 0 else None
+        return False
+
+    def num_children(self):
+        return self.size if self.first is not None else 0
+
+    def get_child_index(self, name):
+        try:
+            return int(name.lstrip("[").rstrip("]"))
+        except ValueError:
+            return -1
+
+    def get_child_at_index(self, index):
+        if self.first is None or index < 0 or index >= self.size:
+            return None
+        elem_type = self.first.GetType()
+        # The debug type of the buffer claims a fixed number of elements; reach the real ones by
+        # address.
+        address = self.first.GetLoadAddress() + index * elem_type.GetByteSize()
+        return self.valobj.CreateValueFromAddress("[%d]" % index, address, elem_type)
+
+    def has_children(self):
+        return True
+
+
+def array_summary(valobj, internal_dict):
+    return "size=%d" % array_size(valobj)
+
+
+def string_summary(valobj, internal_dict):
+    data = valobj.GetNonSyntheticValue().GetChildMemberWithName("_data")
+    # The last byte of the data is the null terminator, which the string does not include.
+    size = array_size(data) - 1
+    if size <= 0:
+        return '""'
+    first = array_first_element(data)
+    if first is None:
+        return '""'
+    error = lldb.SBError()
+    text = valobj.GetProcess().ReadMemory(first.GetLoadAddress(), size, error)
+    if not error.Success():
+        return "<unreadable string>"
+    return '"%s"' % text.decode("utf-8", errors="replace")
+
+
+def union_summary(valobj, internal_dict):
+    valobj = valobj.GetNonSyntheticValue()
+    tag = valobj.GetChildMemberWithName(UNION_TAG_NAME).GetValueAsUnsigned()
+    payload = valobj.GetChildMemberWithName(UNION_VALUE_NAME)
+    if tag >= payload.GetNumChildren():
+        return "<invalid union tag %d>" % tag
+    value = payload.GetChildAtIndex(tag)
+    variant = value.GetName()
+    if is_struct(value.GetType().GetCanonicalType()) and value.GetNumChildren() == 0:
+        return variant
+    shown = value.GetSummary() or value.GetValue()
+    if shown is None:
+        shown = str(value).split(" = ", 1)[-1]
+    return "%s(%s)" % (variant, shown)
+
+
+class BoxedStructSynthProvider:
+    """A struct behind a box, shown by its fields without its control block."""
+
+    def __init__(self, valobj, internal_dict):
+        self.valobj = valobj
+
+    def update(self):
+        return False
+
+    def fields(self):
+        val = self.valobj.GetNonSyntheticValue()
+        return [
+            val.GetChildAtIndex(i)
+            for i in range(val.GetNumChildren())
+            if val.GetChildAtIndex(i).GetName() != CONTROL_BLOCK_NAME
+        ]
+
+    def num_children(self):
+        return len(self.fields())
+
+    def get_child_index(self, name):
+        for i, field in enumerate(self.fields()):
+            if field.GetName() == name:
+                return i
+        return -1
+
+    def get_child_at_index(self, index):
+        fields = self.fields()
+        if index < 0 or index >= len(fields):
+            return None
+        return fields[index]
+
+    def has_children(self):
+        return True
+
+
+def __lldb_init_module(debugger, internal_dict):
+    module = __name__
+    category = "fix"
+    for command in [
+        'type summary add -w %s -F %s.string_summary "%s"' % (category, module, STRING_NAME),
+        "type synthetic add -w %s -l %s.ArraySynthProvider --recognizer-function %s.is_array"
+        % (category, module, module),
+        "type summary add -w %s -F %s.array_summary --recognizer-function %s.is_array"
+        % (category, module, module),
+        "type summary add -w %s -F %s.union_summary --recognizer-function %s.is_union"
+        % (category, module, module),
+        "type synthetic add -w %s -l %s.BoxedStructSynthProvider "
+        "--recognizer-function %s.is_boxed_struct" % (category, module, module),
+        "type category enable %s" % category,
+    ]:
+        debugger.HandleCommand(command)
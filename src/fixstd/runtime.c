@@ -326,6 +326,538 @@ __attribute__((noreturn)) void fixruntime_array_size_overflow(int64_t size)
     fixruntime_abort();
 }
 
+// Whether the test named `name` is selected by the command line arguments of the test program: it is
+// when no argument is given, or when its name contains one of them.
+static int fixruntime_test_is_selected(const char *name, int32_t argc, char **argv)
+{
+    if (argc <= 1)
+    {
+        return 1;
+    }
+    for (int32_t i = 1; i < argc; i++)
+    {
+        if (strstr(name, argv[i]))
+        {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+// Runs the tests of a test build, which its `main` function hands over: `names[i]` is the name of the
+// i-th test, and `tests[i]` runs it.
+//
+// Each selected test runs in a child process of its own, so that a test ending its process, by
+// `undefined`, a failed assertion or a signal, fails that test alone. A test passes when its process
+// exits with status 0. One line is printed for each test, and then a summary.
+//
+// Returns the exit status of the test program: 0 when every selected test passes, and 1 otherwise.
+int32_t fixruntime_run_tests(int32_t argc, char **argv, int64_t count, const char **names, void (**tests)(void))
+{
+    int64_t selected = 0;
+    for (int64_t i = 0; i < count; i++)
+    {
+        selected += fixruntime_test_is_selected(names[i], argc, argv);
+    }
+    printf("running %" PRId64 " test%s\n", selected, selected == 1 ? "" : "s");
+    fflush(stdout);
+
+    const char **failures = (const char **)malloc(sizeof(const char *) * (count > 0 ? count : 1));
+    int64_t passed = 0;
+    int64_t failed = 0;
+    for (int64_t i = 0; i < count; i++)
+    {
+        if (!fixruntime_test_is_selected(names[i], argc, argv))
+        {
+            continue;
+        }
+        char outcome[64];
+        strcpy(outcome, "ok");
+#ifndef __MINGW32__
+        // Flush before forking, so that what is buffered is not written twice.
+        fflush(stdout);
+        fflush(stderr);
+        pid_t pid = fork();
+        if (pid == 0)
+        {
+            tests[i]();
+            exit(0);
+        }
+        int status = 0;
+        if (pid < 0)
+        {
+            snprintf(outcome, sizeof outcome, "FAILED (could not start: %s)", strerror(errno));
+        }
+        else
+        {
+            while (waitpid(pid, &status, 0) < 0 && errno == EINTR)
+            {
+            }
+            if (WIFEXITED(status) && WEXITSTATUS(status) != 0)
+            {
+                snprintf(outcome, sizeof outcome, "FAILED (exit status %d)", WEXITSTATUS(status));
+            }
+            else if (WIFSIGNALED(status))
+            {
+                snprintf(outcome, sizeof outcome, "FAILED (signal %d)", WTERMSIG(status));
+            }
+        }
+#else
+        // No `fork` here: the test runs in this process, and one ending the process ends the run.
+        tests[i]();
+#endif
+        fflush(stdout);
+        fflush(stderr);
+        if (strcmp(outcome, "ok") == 0)
+        {
+            passed++;
+        }
+        else
+        {
+            failures[failed++] = names[i];
+        }
+        printf("test %s ... %s\n", names[i], outcome);
+        fflush(stdout);
+    }
+
+    if (failed > 0)
+    {
+        printf("\nfailures:\n");
+        for (int64_t i = 0; i < failed; i++)
+        {
+            printf("    %s\n", failures[i]);
+        }
+    }
+    printf("\ntest result: %s. %" PRId64 " passed; %" PRId64 " failed; %" PRId64 " filtered out\n",
+           failed > 0 ? "FAILED" : "ok", passed, failed, count - selected);
+    fflush(stdout);
+    free(failures);
+    return failed > 0 ? 1 : 0;
+}
+
+// A setting of the benchmark program: the number the environment variable `name` holds, or
+// `default_value` when it holds none, or something other than a non-negative number.
+static double fixruntime_bench_setting(const char *name, double default_value)
+{
+    const char *value = getenv(name);
+    if (!value)
+    {
+        return default_value;
+    }
+    char *end;
+    double v = strtod(value, &end);
+    if (end == value || *end != '\0' || !(v >= 0))
+    {
+        return default_value;
+    }
+    return v;
+}
+
+// Append `line` to the file at `path`, or write it to stdout when `path` is NULL.
+static void fixruntime_bench_write_result(const char *path, const char *line)
+{
+    FILE *out = path ? fopen(path, "a") : stdout;
+    if (!out)
+    {
+        fprintf(stderr, "Failed to open \"%s\": %s\n", path, strerror(errno));
+        exit(1);
+    }
+    fputs(line, out);
+    fflush(out);
+    if (path)
+    {
+        fclose(out);
+    }
+}
+
+// Measure the benchmark `bench` in the calling process, by the processor time `fixruntime_clock`
+// gives. It is run over and over for `warm_up` seconds first, which also shows how long one run
+// takes. Then `samples` samples are timed, each of as many runs as fill its share of `measurement`
+// seconds, and one line is written to the results: "ok", the name, the number of runs a sample
+// timed, and the seconds one run took in each sample, separated by tabs.
+static void fixruntime_measure_bench(const char *results_path, const char *name, void (*bench)(void),
+                                     double warm_up, double measurement, int64_t samples)
+{
+    int64_t runs = 0;
+    int64_t start = fixruntime_clock();
+    double elapsed;
+    do
+    {
+        bench();
+        runs++;
+        elapsed = fixruntime_clocks_to_sec(fixruntime_clock() - start);
+    } while (elapsed < warm_up);
+    double per_run = elapsed / runs;
+    int64_t runs_per_sample = 1;
+    if (per_run > 0 && measurement / samples / per_run > 1)
+    {
+        runs_per_sample = (int64_t)(measurement / samples / per_run);
+    }
+
+    // The line is formed once every sample is taken, so that what the benchmark prints itself does
+    // not land in the middle of it.
+    size_t size = strlen(name) + 64 + 32 * samples;
+    char *line = (char *)malloc(size);
+    if (!line)
+    {
+        fprintf(stderr, "Failed to allocate the result of benchmark \"%s\".\n", name);
+        exit(1);
+    }
+    int len = snprintf(line, size, "ok\t%s\t%" PRId64, name, runs_per_sample);
+    for (int64_t i = 0; i < samples; i++)
+    {
+        int64_t t = fixruntime_clock();
+        for (int64_t j = 0; j < runs_per_sample; j++)
+        {
+            bench();
+        }
+        double seconds = fixruntime_clocks_to_sec(fixruntime_clock() - t);
+        len += snprintf(line + len, size - len, "\t%.9e", seconds / runs_per_sample);
+    }
+    snprintf(line + len, size - len, "\n");
+    fixruntime_bench_write_result(results_path, line);
+    free(line);
+}
+
+// Measures the benchmarks of a benchmark build, which its `main` function hands over as
+// `fixruntime_run_tests` is handed the tests: `names[i]` is the name of the i-th benchmark, and
+// `benches[i]` runs it once.
+//
+// The environment variables `FIX_BENCH_WARM_UP_TIME` and `FIX_BENCH_MEASUREMENT_TIME` give the
+// seconds each benchmark is warmed up and measured for, 1 and 3 unless they say otherwise, and
+// `FIX_BENCH_SAMPLES` the number of samples taken, 30 unless it says otherwise. The times measured
+// are written to the file `FIX_BENCH_RESULTS` names, which `fix bench` reads them from, or to
+// stdout. A benchmark that fails is written there as "failed", its name and the outcome.
+//
+// Each selected benchmark is measured in a child process of its own, so that a benchmark ending its
+// process fails that benchmark alone, and one line is printed for each.
+//
+// Returns the exit status of the benchmark program: 0 when every selected benchmark was measured,
+// and 1 otherwise.
+int32_t fixruntime_run_benches(int32_t argc, char **argv, int64_t count, const char **names, void (**benches)(void))
+{
+    double warm_up = fixruntime_bench_setting("FIX_BENCH_WARM_UP_TIME", 1.0);
+    double measurement = fixruntime_bench_setting("FIX_BENCH_MEASUREMENT_TIME", 3.0);
+    int64_t samples = (int64_t)fixruntime_bench_setting("FIX_BENCH_SAMPLES", 30.0);
+    if (samples < 1)
+    {
+        samples = 1;
+    }
+    const char *results_path = getenv("FIX_BENCH_RESULTS");
+
+    int64_t selected = 0;
+    for (int64_t i = 0; i < count; i++)
+    {
+        selected += fixruntime_test_is_selected(names[i], argc, argv);
+    }
+    printf("running %" PRId64 " benchmark%s\n", selected, selected == 1 ? "" : "s");
+    fflush(stdout);
+
+    int64_t failed = 0;
+    for (int64_t i = 0; i < count; i++)
+    {
+        if (!fixruntime_test_is_selected(names[i], argc, argv))
+        {
+            continue;
+        }
+        char outcome[64];
+        strcpy(outcome, "ok");
+#ifndef __MINGW32__
+        // Flush before forking, so that what is buffered is not written twice.
+        fflush(stdout);
+        fflush(stderr);
+        pid_t pid = fork();
+        if (pid == 0)
+        {
+            fixruntime_measure_bench(results_path, names[i], benches[i], warm_up, measurement, samples);
+            exit(0);
+        }
+        int status = 0;
+        if (pid < 0)
+        {
+            snprintf(outcome, sizeof outcome, "FAILED (could not start: %s)", strerror(errno));
+        }
+        else
+        {
+            while (waitpid(pid, &status, 0) < 0 && errno == EINTR)
+            {
+            }
+            if (WIFEXITED(status) && WEXITSTATUS(status) != 0)
+            {
+                snprintf(outcome, sizeof outcome, "FAILED (exit status %d)", WEXITSTATUS(status));
+            }
+            else if (WIFSIGNALED(status))
+            {
+                snprintf(outcome, sizeof outcome, "FAILED (signal %d)", WTERMSIG(status));
+            }
+        }
+#else
+        // No `fork` here: the benchmark runs in this process, and one ending the process ends the run.
+        fixruntime_measure_bench(results_path, names[i], benches[i], warm_up, measurement, samples);
+#endif
+        fflush(stdout);
+        fflush(stderr);
+        if (strcmp(outcome, "ok") != 0)
+        {
+            failed++;
+            size_t size = strlen(names[i]) + sizeof(outcome) + 16;
+            char *line = (char *)malloc(size);
+            if (!line)
+            {
+                fprintf(stderr, "Failed to allocate the result of benchmark \"%s\".\n", names[i]);
+                exit(1);
+            }
+            snprintf(line, size, "failed\t%s\t%s\n", names[i], outcome);
+            fixruntime_bench_write_result(results_path, line);
+            free(line);
+        }
+        printf("bench %s ... %s\n", names[i], outcome);
+        fflush(stdout);
+    }
+    return failed > 0 ? 1 : 0;
+}
+
+// Run `entry` in a process of its own, as `fixruntime_run_tests` runs a test, and return the status the
+// process ended with: its exit status, or 128 plus the number of the signal that ended it.
+// `fix repl` runs each input in the compiler's own process through this, so that an input ending its
+// process, by `exit` or by a failed check, ends the session for that input alone.
+int32_t fixruntime_run_isolated(void (*entry)(void))
+{
+#ifndef __MINGW32__
+    // Flush before forking, so that what is buffered is not written twice.
+    fflush(stdout);
+    fflush(stderr);
+    pid_t pid = fork();
+    if (pid == 0)
+    {
+        entry();
+        fflush(stdout);
+        fflush(stderr);
+        // `_exit`, not `exit`: what the calling process registered to run at its exit is its own.
+        _exit(0);
+    }
+    if (pid < 0)
+    {
+        fprintf(stderr, "Failed to start a process: %s\n", strerror(errno));
+        return 1;
+    }
+    int status = 0;
+    while (waitpid(pid, &status, 0) < 0 && errno == EINTR)
+    {
+    }
+    if (WIFSIGNALED(status))
+    {
+        return 128 + WTERMSIG(status);
+    }
+    return WIFEXITED(status) ? WEXITSTATUS(status) : 1;
+#else
+    // No `fork` here: the input runs in this process, and one ending the process ends the session.
+    entry();
+    fflush(stdout);
+    fflush(stderr);
+    return 0;
+#endif
+}
+
+#if defined(PROFILE_RC)
+// The reference-count profile of a program built with `--profile=rc`. The generated code calls
+// `fixruntime_profile_rc` at each event it counts, passing the source location of the event as a
+// string constant, and the counts are kept per location. At exit, the locations are ranked by the
+// number of events and the report is written to the file `FIX_PROFILE_RC_OUTPUT` names, or to
+// `fix-profile-rc.txt` in the working directory.
+
+// The events, as `RcProfileEvent` in the compiler numbers them.
+#define FIXRUNTIME_PROFILE_RC_ALLOC 0
+#define FIXRUNTIME_PROFILE_RC_RETAIN 1
+#define FIXRUNTIME_PROFILE_RC_RELEASE 2
+#define FIXRUNTIME_PROFILE_RC_CLONE 3
+#define FIXRUNTIME_PROFILE_RC_EVENT_COUNT 4
+
+// The number of locations the table holds. The events at a location arriving after it is full are
+// counted against `fixruntime_profile_rc_overflow`.
+#define FIXRUNTIME_PROFILE_RC_TABLE_SIZE 65536
+// The number of locations the report lists, unless `FIX_PROFILE_RC_TOP` says otherwise.
+#define FIXRUNTIME_PROFILE_RC_DEFAULT_TOP 20
+
+typedef struct
+{
+    const char *site;
+    uint64_t counts[FIXRUNTIME_PROFILE_RC_EVENT_COUNT];
+} fixruntime_profile_rc_entry;
+
+// Keyed by the address of the location string. Each module of the program emits its own constant for
+// a location, so one location may take several entries, which the report merges.
+static fixruntime_profile_rc_entry fixruntime_profile_rc_table[FIXRUNTIME_PROFILE_RC_TABLE_SIZE];
+static fixruntime_profile_rc_entry fixruntime_profile_rc_overflow = {"<other locations>", {0}};
+static int64_t fixruntime_profile_rc_used = 0;
+static char fixruntime_profile_rc_lock = 0;
+static int fixruntime_profile_rc_registered = 0;
+
+static uint64_t fixruntime_profile_rc_entry_total(const fixruntime_profile_rc_entry *entry)
+{
+    uint64_t total = 0;
+    for (int i = 0; i < FIXRUNTIME_PROFILE_RC_EVENT_COUNT; i++)
+    {
+        total += entry->counts[i];
+    }
+    return total;
+}
+
+static int fixruntime_profile_rc_by_site(const void *lhs, const void *rhs)
+{
+    return strcmp(((const fixruntime_profile_rc_entry *)lhs)->site, ((const fixruntime_profile_rc_entry *)rhs)->site);
+}
+
+// Orders the locations by their number of events, the most first, and then by the location, so that
+// the report of a run is the same every time.
+static int fixruntime_profile_rc_by_total(const void *lhs, const void *rhs)
+{
+    const fixruntime_profile_rc_entry *l = (const fixruntime_profile_rc_entry *)lhs;
+    const fixruntime_profile_rc_entry *r = (const fixruntime_profile_rc_entry *)rhs;
+    uint64_t lt = fixruntime_profile_rc_entry_total(l);
+    uint64_t rt = fixruntime_profile_rc_entry_total(r);
+    if (lt != rt)
+    {
+        return lt > rt ? -1 : 1;
+    }
+    return strcmp(l->site, r->site);
+}
+
+static void fixruntime_profile_rc_write_row(FILE *out, int64_t rank, const fixruntime_profile_rc_entry *entry)
+{
+    fprintf(out, "%4" PRId64 "  %12" PRIu64 "  %12" PRIu64 "  %12" PRIu64 "  %12" PRIu64 "  %12" PRIu64 "  %s\n",
+            rank, fixruntime_profile_rc_entry_total(entry),
+            entry->counts[FIXRUNTIME_PROFILE_RC_ALLOC], entry->counts[FIXRUNTIME_PROFILE_RC_RETAIN],
+            entry->counts[FIXRUNTIME_PROFILE_RC_RELEASE], entry->counts[FIXRUNTIME_PROFILE_RC_CLONE],
+            entry->site);
+}
+
+static void fixruntime_profile_rc_report(void)
+{
+    // Gather the locations, merging the entries that name one location.
+    int64_t count = 0;
+    fixruntime_profile_rc_entry *entries = (fixruntime_profile_rc_entry *)malloc(
+        sizeof(fixruntime_profile_rc_entry) * (fixruntime_profile_rc_used + 1));
+    for (int64_t i = 0; i < FIXRUNTIME_PROFILE_RC_TABLE_SIZE; i++)
+    {
+        if (fixruntime_profile_rc_table[i].site)
+        {
+            entries[count++] = fixruntime_profile_rc_table[i];
+        }
+    }
+    if (fixruntime_profile_rc_entry_total(&fixruntime_profile_rc_overflow) > 0)
+    {
+        entries[count++] = fixruntime_profile_rc_overflow;
+    }
+    qsort(entries, count, sizeof(fixruntime_profile_rc_entry), fixruntime_profile_rc_by_site);
+    int64_t merged = 0;
+    for (int64_t i = 0; i < count; i++)
+    {
+        if (merged > 0 && strcmp(entries[merged - 1].site, entries[i].site) == 0)
+        {
+            for (int e = 0; e < FIXRUNTIME_PROFILE_RC_EVENT_COUNT; e++)
+            {
+                entries[merged - 1].counts[e] += entries[i].counts[e];
+            }
+        }
+        else
+        {
+            entries[merged++] = entries[i];
+        }
+    }
+    count = merged;
+    qsort(entries, count, sizeof(fixruntime_profile_rc_entry), fixruntime_profile_rc_by_total);
+
+    fixruntime_profile_rc_entry total = {"<total>", {0}};
+    for (int64_t i = 0; i < count; i++)
+    {
+        for (int e = 0; e < FIXRUNTIME_PROFILE_RC_EVENT_COUNT; e++)
+        {
+            total.counts[e] += entries[i].counts[e];
+        }
+    }
+
+    int64_t top = FIXRUNTIME_PROFILE_RC_DEFAULT_TOP;
+    const char *top_env = getenv("FIX_PROFILE_RC_TOP");
+    if (top_env && atoll(top_env) > 0)
+    {
+        top = atoll(top_env);
+    }
+    const char *path = getenv("FIX_PROFILE_RC_OUTPUT");
+    if (!path || !*path)
+    {
+        path = "fix-profile-rc.txt";
+    }
+    FILE *out = fopen(path, "w");
+    if (!out)
+    {
+        fprintf(stderr, "Failed to write the reference-count profile to \"%s\": %s\n", path, strerror(errno));
+        free(entries);
+        return;
+    }
+    fprintf(out, "Reference-count profile: %" PRIu64 " allocations, %" PRIu64 " retains, %" PRIu64 " releases, %" PRIu64 " force-unique clones at %" PRId64 " locations.\n",
+            total.counts[FIXRUNTIME_PROFILE_RC_ALLOC], total.counts[FIXRUNTIME_PROFILE_RC_RETAIN],
+            total.counts[FIXRUNTIME_PROFILE_RC_RELEASE], total.counts[FIXRUNTIME_PROFILE_RC_CLONE], count);
+    fprintf(out, "\nTop locations by events:\n");
+    fprintf(out, "%4s  %12s  %12s  %12s  %12s  %12s  %s\n", "rank", "events", "allocs", "retains", "releases", "clones", "location");
+    for (int64_t i = 0; i < count && i < top; i++)
+    {
+        fixruntime_profile_rc_write_row(out, i + 1, &entries[i]);
+    }
+
+    // A force-unique clone copies a whole value, so the locations cloning are listed on their own
+    // even where their other events are few.
+    if (total.counts[FIXRUNTIME_PROFILE_RC_CLONE] > 0)
+    {
+        fprintf(out, "\nLocations cloning a shared value to modify it:\n");
+        fprintf(out, "%4s  %12s  %12s  %12s  %12s  %12s  %s\n", "rank", "events", "allocs", "retains", "releases", "clones", "location");
+        int64_t rank = 0;
+        for (int64_t i = 0; i < count && rank < top; i++)
+        {
+            if (entries[i].counts[FIXRUNTIME_PROFILE_RC_CLONE] > 0)
+            {
+                fixruntime_profile_rc_write_row(out, ++rank, &entries[i]);
+            }
+        }
+    }
+    fclose(out);
+    free(entries);
+    fprintf(stderr, "Reference-count profile written to \"%s\".\n", path);
+}
+
+void fixruntime_profile_rc(int32_t event, const char *site)
+{
+    while (__atomic_test_and_set(&fixruntime_profile_rc_lock, __ATOMIC_ACQUIRE))
+    {
+    }
+    if (!fixruntime_profile_rc_registered)
+    {
+        fixruntime_profile_rc_registered = 1;
+        atexit(fixruntime_profile_rc_report);
+    }
+    uint64_t slot = ((uintptr_t)site >> 3) * 0x9E3779B97F4A7C15ull % FIXRUNTIME_PROFILE_RC_TABLE_SIZE;
+    fixruntime_profile_rc_entry *entry = &fixruntime_profile_rc_overflow;
+    for (int64_t probe = 0; probe < FIXRUNTIME_PROFILE_RC_TABLE_SIZE; probe++)
+    {
+        fixruntime_profile_rc_entry *candidate = &fixruntime_profile_rc_table[(slot + probe) % FIXRUNTIME_PROFILE_RC_TABLE_SIZE];
+        if (candidate->site == site)
+        {
+            entry = candidate;
+            break;
+        }
+        if (!candidate->site)
+        {
+            candidate->site = site;
+            fixruntime_profile_rc_used++;
+            entry = candidate;
+            break;
+        }
+    }
+    entry->counts[event]++;
+    __atomic_clear(&fixruntime_profile_rc_lock, __ATOMIC_RELEASE);
+}
+#endif // PROFILE_RC
+
 // void fixruntime_union_variant_mismatch(uint8_t expected, uint8_t actual)
 // {
 //     fprintf(stderr, "Union variant mismatch: expected=%" PRIu8 ", actual=%" PRIu8 "\n", expected, actual);
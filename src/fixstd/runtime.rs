@@ -1,4 +1,4 @@
-use crate::configuration::OutputFileType;
+use crate::configuration::{OutputFileType, Profile};
 use crate::constants::{C_ENTRY_POINT_NAME, GLOBAL_VAR_NAME_ARGC, GLOBAL_VAR_NAME_ARGV};
 use crate::generator::Generator;
 use inkwell::attributes::AttributeLoc;
@@ -33,6 +33,18 @@ pub const RUNTIME_PTHREAD_ONCE: &str = "pthread_once";
 pub const RUNTIME_GET_ARGC: &str = "fixruntime_get_argc";
 /// The runtime function giving the command line argument at an index, as a C string.
 pub const RUNTIME_GET_ARGV: &str = "fixruntime_get_argv";
+/// The runtime function the entry point of a test build hands the tests to. It takes `argc` and
+/// `argv`, the number of tests, and two arrays giving the name of each test and the function running
+/// it; it runs the tests the arguments select, each in a process of its own, and returns the exit
+/// status of the test program.
+pub const RUNTIME_RUN_TESTS: &str = "fixruntime_run_tests";
+/// The runtime function the entry point of a benchmark build hands the benchmarks to. It takes what
+/// `RUNTIME_RUN_TESTS` takes; it measures the benchmarks the arguments select, each in a process of
+/// its own, writes the times it measured, and returns the exit status of the benchmark program.
+pub const RUNTIME_RUN_BENCHES: &str = "fixruntime_run_benches";
+/// The runtime function the entry point of a `fix repl` input hands the function running the input
+/// to. It runs the function in a process of its own and returns the status that process ended with.
+pub const RUNTIME_RUN_ISOLATED: &str = "fixruntime_run_isolated";
 /// libc `malloc`, declared with a 64-bit size parameter.
 ///
 /// We declare it ourselves rather than using inkwell's `build_malloc` /
@@ -46,6 +58,25 @@ pub const RUNTIME_MALLOC: &str = "malloc";
 /// growing a uniquely owned array's capacity avoids copying its elements.
 pub const RUNTIME_REALLOC: &str = "realloc";
 
+/// The runtime function a program built with `--profile=rc` calls at each event it counts. It takes
+/// the `RcProfileEvent` as an i32 and the source location the event is performed at, as a C string
+/// the runtime groups the counts by; see `Generator::build_profile_rc_event`.
+pub const RUNTIME_PROFILE_RC: &str = "fixruntime_profile_rc";
+
+/// An event a program built with `--profile=rc` counts. The discriminants are the values
+/// `fixruntime_profile_rc` receives, which the runtime's `FIXRUNTIME_PROFILE_RC_*` constants mirror.
+#[derive(Clone, Copy)]
+pub enum RcProfileEvent {
+    /// A boxed object or an array's storage is allocated.
+    Alloc = 0,
+    /// A `Retain` of the RC IR is performed.
+    Retain = 1,
+    /// A `Release` of the RC IR is performed.
+    Release = 2,
+    /// A value found shared where it was to be modified in place is cloned.
+    Clone = 3,
+}
+
 /// The prefix under which the compiler names the runtime's own functions, and the globals holding
 /// `argc` and `argv`.
 pub const RUNTIME_NAME_PREFIX: &str = "fixruntime_";
@@ -66,7 +97,7 @@ pub const RUNTIME_NAME_PREFIX: &str = "fixruntime_";
 ///
 /// # Arguments
 /// * `output` — what is being built. The entry point is written into an executable alone, so a
-///   dynamic library is free to carry a `main` of its own.
+///   library is free to carry a `main` of its own.
 pub fn compiler_defined_c_function_reason(name: &str, output: OutputFileType) -> Option<String> {
     if name == C_ENTRY_POINT_NAME && output == OutputFileType::Executable {
         return Some(
@@ -104,8 +135,13 @@ pub fn build_runtime<'c, 'm, 'b>(gc: &mut Generator<'c, 'm>, mode: BuildMode) {
     }
     build_get_argc_function(gc, mode);
     build_get_argv_function(gc, mode);
+    build_run_tests_function(gc, mode);
+    build_run_isolated_function(gc, mode);
     build_malloc_function(gc, mode);
     build_realloc_function(gc, mode);
+    if gc.config.profile == Profile::Rc {
+        build_profile_rc_function(gc, mode);
+    }
 }
 
 /// Which part of a runtime function a call in `build_runtime` emits.
@@ -195,6 +231,53 @@ fn build_eprintf_function<'c, 'm, 'b>(gc: &Generator<'c, 'm>, mode: BuildMode) {
     return;
 }
 
+/// Declare `fixruntime_run_tests`, which runs the tests of a test build and returns the exit status
+/// of the test program, and `fixruntime_run_benches`, which does the same for the benchmarks of a
+/// benchmark build.
+fn build_run_tests_function<'c, 'm, 'b>(gc: &Generator<'c, 'm>, mode: BuildMode) {
+    if mode != BuildMode::Declare {
+        return;
+    }
+    if let Some(_func) = gc.module.get_function(RUNTIME_RUN_TESTS) {
+        return;
+    }
+
+    let context = gc.context;
+    let ptr_ty = context.ptr_type(AddressSpace::from(0));
+    let i32_ty = context.i32_type();
+
+    let fn_ty = i32_ty.fn_type(
+        &[
+            i32_ty.into(),             /* argc */
+            ptr_ty.into(),             /* argv */
+            context.i64_type().into(), /* number of tests */
+            ptr_ty.into(),             /* names of the tests */
+            ptr_ty.into(),             /* functions running the tests */
+        ],
+        false,
+    );
+    gc.module.add_function(RUNTIME_RUN_TESTS, fn_ty, None);
+    gc.module.add_function(RUNTIME_RUN_BENCHES, fn_ty, None);
+}
+
+/// Declare `fixruntime_run_isolated`, which runs a function taking and returning nothing in a process
+/// of its own and returns the status that process ended with.
+fn build_run_isolated_function<'c, 'm, 'b>(gc: &Generator<'c, 'm>, mode: BuildMode) {
+    if mode != BuildMode::Declare {
+        return;
+    }
+    if let Some(_func) = gc.module.get_function(RUNTIME_RUN_ISOLATED) {
+        return;
+    }
+
+    let context = gc.context;
+    let ptr_ty = context.ptr_type(AddressSpace::from(0));
+    let fn_ty = context
+        .i32_type()
+        .fn_type(&[ptr_ty.into() /* the function to run */], false);
+    gc.module.add_function(RUNTIME_RUN_ISOLATED, fn_ty, None);
+}
+
 /// Declare `sprintf`, which takes the output buffer and the format string and goes on to take the
 /// values the format names.
 fn build_sprintf_function<'c, 'm, 'b>(gc: &Generator<'c, 'm>, mode: BuildMode) {
@@ -290,6 +373,26 @@ fn build_ptr_add_offset_function<'c, 'm, 'b>(gc: &mut Generator<'c, 'm>, mode: B
     return;
 }
 
+/// Declare `fixruntime_profile_rc`, which counts an event of a program built with `--profile=rc`
+/// against the source location it is performed at.
+fn build_profile_rc_function<'c, 'm>(gc: &Generator<'c, 'm>, mode: BuildMode) {
+    if mode != BuildMode::Declare {
+        return;
+    }
+    if gc.module.get_function(RUNTIME_PROFILE_RC).is_some() {
+        return;
+    }
+    let ptr_ty = gc.context.ptr_type(AddressSpace::from(0));
+    let fn_ty = gc.context.void_type().fn_type(
+        &[
+            gc.context.i32_type().into(), /* event */
+            ptr_ty.into(),                /* source location */
+        ],
+        false,
+    );
+    gc.module.add_function(RUNTIME_PROFILE_RC, fn_ty, None);
+}
+
 /// Declare `pthread_once`, which takes the flag recording whether the initializer has run and the
 /// initializer itself. A multi-threaded program initializes each global through it.
 pub fn build_pthread_once_function<'c, 'm, 'b>(gc: &mut Generator<'c, 'm>, mode: BuildMode) {
@@ -462,7 +565,7 @@ fn build_realloc_function<'c, 'm, 'b>(gc: &Generator<'c, 'm>, mode: BuildMode) {
 mod tests {
     use super::*;
 
-    /// The compiler writes the entry point into an executable alone, so a dynamic library is free to
+    /// The compiler writes the entry point into an executable alone, so a library is free to
     /// carry a `main` of its own, while the runtime's own names are the compiler's whatever is being
     /// built and the C library functions it merely calls are the program's either way.
     #[test]
@@ -476,7 +579,16 @@ mod tests {
             OutputFileType::DynamicLibrary
         )
         .is_none());
-        for output in [OutputFileType::Executable, OutputFileType::DynamicLibrary] {
+        assert!(compiler_defined_c_function_reason(
+            C_ENTRY_POINT_NAME,
+            OutputFileType::StaticLibrary
+        )
+        .is_none());
+        for output in [
+            OutputFileType::Executable,
+            OutputFileType::DynamicLibrary,
+            OutputFileType::StaticLibrary,
+        ] {
             assert!(compiler_defined_c_function_reason(RUNTIME_ABORT, output).is_some());
             assert!(compiler_defined_c_function_reason(RUNTIME_GET_ARGC, output).is_some());
             assert!(compiler_defined_c_function_reason(RUNTIME_MALLOC, output).is_none());
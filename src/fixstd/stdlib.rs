@@ -13,8 +13,8 @@ use crate::{
     constants::{
         ARRAY_CHECK_RANGE, ARRAY_CHECK_SIZE, ARRAY_NAME, ARRAY_UNSAFE_EMPTY_NAME,
         ARRAY_UNSAFE_GET_BOUNDS_UNCHECKED, DESTRUCTOR_NAME, F32_NAME, F64_NAME, FFI_NAME,
-        HOLE_NAME, IOSTATE_NAME, IO_NAME, MARK_THREADED_NAME, PUNCHED_ARRAY_NAME, STD_NAME,
-        WITH_RETAINED_NAME,
+        GET_FUNPTR_CALLBACK_NAME, HOLE_NAME, IOSTATE_NAME, IO_NAME, MARK_THREADED_NAME,
+        PUNCHED_ARRAY_NAME, STD_NAME, WITH_RETAINED_NAME,
     },
     error::Errors,
     fixstd::builtin::{
@@ -29,18 +29,19 @@ use crate::{
         cast_between_integral_function, cast_float_to_int_function, cast_int_to_float_function,
         destructor_make, divide_trait_instance_float, divide_trait_instance_int,
         eq_trait_instance_float, eq_trait_instance_int, eq_trait_instance_ptr, fix, floating_types,
-        get_get_boxed_ptr, get_mutate_boxed_internal, get_mutate_boxed_ios_internal, get_ptr_array,
-        get_release_function_of_boxed_value, get_retain_function_of_boxed_value, grow_size_array,
-        hole_function, infinity_value, integral_types, is_unique_function,
-        less_than_or_equal_to_trait_instance_float, less_than_or_equal_to_trait_instance_int,
-        less_than_trait_instance_float, less_than_trait_instance_int, make_bool_ty,
-        make_dynamic_object_ty, make_floating_ty, make_integral_ty, make_iostate_unsafe_create,
-        make_ptr_ty, mark_threaded_function, multiply_trait_instance_float,
-        multiply_trait_instance_int, negate_trait_instance_float, negate_trait_instance_int,
-        not_trait_instance_bool, punched_array_plug, quiet_nan_value, remainder_trait_instance_int,
-        set_array, shift_function, subtract_trait_instance_float, subtract_trait_instance_int,
-        swap_array, swap_bounds_unchecked_array, undefined_internal_function,
-        unsafe_set_bounds_unchecked_array, with_retained_function, BitOperationType,
+        get_callback_function_of_closure, get_get_boxed_ptr, get_mutate_boxed_internal,
+        get_mutate_boxed_ios_internal, get_ptr_array, get_release_function_of_boxed_value,
+        get_retain_function_of_boxed_value, grow_size_array, hole_function, infinity_value,
+        integral_types, is_unique_function, less_than_or_equal_to_trait_instance_float,
+        less_than_or_equal_to_trait_instance_int, less_than_trait_instance_float,
+        less_than_trait_instance_int, make_bool_ty, make_dynamic_object_ty, make_floating_ty,
+        make_integral_ty, make_iostate_unsafe_create, make_ptr_ty, mark_threaded_function,
+        multiply_trait_instance_float, multiply_trait_instance_int, negate_trait_instance_float,
+        negate_trait_instance_int, not_trait_instance_bool, punched_array_plug, quiet_nan_value,
+        remainder_trait_instance_int, set_array, shift_function, subtract_trait_instance_float,
+        subtract_trait_instance_int, swap_array, swap_bounds_unchecked_array,
+        undefined_internal_function, unsafe_set_bounds_unchecked_array, with_retained_function,
+        BitOperationType,
     },
     misc::{make_map, upper_camel_to_lower_snake, Map},
     parse::parser::parse_and_save_to_temporary_file,
@@ -645,6 +646,13 @@ pub fn make_std_mod(config: &Configuration) -> Result<Program, Errors> {
         None,
         Some(include_str!("../docs/std_ffi_get_funptr_retain.md").to_string()),
     ));
+    errors.eat_err(fix_module.add_global_value(
+        FullName::from_strs(&[STD_NAME, FFI_NAME], GET_FUNPTR_CALLBACK_NAME),
+        get_callback_function_of_closure(),
+        None,
+        None,
+        Some(include_str!("../docs/std_ffi_get_funptr_callback.md").to_string()),
+    ));
     errors.eat_err(fix_module.add_global_value(
         FullName::from_strs(&[STD_NAME, FFI_NAME], "_get_boxed_ptr"),
         get_get_boxed_ptr(),
@@ -8,7 +8,7 @@ use crate::ast::program::TypeEnv;
 use crate::ast::types::type_tycon;
 use crate::ast::types::TyCon;
 use crate::ast::types::TypeNode;
-use crate::configuration::Configuration;
+use crate::configuration::{Configuration, Profile};
 use crate::constants::RefcntState;
 use crate::constants::TraverserWorkType;
 use crate::constants::CLOSURE_CAPTURE_IDX;
@@ -22,11 +22,13 @@ use crate::constants::DYNAMIC_OBJ_TRAVARSER_IDX;
 use crate::constants::SYMBOL_VERSION_SEPARATOR;
 use crate::constants::SYMBOL_VERSION_SEPARATOR_SUBSTITUTE;
 use crate::error::panic_with_msg;
+use crate::ffi::c_struct_value_type;
 use crate::ffi::CSignature;
 use crate::fixstd::builtin::make_dynamic_object_ty;
 use crate::fixstd::builtin::run_io_or_ios_runner;
 use crate::fixstd::runtime::RUNTIME_ABORT;
 use crate::fixstd::runtime::RUNTIME_EPRINTLN;
+use crate::fixstd::runtime::{RcProfileEvent, RUNTIME_PROFILE_RC};
 use crate::misc::flatten_opt;
 use crate::misc::Map;
 use crate::object::build_free_boxed;
@@ -48,7 +50,6 @@ use crate::return_abi::{
     lambda_calling_convention_of_target, return_registers_of_target, returns_through_out_pointer,
     ReturnRegisters,
 };
-use either::Either;
 use either::Either::Left;
 use either::Either::Right;
 use inkwell::builder::Builder;
@@ -2562,6 +2563,26 @@ impl<'c, 'm> Generator<'c, 'm> {
         self.set_debug_location(span);
     }
 
+    /// Counts `event` against the source location code is being generated for, in a program built
+    /// with `--profile=rc`; in any other build this emits nothing. The location is the innermost
+    /// span `push_debug_location` pushed, written as `file:line:column`, which is what the report
+    /// the runtime writes at exit ranks.
+    pub fn build_profile_rc_event(&mut self, event: RcProfileEvent) {
+        if self.config.profile != Profile::Rc {
+            return;
+        }
+        let site = match flatten_opt(self.debug_location.last().cloned()) {
+            Some(span) => {
+                let (line, col) = span.start_line_col();
+                format!("{}:{}:{}", span.input.file_path.display(), line, col)
+            }
+            None => "<unknown location>".to_string(),
+        };
+        let site = self.add_global_string(&site).as_pointer_value();
+        let event = self.context.i32_type().const_int(event as u64, false);
+        self.call_runtime(RUNTIME_PROFILE_RC, &[event.into(), site.into()]);
+    }
+
     /// Drops the location pushed last, so the instructions generated from here on carry the one
     /// that was current before it.
     pub fn pop_debug_location(&mut self) {
@@ -2613,35 +2634,32 @@ impl<'c, 'm> Generator<'c, 'm> {
         is_io: bool,
     ) -> Object<'c> {
         // Get c function
-        let c_fun = CSignature::of_ffi_call(ret_tycon, param_tys, is_var_args)
-            .get_or_declare_in_module(fun_name, self);
-
-        // Get argment values
-        let args_vals = arg_objs
-            .iter()
-            .map(|obj| obj.extract_field(self, 0).into())
-            .collect::<Vec<_>>();
+        let signature = CSignature::of_ffi_call(ret_tycon, param_tys, is_var_args);
+        let c_fun = signature.get_or_declare_in_module(fun_name, self);
 
         // Call c function
-        let call_site = self
-            .builder()
-            .build_call(c_fun, &args_vals, &format!("FFI_CALL({})", fun_name))
-            .unwrap();
-        match call_site.try_as_basic_value() {
-            Either::Left(ret_c_val) => {
-                if is_io {
-                    let ret_struct_ty = type_tycon(ret_tycon).get_struct_type(self);
-                    let ret_struct_val = ret_struct_ty.get_undef();
-                    let ret_struct_val = self
-                        .builder()
-                        .build_insert_value(ret_struct_val, ret_c_val, 0, "")
-                        .unwrap();
-                    obj = obj.insert_field(self, 1, ret_struct_val);
-                } else {
-                    obj = obj.insert_field(self, 0, ret_c_val);
-                }
+        let Some(ret_c_val) = signature.build_call(c_fun, &arg_objs, self) else {
+            return obj;
+        };
+        if ret_tycon.is_c_struct() {
+            // A struct comes back as the value Fix lays it down in.
+            let ret_ty = c_struct_value_type(ret_tycon, self.type_env());
+            let ret_obj = Object::new(ret_c_val, ret_ty, self);
+            if is_io {
+                obj = obj.insert_field_object(self, 1, &ret_obj);
+            } else {
+                obj = ret_obj;
             }
-            Either::Right(_) => {}
+        } else if is_io {
+            let ret_struct_ty = type_tycon(ret_tycon).get_struct_type(self);
+            let ret_struct_val = ret_struct_ty.get_undef();
+            let ret_struct_val = self
+                .builder()
+                .build_insert_value(ret_struct_val, ret_c_val, 0, "")
+                .unwrap();
+            obj = obj.insert_field(self, 1, ret_struct_val);
+        } else {
+            obj = obj.insert_field(self, 0, ret_c_val);
         }
 
         obj
@@ -3,6 +3,7 @@ extern crate pest_derive;
 
 pub mod ast;
 pub mod build;
+pub mod c_struct;
 pub mod commands;
 pub mod configuration;
 pub mod constants;
@@ -23,6 +23,7 @@ extern crate urlencoding;
 
 mod ast;
 mod build;
+mod c_struct;
 mod commands;
 mod configuration;
 mod constants;
@@ -51,18 +52,22 @@ mod tool;
 mod type_size;
 
 use clap::{value_parser, App, AppSettings, Arg, ArgAction, ArgMatches, PossibleValue};
-use commands::{check, clean, deps, docs, lsp::server::launch_language_server, run};
+use commands::{
+    bench, check, clean, deps, docs, doctest, fmt, lsp::server::launch_language_server, repl, run,
+    semver_check, watch,
+};
 use configuration::{
-    BuildConfigType, Configuration, DeprecationMode, FixOptimizationLevel, LinkType,
-    OutputFileType, Sanitizer, SubCommand,
+    BenchConfig, BuildConfigType, Configuration, DeprecationMode, DocsFormat, FixOptimizationLevel,
+    LinkType, OutputFileType, Profile, Sanitizer, SubCommand,
 };
 use constants::{
-    DEFAULT_COMPILATION_UNIT_MAX_SIZE_STR, DEFAULT_REGISTRY, OPTIMIZATION_LEVEL_BASIC,
-    OPTIMIZATION_LEVEL_EXPERIMENTAL, OPTIMIZATION_LEVEL_MAX, OPTIMIZATION_LEVEL_NONE,
-    PROJECT_FILE_PATH,
+    BENCH_BASELINE_PATH, DEFAULT_COMPILATION_UNIT_MAX_SIZE_STR, DEFAULT_REGISTRY,
+    OPTIMIZATION_LEVEL_BASIC, OPTIMIZATION_LEVEL_EXPERIMENTAL, OPTIMIZATION_LEVEL_MAX,
+    OPTIMIZATION_LEVEL_NONE, PROJECT_FILE_PATH,
 };
 use edit::edit_explict_import;
-use error::{panic_if_err, Errors};
+use edit::edit_unused_imports;
+use error::{panic_if_err, report_if_err, Errors, MessageFormat};
 use git_version::git_version;
 use metafiles::{config_file::ConfigFile, project_file::ProjectFile};
 use mimalloc::MiMalloc;
@@ -200,6 +205,17 @@ fn run_cli() {
              The instrumented program runs several times slower and uses much more memory.",
         ))
         .help("Sanitizer to instrument the built program with.");
+    let profile = Arg::new("profile")
+        .long("profile")
+        .takes_value(true)
+        .possible_value(
+            PossibleValue::new("none").help("Build the program as it is built for use."),
+        )
+        .possible_value(PossibleValue::new("rc").help(
+            "Count the allocations, retains, releases and force-unique clones the program \
+             performs by source location, and write a report ranking the locations when it exits.",
+        ))
+        .help("What the built program counts about itself while it runs.");
     let output_file = Arg::new("output-file")
         .long("output")
         .short('o')
@@ -210,9 +226,10 @@ fn run_cli() {
         .takes_value(true)
         .possible_value(PossibleValue::new("exe").help("Builds an executable file."))
         .possible_value(PossibleValue::new("dylib").help("Builds a dynamic library."))
+        .possible_value(PossibleValue::new("staticlib").help("Builds a static library."))
         // The option carries no default value, so that an invocation that gives it explicitly is
         // told apart from one that leaves the kind to the project file.
-        .help("The kind of file the build produces. An executable file, unless this option or the project file asks for a dynamic library.");
+        .help("The kind of file the build produces. An executable file, unless this option or the project file asks for a library.");
     let verbose = Arg::new("verbose")
         .long("verbose")
         .short('v')
@@ -228,6 +245,16 @@ fn run_cli() {
             Decreasing this value improves parallelism of compilation, but increases time for linking.\n\
             NOTE: Separate compilation is disabled under the default optimization level.\n",
         );
+    let jobs = Arg::new("jobs")
+        .long("jobs")
+        .short('j')
+        .takes_value(true)
+        .value_name("N")
+        .value_parser(value_parser!(u64).range(1..))
+        .help(
+            "Number of threads the compiler works on, for type checking and for generating the \
+             object files of compilation units. Defaults to the number of processors.",
+        );
     let llvm_passes_file = Arg::new("llvm-passes-file")
         .long("llvm-passes-file")
         .takes_value(true)
@@ -242,6 +269,13 @@ fn run_cli() {
         .takes_value(true)
         .value_name("MODULE")
         .help("Write the RC IR of a module's symbols to `.fixlang/rc_ir.<module>.txt`, or `all` for every module to `.fixlang/rc_ir.txt` (intended for compiler development).");
+    let watch = Arg::new("watch")
+        .long("watch")
+        .takes_value(false)
+        .help("Run the command again each time a source file of the project or of its dependencies, or the project file, changes.");
+    let explain_uniqueness = Arg::new("explain-uniqueness")
+        .long("explain-uniqueness")
+        .help("Report each operation modifying a value in place (such as `Array::set`, `mod` and `act`) that still checks at run time whether the value is shared, and why the value is not known to be unique there.");
     let program_args = Arg::new("program-args")
         .last(true)
         .takes_value(true)
@@ -283,6 +317,16 @@ fn run_cli() {
         .long("deny-deprecated")
         .takes_value(false)
         .help("Treat warnings about uses of `DEPRECATED` items as errors.");
+    let message_format = Arg::new("message-format")
+        .long("message-format")
+        .takes_value(true)
+        .possible_value(
+            PossibleValue::new("human").help("Print diagnostics as text to standard error."),
+        )
+        .possible_value(PossibleValue::new("json").help(
+            "Print each diagnostic as a JSON object on a line of its own to standard output.",
+        ))
+        .help("The form in which errors and warnings are reported. `human` unless specified.");
 
     // "fix version" subcommand
     let version_subc = App::new("version").about("Prints the version of the Fix compiler.");
@@ -304,17 +348,22 @@ fn run_cli() {
         .arg(emit_llvm.clone())
         .arg(threaded.clone())
         .arg(sanitize.clone())
+        .arg(profile.clone())
         .arg(verbose.clone())
         .arg(max_cu_size.clone())
+        .arg(jobs.clone())
         .arg(llvm_passes_file.clone())
         .arg(emit_symbols.clone())
         .arg(emit_rc_ir.clone())
+        .arg(explain_uniqueness.clone())
         .arg(backtrace.clone())
         .arg(no_runtime_check.clone())
         .arg(skip_eval.clone())
         .arg(allow_preliminary_commands.clone())
         .arg(allow_deprecated.clone())
-        .arg(deny_deprecated.clone());
+        .arg(deny_deprecated.clone())
+        .arg(message_format.clone())
+        .arg(watch.clone());
 
     // The options of a subcommand that builds a Fix program and then executes it. They are listed
     // in the order `--help` shows them.
@@ -332,11 +381,14 @@ fn run_cli() {
             .arg(emit_llvm.clone())
             .arg(threaded.clone())
             .arg(sanitize.clone())
+            .arg(profile.clone())
             .arg(verbose.clone())
             .arg(max_cu_size.clone())
+            .arg(jobs.clone())
             .arg(llvm_passes_file.clone())
             .arg(emit_symbols.clone())
             .arg(emit_rc_ir.clone())
+            .arg(explain_uniqueness.clone())
             .arg(program_args.clone())
             .arg(backtrace.clone())
             .arg(no_runtime_check.clone())
@@ -344,6 +396,7 @@ fn run_cli() {
             .arg(allow_preliminary_commands.clone())
             .arg(allow_deprecated.clone())
             .arg(deny_deprecated.clone())
+            .arg(watch.clone())
     };
 
     // "fix run" subcommand
@@ -357,7 +410,68 @@ fn run_cli() {
     let test_subc = add_run_and_test_options(
         App::new("test")
             .trailing_var_arg(true)
-            .about("Tests a Fix program. Executes `Test::test : IO ()`."),
+            .about("Tests a Fix program. Runs `Test::test` and the values named `test_*` of type `IO ()` or `IO (Result ErrMsg ())` in the source files of the `[build.test]` section, each in a process of its own. Arguments after `--` select the tests whose names contain one of them."),
+    )
+    .arg(
+        Arg::new("doc")
+            .long("doc")
+            .takes_value(false)
+            .help("Test the code examples of the documentation instead: build and run each code block fenced with \"```fix\" in the documentation comments of the project's modules, as a module `Main` importing the module documented. \"```fix,no_run\" builds a block without running it, and \"```fix,ignore\" leaves it untested."),
+    );
+
+    // "fix bench" subcommand
+    let bench_subc = add_run_and_test_options(
+        App::new("bench")
+            .trailing_var_arg(true)
+            .about("Benchmarks a Fix program. Builds the values named `bench_*` of type `IO ()` or `IO (Result ErrMsg ())` in the source files of the `[build.bench]` section at the `max` optimization level, measures each one in a process of its own, and compares the times with the saved baseline. Arguments after `--` select the benchmarks whose names contain one of them."),
+    )
+    .arg(
+        Arg::new("warm-up-time")
+            .long("warm-up-time")
+            .takes_value(true)
+            .value_name("SECONDS")
+            .default_value("1")
+            .value_parser(value_parser!(f64))
+            .help("How long each benchmark is run before it is measured."),
+    )
+    .arg(
+        Arg::new("measurement-time")
+            .long("measurement-time")
+            .takes_value(true)
+            .value_name("SECONDS")
+            .default_value("3")
+            .value_parser(value_parser!(f64))
+            .help("How long each benchmark is measured for, over all of its samples."),
+    )
+    .arg(
+        Arg::new("samples")
+            .long("samples")
+            .takes_value(true)
+            .value_name("N")
+            .default_value("30")
+            .value_parser(value_parser!(u64).range(1..))
+            .help("The number of samples taken of each benchmark."),
+    )
+    .arg(
+        Arg::new("baseline")
+            .long("baseline")
+            .takes_value(true)
+            .value_name("PATH")
+            .default_value(BENCH_BASELINE_PATH)
+            .help("The JSON file the times are compared with, and saved to when no baseline is saved there yet."),
+    )
+    .arg(
+        Arg::new("save-baseline")
+            .long("save-baseline")
+            .takes_value(false)
+            .help("Save the times as the baseline, replacing the times it has for the benchmarks measured."),
+    );
+
+    // "fix repl" subcommand
+    let repl_subc = add_run_and_test_options(
+        App::new("repl")
+            .trailing_var_arg(true)
+            .about("Starts an interactive session that evaluates Fix expressions with the names of the project in scope. Arguments after `--` are passed to each input as the arguments of the program."),
     );
 
     // "fix deps" subcommand
@@ -393,23 +507,59 @@ fn run_cli() {
                 .help("Output the result in JSON format. NOTE: this option is experimental and may be removed in the future."),
         );
 
+    let deps_tree = App::new("tree")
+        .about("Print the dependency graph the lock file resolves, with the version of each project and whether it is a path or a git dependency, with the commit it is checked out at. By default, the build dependencies are printed. Use --test to also print the test dependencies, marked \"[test]\".")
+        .arg(test_flag.clone())
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .takes_value(false)
+                .help("Output the projects of the graph and their dependencies in JSON format."),
+        );
+    let deps_why = App::new("why")
+        .about("Print every path from the root project to the given project in the dependency graph, including the paths through test dependencies, whose steps are marked \"[test]\".")
+        .arg(
+            Arg::new("project")
+                .index(1)
+                .takes_value(true)
+                .required(true)
+                .help("The name of the project depended on."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .takes_value(false)
+                .help("Output the paths in JSON format, each as the list of its steps."),
+        );
+
     let deps_subc = deps
         .subcommand(deps_install)
         .subcommand(deps_update)
         .subcommand(deps_add)
-        .subcommand(deps_list);
+        .subcommand(deps_list)
+        .subcommand(deps_tree)
+        .subcommand(deps_why);
 
     // "fix clean" subcommand
-    let clean_subc = App::new("clean").about("Removes intermediate files or cache files.");
+    let clean_subc = App::new("clean")
+        .about("Removes intermediate files, and the entries of the type-check cache no build can use any more. The baseline of `fix bench` is kept.")
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .takes_value(false)
+                .help("Also remove the whole type-check cache and the baseline of `fix bench`."),
+        );
 
     // "fix language-server" subcommand
     let lsp_subc = App::new("language-server").about("Launch language server for Fix.");
 
     // "fix docs" subcommand
     let docs_subc = App::new("docs")
-        .about("Generate documentations (Markdown files).")
-        .long_about("Generate documentations (Markdown files).\n\n\
+        .about("Generate documentations (Markdown files, HTML pages, or JSON files).")
+        .long_about("Generate documentations (Markdown files, HTML pages, or JSON files).\n\n\
 This command generates documentation for the Fix project located in the current directory.\n\n\
+With `--format html`, it writes a page for each module, where the names in signatures link to the pages documenting them, together with \"index.html\" and a search index. The pages work when opened from the disk, without a server.\n\n\
+With `--format json`, it writes a JSON file for each module describing its types, traits, trait implementations and values with their signatures and source locations, for other tools to read. The field \"schema_version\" of each file is raised whenever the layout changes incompatibly.\n\n\
 The target Fix project must be free of errors.\n\n\
 Consecutive line comments immediately preceding an entity declaration in the source files are treated as documentation for that entity.")
         .arg(
@@ -426,6 +576,15 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             Arg::new("out-dir").long("out-dir").short('o').takes_value(true).help("Output directory for generated documents.").default_value("docs"),
         ).arg(
             Arg::new("private").long("with-private").help("Include private values (i.e., values whose name starts with underscore) in the documentation."),
+        ).arg(
+            Arg::new("format")
+                .long("format")
+                .takes_value(true)
+                .possible_value(PossibleValue::new("markdown").help("Write a Markdown file for each module."))
+                .possible_value(PossibleValue::new("html").help("Write an HTML page for each module, with cross-links, trait implementations and a search box."))
+                .possible_value(PossibleValue::new("json").help("Write a JSON file for each module, describing its entries for other tools."))
+                .default_value("markdown")
+                .help("The form the documentation is written in."),
         ).arg(
             Arg::new("test").long("test").help("Include test modules in the documentation."));
 
@@ -441,13 +600,55 @@ Consecutive line comments immediately preceding an entity declaration in the sou
          This command checks if the project has errors, and for each source file,\n\
          collects all referenced names and rewrites import statements.",
     );
+    let edit_unused_imports = App::new("unused-imports").about(
+        "Remove the import statements, and the items of import statements, through which nothing is used.\n\
+         This command checks if the project has errors other than lints, and for each source file,\n\
+         removes what the \"unused-import\" lint reports.",
+    );
     let edit_subc = App::new("edit")
         .about("Edit source code.")
-        .subcommand(edit_explicit_import);
+        .subcommand(edit_explicit_import)
+        .subcommand(edit_unused_imports);
+
+    // "fix fmt" subcommand
+    let fmt_subc = App::new("fmt")
+        .about("Formats Fix source files into the canonical layout.")
+        .long_about("Formats Fix source files into the canonical layout.\n\n\
+This command re-indents each file, normalizes the spacing within lines, removes trailing whitespace and collapses runs of blank lines. Line breaks and comments are kept where they are.\n\n\
+If no file is given, every source file listed in the project file in the current directory is formatted, including test files.")
+        .arg(
+            Arg::new("files")
+                .multiple_values(true)
+                .takes_value(true)
+                .help("Source files to be formatted."),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .takes_value(false)
+                .help("Do not write the files; fail if any of them is not formatted (intended for CI)."),
+        );
+
+    // "fix semver-check" subcommand
+    let semver_check_subc = App::new("semver-check")
+        .about("Compares the public API of the project with the one at a git revision, and checks that the version in the project file is raised as far as the changes need.")
+        .long_about("Compares the public API of the project with the one at a git revision, and checks that the version in the project file is raised as far as the changes need.\n\n\
+The revision is checked out from a clone of the git repository holding the project, and the API of each side is what `fix docs --format json` describes of its modules. \
+Each difference is printed as \"major\" when it may break code written against the revision, such as a removed value or field, a changed type or a new trait constraint, and as \"minor\" when it only adds to the API.\n\n\
+The command fails when `[general] version` is not incompatible with the version of the revision after a major change, or not a minor version bump from it after a minor one, with compatibility judged as Cargo judges it.")
+        .arg(
+            Arg::new("baseline")
+                .index(1)
+                .takes_value(true)
+                .required(true)
+                .help("The git tag, branch or commit of the version the API is compared with (e.g., \"v1.2.0\")."),
+        );
 
     // "fix check" subcommand
     let check_subc = App::new("check")
-        .about("Checks whether a Fix project compiles without errors. Type-checks all entities including test code.");
+        .about("Checks whether a Fix project compiles without errors. Type-checks all entities including test code.")
+        .arg(message_format.clone())
+        .arg(watch.clone());
 
     let mut app = App::new("fix")
         .bin_name("fix")
@@ -459,12 +660,16 @@ Consecutive line comments immediately preceding an entity declaration in the sou
         .subcommand(build_subc)
         .subcommand(run_subc)
         .subcommand(test_subc)
+        .subcommand(bench_subc)
+        .subcommand(repl_subc)
         .subcommand(clean_subc)
         .subcommand(lsp_subc)
         .subcommand(deps_subc)
         .subcommand(docs_subc)
         .subcommand(init_subc)
         .subcommand(edit_subc)
+        .subcommand(fmt_subc)
+        .subcommand(semver_check_subc)
         .subcommand(check_subc);
 
     /// Every path the option `opt_id` collects, across all of its occurrences.
@@ -484,6 +689,15 @@ Consecutive line comments immediately preceding an entity declaration in the sou
         }
     }
 
+    /// The form of diagnostics the `--message-format` option asks for, or the human-readable form
+    /// where the invocation does not give the option.
+    fn read_message_format_option(args: &ArgMatches) -> Result<MessageFormat, Errors> {
+        match args.get_one::<String>("message-format") {
+            None => Ok(MessageFormat::Human),
+            Some(format) => MessageFormat::from_str(format),
+        }
+    }
+
     /// Apply the options of one `fix docs` invocation to the documentation settings `config`
     /// carries.
     fn read_docs_options(args: &ArgMatches, config: &mut Configuration) -> Result<(), Errors> {
@@ -511,12 +725,40 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             .expect("the `--out-dir` option carries a default value");
         docs_config.out_dir = PathBuf::from(dir);
 
+        // `format` option
+        let format = args
+            .get_one::<String>("format")
+            .expect("the `--format` option carries a default value");
+        docs_config.format = DocsFormat::from_str(format)?;
+
         // `test` option
         docs_config.mode = get_build_mode(args);
 
         Ok(())
     }
 
+    /// The settings of one `fix bench` invocation: how the benchmarks are measured, and the baseline
+    /// they are compared with.
+    fn read_bench_options(args: &ArgMatches) -> BenchConfig {
+        let seconds = |opt_id: &str| {
+            *args
+                .get_one::<f64>(opt_id)
+                .unwrap_or_else(|| panic!("the `--{}` option carries a default value", opt_id))
+        };
+        BenchConfig {
+            warm_up_time: seconds("warm-up-time"),
+            measurement_time: seconds("measurement-time"),
+            samples: *args
+                .get_one::<u64>("samples")
+                .expect("the `--samples` option carries a default value"),
+            baseline_path: PathBuf::from(
+                args.get_one::<String>("baseline")
+                    .expect("the `--baseline` option carries a default value"),
+            ),
+            save_baseline: args.contains_id("save-baseline"),
+        }
+    }
+
     /// The path the `--output` option names for the built file, if the invocation gives that
     /// option.
     fn read_output_file_option(args: &ArgMatches) -> Option<PathBuf> {
@@ -643,6 +885,11 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             panic_if_err(config.set_sanitizer(panic_if_err(Sanitizer::from_str(sanitizer))));
         }
 
+        // Set `profile`.
+        if let Some(profile) = args.value_of("profile") {
+            config.set_profile(panic_if_err(Profile::from_str(profile)));
+        }
+
         // Set `debug_info`.
         if args.contains_id("debug-info") {
             config.set_debug_info();
@@ -695,6 +942,11 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             .get_one::<usize>("max-cu-size")
             .expect("the `--max-cu-size` option carries a default value");
 
+        // Set `num_worker_thread`.
+        if let Some(jobs) = args.get_one::<u64>("jobs") {
+            config.num_worker_thread = *jobs as usize;
+        }
+
         // Set `llvm_passes_override`.
         // Reading the file here puts the passes into `Configuration::object_generation_hash`, so
         // that a change to them invalidates the objects compiled under the previous ones.
@@ -710,6 +962,11 @@ Consecutive line comments immediately preceding an entity declaration in the sou
         // Set `emit_rc_ir`.
         config.emit_rc_ir = args.get_one::<String>("emit-rc-ir").cloned();
 
+        // Set `explain_uniqueness`.
+        if args.contains_id("explain-uniqueness") {
+            config.explain_uniqueness = true;
+        }
+
         // Set `backtrace`.
         if args.contains_id("backtrace") {
             config.set_backtrace();
@@ -746,7 +1003,7 @@ Consecutive line comments immediately preceding an entity declaration in the sou
 
         // Set `run_program_args`.
         match config.subcommand {
-            SubCommand::Run | SubCommand::Test => {
+            SubCommand::Run | SubCommand::Test | SubCommand::Bench(_) | SubCommand::Repl => {
                 let mut program_args = args
                     .get_many::<String>("program-args")
                     .unwrap_or_default()
@@ -763,19 +1020,64 @@ Consecutive line comments immediately preceding an entity declaration in the sou
     /// Create configuration from the command line arguments and the project file. The project
     /// file's settings are laid down first, so an option on the command line overrides them.
     fn create_config(subcommand: SubCommand, args: &ArgMatches) -> Configuration {
+        let format = subcommand_message_format(&subcommand, args);
+        report_if_err(try_create_config(subcommand, args), format)
+    }
+
+    /// The form of diagnostics `subcommand` reports in. The `--message-format` argument is declared
+    /// on `fix build` alone, and reading an argument a subcommand does not declare panics in a debug
+    /// build.
+    fn subcommand_message_format(subcommand: &SubCommand, args: &ArgMatches) -> MessageFormat {
+        match subcommand {
+            SubCommand::Build => panic_if_err(read_message_format_option(args)),
+            _ => MessageFormat::Human,
+        }
+    }
+
+    /// The configuration `create_config` makes, or what stopped it from being made.
+    fn try_create_config(
+        subcommand: SubCommand,
+        args: &ArgMatches,
+    ) -> Result<Configuration, Errors> {
         let mode = subcommand.build_mode();
-        let mut config = panic_if_err(Configuration::release_mode(subcommand));
+
+        // The format is read first, so that an error in the project file is reported in the form
+        // asked for.
+        let format = subcommand_message_format(&subcommand, args);
+        let mut config = Configuration::release_mode(subcommand)?;
+        config.message_format = format;
 
         // Set up configuration from the project file if it exists.
         if Path::new(PROJECT_FILE_PATH).exists() {
-            let proj_file = panic_if_err(ProjectFile::read_root_file());
-            panic_if_err(proj_file.set_config(&mut config));
-            panic_if_err(proj_file.install_dependencies(&mut config, mode));
+            let proj_file = ProjectFile::read_root_file()?;
+            proj_file.set_config(&mut config)?;
+            proj_file.install_dependencies(&mut config, mode)?;
+        }
+
+        // A benchmark build measures the program as it is built for use, at the highest level,
+        // whatever the project file asks for. `--opt-level` may still choose another.
+        if matches!(config.subcommand, SubCommand::Bench(_)) {
+            config.set_fix_opt_level(FixOptimizationLevel::Max);
         }
 
         // Set up configuration from the command line arguments, to overwrite the configuration described in the project file.
-        panic_if_err(set_config_from_args(&mut config, args));
-        config
+        set_config_from_args(&mut config, args)?;
+        Ok(config)
+    }
+
+    /// Runs `run_once` under the configuration of `subcommand`, and again after each change to the
+    /// files it read, for the `--watch` option. The configuration is created anew for each run.
+    fn watch_subcommand(
+        subcommand: SubCommand,
+        args: &ArgMatches,
+        run_once: impl FnMut(&Configuration) -> Result<(), Errors>,
+    ) -> ! {
+        let format = subcommand_message_format(&subcommand, args);
+        watch::watch(
+            || try_create_config(subcommand.clone(), args),
+            run_once,
+            format,
+        )
     }
 
     /// Print the help of the subcommand `name`, as `fix <name> --help` prints it. The subcommand
@@ -802,16 +1104,40 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             process::exit(0);
         }
         Some(("build", args)) => {
-            panic_if_err(commands::build::build(&mut create_config(
-                SubCommand::Build,
-                args,
-            )));
+            if args.contains_id("watch") {
+                watch_subcommand(SubCommand::Build, args, commands::build::build);
+            }
+            let config = create_config(SubCommand::Build, args);
+            report_if_err(commands::build::build(&config), config.message_format);
         }
         Some(("run", args)) => {
+            if args.contains_id("watch") {
+                watch_subcommand(SubCommand::Run, args, run::run_watched);
+            }
             run::run_command(&create_config(SubCommand::Run, args));
         }
         Some(("test", args)) => {
-            run::run_command(&create_config(SubCommand::Test, args));
+            if args.contains_id("doc") {
+                if args.contains_id("watch") {
+                    watch_subcommand(SubCommand::Test, args, doctest::doctest_watched);
+                }
+                doctest::doctest_command(&create_config(SubCommand::Test, args));
+            } else {
+                if args.contains_id("watch") {
+                    watch_subcommand(SubCommand::Test, args, run::run_watched);
+                }
+                run::run_command(&create_config(SubCommand::Test, args));
+            }
+        }
+        Some(("bench", args)) => {
+            let subcommand = SubCommand::Bench(read_bench_options(args));
+            if args.contains_id("watch") {
+                watch_subcommand(subcommand, args, bench::bench_watched);
+            }
+            bench::bench_command(create_config(subcommand, args));
+        }
+        Some(("repl", args)) => {
+            panic_if_err(repl::repl(create_config(SubCommand::Repl, args)));
         }
         Some(("deps", args)) => match args.subcommand() {
             Some(("install", args)) => {
@@ -826,13 +1152,19 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             Some(("list", args)) => {
                 deps::deps_list_command(args, &fix_config);
             }
+            Some(("tree", args)) => {
+                deps::deps_tree_command(args);
+            }
+            Some(("why", args)) => {
+                deps::deps_why_command(args);
+            }
             _ => print_subcommand_help(&mut app, "deps"),
         },
         Some(("language-server", _args)) => {
             launch_language_server();
         }
-        Some(("clean", _args)) => {
-            clean::clean_command();
+        Some(("clean", args)) => {
+            clean::clean_command(args.contains_id("all"));
         }
         Some(("docs", args)) => {
             // Create the configuration.
@@ -848,14 +1180,41 @@ Consecutive line comments immediately preceding an entity declaration in the sou
             panic_if_err(ProjectFile::validate_project_name(&project_name, None));
             panic_if_err(ProjectFile::create_example_file(project_name));
         }
-        Some(("check", _args)) => {
-            let config = panic_if_err(Configuration::check_mode());
-            panic_if_err(check::check(config));
+        Some(("check", args)) => {
+            let format = panic_if_err(read_message_format_option(args));
+            if args.contains_id("watch") {
+                watch::watch(
+                    || {
+                        let mut config = Configuration::check_mode()?;
+                        config.message_format = format;
+                        check::configure_check(&mut config)?;
+                        Ok(config)
+                    },
+                    |config| check::check_configured(config.clone()),
+                    format,
+                );
+            }
+            let mut config = report_if_err(Configuration::check_mode(), format);
+            config.message_format = format;
+            report_if_err(check::check(config), format);
+        }
+        Some(("semver-check", args)) => {
+            let baseline = args
+                .get_one::<String>("baseline")
+                .expect("the baseline of `fix semver-check` is a required argument");
+            semver_check::semver_check_command(baseline);
+        }
+        Some(("fmt", args)) => {
+            let files = read_path_list_option(args, "files");
+            panic_if_err(fmt::fmt_command(files, args.contains_id("check")));
         }
         Some(("edit", args)) => match args.subcommand() {
             Some(("explicit-import", _args)) => {
                 panic_if_err(edit_explict_import::run_explicit_import_command());
             }
+            Some(("unused-imports", _args)) => {
+                panic_if_err(edit_unused_imports::run_unused_imports_command());
+            }
             _ => print_subcommand_help(&mut app, "edit"),
         },
         // A command line naming no subcommand is answered by the help, and one naming a subcommand
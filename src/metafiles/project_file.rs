@@ -1,7 +1,7 @@
 use crate::{
     configuration::{
-        BuildConfigType, Configuration, FixOptimizationLevel, LinkType, OutputFileType,
-        ProjectSources, Sanitizer, ValgrindTool,
+        BuildConfigType, Configuration, FixOptimizationLevel, LinkType, LintLevel, OutputFileType,
+        ProjectSources, Sanitizer, SubCommand, ValgrindTool,
     },
     constants::{
         PROJECT_FILE_PATH, SAMPLE_MAIN_FILE_PATH, SAMPLE_TEST_FILE_PATH, TRY_FIX_DEPS_UPDATE,
@@ -11,6 +11,7 @@ use crate::{
         clone_git_repo, get_lock_file_path, get_versions_from_repo, DependecyLockFile,
         LockFileType, ProjectSource,
     },
+    elaboration::lint::LINT_CODES,
     error::Errors,
     metafiles::{config_file::ConfigFile, registry_file::RegistryFile},
     misc::{info_msg, to_absolute_path, warn_msg, Set},
@@ -22,6 +23,7 @@ use reqwest::Url;
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     hash::Hash,
     io::{Read, Write},
@@ -109,6 +111,9 @@ pub struct ProjectFileBuild {
     /// Name of the kind of file `fix build` produces, from the set `OutputFileType::from_str`
     /// accepts.
     output_type: Option<String>,
+    /// The path a `fix build` of a library writes the C header declaring the exported functions to.
+    /// Unset puts it beside the library, under the library's name with the extension `.h`.
+    c_header: Option<PathBuf>,
     /// Whether the program prints a backtrace when a run-time error ends it. Unset ends it with the
     /// error message alone.
     backtrace: Option<bool>,
@@ -126,6 +131,20 @@ pub struct ProjectFileBuild {
     /// The `build.test` sub-section, which supplies the settings a `fix test` build reads beside
     /// the ones this `build` section gives.
     test: Option<ProjectFileBuildTest>,
+    /// The `build.bench` sub-section, which lists the source files of the benchmarks `fix bench`
+    /// measures.
+    bench: Option<ProjectFileBuildBench>,
+}
+
+/// The `build.bench` section of the project file. A benchmark build takes every other setting from
+/// the `build` section, and its dependencies from the `dependencies` section.
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectFileBuildBench {
+    /// The Fix source files a benchmark build compiles, added to the ones the `build` section
+    /// gives. Each is resolved against the project directory and paired with its byte range in the
+    /// project file.
+    files: Vec<Spanned<PathBuf>>,
 }
 
 /// The `build.test` section of the project file, holding the settings a `fix test` build reads.
@@ -309,6 +328,11 @@ pub struct ProjectFile {
     /// the ones `dependencies` names.
     #[serde(default)]
     pub test_dependencies: Vec<ProjectFileDependency>,
+    /// The `lints` section: the level, `"allow"`, `"warn"` or `"deny"`, each lint is reported at,
+    /// keyed by the lint's diagnostic code, such as `unused-import = "allow"`. Read from the root
+    /// project alone, since the lints report on the root project's sources alone.
+    #[serde(default)]
+    pub lints: BTreeMap<String, Spanned<String>>,
     /// The path this file was read from. Every relative path the file writes is resolved against
     /// the directory holding it.
     #[serde(skip)]
@@ -583,11 +607,13 @@ impl ProjectFile {
     ///
     /// # Arguments
     ///
-    /// * `mode` - `Test` also takes the files listed in the `[build.test]` section.
+    /// * `mode` - `Test` also takes the files listed in the `[build.test]` and `[build.bench]`
+    ///   sections.
     fn source_file_entries(&self, mode: BuildConfigType) -> Vec<&Spanned<PathBuf>> {
         let mut entries: Vec<&Spanned<PathBuf>> = self.build.files.iter().collect();
         if mode == BuildConfigType::Test {
             entries.extend(self.test_only_file_entries());
+            entries.extend(self.bench_only_file_entries());
         }
         entries
     }
@@ -602,6 +628,17 @@ impl ProjectFile {
             .map_or(vec![], |test| test.files.iter().collect())
     }
 
+    /// The source-file entries listed in the `[build.bench]` section, which a benchmark build
+    /// compiles beside the ones an ordinary build compiles. A test build compiles them too, so that
+    /// `fix test`, `fix check` and the language server see the benchmarks. Each is paired with its
+    /// byte range in the project file.
+    fn bench_only_file_entries(&self) -> Vec<&Spanned<PathBuf>> {
+        self.build
+            .bench
+            .as_ref()
+            .map_or(vec![], |bench| bench.files.iter().collect())
+    }
+
     /// The names of the projects this project declares as dependencies for a build of the given
     /// mode. A test build declares the test dependencies beside the ordinary ones.
     fn declared_dependency_names(&self, mode: BuildConfigType) -> Set<ProjectName> {
@@ -615,7 +652,8 @@ impl ProjectFile {
     ///
     /// # Arguments
     ///
-    /// * `mode` - `Test` also takes the files listed in the `[build.test]` section.
+    /// * `mode` - `Test` also takes the files listed in the `[build.test]` and `[build.bench]`
+    ///   sections.
     pub fn get_files(&self, mode: BuildConfigType) -> Vec<PathBuf> {
         self.source_file_entries(mode)
             .iter()
@@ -623,29 +661,51 @@ impl ProjectFile {
             .collect()
     }
 
-    /// The paths of the source files a test build compiles beside the ones an ordinary build
-    /// compiles, resolved against the project directory. A file the `[build.test]` section repeats
-    /// from the `build` section is one of the ordinary sources, so it stays out.
+    /// The paths of the source files the `[build.test]` section adds to the ones an ordinary build
+    /// compiles, resolved against the project directory. A file the section repeats from the
+    /// `build` section is one of the ordinary sources, so it stays out.
     fn get_test_only_files(&self) -> Vec<PathBuf> {
         let build_files: Set<PathBuf> =
             self.get_files(BuildConfigType::Build).into_iter().collect();
-        self.test_only_file_entries()
-            .iter()
-            .map(|entry| self.join_to_project_dir(entry.get_ref()))
-            .filter(|path| !build_files.contains(path))
-            .collect()
+        self.files_beside(self.test_only_file_entries(), &build_files)
     }
 
-    /// Checks that every source file listed in the project file exists on disk. Each error points at
-    /// the offending entry, so an editor attaches the problem to the project file, which is where
-    /// its cause is.
+    /// The paths of the source files the `[build.bench]` section adds to the ones an ordinary build
+    /// compiles, resolved against the project directory.
     ///
     /// # Arguments
     ///
-    /// * `mode` - `Test` also checks the files listed in the `[build.test]` section.
-    fn check_source_files_exist(&self, mode: BuildConfigType) -> Result<(), Errors> {
+    /// * `mode` - `Test` also leaves out the files the `[build.test]` section lists, which a test
+    ///   build compiles already.
+    fn get_bench_only_files(&self, mode: BuildConfigType) -> Vec<PathBuf> {
+        let mut compiled_files: Set<PathBuf> =
+            self.get_files(BuildConfigType::Build).into_iter().collect();
+        if mode == BuildConfigType::Test {
+            compiled_files.extend(self.get_test_only_files());
+        }
+        self.files_beside(self.bench_only_file_entries(), &compiled_files)
+    }
+
+    /// The paths of `entries`, resolved against the project directory, but for the ones in
+    /// `compiled_files`.
+    fn files_beside(
+        &self,
+        entries: Vec<&Spanned<PathBuf>>,
+        compiled_files: &Set<PathBuf>,
+    ) -> Vec<PathBuf> {
+        entries
+            .iter()
+            .map(|entry| self.join_to_project_dir(entry.get_ref()))
+            .filter(|path| !compiled_files.contains(path))
+            .collect()
+    }
+
+    /// Checks that every source file of `entries` exists on disk. Each error points at the
+    /// offending entry, so an editor attaches the problem to the project file, which is where its
+    /// cause is.
+    fn check_source_files_exist(&self, entries: Vec<&Spanned<PathBuf>>) -> Result<(), Errors> {
         let mut errors = Errors::empty();
-        for entry in self.source_file_entries(mode) {
+        for entry in entries {
             if self.join_to_project_dir(entry.get_ref()).exists() {
                 continue;
             }
@@ -731,15 +791,24 @@ impl ProjectFile {
         if is_dependent_proj {
             mode = BuildConfigType::Build;
         }
+        // A benchmark build is an ordinary build of the root project with the benchmark sources
+        // added.
+        let bench = !is_dependent_proj && matches!(config.subcommand, SubCommand::Bench(_));
 
         // Reject missing source files up front, so that the error points at the project-file entry
         // naming the file and an editor attaches it there.
-        self.check_source_files_exist(mode)?;
+        let mut entries = self.source_file_entries(mode);
+        if bench {
+            entries.extend(self.bench_only_file_entries());
+        }
+        self.check_source_files_exist(entries)?;
 
         // Record what this project provides and what it declares, so that an import reaching past
         // the projects it declares can be told from one that stays within them. The sources of a
         // test build carry declarations of their own, since the test dependencies are the ones the
         // test sources may use, so they are recorded as a contribution beside the ordinary sources.
+        // The benchmark sources are built with the ordinary dependencies alone, in a benchmark
+        // build and in a test build alike, so they are a contribution of their own too.
         config.project_sources.push(ProjectSources {
             name: self.general.name.clone(),
             version: self.general.version.clone(),
@@ -756,12 +825,31 @@ impl ProjectFile {
                 files: self.get_test_only_files(),
             });
         }
+        if mode == BuildConfigType::Test || bench {
+            config.project_sources.push(ProjectSources {
+                name: self.general.name.clone(),
+                version: self.general.version.clone(),
+                origin: project_origin.clone(),
+                declared_dependencies: self.declared_dependency_names(BuildConfigType::Build),
+                files: self.get_bench_only_files(mode),
+            });
+        }
 
         // The records above are what the build compiles. The root project's files are the user's
         // own as well, which scopes diagnostics to the code they can edit: a deprecated use inside
         // a dependency is the dependency's problem.
         if !is_dependent_proj {
             config.root_source_files.extend(self.get_files(mode));
+            if mode == BuildConfigType::Test {
+                config
+                    .root_test_only_source_files
+                    .extend(self.get_test_only_files());
+            }
+            if bench {
+                let bench_files = self.get_bench_only_files(mode);
+                config.root_source_files.extend(bench_files.clone());
+                config.root_bench_only_source_files.extend(bench_files);
+            }
         }
 
         // Append object files.
@@ -898,6 +986,13 @@ impl ProjectFile {
             return Ok(());
         }
 
+        // Set the lint levels.
+        for (code, level) in &self.lints {
+            config
+                .lint_levels
+                .insert(code.clone(), self.read_lint_level(code, level)?);
+        }
+
         // Set threaded-mode.
         if let Some(threaded) = self.build.threaded {
             if threaded {
@@ -976,6 +1071,9 @@ impl ProjectFile {
             if let Some(output_file_type) = output_file_type {
                 config.output_file_type = output_file_type;
             }
+            if let Some(c_header) = self.build.c_header.as_ref() {
+                config.c_header_path = Some(PathBuf::from(c_header));
+            }
         }
 
         // Set backtrace mode.
@@ -1155,6 +1253,37 @@ impl ProjectFile {
         Ok(())
     }
 
+    /// The level a `lints` entry sets for the lint `code`. An entry naming a lint or a level that
+    /// does not exist is reported at the entry.
+    fn read_lint_level(&self, code: &str, level: &Spanned<String>) -> Result<LintLevel, Errors> {
+        let span = level.span();
+        let src = Some(self.project_file_span(span.start, span.end));
+        if !LINT_CODES.contains(&code) {
+            return Err(Errors::from_msg_srcs(
+                format!(
+                    "Unknown lint \"{}\" in the `lints` section. Available lints are {}.",
+                    code,
+                    LINT_CODES
+                        .iter()
+                        .map(|code| format!("\"{}\"", code))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                &[&src],
+            ));
+        }
+        LintLevel::from_str(level.get_ref()).map_err(|_| {
+            Errors::from_msg_srcs(
+                format!(
+                    "Unknown level \"{}\" of the lint \"{}\". Available levels are \"allow\", \"warn\" and \"deny\".",
+                    level.get_ref(),
+                    code
+                ),
+                &[&src],
+            )
+        })
+    }
+
     /// The place in this project file a diagnostic points at.
     ///
     /// # Arguments
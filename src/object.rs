@@ -16,7 +16,7 @@ use crate::fixstd::builtin::{
     make_u64_ty, make_u8_ty,
 };
 use crate::fixstd::runtime::{
-    RUNTIME_ARRAY_SIZE_OVERFLOW, RUNTIME_INDEX_OUT_OF_RANGE, RUNTIME_MALLOC,
+    RcProfileEvent, RUNTIME_ARRAY_SIZE_OVERFLOW, RUNTIME_INDEX_OUT_OF_RANGE, RUNTIME_MALLOC,
     RUNTIME_NEGATIVE_ARRAY_SIZE,
 };
 use crate::generator::{is_const_one, Generator, Object};
@@ -143,7 +143,7 @@ impl ObjectFieldType {
 
     /// The debug-info type describing this field: the Fix type name and encoding a debugger displays
     /// it under. A union's payload buffer gets a synthetic member per variant, all at offset zero,
-    /// so that every variant is readable from the one buffer.
+    /// so that every variant is readable from the one buffer; see `union_buf_di_type`.
     pub fn to_debug_type<'c, 'm>(&self, gc: &mut Generator<'c, 'm>) -> DIType<'c> {
         match self {
             ObjectFieldType::ControlBlock => control_block_di_type(gc),
@@ -202,56 +202,10 @@ impl ObjectFieldType {
                 .as_type(),
             ObjectFieldType::SubObject(ty, _is_punched) => ty_to_debug_embedded_ty(ty.clone(), gc),
             ObjectFieldType::UnionBuf(tys) => {
-                let basic_ty = self.to_basic_type(gc);
-                let size_in_bits = gc.target_data.get_bit_size(&basic_ty);
-                let align_in_bits = gc.target_data.get_abi_alignment(&basic_ty) * 8;
-
-                let mut elements = vec![];
-                for (i, ty) in tys.iter().enumerate() {
-                    let variant_ty = ty.get_embedded_type(gc);
-                    let variant_debug_ty = ty_to_debug_embedded_ty(ty.clone(), gc);
-                    let size_in_bits = gc.target_data.get_bit_size(&variant_ty);
-                    let align_in_bits = gc.target_data.get_abi_alignment(&variant_ty) * 8;
-                    // Every variant starts at the beginning of the union buffer.
-                    let offset_in_bits = 0;
-                    let mem_ty = gc
-                        .get_di_builder()
-                        .create_member_type(
-                            gc.get_di_compile_unit().as_debug_info_scope(),
-                            &format!("<union variant {}>", i),
-                            gc.create_di_file(None),
-                            0,
-                            size_in_bits,
-                            align_in_bits,
-                            offset_in_bits,
-                            0,
-                            variant_debug_ty,
-                        )
-                        .as_type();
-                    elements.push(mem_ty);
-                }
-                let name = &format!(
-                    "<union value {}>",
-                    tys.iter()
-                        .map(|ty| ty.to_string())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                );
-                // It seems that the second parameter of create_union_type (`name`, not `unique_id`) should vary depending on the element type, at least for lldb.
-                gc.get_di_builder()
-                    .create_union_type(
-                        gc.get_di_compile_unit().as_debug_info_scope(),
-                        &name,
-                        gc.create_di_file(None),
-                        0,
-                        size_in_bits,
-                        align_in_bits,
-                        0,
-                        &elements,
-                        0,
-                        &name,
-                    )
-                    .as_type()
+                let variant_names = (0..tys.len())
+                    .map(|i| format!("<union variant {}>", i))
+                    .collect::<Vec<_>>();
+                union_buf_di_type(tys, &variant_names, gc)
             }
             ObjectFieldType::UnionTag => gc
                 .get_di_builder()
@@ -1406,6 +1360,71 @@ pub fn control_block_di_type<'c, 'm>(gc: &mut Generator<'c, 'm>) -> DIType<'c> {
         .as_type()
 }
 
+/// The debug info type of a union's payload buffer holding one of `tys`: a union with a member per
+/// variant, all at offset zero, named by `variant_names`. The members are in the order of the
+/// variants, so the value of the union's tag is the index of the member that is active, which is
+/// what the pretty-printers `fix build -g` writes beside the program select the variant by.
+pub fn union_buf_di_type<'c, 'm>(
+    tys: &[Arc<TypeNode>],
+    variant_names: &[String],
+    gc: &mut Generator<'c, 'm>,
+) -> DIType<'c> {
+    assert_eq!(tys.len(), variant_names.len());
+    let basic_ty = union_buf_type(gc, tys);
+    let size_in_bits = gc.target_data.get_bit_size(&basic_ty);
+    let align_in_bits = gc.target_data.get_abi_alignment(&basic_ty) * 8;
+
+    let mut elements = vec![];
+    for (ty, variant_name) in tys.iter().zip(variant_names) {
+        let variant_ty = ty.get_embedded_type(gc);
+        let variant_debug_ty = ty_to_debug_embedded_ty(ty.clone(), gc);
+        let size_in_bits = gc.target_data.get_bit_size(&variant_ty);
+        let align_in_bits = gc.target_data.get_abi_alignment(&variant_ty) * 8;
+        // Every variant starts at the beginning of the union buffer.
+        let offset_in_bits = 0;
+        let mem_ty = gc
+            .get_di_builder()
+            .create_member_type(
+                gc.get_di_compile_unit().as_debug_info_scope(),
+                variant_name,
+                gc.create_di_file(None),
+                0,
+                size_in_bits,
+                align_in_bits,
+                offset_in_bits,
+                0,
+                variant_debug_ty,
+            )
+            .as_type();
+        elements.push(mem_ty);
+    }
+    let name = &format!(
+        "<union value {}>",
+        tys.iter()
+            .map(|ty| ty.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    // It seems that the second parameter of create_union_type (`name`, not `unique_id`) should vary depending on the element type, at least for lldb.
+    // The unique id also carries the variant names, so that two unions whose variants share types
+    // but not names do not collapse into one debug type.
+    let unique_id = &format!("{} {}", name, variant_names.join(" "));
+    gc.get_di_builder()
+        .create_union_type(
+            gc.get_di_compile_unit().as_debug_info_scope(),
+            name,
+            gc.create_di_file(None),
+            0,
+            size_in_bits,
+            align_in_bits,
+            0,
+            &elements,
+            0,
+            unique_id,
+        )
+        .as_type()
+}
+
 /// The debug info type of a pointer of the target's width, presented to a debugger under `name`.
 pub fn ptr_di_type<'c, 'm>(name: &str, gc: &mut Generator<'c, 'm>) -> DIType<'c> {
     let ptr_ty = gc.context.ptr_type(AddressSpace::from(0));
@@ -1719,11 +1738,14 @@ pub fn alloc_array_storage<'c, 'm>(
 /// within the header, at a fixed low address, and no capacity or index the program computed reaches
 /// it. Deferring that initialization would put a value the program chose into the faulting address
 /// and turn this into a wild write.
+///
+/// A program built with `--profile=rc` counts the call as an allocation.
 fn build_malloc<'c, 'm>(
-    gc: &Generator<'c, 'm>,
+    gc: &mut Generator<'c, 'm>,
     sizeof: IntValue<'c>,
     name: &str,
 ) -> PointerValue<'c> {
+    gc.build_profile_rc_event(RcProfileEvent::Alloc);
     let malloc_fn = gc
         .module
         .get_function(RUNTIME_MALLOC)
@@ -2534,6 +2556,14 @@ fn ty_to_debug_struct_ty_body<'c, 'm>(ty: Arc<TypeNode>, gc: &mut Generator<'c,
                 .map(|field| field.name.clone())
                 .collect();
         }
+        // A union's fields are its variants, which its payload buffer names its members after.
+        let variant_names = if !ty.is_closure()
+            && ty.toplevel_tycon_info(gc.type_env()).variant == TyConVariant::Union
+        {
+            Some(subelement_names.clone())
+        } else {
+            None
+        };
 
         let mut elements = vec![];
         for (i, field) in obj_type.field_types.iter().enumerate() {
@@ -2577,7 +2607,12 @@ fn ty_to_debug_struct_ty_body<'c, 'm>(ty: Arc<TypeNode>, gc: &mut Generator<'c,
                 };
             }
 
-            let element_di_ty = field.to_debug_type(gc);
+            let element_di_ty = match (field, &variant_names) {
+                (ObjectFieldType::UnionBuf(tys), Some(variant_names)) => {
+                    union_buf_di_type(tys, variant_names, gc)
+                }
+                _ => field.to_debug_type(gc),
+            };
             let element_ty = field.to_basic_type(gc);
             let size_in_bits = element_di_ty.get_size_in_bits();
             let align_in_bits = gc.target_data.get_abi_alignment(&element_ty) * 8;
@@ -14,7 +14,7 @@ mod optimize_act;
 mod pull_let;
 mod remove_tyanno;
 pub mod rename;
-mod simplify_symbol_names;
+pub mod simplify_symbol_names;
 mod skip_eval;
 mod uncurry;
 mod unique_local_names;
@@ -69,6 +69,12 @@ pub fn run(prg: &mut Program) {
     if let Some(entry_io) = &mut prg.entry_io_value {
         *entry_io = rename_var_expr(entry_io.clone(), &old_to_new_names);
     }
+    for test in &mut prg.test_cases {
+        test.expr = rename_var_expr(test.expr.clone(), &old_to_new_names);
+        if let Some(runner) = &mut test.result_runner {
+            *runner = rename_var_expr(runner.clone(), &old_to_new_names);
+        }
+    }
     for export_stmt in &mut prg.export_statements {
         if let Some(entry_io) = &mut export_stmt.value_expr {
             *entry_io = rename_var_expr(entry_io.clone(), &old_to_new_names);
@@ -86,7 +92,9 @@ fn rename_var_expr(
     new_expr
 }
 
-fn get_base_name(full_name: &FullName) -> FullName {
+/// The name `full_name` was instantiated from, with the `#` suffixes instantiation appends removed:
+/// `Main::table#0` is `Main::table`.
+pub fn get_base_name(full_name: &FullName) -> FullName {
     let name = &full_name.name;
     let new_name = if name.starts_with('#') {
         // To avoid the name becomes empty, remove after second '#' if exists.
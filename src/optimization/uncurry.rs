@@ -102,6 +102,14 @@ pub fn run(fix_mod: &mut Program) {
                 Some(expr_var(sym.name.clone(), None).set_type(sym.ty.clone()));
         }
     }
+
+    // Likewise for the tests. A test's runner is applied to the test's IO value alone, so it is
+    // left curried.
+    for test in &mut fix_mod.test_cases {
+        if let Some(sym) = uncurried_symbol(&fix_mod.symbols, &test.expr, 1) {
+            test.expr = expr_var(sym.name.clone(), None).set_type(sym.ty.clone());
+        }
+    }
 }
 
 /// The uncurried symbol to use in place of `value`, an expression referring to a global value.
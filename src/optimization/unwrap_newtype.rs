@@ -125,6 +125,14 @@ fn unwrap_pattern(pat: &Arc<PatternNode>, type_env: &TypeEnv) -> Arc<PatternNode
                 info,
             })
         }
+        Pattern::Lit(val, ty) => {
+            let mut info = pat.info.clone();
+            unwrap_pattern_info(&mut info, type_env);
+            Arc::new(PatternNode {
+                pattern: Pattern::Lit(*val, ty.clone()),
+                info,
+            })
+        }
     }
 }
 
@@ -155,12 +163,19 @@ fn run_on_exported_statements(prg: &mut Program, type_env: &TypeEnv) {
     }
 }
 
-/// Unwraps the type inferred for the `IO` value the program runs at entry.
+/// Unwraps the types inferred for the `IO` values the program runs at entry: `Main::main`, or the
+/// tests and their runners.
 fn run_on_entry_io_value(prg: &mut Program, type_env: &TypeEnv) {
     if let Some(entry_io_value) = &mut prg.entry_io_value {
         let expr = unwrap_inferred_type(entry_io_value, type_env);
         prg.entry_io_value = Some(expr);
     }
+    for test in &mut prg.test_cases {
+        test.expr = unwrap_inferred_type(&test.expr, type_env);
+        if let Some(runner) = &mut test.result_runner {
+            *runner = unwrap_inferred_type(runner, type_env);
+        }
+    }
 }
 
 /// Unwraps the type of one symbol and rewrites the expression that defines it.
@@ -162,6 +162,100 @@ fn refine_namespaces(content: &str, tokens: &mut [LexToken]) {
     }
 }
 
+/// The role a token of the layout scan plays for the source formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutTokenKind {
+    /// A run of spaces, tabs or no-break spaces within one line.
+    Space,
+    /// One line break.
+    Newline,
+    /// A `//` line comment, without the line break that ends it.
+    LineComment,
+    /// A `/* */` block comment, which may span lines.
+    BlockComment,
+    /// A string or character literal. A string literal may span lines.
+    Literal,
+    /// A name, a capitalized name, or a number, boolean or `nullptr` literal: a token that has to
+    /// be kept apart from a neighboring one of its kind by a space.
+    Word,
+    /// A keyword, as the highlighting scanner recognizes it.
+    Keyword,
+    /// An operator, a bracket or any other punctuation.
+    Symbol,
+}
+
+/// One token of the layout scan: a half-open byte range `[start, end)` into the source string and
+/// the role it plays for the formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutToken {
+    pub start: usize,
+    pub end: usize,
+    pub kind: LayoutTokenKind,
+}
+
+// Map a grammar rule (the inner rule of a `layout_token`, or of the `lex_token` it wraps) to its
+// layout role.
+fn layout_kind_of_rule(rule: Rule) -> LayoutTokenKind {
+    match rule {
+        Rule::layout_space => LayoutTokenKind::Space,
+        Rule::layout_newline => LayoutTokenKind::Newline,
+        Rule::lex_line_comment => LayoutTokenKind::LineComment,
+        Rule::lex_block_comment => LayoutTokenKind::BlockComment,
+        Rule::expr_string_lit | Rule::lex_string | Rule::lex_char => LayoutTokenKind::Literal,
+        Rule::expr_number_lit
+        | Rule::expr_bool_lit
+        | Rule::expr_nullptr_lit
+        | Rule::lex_field_accessor
+        | Rule::capital_name
+        | Rule::name => LayoutTokenKind::Word,
+        Rule::lex_keyword => LayoutTokenKind::Keyword,
+        _ => LayoutTokenKind::Symbol,
+    }
+}
+
+/// Scan `content` into the tokens the source formatter lays out, covering every byte of it: unlike
+/// `lex_tokens`, whitespace and line breaks are tokens too, and every punctuation character is
+/// kept. The returned tokens are ordered by, and do not overlap in, their source position.
+///
+/// The scan is meant for a source the parser accepts; on other input it still never fails, but a
+/// string literal left open is read only up to the end of its line.
+pub fn lex_layout_tokens(content: &str) -> Vec<LayoutToken> {
+    let mut pairs = match FixParser::parse(Rule::layout_tokens, content) {
+        Ok(pairs) => pairs,
+        Err(_) => return vec![],
+    };
+    // `Rule::layout_tokens` always yields exactly one top pair.
+    let Some(top) = pairs.next() else {
+        return vec![];
+    };
+
+    let mut tokens = vec![];
+    for layout_token in top.into_inner() {
+        // Skip the trailing `EOI` pair produced by pest.
+        if layout_token.as_rule() != Rule::layout_token {
+            continue;
+        }
+        // `layout_token` wraps exactly one rule, which is `lex_token` for anything but whitespace,
+        // comments and string literals; `lex_token` in turn wraps one category rule.
+        let Some(mut inner) = layout_token.into_inner().next() else {
+            continue;
+        };
+        if inner.as_rule() == Rule::lex_token {
+            let Some(category) = inner.into_inner().next() else {
+                continue;
+            };
+            inner = category;
+        }
+        let span = inner.as_span();
+        tokens.push(LayoutToken {
+            start: span.start(),
+            end: span.end(),
+            kind: layout_kind_of_rule(inner.as_rule()),
+        });
+    }
+    tokens
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -451,4 +545,40 @@ mod tests {
             ]
         );
     }
+
+    /// Verifies that the layout scan covers every byte, keeping whitespace, line breaks and
+    /// punctuation as tokens of their own.
+    #[test]
+    fn layout_scan_covers_whole_source() {
+        use LayoutTokenKind as L;
+        let src = "f(x) = \"a\nb\"; // c\n";
+        let toks = lex_layout_tokens(src);
+        let rendered: Vec<(LayoutTokenKind, &str)> = toks
+            .iter()
+            .map(|t| (t.kind, &src[t.start..t.end]))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                (L::Word, "f"),
+                (L::Symbol, "("),
+                (L::Word, "x"),
+                (L::Symbol, ")"),
+                (L::Space, " "),
+                (L::Symbol, "="),
+                (L::Space, " "),
+                (L::Literal, "\"a\nb\""),
+                (L::Symbol, ";"),
+                (L::Space, " "),
+                (L::LineComment, "// c"),
+                (L::Newline, "\n"),
+            ]
+        );
+        let mut covered = 0;
+        for t in &toks {
+            assert_eq!(t.start, covered, "the layout scan left a gap");
+            covered = t.end;
+        }
+        assert_eq!(covered, src.len());
+    }
 }
@@ -6,16 +6,17 @@ pub(crate) struct FixParser;
 
 use crate::ast::{
     deprecation::DeprecationStatement,
+    derive::{make_derived_impls_mod, DerivableTrait, DeriveStatement},
     equality::Equality,
     export_statement::ExportStatement,
     expr::{
         expr_abs, expr_abs_param_src, expr_app, expr_array_lit, expr_eval, expr_ffi_call,
         expr_hole, expr_if, expr_let, expr_make_struct, expr_make_struct_with_spans, expr_match,
-        expr_tyanno, expr_var, var_local, var_var, AppSourceCodeOrderType, ExprNode, Var,
+        expr_tyanno, expr_var, var_local, var_var, AppSourceCodeOrderType, Expr, ExprNode, Var,
     },
     import::{ImportStatement, ImportTreeNode},
     name::{FullName, Name, NameSpace},
-    pattern::PatternNode,
+    pattern::{Pattern, PatternNode},
     predicate::Predicate,
     program::{GlobalValueDecl, GlobalValueDefn, ModuleInfo, Program},
     qual_pred::QualPred,
@@ -32,16 +33,17 @@ use crate::ast::{
 };
 use crate::configuration::{Configuration, DiagnosticsConfig, SubCommand};
 use crate::constants::{
-    COMPOSE_FUNCTION_NAME, F64_NAME, I64_NAME, INDEXABLE_TRAIT_ACT_NAME, INDEXABLE_TRAIT_NAME,
-    IO_DATA_NAME, MODULE_SEPARATOR, MONAD_BIND_NAME, MONAD_NAME, PARAM_NAME,
-    PATTERN_WILDCARD_VAR_PREFIX, STD_NAME, STRUCT_ACT_SYMBOL, TYPE_WILDCARD_VAR_PREFIX,
+    BOOL_NAME, COMPOSE_FUNCTION_NAME, ERR_NON_EXHAUSTIVE_MATCH, F64_NAME, I64_NAME,
+    INDEXABLE_TRAIT_ACT_NAME, INDEXABLE_TRAIT_NAME, IO_DATA_NAME, MATCH_VALUE_VAR_NAME,
+    MODULE_SEPARATOR, MONAD_BIND_NAME, MONAD_NAME, PARAM_NAME, PATTERN_WILDCARD_VAR_PREFIX,
+    STD_NAME, STRUCT_ACT_SYMBOL, TYPE_WILDCARD_VAR_PREFIX,
 };
 use crate::error::Errors;
 use crate::fixstd::builtin::{
     expr_bool_lit, expr_float_lit, expr_int_lit, expr_nullptr_lit, integral_ty_range, make_f64_ty,
     make_i64_ty, make_io_tycon, make_numeric_ty, make_string_lit, make_tuple_name_abs, make_u8_ty,
-    ADD_TRAIT_ADD_NAME, ADD_TRAIT_NAME, DIVIDE_TRAIT_DIVIDE_NAME, DIVIDE_TRAIT_NAME,
-    EQ_TRAIT_EQ_NAME, EQ_TRAIT_NAME, LESS_THAN_OR_EQUAL_TO_TRAIT_NAME,
+    InlineLLVMIntLit, ADD_TRAIT_ADD_NAME, ADD_TRAIT_NAME, DIVIDE_TRAIT_DIVIDE_NAME,
+    DIVIDE_TRAIT_NAME, EQ_TRAIT_EQ_NAME, EQ_TRAIT_NAME, LESS_THAN_OR_EQUAL_TO_TRAIT_NAME,
     LESS_THAN_OR_EQUAL_TO_TRAIT_OP_NAME, LESS_THAN_TRAIT_LT_NAME, LESS_THAN_TRAIT_NAME,
     MULTIPLY_TRAIT_MULTIPLY_NAME, MULTIPLY_TRAIT_NAME, NEGATE_TRAIT_NAME, NEGATE_TRAIT_NEGATE_NAME,
     NOT_TRAIT_NAME, NOT_TRAIT_OP_NAME, REMAINDER_TRAIT_NAME, REMAINDER_TRAIT_REMAINDER_NAME,
@@ -472,6 +474,16 @@ pub fn parse_source_file(source: SourceFile, config: &Configuration) -> Result<P
     parse_file(file, source_cloned, config)
 }
 
+/// Checks that the grammar accepts the content of `source`, reporting a failure as the compilation
+/// of that source reports it. No program is built, so nothing beyond the grammar is checked.
+pub fn validate_source_syntax(source: &SourceFile) -> Result<(), Errors> {
+    let source_code = source.string()?;
+    match FixParser::parse(Rule::file, &source_code) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(message_parse_error(e, source)),
+    }
+}
+
 /// The program the parsed file `file` declares, which is the one module it is made of. `src` is the
 /// source it was parsed from, which its spans point into.
 fn parse_file(
@@ -561,6 +573,7 @@ fn parse_module(
     let mut import_statements: Vec<ImportStatement> = vec![];
     let mut export_statements: Vec<ExportStatement> = vec![];
     let mut deprecation_statements: Vec<DeprecationStatement> = vec![];
+    let mut derive_statements: Vec<DeriveStatement> = vec![];
 
     for pair in pairs {
         match pair.as_rule() {
@@ -575,6 +588,7 @@ fn parse_module(
                 &mut trait_impls,
                 &mut export_statements,
                 &mut deprecation_statements,
+                &mut derive_statements,
             )),
             Rule::import_statement => {
                 import_statements.push(parse_import_statement(pair, &mut ctx));
@@ -590,6 +604,20 @@ fn parse_module(
     fix_mod.used_tuple_sizes.append(&mut ctx.tuple_sizes);
     fix_mod.export_statements = replace(&mut export_statements, vec![]);
     fix_mod.deprecation_statements = replace(&mut deprecation_statements, vec![]);
+    if !derive_statements.is_empty() {
+        // The implementations are generated here, where the definitions of the types are known.
+        let derived = make_derived_impls_mod(
+            &ctx.module_name,
+            &derive_statements,
+            &fix_mod.type_defns,
+            config,
+        );
+        match derived {
+            Ok(derived) => errors.eat_err(fix_mod.link(derived, true)),
+            Err(e) => errors.append(e),
+        }
+        fix_mod.derive_statements = replace(&mut derive_statements, vec![]);
+    }
 
     fix_mod.inject_abs_path_implicit_imports(&ctx.module_name, take(&mut ctx.abs_path_uses));
 
@@ -610,6 +638,7 @@ fn parse_global_defns(
     trait_impls: &mut Vec<TraitImpl>,
     export_statements: &mut Vec<ExportStatement>,
     deprecation_statements: &mut Vec<DeprecationStatement>,
+    derive_statements: &mut Vec<DeriveStatement>,
 ) -> Result<(), Errors> {
     assert_eq!(pair.as_rule(), Rule::global_defns);
     let mut errors = Errors::empty();
@@ -629,6 +658,7 @@ fn parse_global_defns(
                     trait_impls,
                     export_statements,
                     deprecation_statements,
+                    derive_statements,
                 ));
             }
             Rule::type_defn => {
@@ -666,6 +696,11 @@ fn parse_global_defns(
                     deprecation_statements.push(stmt);
                 });
             }
+            Rule::derive_statement => {
+                errors.eat_err_or(parse_derive_statement(pair, ctx), |stmt| {
+                    derive_statements.push(stmt);
+                });
+            }
             _ => unreachable!(),
         }
     }
@@ -685,6 +720,7 @@ fn parse_global_defns_in_namespace(
     trait_impls: &mut Vec<TraitImpl>,
     export_statements: &mut Vec<ExportStatement>,
     deprecation_statements: &mut Vec<DeprecationStatement>,
+    derive_statements: &mut Vec<DeriveStatement>,
 ) -> Result<(), Errors> {
     assert_eq!(pair.as_rule(), Rule::global_defns_in_namespace);
     let src = Span::from_pair(&ctx.source, &pair);
@@ -713,6 +749,7 @@ fn parse_global_defns_in_namespace(
             trait_impls,
             export_statements,
             deprecation_statements,
+            derive_statements,
         )?;
     }
     ctx.namespace = bak_namespace;
@@ -1165,6 +1202,62 @@ fn parse_deprecated_statement(
     })
 }
 
+/// Parse a `DERIVE[<type>, <trait>, ...];` statement into a `DeriveStatement` naming the type
+/// under the current namespace and the traits to implement for it.
+fn parse_derive_statement(
+    pair: Pair<Rule>,
+    ctx: &mut ParseContext,
+) -> Result<DeriveStatement, Errors> {
+    assert_eq!(pair.as_rule(), Rule::derive_statement);
+    let mut pairs = pair.into_inner();
+    pairs.next().unwrap(); // Skip `DERIVE`.
+    let type_pair = pairs.next().unwrap();
+    let type_name_src = Some(Span::from_pair(&ctx.source, &type_pair));
+    let mut type_name = parse_capital_fullname(type_pair, ctx).join_under(&ctx.namespace);
+    // An absolute path names the same type as the path without the leading `::`.
+    type_name.namespace.is_absolute = false;
+
+    let mut errors = Errors::empty();
+    let mut traits: Vec<(DerivableTrait, Option<Span>)> = vec![];
+    for pair in pairs {
+        if pair.as_rule() != Rule::capital_fullname {
+            continue; // The semicolon.
+        }
+        let trait_src = Some(Span::from_pair(&ctx.source, &pair));
+        let path = parse_capital_fullname(pair, ctx);
+        match DerivableTrait::from_path(&path) {
+            Some(derived) if traits.iter().any(|(t, _)| *t == derived) => {
+                errors.append(Errors::from_msg_srcs(
+                    format!("`{}` is derived twice.", derived.name()),
+                    &[&trait_src],
+                ));
+            }
+            Some(derived) => traits.push((derived, trait_src)),
+            None => {
+                let derivable = DerivableTrait::ALL
+                    .iter()
+                    .map(|t| format!("`{}`", t.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                errors.append(Errors::from_msg_srcs(
+                    format!(
+                        "`{}` cannot be derived. The traits `DERIVE` implements are {}.",
+                        path.to_string(),
+                        derivable
+                    ),
+                    &[&trait_src],
+                ));
+            }
+        }
+    }
+    errors.to_result()?;
+    Ok(DeriveStatement {
+        type_name,
+        type_name_src,
+        traits,
+    })
+}
+
 fn parse_predicate_qualified(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<QualPred, Errors> {
     assert_eq!(pair.as_rule(), Rule::predicate_qualified);
     let mut pairs = pair.into_inner();
@@ -1554,6 +1647,17 @@ fn parse_expr_or_hole(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<Arc<Ex
 /// inside the sub-expression do not leak into the surrounding
 /// context. The hole case has no `*` operators, so `expand_binds` is
 /// a no-op for it.
+fn parse_expr_with_new_do(
+    pair: Pair<Rule>,
+    ctx: &mut ParseContext,
+) -> Result<Arc<ExprNode>, Errors> {
+    let old_doctx = replace(&mut ctx.do_context, DoContext::default());
+    let expr = parse_expr(pair, ctx)?;
+    let expr = ctx.do_context.expand_binds(expr);
+    ctx.do_context = old_doctx;
+    Ok(expr)
+}
+
 fn parse_expr_or_hole_with_new_do(
     pair: Pair<Rule>,
     ctx: &mut ParseContext,
@@ -2450,29 +2554,174 @@ fn parse_expr_match(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<Arc<Expr
     let mut pairs = pair.into_inner();
     let cond = pairs.next().unwrap();
     let cond = parse_expr(cond, ctx)?;
-    let mut cases = vec![];
+    let mut arms = vec![];
     while pairs.peek().is_some() {
         let pair = pairs.next().unwrap();
-        let pat = parse_pattern_case(pair, ctx); // Parse pattern.
-        let pair = pairs.next().unwrap();
+        let pat = parse_pattern_case(pair, ctx)?; // Parse pattern.
+        let mut pair = pairs.next().unwrap();
+        let guard = if pair.as_rule() == Rule::match_guard {
+            let guard = parse_expr_with_new_do(pair.into_inner().next().unwrap(), ctx)?;
+            pair = pairs.next().unwrap();
+            Some(guard)
+        } else {
+            None
+        };
         assert_eq!(pair.as_rule(), Rule::match_arrow); // Skip `=>`.
         let pair = pairs.next().unwrap();
-        let expr = parse_expr_or_hole_with_new_do(pair, ctx)?; // Parse value expression.
+        let val = parse_expr_or_hole_with_new_do(pair, ctx)?; // Parse value expression.
         if pairs.peek().is_some() {
             // Skip `,` if exists.
             let pair = pairs.next().unwrap();
             assert_eq!(pair.as_rule(), Rule::comma_);
         }
-        cases.push((pat, expr));
+        arms.push(MatchArmSyntax { pat, guard, val });
     }
     // Forbid empty match.
-    if cases.is_empty() {
+    if arms.is_empty() {
         return Err(Errors::from_msg_srcs(
             "Empty `match` is not allowed.".to_string(),
             &[&Some(span)],
         ));
     }
-    Ok(expr_match(cond, cases, Some(span)))
+    // An arm with a guard or a string literal is tested by an `if`.
+    let last_tested = arms
+        .iter()
+        .rposition(|arm| arm.guard.is_some() || arm.pat.pat.is_none());
+    match last_tested {
+        Some(last_tested) => desugar_match_arms(cond, arms, last_tested, span),
+        None => {
+            let cases = arms
+                .into_iter()
+                .map(|arm| (arm.pat.pat.unwrap(), arm.val))
+                .collect();
+            Ok(expr_match(cond, cases, Some(span)))
+        }
+    }
+}
+
+/// An arm of a `match` as it is written.
+struct MatchArmSyntax {
+    pat: ArmPattern,
+    guard: Option<Arc<ExprNode>>,
+    val: Arc<ExprNode>,
+}
+
+/// The pattern of a `match` arm. A literal keeps its expression besides, which the matched value is
+/// compared with where the arm is tested by an `if`. A string literal has no pattern, and is always
+/// compared.
+struct ArmPattern {
+    pat: Option<Arc<PatternNode>>,
+    lit: Option<Arc<ExprNode>>,
+    src: Span,
+}
+
+/// A `match` of `cond` some of whose arms have guards or string literals, as a chain of `if`s
+/// testing the arms in order up to `arms[last_tested]`, the last of those, which ends in a `match`
+/// of the arms after it.
+///
+/// The matched value is bound to `MATCH_VALUE_VAR_NAME`. An arm is taken where its pattern matches
+/// the value and its guard, evaluated with the names of the pattern bound, holds. The ending `match`
+/// also has the arms before `arms[last_tested]` that a pattern alone tests, with values it never
+/// reaches, so that the type check finds whether the arms cover every value.
+fn desugar_match_arms(
+    cond: Arc<ExprNode>,
+    arms: Vec<MatchArmSyntax>,
+    last_tested: usize,
+    span: Span,
+) -> Result<Arc<ExprNode>, Errors> {
+    let matched = || expr_var(FullName::local(MATCH_VALUE_VAR_NAME), None);
+
+    // An arm that matches every value leaves nothing to the arms after it.
+    for (i, arm) in arms[..last_tested].iter().enumerate() {
+        let Some(pat) = &arm.pat.pat else {
+            continue;
+        };
+        if arm.guard.is_none() && pat.is_irrefutable() {
+            return Err(Errors::from_msg_srcs(
+                format!(
+                    "Pattern after `{}` is unreachable.",
+                    pat.pattern.to_string()
+                ),
+                &[&Some(arms[i + 1].pat.src.clone())],
+            ));
+        }
+    }
+
+    // The arms of the ending `match`.
+    let mut rest = vec![];
+    for arm in &arms[..last_tested] {
+        if let (Some(pat), None) = (&arm.pat.pat, &arm.guard) {
+            let mut undefined = FullName::from_strs(&[STD_NAME], "undefined");
+            undefined.global_to_absolute();
+            let msg = "An arm of `match` tested by `if` is reached again.".to_string();
+            let val = expr_app(
+                expr_var(undefined, None),
+                vec![make_string_lit(msg, None)],
+                None,
+            );
+            rest.push((pat.clone(), val));
+        }
+    }
+    for arm in &arms[last_tested + 1..] {
+        rest.push((arm.pat.pat.clone().unwrap(), arm.val.clone()));
+    }
+    if rest.is_empty() {
+        let mut err = crate::error::Error::from_msg_srcs(
+            "Every arm of this `match` has a guard or a string literal, so a value may match none of them. Add an arm without a guard, such as `_ => ...`, after them.".to_string(),
+            &[&Some(span)],
+        );
+        err.code = Some(ERR_NON_EXHAUSTIVE_MATCH);
+        return Err(Errors::from_err(err));
+    }
+    let mut expr = expr_match(matched(), rest, Some(span.clone()));
+
+    for arm in arms[..=last_tested].iter().rev() {
+        let src = Some(arm.pat.src.clone());
+        // Whether the pattern matches the value, where it can fail to.
+        let test = match (&arm.pat.lit, arm.pat.pat.as_ref().map(|pat| &pat.pattern)) {
+            (Some(lit), _) => {
+                let mut eq = FullName::from_strs(&[STD_NAME, EQ_TRAIT_NAME], EQ_TRAIT_EQ_NAME);
+                eq.global_to_absolute();
+                Some(expr_app(
+                    expr_app(expr_var(eq, None), vec![lit.clone()], src.clone()),
+                    vec![matched()],
+                    src.clone(),
+                ))
+            }
+            (None, Some(Pattern::Union(variant, _, _))) => {
+                let is_variant = FullName::new(&variant.namespace, &format!("is_{}", variant.name));
+                Some(expr_app(
+                    expr_var(is_variant, None),
+                    vec![matched()],
+                    src.clone(),
+                ))
+            }
+            _ => None,
+        };
+        // `val` with the names of the pattern bound.
+        let bind = |val: &Arc<ExprNode>| match arm.pat.pat.as_ref().map(|pat| &pat.pattern) {
+            Some(Pattern::Union(variant, _, subpat)) => {
+                let as_variant = FullName::new(&variant.namespace, &format!("as_{}", variant.name));
+                let payload = expr_app(expr_var(as_variant, None), vec![matched()], src.clone());
+                expr_let(subpat.clone(), payload, val.clone(), None)
+            }
+            Some(Pattern::Var(_, _)) | Some(Pattern::Struct(_, _)) => {
+                expr_let(arm.pat.pat.clone().unwrap(), matched(), val.clone(), None)
+            }
+            Some(Pattern::Lit(_, _)) | None => val.clone(),
+        };
+        let test = match (test, &arm.guard) {
+            (Some(test), Some(guard)) => {
+                expr_if(test, bind(guard), expr_bool_lit(false, None), None)
+            }
+            (None, Some(guard)) => bind(guard),
+            (Some(test), None) => test,
+            (None, None) => unreachable!("an arm tested by `if` can fail to match"),
+        };
+        expr = expr_if(test, bind(&arm.val), expr, None);
+    }
+    let matched_pat = PatternNode::make_var(var_local(MATCH_VALUE_VAR_NAME), None);
+    Ok(expr_let(matched_pat, cond, expr, Some(span)))
 }
 
 fn parse_expr_do(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<Arc<ExprNode>, Errors> {
@@ -2623,22 +2872,30 @@ fn parse_expr_call_c(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<Arc<Exp
 
 // Parses one type written in a C function signature into the Fix type constructor that represents
 // it. A C type name such as `CInt` becomes the sized type it has on the target, and `()` becomes
-// the unit type, which stands for `void`.
+// the unit type, which stands for `void`. Any other name is a struct passed by value, and is resolved
+// later as a type name written anywhere else is.
 fn parse_ffi_c_fun_ty(pair: Pair<Rule>, ctx: &mut ParseContext) -> Arc<TyCon> {
     assert_eq!(pair.as_rule(), Rule::ffi_c_fun_ty);
-    let mut name = if pair.as_str() == "()" {
-        make_tuple_name_abs(0)
-    } else {
-        let mut name = pair.as_str().to_string();
-        for (c_type_name, sign, size) in ctx.config.c_type_sizes.get_c_types() {
-            if c_type_name == pair.as_str() {
-                name = format!("{}{}", sign, size);
-            }
+    if pair.as_str() == "()" {
+        let mut name = make_tuple_name_abs(0);
+        name.set_absolute();
+        return tycon(name);
+    }
+    let mut scalar_name = pair.as_str().to_string();
+    for (c_type_name, sign, size) in ctx.config.c_type_sizes.get_c_types() {
+        if c_type_name == pair.as_str() {
+            scalar_name = format!("{}{}", sign, size);
         }
-        FullName::from_strs(&[STD_NAME], &name)
-    };
-    name.set_absolute();
-    tycon(name)
+    }
+    let mut name = FullName::from_strs(&[STD_NAME], &scalar_name);
+    if tycon(name.clone()).is_c_scalar() {
+        name.set_absolute();
+        return tycon(name);
+    }
+    tycon(parse_capital_fullname(
+        pair.into_inner().next().unwrap(),
+        ctx,
+    ))
 }
 
 // Parses the parameter types of a C function signature written in `FFI_CALL`. A parameter written
@@ -3100,18 +3357,71 @@ fn parse_pattern_nounion(pair: Pair<Rule>, ctx: &mut ParseContext) -> Arc<Patter
     .set_source(span)
 }
 
-fn parse_pattern_case(pair: Pair<Rule>, ctx: &mut ParseContext) -> Arc<PatternNode> {
+fn parse_pattern_case(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<ArmPattern, Errors> {
     assert_eq!(pair.as_rule(), Rule::pattern_case);
     let span = Span::from_pair(&ctx.source, &pair);
     let pair = pair.into_inner().next().unwrap();
-    match pair.as_rule() {
+    let pat = match pair.as_rule() {
+        Rule::pattern_lit => return parse_pattern_lit(pair, ctx),
         Rule::pattern_var => parse_pattern_var(pair, ctx),
         Rule::pattern_union => parse_pattern_union(pair, ctx),
         Rule::pattern_struct => parse_pattern_struct(pair, ctx),
         Rule::pattern_tuple => parse_pattern_tuple(pair, ctx),
         _ => unreachable!(),
     }
-    .set_source(span)
+    .set_source(span.clone());
+    Ok(ArmPattern {
+        pat: Some(pat),
+        lit: None,
+        src: span,
+    })
+}
+
+// Parses a literal pattern. An integer or a `U8` character becomes a literal pattern, and `true` or
+// `false` the pattern of a variant of `Bool`; a string has no pattern.
+fn parse_pattern_lit(pair: Pair<Rule>, ctx: &mut ParseContext) -> Result<ArmPattern, Errors> {
+    assert_eq!(pair.as_rule(), Rule::pattern_lit);
+    let span = Span::from_pair(&ctx.source, &pair);
+    let pair = pair.into_inner().next().unwrap();
+    let rule = pair.as_rule();
+    let text = pair.as_str();
+    let lit = match rule {
+        Rule::expr_number_lit => parse_expr_number_lit(pair, ctx)?,
+        Rule::expr_u8_lit => parse_expr_u8_lit(pair, ctx),
+        Rule::expr_string_lit => parse_expr_string_lit(pair, ctx)?,
+        Rule::expr_bool_lit => parse_expr_bool_lit(pair, ctx),
+        _ => unreachable!(),
+    };
+    let pat = match rule {
+        Rule::expr_string_lit => None,
+        Rule::expr_bool_lit => {
+            let tag = if text == "true" { "_true" } else { "_false" };
+            let mut variant = FullName::from_strs(&[STD_NAME, BOOL_NAME], tag);
+            variant.global_to_absolute();
+            let unit = PatternNode::make_struct(tycon(make_tuple_name_abs(0)), vec![]);
+            Some(PatternNode::make_union_with_span(variant, None, unit))
+        }
+        _ => {
+            let Expr::LLVM(llvm) = &*lit.expr else {
+                unreachable!("a number literal is an inline LLVM expression")
+            };
+            let Some(int_lit) = llvm.generator.as_any().downcast_ref::<InlineLLVMIntLit>() else {
+                return Err(Errors::from_msg_srcs(
+                    "A floating point number cannot be a pattern.".to_string(),
+                    &[&Some(span)],
+                ));
+            };
+            Some(PatternNode::make_lit(
+                int_lit.val(),
+                llvm.generic_ty.clone(),
+            ))
+        }
+    };
+    Ok(ArmPattern {
+        pat: pat.map(|pat| pat.set_source(span.clone())),
+        lit: Some(lit),
+        src: span,
+    })
 }
 
 fn parse_pattern_var(pair: Pair<Rule>, ctx: &mut ParseContext) -> Arc<PatternNode> {
@@ -3313,10 +3623,13 @@ fn rule_to_string(r: &Rule) -> String {
         Rule::semicolon => "`;`".to_string(),
         Rule::comma_ => ",".to_string(),
         Rule::export_symbol => "FFI_EXPORT".to_string(),
+        Rule::derive_symbol => "DERIVE".to_string(),
         Rule::global_defns => "definitions".to_string(),
         Rule::exported_c_function_name => "C function name".to_string(),
         Rule::operator_and_then => "`;;`".to_string(),
         Rule::match_arrow => "`=>`".to_string(),
+        Rule::match_guard => "`if`".to_string(),
+        Rule::pattern_lit => "literal".to_string(),
         Rule::double_colon => "`::`".to_string(),
         _ => format!("{:?}", r),
     }
@@ -176,13 +176,17 @@ pub enum RcTarget {
 /// One arm of a `Match`: the variant it matches, the variable its payload is bound to, the state of
 /// the payload it retains out of a boxed union, and the arm body, whose value is its final `Ret`.
 /// `tag` is `Some` for a variant arm, whose payload is that variant's value; it is `None` for a
-/// catch-all arm, whose payload is the whole scrutinee.
+/// catch-all arm, whose payload is the whole scrutinee. A literal arm of a match on an integer has
+/// `tag` `None` too, and `lit` the bits of the value it matches; as a catch-all does, it binds the
+/// scrutinee as its payload.
 /// Code generation treats the last arm as the default case (mirroring the tag switch), so a
 /// catch-all is always the final arm.
 #[derive(Clone)]
 pub struct MatchArm {
     /// The variant number this arm matches, or `None` for a catch-all arm.
     pub tag: Option<usize>,
+    /// The integer value this arm matches, for a literal arm.
+    pub lit: Option<u64>,
     /// The variable `body` reads the matched value through.
     pub payload: RcVar,
     /// What is known about the payload a variant arm of a boxed union retains out of the container.
@@ -12,7 +12,7 @@ use crate::constants::{
     CLOSURE_FUNPTR_IDX, DYNAMIC_OBJ_CAP_IDX,
 };
 use crate::fixstd::builtin::make_dynamic_object_ty;
-use crate::fixstd::runtime::RUNTIME_PTHREAD_ONCE;
+use crate::fixstd::runtime::{RcProfileEvent, RUNTIME_PTHREAD_ONCE};
 use crate::generator::{
     global_accessor_name, object_file_symbol_name, EmittedGlobal, Generator, Object,
 };
@@ -231,6 +231,7 @@ impl<'c, 'm> Generator<'c, 'm> {
             RcExpr::Retain(x, path, state, k) => {
                 let obj = self.get_scoped_obj_noretain(&x.name);
                 let obj = self.project_rc_unit(obj, path);
+                self.build_profile_rc_event(RcProfileEvent::Retain);
                 if x.skip_null_check {
                     // A statically non-null boxed value (a non-empty capture object): retain
                     // without the null check that a possibly-null capture object needs.
@@ -258,6 +259,7 @@ impl<'c, 'm> Generator<'c, 'm> {
             RcExpr::Release(x, path, state, k) => {
                 let obj = self.get_scoped_obj_noretain(&x.name);
                 let obj = self.project_rc_unit(obj, path);
+                self.build_profile_rc_event(RcProfileEvent::Release);
                 if x.skip_null_check {
                     // A statically non-null boxed value (a non-empty capture object): release
                     // without the null check that a possibly-null capture object needs.
@@ -529,6 +531,8 @@ impl<'c, 'm> Generator<'c, 'm> {
         };
 
         // A basic block per arm, and the (tag, block) cases for the switch (all arms but the last).
+        // A match on an integer switches on its value, each literal arm giving a case.
+        let is_lit_match = arms.iter().any(|arm| arm.lit.is_some());
         let mut arm_bbs = vec![];
         for (i, _arm) in arms.iter().enumerate() {
             arm_bbs.push(
@@ -538,14 +542,30 @@ impl<'c, 'm> Generator<'c, 'm> {
         }
         let else_bb = *arm_bbs.last().expect("a match has at least one arm");
         let mut cases: Vec<(IntValue<'c>, BasicBlock<'c>)> = vec![];
+        let int_val = if is_lit_match {
+            Some(scrut_obj.extract_field(self, 0).into_int_value())
+        } else {
+            None
+        };
         for (i, arm) in arms.iter().enumerate().take(arms.len() - 1) {
+            if let Some(int_val) = int_val {
+                let lit = arm
+                    .lit
+                    .expect("a non-final arm of a match on an integer must be a literal arm");
+                cases.push((int_val.get_type().const_int(lit, false), arm_bbs[i]));
+                continue;
+            }
             let tag = arm
                 .tag
                 .expect("a non-final match arm must be a variant arm");
             let tag_val = union_tag_type(self.context).const_int(tag as u64, false);
             cases.push((tag_val, arm_bbs[i]));
         }
-        if cases.is_empty() {
+        if let Some(int_val) = int_val {
+            self.builder()
+                .build_switch(int_val, else_bb, &cases)
+                .unwrap();
+        } else if cases.is_empty() {
             // The only arm takes every value of the scrutinee: it is either a catch-all, or the one
             // variant of its union. A variant arm standing alone over a multi-variant union would
             // bind the payload of whichever variant is actually there.
@@ -578,6 +598,7 @@ impl<'c, 'm> Generator<'c, 'm> {
                     );
                     if scrut_is_boxed {
                         let one = self.context.i64_type().const_int(1, false);
+                        self.build_profile_rc_event(RcProfileEvent::Retain);
                         self.build_retain(value.clone(), one, arm.payload_state);
                     }
                     value
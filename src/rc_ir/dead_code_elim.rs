@@ -386,6 +386,7 @@ mod tests {
                 at(4),
                 vec![MatchArm {
                     tag: Some(0),
+                    lit: None,
                     payload: local("payload"),
                     payload_state: RcState::Unknown,
                     body: node(RcExpr::Ret(at(5))),
@@ -0,0 +1,595 @@
+//! Explanations of the uniqueness checks that unique-check elimination keeps, for
+//! `--explain-uniqueness`.
+//!
+//! An operation that mutates a container in place (`Array::set`, `swap`, `mod`/`act` through punch
+//! and plug, struct `set`/`mod`) checks at run time whether the container is shared, and clones it
+//! if it is, unless `unique_check_elim` proved the container unique there and dropped the check. This
+//! module reads the program that pass leaves and reports every operation still carrying its check, at
+//! the place in the user's sources it was written, together with why the container is not known to
+//! be unique there.
+//!
+//! The provenance analysis says *whether* a container is unique at an operation; it does not keep
+//! where an `Unknown` came from. So the explanation traces the container backward from the
+//! operation, through the bindings it was renamed, projected and matched along, to the first fact
+//! that makes it shared: a `Retain` made because the value is used again later, a read out of a boxed
+//! container, the result of a call, a global, or a parameter some call passes a value of unknown
+//! sharing.
+
+use crate::ast::inline_llvm::LLVMGen;
+use crate::ast::name::FullName;
+use crate::ast::program::TypeEnv;
+use crate::ast::types::TypeNode;
+use crate::configuration::Configuration;
+use crate::error::{Error, Errors, NOTE_KEPT_UNIQUE_CHECK};
+use crate::misc::{grow_stack, to_absolute_path, Map, Set};
+use crate::optimization::simplify_symbol_names::get_base_name;
+use crate::parse::sourcefile::Span;
+use crate::rc_ir::ast::{FieldPath, MatchArm, RcExpr, RcExprNode, RcFunc, RcProgram, RcRhs, RcVar};
+use crate::rc_ir::provenance::{analyze_program, LeafOrigin, ProvenanceAnalysis};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::Arc;
+
+/// The number of call sites a note on a parameter points to, at most.
+const MAX_CALL_SITES_SHOWN: usize = 3;
+
+/// One reason a value is not known to be unique: a sentence, and the place it is about.
+type Reason = (String, Option<Span>);
+
+/// A construct between a function's entry and a program point inside it, which the trace reads
+/// backward from that point.
+#[derive(Clone, Copy)]
+enum Step<'a> {
+    /// A statement whose continuation the program point lies in.
+    Node(&'a RcExprNode),
+    /// The entry into an arm of a `match` on the variable: the arm binds its payload from it.
+    Arm(&'a RcVar, &'a MatchArm),
+}
+
+/// A note for every operation of `prog` that checks a container's uniqueness at run time, written in
+/// one of the user's source files (`Configuration::root_source_files`), ordered by source location.
+/// `prog` is the program unique-check elimination leaves, so what is reported is what the program
+/// built from it checks.
+pub fn explain_kept_unique_checks(
+    prog: &RcProgram,
+    type_env: &TypeEnv,
+    config: &Configuration,
+) -> Errors {
+    let user_files: Set<PathBuf> = config
+        .root_source_files
+        .iter()
+        .filter_map(|path| to_absolute_path(path).ok())
+        .collect();
+    let mut explainer = Explainer {
+        type_env,
+        analysis: analyze_program(prog, type_env),
+        call_sites: call_sites(prog),
+        user_files,
+        is_user_file: RefCell::new(Map::default()),
+        reported: Set::default(),
+        notes: vec![],
+    };
+
+    // Walk in name order, so that of the versions specialization made of one function, the same one
+    // is explained on every run.
+    let mut funcs: Vec<&RcFunc> = prog.funcs.values().collect();
+    funcs.sort_by(|a, b| a.name.name.cmp(&b.name.name));
+    for func in funcs {
+        explainer.walk(Some(func), &func.body, &mut vec![]);
+    }
+    for glob in &prog.globals {
+        explainer.walk(None, &glob.init, &mut vec![]);
+    }
+
+    let mut notes = explainer.notes;
+    notes.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+    let mut errs = Errors::empty();
+    for (_, note) in notes {
+        errs.append(Errors::from_err(note));
+    }
+    errs
+}
+
+/// The places each function of `prog` is called directly from, keyed by the function's name.
+fn call_sites(prog: &RcProgram) -> Map<FullName, Vec<Option<Span>>> {
+    fn collect(node: &RcExprNode, sites: &mut Map<FullName, Vec<Option<Span>>>) {
+        let mut node = node;
+        loop {
+            match node.expr.as_ref() {
+                RcExpr::Let(_, rhs, k) => {
+                    match rhs {
+                        RcRhs::App(callee, _) => {
+                            let site = callee.source.clone().or_else(|| node.source.clone());
+                            sites.entry(callee.name.clone()).or_default().push(site);
+                        }
+                        RcRhs::Match(_, arms) => {
+                            for arm in arms {
+                                grow_stack(|| collect(&arm.body, sites));
+                            }
+                        }
+                        RcRhs::Var(_) | RcRhs::Closure(..) | RcRhs::Llvm(..) => {}
+                    }
+                    node = k;
+                }
+                RcExpr::Retain(_, _, _, k)
+                | RcExpr::Release(_, _, _, k)
+                | RcExpr::Destructure(_, _, _, k)
+                | RcExpr::Eval(_, k) => node = k,
+                RcExpr::Ret(_) => return,
+            }
+        }
+    }
+    let mut sites = Map::default();
+    for func in prog.funcs.values() {
+        collect(&func.body, &mut sites);
+    }
+    for glob in &prog.globals {
+        collect(&glob.init, &mut sites);
+    }
+    sites
+}
+
+/// The state of one explanation run over a program.
+struct Explainer<'a> {
+    /// The type definitions, for telling a boxed container from an unboxed one.
+    type_env: &'a TypeEnv,
+    /// The provenance analysis of the program, whose facts at each operation say which of the
+    /// container's origins are not known to be unique.
+    analysis: ProvenanceAnalysis,
+    /// The places each function is called directly from (`call_sites`).
+    call_sites: Map<FullName, Vec<Option<Span>>>,
+    /// The absolute paths of the user's source files.
+    user_files: Set<PathBuf>,
+    /// Whether each file a span names is one of `user_files`, as it is asked.
+    is_user_file: RefCell<Map<PathBuf, bool>>,
+    /// The places already reported, each as its file and byte range. Specialization copies a function
+    /// per input uniqueness, and a check each copy keeps is one check in the source.
+    reported: Set<(PathBuf, usize, usize)>,
+    /// The notes made, each with the place it is reported at.
+    notes: Vec<(Span, Error)>,
+}
+
+impl<'a> Explainer<'a> {
+    /// Report the uniqueness checks in `node` and the arms of the `match`es in it. `steps` holds the
+    /// constructs between the entry of `func` (`None` for a global initializer) and `node`.
+    fn walk(&mut self, func: Option<&'a RcFunc>, node: &'a RcExprNode, steps: &mut Vec<Step<'a>>) {
+        let depth = steps.len();
+        let mut node = node;
+        loop {
+            match node.expr.as_ref() {
+                RcExpr::Let(x, rhs, k) => {
+                    match rhs {
+                        RcRhs::Llvm(llvm_gen, args) => {
+                            self.visit_op(func, node, x, llvm_gen.as_ref(), args, steps)
+                        }
+                        RcRhs::Match(scrutinee, arms) => {
+                            for arm in arms {
+                                steps.push(Step::Arm(scrutinee, arm));
+                                grow_stack(|| self.walk(func, &arm.body, steps));
+                                steps.pop();
+                            }
+                        }
+                        RcRhs::Var(_) | RcRhs::App(..) | RcRhs::Closure(..) => {}
+                    }
+                    steps.push(Step::Node(node));
+                    node = k;
+                }
+                RcExpr::Retain(_, _, _, k)
+                | RcExpr::Release(_, _, _, k)
+                | RcExpr::Destructure(_, _, _, k)
+                | RcExpr::Eval(_, k) => {
+                    steps.push(Step::Node(node));
+                    node = k;
+                }
+                RcExpr::Ret(_) => break,
+            }
+        }
+        steps.truncate(depth);
+    }
+
+    /// Report the operation `op` binding `result`, if it checks a container's uniqueness at run time.
+    fn visit_op(
+        &mut self,
+        func: Option<&'a RcFunc>,
+        op: &'a RcExprNode,
+        result: &RcVar,
+        llvm_gen: &dyn LLVMGen,
+        args: &'a [RcVar],
+        steps: &[Step<'a>],
+    ) {
+        let arg_tys: Vec<Arc<TypeNode>> = args.iter().map(|a| a.ty.clone()).collect();
+        let Some(check) = llvm_gen.unique_check_operand(&arg_tys, self.type_env) else {
+            return;
+        };
+        let container = &args[check.container_index];
+        let Some(site) = self.site_of(op, container, steps) else {
+            return;
+        };
+        let key = (site.input.file_path.clone(), site.start, site.end);
+        if self.reported.contains(&key) {
+            return;
+        }
+
+        // The origins of the container at the operation, which decided that the check stays.
+        let mut origins: Vec<LeafOrigin> = self
+            .analysis
+            .unique_check_operand_provs
+            .get(&result.name)
+            .and_then(|prov| prov.leaf_origins_at(&check.path))
+            .map(|origins| origins.iter().cloned().collect())
+            .unwrap_or_else(|| vec![LeafOrigin::Unknown]);
+        origins.sort_by_key(origin_order);
+        let mut reasons: Vec<Reason> = vec![];
+        for origin in origins {
+            let found = match origin {
+                LeafOrigin::Fresh => continue,
+                LeafOrigin::Unknown => self.trace(func, container, check.path.clone(), steps, op),
+                LeafOrigin::Arg(i, _) => self.input_reasons(func, i),
+            };
+            for reason in found {
+                if !reasons.contains(&reason) {
+                    reasons.push(reason);
+                }
+            }
+        }
+
+        let value = match &container.debug_name {
+            Some(name) => format!("`{}`", name),
+            None => "the value".to_string(),
+        };
+        let mut msg = format!(
+            "This operation checks at run time whether {} is shared, and copies it before modifying it if so, since {} is not known to be unique here.",
+            value, value
+        );
+        let mut err = Error::note_from_msg_srcs(String::new(), &[&Some(site.clone())]);
+        for (reason, span) in reasons {
+            match span {
+                Some(span) => err.add_src(reason, span),
+                None => {
+                    msg += " ";
+                    msg += &reason;
+                }
+            }
+        }
+        err.msg = msg;
+        err.code = Some(NOTE_KEPT_UNIQUE_CHECK);
+        self.reported.insert(key);
+        self.notes.push((site, err));
+    }
+
+    /// The place in the user's sources an operation is reported at: where the operation is written,
+    /// or where its container is. An operation inlined from a function of a library is written in the
+    /// library, so it is reported at the nearest statement before it that is written in the user's
+    /// sources. `None` where nothing on the way to it is.
+    fn site_of(&self, op: &RcExprNode, container: &RcVar, steps: &[Step]) -> Option<Span> {
+        let candidates = [&op.source, &container.source].into_iter().cloned();
+        let before = steps.iter().rev().map(|step| match step {
+            Step::Node(node) => node.source.clone(),
+            Step::Arm(_, arm) => arm.body.source.clone(),
+        });
+        candidates
+            .chain(before)
+            .flatten()
+            .find(|span| self.in_user_file(span))
+    }
+
+    /// Whether `span` lies in one of the user's source files.
+    fn in_user_file(&self, span: &Span) -> bool {
+        let path = &span.input.file_path;
+        if let Some(is_user) = self.is_user_file.borrow().get(path) {
+            return *is_user;
+        }
+        let is_user = to_absolute_path(path)
+            .map(|abs| self.user_files.contains(&abs))
+            .unwrap_or(false);
+        self.is_user_file.borrow_mut().insert(path.clone(), is_user);
+        is_user
+    }
+
+    /// Why the boxed leaf at `path` of `var` is not known to be unique at the end of `steps`, traced
+    /// backward through them. `op` is the operation checking it.
+    fn trace(
+        &self,
+        func: Option<&'a RcFunc>,
+        var: &'a RcVar,
+        path: FieldPath,
+        steps: &[Step<'a>],
+        op: &'a RcExprNode,
+    ) -> Vec<Reason> {
+        let mut var = var;
+        let mut path = path;
+        for i in (0..steps.len()).rev() {
+            if !var.name.is_local() {
+                break;
+            }
+            match steps[i] {
+                Step::Arm(scrutinee, arm) if arm.payload.name == var.name => match arm.tag {
+                    Some(_) if scrutinee.ty.is_box(self.type_env) => {
+                        return vec![(
+                            "It is the value held by a boxed union, taken out of it here, and a value held by a boxed union may also be reached through the union:".to_string(),
+                            arm.payload.source.clone().or_else(|| scrutinee.source.clone()),
+                        )];
+                    }
+                    Some(tag) => {
+                        path.insert(0, tag);
+                        var = scrutinee;
+                    }
+                    None => var = scrutinee,
+                },
+                Step::Arm(..) => {}
+                Step::Node(node) => match node.expr.as_ref() {
+                    RcExpr::Retain(retained, retained_path, _, k)
+                        if retained.name == var.name && paths_overlap(retained_path, &path) =>
+                    {
+                        let mut reasons = vec![(
+                            "A second reference to it is made here, because it is used again after the operation:".to_string(),
+                            node.source.clone().or_else(|| retained.source.clone()),
+                        )];
+                        if let Some(span) = later_use(op, k, &var.name) {
+                            reasons.push(("It is used again here:".to_string(), Some(span)));
+                        }
+                        return reasons;
+                    }
+                    RcExpr::Let(x, rhs, _) if x.name == var.name => {
+                        return self.trace_definition(func, node, x, rhs, path, &steps[..i], op);
+                    }
+                    RcExpr::Destructure(container, fields, _, _) => {
+                        let Some((index, _)) = fields.iter().find(|(_, f)| f.name == var.name)
+                        else {
+                            continue;
+                        };
+                        if container.ty.is_box(self.type_env) {
+                            return vec![(
+                                "It is a field of a boxed struct, taken out of it here, and a field of a boxed struct may also be reached through the struct:".to_string(),
+                                node.source.clone().or_else(|| container.source.clone()),
+                            )];
+                        }
+                        path.insert(0, *index);
+                        var = container;
+                    }
+                    _ => {}
+                },
+            }
+        }
+        if !var.name.is_local() {
+            return vec![(
+                format!(
+                    "It is the value of the global `{}`, which every reader of the global shares.",
+                    get_base_name(&var.name).to_string()
+                ),
+                var.source.clone(),
+            )];
+        }
+        // Nothing in the body binds it, so it is an input of the function.
+        let Some(func) = func else {
+            return vec![];
+        };
+        if func.capture.as_ref().map(|c| &c.name) == Some(&var.name) {
+            return vec![(
+                "It is captured by the closure defined here, and every call of a closure reads its captured values from the one closure, so they may be shared:".to_string(),
+                func.source.clone(),
+            )];
+        }
+        match func.params.iter().position(|p| p.name == var.name) {
+            Some(i) => self.input_reasons(Some(func), i),
+            None => vec![],
+        }
+    }
+
+    /// Why the boxed leaf at `path` of `x`, bound by `node` to `rhs`, is not known to be unique.
+    /// `steps` are the constructs before `node`.
+    fn trace_definition(
+        &self,
+        func: Option<&'a RcFunc>,
+        node: &'a RcExprNode,
+        x: &RcVar,
+        rhs: &'a RcRhs,
+        path: FieldPath,
+        steps: &[Step<'a>],
+        op: &'a RcExprNode,
+    ) -> Vec<Reason> {
+        let here = x.source.clone().or_else(|| node.source.clone());
+        match rhs {
+            RcRhs::Var(y) => self.trace(func, y, path, steps, op),
+            RcRhs::Llvm(llvm_gen, args) => {
+                let arg_tys: Vec<Arc<TypeNode>> = args.iter().map(|a| a.ty.clone()).collect();
+                let prov = llvm_gen.result_prov(&x.ty, &arg_tys, self.type_env);
+                let mut origins: Vec<LeafOrigin> = prov
+                    .leaf_origins_at(&path)
+                    .map(|origins| origins.iter().cloned().collect())
+                    .unwrap_or_else(|| vec![LeafOrigin::Unknown]);
+                origins.sort_by_key(origin_order);
+                let mut reasons = vec![];
+                for origin in origins {
+                    let found = match origin {
+                        LeafOrigin::Fresh => continue,
+                        LeafOrigin::Unknown => vec![(
+                            "It is read out of another value here, which may still refer to it:"
+                                .to_string(),
+                            here.clone(),
+                        )],
+                        LeafOrigin::Arg(j, arg_path) => {
+                            self.trace(func, &args[j], arg_path, steps, op)
+                        }
+                    };
+                    for reason in found {
+                        if !reasons.contains(&reason) {
+                            reasons.push(reason);
+                        }
+                    }
+                }
+                reasons
+            }
+            RcRhs::App(callee, _) => {
+                // A direct call names the function it calls; a call of a closure names the local
+                // variable holding it.
+                let msg = if !callee.name.is_local() {
+                    "It is returned by the function called here, which may return a value that is referred to from elsewhere as well:"
+                } else {
+                    "It is returned by a call of a closure here, and what a closure returns is not known:"
+                };
+                vec![(msg.to_string(), here)]
+            }
+            RcRhs::Closure(..) => vec![(
+                "It is a closure made here, whose captured values may be shared:".to_string(),
+                here,
+            )],
+            RcRhs::Match(scrutinee, arms) => {
+                let mut reasons = vec![];
+                for arm in arms {
+                    let mut arm_steps = steps.to_vec();
+                    arm_steps.push(Step::Arm(scrutinee, arm));
+                    let ret = push_statements(&arm.body, &mut arm_steps);
+                    for reason in self.trace(func, ret, path.clone(), &arm_steps, op) {
+                        if !reasons.contains(&reason) {
+                            reasons.push(reason);
+                        }
+                    }
+                }
+                reasons
+            }
+        }
+    }
+
+    /// Why input `i` of `func` is not known to be unique: a parameter is passed a value of unknown
+    /// sharing by some call, and the capture of a closure is shared by every call of it.
+    fn input_reasons(&self, func: Option<&'a RcFunc>, i: usize) -> Vec<Reason> {
+        let Some(func) = func else {
+            return vec![];
+        };
+        if i >= func.params.len() {
+            return vec![(
+                "It is captured by the closure defined here, and every call of a closure reads its captured values from the one closure, so they may be shared:".to_string(),
+                func.source.clone(),
+            )];
+        }
+        let param = match &func.params[i].debug_name {
+            Some(name) => format!("the parameter `{}`", name),
+            None => "a parameter".to_string(),
+        };
+        let sites = self
+            .call_sites
+            .get(&func.name.name)
+            .map(|sites| sites.as_slice())
+            .unwrap_or_default();
+        if sites.is_empty() {
+            return vec![(
+                format!(
+                    "It is {} of the function defined here, which is called through a closure value or from outside the program, so what it is passed is not known:",
+                    param
+                ),
+                func.source.clone(),
+            )];
+        }
+        let mut reasons = vec![(
+            format!(
+                "It is {} of the function defined here, and not every call of the function passes it a value known to be unique:",
+                param
+            ),
+            func.source.clone(),
+        )];
+        for site in sites.iter().flatten().take(MAX_CALL_SITES_SHOWN) {
+            reasons.push((
+                "The function is called here:".to_string(),
+                Some(site.clone()),
+            ));
+        }
+        reasons
+    }
+}
+
+/// An order of the origins of a leaf, so that the reasons a note gives come in the same order on
+/// every run.
+fn origin_order(origin: &LeafOrigin) -> (usize, usize, FieldPath) {
+    match origin {
+        LeafOrigin::Fresh => (0, 0, vec![]),
+        LeafOrigin::Unknown => (1, 0, vec![]),
+        LeafOrigin::Arg(i, path) => (2, *i, path.clone()),
+    }
+}
+
+/// Whether one of two paths into a value lies under the other, so that a reference-count operation
+/// on the one reaches the boxed leaf at the other.
+fn paths_overlap(lhs: &[usize], rhs: &[usize]) -> bool {
+    lhs.iter().zip(rhs.iter()).all(|(l, r)| l == r)
+}
+
+/// Push the statements of `node` onto `steps` up to its `Ret`, and return the variable that `Ret`
+/// returns. The arms of a `match` in it are not entered.
+fn push_statements<'a>(node: &'a RcExprNode, steps: &mut Vec<Step<'a>>) -> &'a RcVar {
+    let mut node = node;
+    loop {
+        match node.expr.as_ref() {
+            RcExpr::Let(_, _, k)
+            | RcExpr::Retain(_, _, _, k)
+            | RcExpr::Release(_, _, _, k)
+            | RcExpr::Destructure(_, _, _, k)
+            | RcExpr::Eval(_, k) => {
+                steps.push(Step::Node(node));
+                node = k;
+            }
+            RcExpr::Ret(v) => return v,
+        }
+    }
+}
+
+/// Where the variable `name`, retained at the start of `after`, is read next other than by the
+/// operation `op`: after `op` where it is read there, and otherwise anywhere in `after`.
+fn later_use(op: &RcExprNode, after: &RcExprNode, name: &FullName) -> Option<Span> {
+    if let RcExpr::Let(_, _, k) = op.expr.as_ref() {
+        if let Some(span) = first_use(k, name, op) {
+            return Some(span);
+        }
+    }
+    first_use(after, name, op)
+}
+
+/// The first place `node` reads the variable `name`, in the order it runs, leaving out `skip`.
+fn first_use(node: &RcExprNode, name: &FullName, skip: &RcExprNode) -> Option<Span> {
+    let mut node = node;
+    loop {
+        let read = |v: &RcVar| (v.name == *name).then(|| v.source.clone().or(node.source.clone()));
+        let at_node = !ptr::eq(node, skip);
+        match node.expr.as_ref() {
+            RcExpr::Let(_, rhs, k) => {
+                if at_node {
+                    let found = match rhs {
+                        RcRhs::Var(y) => read(y),
+                        RcRhs::App(callee, args) => {
+                            read(callee).or_else(|| args.iter().find_map(|a| read(a)))
+                        }
+                        RcRhs::Closure(_, captures) => captures.iter().find_map(|c| read(c)),
+                        RcRhs::Llvm(_, args) => args.iter().find_map(|a| read(a)),
+                        RcRhs::Match(scrutinee, _) => read(scrutinee),
+                    };
+                    if let Some(span) = found {
+                        return span;
+                    }
+                }
+                if let RcRhs::Match(_, arms) = rhs {
+                    for arm in arms {
+                        if let Some(span) = grow_stack(|| first_use(&arm.body, name, skip)) {
+                            return Some(span);
+                        }
+                    }
+                }
+                node = k;
+            }
+            RcExpr::Destructure(container, _, _, k) => {
+                if let Some(span) = read(container) {
+                    return span;
+                }
+                node = k;
+            }
+            RcExpr::Eval(v, k) => {
+                if let Some(span) = read(v) {
+                    return span;
+                }
+                node = k;
+            }
+            RcExpr::Retain(_, _, _, k) | RcExpr::Release(_, _, _, k) => node = k,
+            RcExpr::Ret(v) => return read(v).flatten(),
+        }
+    }
+}
@@ -871,6 +871,7 @@ impl<'a> Walk<'a> {
             });
             out.push(MatchArm {
                 tag: arm.tag,
+                lit: arm.lit,
                 payload: arm.payload.clone(),
                 payload_state,
                 body,
@@ -584,12 +584,14 @@ impl<'a> Lowerer<'a> {
         let then_arm = MatchArm {
             payload_state: RcState::Unknown,
             tag: Some(BOOL_TRUE_TAG),
+            lit: None,
             payload: self.fresh_var("unit", payload_tys[BOOL_TRUE_TAG].clone(), None),
             body: self.lower_body(then_expr),
         };
         let else_arm = MatchArm {
             payload_state: RcState::Unknown,
             tag: Some(BOOL_FALSE_TAG),
+            lit: None,
             payload: self.fresh_var("unit", payload_tys[BOOL_FALSE_TAG].clone(), None),
             body: self.lower_body(else_expr),
         };
@@ -630,8 +632,9 @@ impl<'a> Lowerer<'a> {
     /// Lower one arm of a match on `scrutinee`. A union pattern gives an arm the variant's tag
     /// selects, whose payload variable holds that variant's contents; a variable or struct pattern
     /// gives the default arm, taken for every value the tagged arms leave, and its payload is the
-    /// whole scrutinee. The pattern's variables are bound while the arm's body is lowered and
-    /// closed after it, so each body is lowered under its own pattern's bindings alone.
+    /// whole scrutinee. A literal pattern gives an arm the integer value selects. The pattern's
+    /// variables are bound while the arm's body is lowered and closed after it, so each body is
+    /// lowered under its own pattern's bindings alone.
     fn lower_match_arm(
         &mut self,
         scrutinee: &RcVar,
@@ -668,6 +671,7 @@ impl<'a> Lowerer<'a> {
                 MatchArm {
                     payload_state: RcState::Unknown,
                     tag: Some(variant_idx),
+                    lit: None,
                     payload,
                     body: Self::fold_bindings(arm_bindings, Self::ret_node(ret_var)),
                 }
@@ -683,6 +687,7 @@ impl<'a> Lowerer<'a> {
                 MatchArm {
                     payload_state: RcState::Unknown,
                     tag: None,
+                    lit: None,
                     payload,
                     body,
                 }
@@ -701,10 +706,22 @@ impl<'a> Lowerer<'a> {
                 MatchArm {
                     payload_state: RcState::Unknown,
                     tag: None,
+                    lit: None,
                     payload,
                     body: Self::fold_bindings(arm_bindings, Self::ret_node(ret_var)),
                 }
             }
+            Pattern::Lit(val, _) => {
+                // A literal arm binds nothing; its payload is the scrutinee, as a catch-all's is.
+                let payload = self.fresh_var("lit", scrutinee.ty.clone(), pat.info.source.clone());
+                MatchArm {
+                    payload_state: RcState::Unknown,
+                    tag: None,
+                    lit: Some(*val),
+                    payload,
+                    body: self.lower_body(body),
+                }
+            }
         }
     }
 
@@ -903,8 +920,8 @@ impl<'a> Lowerer<'a> {
                 }
                 bound_names
             }
-            Pattern::Union(_, _, _) => {
-                panic!("a union pattern in a let-binding is not handled in RC IR lowering")
+            Pattern::Union(_, _, _) | Pattern::Lit(_, _) => {
+                panic!("a refutable pattern in a let-binding is not handled in RC IR lowering")
             }
         }
     }
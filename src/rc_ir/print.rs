@@ -251,9 +251,10 @@ fn rhs_to_string(rhs: &RcRhs, level: usize, ann: Annotations) -> String {
         RcRhs::Match(scrutinee, arms) => {
             let mut out = format!("match {} {{\n", var_name(scrutinee));
             for arm in arms {
-                let variant = match arm.tag {
-                    Some(tag) => tag.to_string(),
-                    None => "_".to_string(),
+                let variant = match (arm.tag, arm.lit) {
+                    (Some(tag), _) => tag.to_string(),
+                    (None, Some(lit)) => format!("={}", lit),
+                    (None, None) => "_".to_string(),
                 };
                 out.push_str(&format!(
                     "{}case {}({}):{}\n",
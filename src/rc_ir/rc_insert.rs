@@ -375,6 +375,7 @@ impl<'a> RcInserter<'a> {
             new_arms.push(MatchArm {
                 payload_state: arm.payload_state,
                 tag: arm.tag,
+                lit: arm.lit,
                 payload,
                 body,
             });
@@ -188,6 +188,7 @@ fn rename_rhs(rhs: &RcRhs, renaming: &Map<FullName, FullName>) -> RcRhs {
                 .map(|arm| MatchArm {
                     payload_state: arm.payload_state,
                     tag: arm.tag,
+                    lit: arm.lit,
                     payload: rename_var(&arm.payload, renaming),
                     body: rename_expr(&arm.body, renaming),
                 })
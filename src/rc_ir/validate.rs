@@ -371,7 +371,7 @@ impl<'a> Validator<'a> {
                 self.use_var(&scrutinee.name);
                 // A match has at least one arm, and a catch-all arm (`tag == None`) — which code
                 // generation compiles as the tag switch's default case — is the last arm, so every
-                // earlier arm names a variant. A rewrite that moved a catch-all before another arm
+                // earlier arm names a variant or an integer value. A rewrite that moved a catch-all before another arm
                 // would shadow the arms after it.
                 if arms.is_empty() {
                     panic!(
@@ -380,7 +380,7 @@ impl<'a> Validator<'a> {
                     );
                 }
                 for arm in &arms[..arms.len() - 1] {
-                    if arm.tag.is_none() {
+                    if arm.tag.is_none() && arm.lit.is_none() {
                         panic!(
                             "[RC IR validate] {}: a catch-all match arm precedes a later arm in `{}`",
                             self.stage, self.location,
@@ -391,6 +391,7 @@ impl<'a> Validator<'a> {
                 // that reads the arms by tag takes the first of two arms carrying one tag, and the
                 // second would be dead where code generation's switch sends the value to the first.
                 let mut tags = Set::default();
+                let mut lits = Set::default();
                 for arm in arms {
                     if let Some(tag) = arm.tag {
                         if !tags.insert(tag) {
@@ -400,6 +401,14 @@ impl<'a> Validator<'a> {
                             );
                         }
                     }
+                    if let Some(lit) = arm.lit {
+                        if !lits.insert(lit) {
+                            panic!(
+                                "[RC IR validate] {}: two match arms carry the value {} in `{}`",
+                                self.stage, lit, self.location,
+                            );
+                        }
+                    }
                 }
                 // Each arm's payload is in scope only within that arm's body, so bind it, check the
                 // body, and unbind it before the next sibling arm.
@@ -617,12 +626,14 @@ mod tests {
             MatchArm {
                 payload_state: RcState::Unknown,
                 tag: None,
+                lit: None,
                 payload: var("c"),
                 body: node(RcExpr::Ret(var("c"))),
             },
             MatchArm {
                 payload_state: RcState::Unknown,
                 tag: Some(1),
+                lit: None,
                 payload: var("p"),
                 body: node(RcExpr::Ret(var("p"))),
             },
@@ -9,6 +9,7 @@ mod test_array_swap;
 mod test_assert;
 mod test_associated_type;
 mod test_basic;
+mod test_bench;
 mod test_bool_union;
 mod test_borrow_tail_call;
 mod test_borrow_unit_ownership;
@@ -24,9 +25,12 @@ mod test_defunctionalize_fix;
 mod test_dependencies;
 mod test_dependency_name_resolution;
 mod test_deprecation;
+mod test_derive;
 mod test_docs;
+mod test_doctest;
 mod test_dynamic_library;
 mod test_evaluation_order;
+mod test_explain_uniqueness;
 mod test_explicit_import;
 mod test_external_projects;
 mod test_ffi;
@@ -41,11 +45,13 @@ mod test_hole_priority;
 mod test_import;
 mod test_index_syntax;
 mod test_let_elimination;
+mod test_lint;
 mod test_llvm_passes;
 mod test_locality;
 mod test_lsp;
 mod test_main_loop;
 mod test_mark_object_graph;
+mod test_match_literal;
 mod test_match_result_alias;
 mod test_match_return_outer;
 mod test_memcheck;
@@ -53,20 +59,26 @@ mod test_no_runtime_check_setting;
 mod test_object_cache_layout_change;
 mod test_opaque_type;
 mod test_output_file_setting;
+mod test_parallel_codegen;
 mod test_predicate_deduction;
 mod test_preliminary_commands;
+mod test_profile_rc;
 mod test_provenance;
 mod test_punched_array;
 mod test_rc_ir_aliasing;
+mod test_repl;
 mod test_sanitize_setting;
+mod test_semver_check;
 mod test_shared_boxed_swap;
 mod test_signal;
 mod test_simplify;
 mod test_skip_eval;
+mod test_static_library;
 mod test_string;
 mod test_struct_destructure;
 mod test_struct_literal;
 mod test_struct_rmw;
+mod test_test_discovery;
 mod test_thread_safety;
 mod test_threaded_rc;
 mod test_threaded_setting;
@@ -87,4 +99,5 @@ mod test_util;
 mod test_valgrind_suppression;
 mod test_value_splitting;
 mod test_version_report;
+mod test_watch;
 mod test_wide_return_tail_call;
@@ -0,0 +1,133 @@
+//! `fix bench` measures every value named `bench_*` of type `IO ()` or `IO (Result ErrMsg ())` that
+//! the benchmark source files define, each in a process of its own, and compares the times with a
+//! baseline it saves as JSON.
+//!
+//! The case under `test_bench/cases` has two benchmarks that run and one that aborts in its
+//! benchmark source file, beside a helper and a `bench_*` value of the ordinary sources, which are
+//! not benchmarks.
+
+use crate::commands::bench::Baseline;
+use crate::constants::BENCH_BASELINE_PATH;
+use crate::tests::test_util::{assert_failed, assert_succeeded, run_fix, setup_case_projects};
+use std::fs;
+use std::path::Path;
+
+/// The directory holding this module's case projects.
+const CASES: &str = "src/tests/test_bench/cases";
+
+/// The options that keep each benchmark to a fraction of a second.
+const QUICK: [&str; 6] = [
+    "--warm-up-time",
+    "0.05",
+    "--measurement-time",
+    "0.2",
+    "--samples",
+    "5",
+];
+
+/// Runs `fix bench` in `project_dir` with `QUICK` and then `args`.
+fn run_bench(project_dir: &Path, args: &[&str]) -> std::process::Output {
+    let mut all_args = vec!["bench"];
+    all_args.extend(QUICK);
+    all_args.extend(args);
+    run_fix(project_dir, &all_args)
+}
+
+/// The baseline saved in `project_dir`.
+fn read_baseline(project_dir: &Path) -> Baseline {
+    let content = fs::read_to_string(project_dir.join(BENCH_BASELINE_PATH))
+        .expect("the baseline should be saved");
+    serde_json::from_str(&content).expect("the baseline should be valid JSON")
+}
+
+/// Each benchmark is reported with its times, one aborting does not hide the others, and the times
+/// of the ones measured are saved as the baseline, which none was saved before.
+#[test]
+fn test_measures_each_benchmark_and_saves_the_baseline() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "root_with_benches");
+    let output = run_bench(&project_dir, &[]);
+    assert_failed(
+        &output,
+        "`fix bench` should fail, because one of the benchmarks aborts.",
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in [
+        "running 3 benchmarks",
+        "bench Bench::bench_fib ... ok",
+        "bench Bench::bench_sum_result ... ok",
+        "bench Bench::bench_undefined ... FAILED",
+        "per run (median)",
+        "bench result: FAILED. 2 measured; 1 failed; 0 regressed; 0 improved",
+    ] {
+        assert!(
+            stdout.contains(line),
+            "the output should contain `{}`.\nstdout: {}\nstderr: {}",
+            line,
+            stdout,
+            stderr
+        );
+    }
+    assert!(
+        !stdout.contains("bench_helper") && !stdout.contains("bench_in_main"),
+        "a helper and a value of the ordinary sources should not be measured.\nstdout: {}",
+        stdout
+    );
+
+    let baseline = read_baseline(&project_dir);
+    assert_eq!(
+        baseline.benchmarks.keys().collect::<Vec<_>>(),
+        ["Bench::bench_fib", "Bench::bench_sum_result"],
+        "the baseline should have the benchmarks measured"
+    );
+    for (name, stats) in &baseline.benchmarks {
+        assert!(
+            stats.samples == 5 && stats.min > 0.0 && stats.min <= stats.median,
+            "the statistics of {} should come from five positive samples",
+            name
+        );
+    }
+}
+
+/// A run against a saved baseline reports how far each benchmark moved from it, and keeps the
+/// baseline unless `--save-baseline` is given. The arguments after `--` select the benchmarks.
+#[test]
+fn test_compares_with_the_baseline() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "root_with_benches");
+    let output = run_bench(&project_dir, &["--", "fib"]);
+    assert_succeeded(&output, "the benchmark of `fib` should be measured.");
+
+    // Make the saved time of `fib` far shorter than any run can be.
+    let path = project_dir.join(BENCH_BASELINE_PATH);
+    let mut baseline = read_baseline(&project_dir);
+    baseline
+        .benchmarks
+        .get_mut("Bench::bench_fib")
+        .expect("the baseline should have `bench_fib`")
+        .median = 1e-12;
+    fs::write(&path, serde_json::to_string(&baseline).unwrap()).unwrap();
+
+    let output = run_bench(&project_dir, &["--", "fib"]);
+    assert_succeeded(&output, "the benchmark of `fib` should be measured again.");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("running 1 benchmark")
+            && stdout.contains("(regressed)")
+            && stdout.contains("bench result: ok. 1 measured; 0 failed; 1 regressed; 0 improved"),
+        "`fib` should be reported as regressed against the baseline.\nstdout: {}",
+        stdout
+    );
+    assert_eq!(
+        read_baseline(&project_dir).benchmarks["Bench::bench_fib"].median,
+        1e-12,
+        "the baseline should be kept without `--save-baseline`"
+    );
+
+    let output = run_bench(&project_dir, &["--save-baseline", "--", "fib"]);
+    assert_succeeded(&output, "the benchmark of `fib` should be measured again.");
+    assert!(
+        read_baseline(&project_dir).benchmarks["Bench::bench_fib"].median > 1e-12,
+        "`--save-baseline` should replace the time of `fib`"
+    );
+}
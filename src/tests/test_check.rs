@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod integration_tests {
     use crate::tests::test_util::{copy_dir_recursive, fix_command};
+    use serde_json::Value;
     use std::path::PathBuf;
     use tempfile::TempDir;
 
@@ -339,4 +340,83 @@ mod integration_tests {
             stderr
         );
     }
+
+    /// The diagnostics `fix check --message-format=json` writes to stdout, one JSON object per
+    /// line, together with whether the command succeeded.
+    fn json_diagnostics(project_dir: &PathBuf) -> (bool, Vec<Value>) {
+        let output = fix_command()
+            .arg("check")
+            .arg("--message-format=json")
+            .current_dir(project_dir)
+            .output()
+            .expect("Failed to execute fix check");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let diagnostics = stdout
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<Value>(line)
+                    .unwrap_or_else(|e| panic!("stdout line {:?} is not JSON: {}", line, e))
+            })
+            .collect();
+        (output.status.success(), diagnostics)
+    }
+
+    /// A type error is written as an error object whose primary span points at the expression.
+    #[test]
+    fn test_check_json_type_error() {
+        let (_temp_dir, project_dir) = setup_test_env("type_error_project");
+
+        let (success, diagnostics) = json_diagnostics(&project_dir);
+        assert!(!success, "fix check should fail on a type error");
+        assert_eq!(diagnostics.len(), 1, "Got: {:?}", diagnostics);
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic["severity"], "error");
+        assert!(diagnostic["code"].is_null(), "Got: {:?}", diagnostic);
+        assert!(
+            diagnostic["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Type mismatch"),
+            "Got: {:?}",
+            diagnostic
+        );
+
+        // `let x : I64 = "hello";` is on line 5, and the string literal spans columns 19 to 26.
+        let span = &diagnostic["spans"][0];
+        assert!(
+            span["file"].as_str().unwrap().ends_with("main.fix"),
+            "Got: {:?}",
+            span
+        );
+        assert_eq!(span["is_primary"], true);
+        assert_eq!(span["line_start"], 5);
+        assert_eq!(span["column_start"], 19);
+        assert_eq!(span["line_end"], 5);
+        assert_eq!(span["column_end"], 26);
+    }
+
+    /// A warning is written as a warning object carrying its code, and does not fail the command.
+    #[test]
+    fn test_check_json_deprecation_warning() {
+        let (_temp_dir, project_dir) = setup_test_env("deprecated_warning_project");
+
+        let (success, diagnostics) = json_diagnostics(&project_dir);
+        assert!(success, "fix check should succeed (warning-only)");
+        assert_eq!(diagnostics.len(), 1, "Got: {:?}", diagnostics);
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic["severity"], "warning");
+        assert_eq!(diagnostic["code"], "deprecated");
+        assert!(
+            diagnostic["message"]
+                .as_str()
+                .unwrap()
+                .contains("Use `new_func` instead."),
+            "Got: {:?}",
+            diagnostic
+        );
+        // `let _ = old_func(10);` is on line 11.
+        assert_eq!(diagnostic["spans"][0]["line_start"], 11);
+    }
 }
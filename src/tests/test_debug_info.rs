@@ -4,6 +4,8 @@
 // that DWARF line information is emitted correctly: a source breakpoint resolves to
 // `main.fix:<line>`, execution stops there, and the backtrace carries per-frame line info up the
 // Fix call chain. Assertions read `file:line`, so they stay valid across name-mangling changes.
+// One scenario loads the pretty-printers `-g` writes beside the program and checks the values they
+// show.
 //
 // The scenarios that need no debugger check that `-g` builds at all — one per optimization level,
 // and two over recursive types — and read what the debug information records out of the bytes of
@@ -16,7 +18,7 @@
 
 #[cfg(test)]
 mod debug_info_tests {
-    use crate::constants::COMPILATION_UNITS_PATH;
+    use crate::constants::{COMPILATION_UNITS_PATH, GDB_SCRIPT_SUFFIX, LLDB_SCRIPT_FILE_NAME};
     use crate::misc::Set;
     use crate::tests::test_util::fix_command_at_opt_level;
     use std::{
@@ -464,16 +466,33 @@ mod debug_info_tests {
     /// Drive `debugger` over `./prog` in `dir`, issuing the given native `commands` in order, and
     /// return its combined stdout+stderr.
     fn drive(debugger: Debugger, dir: &Path, commands: &[String]) -> String {
+        drive_with_init(debugger, dir, &[], commands)
+    }
+
+    /// Drive `debugger` over `./prog` in `dir` as `drive` does, issuing the native `init_commands`
+    /// before the program is loaded.
+    fn drive_with_init(
+        debugger: Debugger,
+        dir: &Path,
+        init_commands: &[String],
+        commands: &[String],
+    ) -> String {
         let mut cmd = Command::new(debugger.program());
         match debugger {
             Debugger::Gdb => {
                 cmd.args(["-batch", "-iex", "set debuginfod enabled off"]);
+                for c in init_commands {
+                    cmd.arg("-iex").arg(c);
+                }
                 for c in commands {
                     cmd.arg("-ex").arg(c);
                 }
             }
             Debugger::Lldb => {
                 cmd.args(["--batch", "--no-lldbinit"]);
+                for c in init_commands {
+                    cmd.arg("-O").arg(c);
+                }
                 for c in commands {
                     cmd.arg("-o").arg(c);
                 }
@@ -849,4 +868,99 @@ mod debug_info_tests {
             array_elements_impl,
         );
     }
+
+    /// Line in cases/debug_printers/main.fix where all locals (arr, s, some, none, pt) are live.
+    const LINE_PRINTERS_BREAK: u32 = 12; // "    eval arr;"
+
+    /// The scripts `fix build -g` writes beside the program teach the debugger to show Fix values:
+    /// an `Array` by its elements, a `String` by its text, a union by its active variant, and a
+    /// boxed struct by its fields. gdb loads its script by itself once the directory is on its
+    /// auto-load safe path; lldb imports its own.
+    fn printers_impl(debugger: Debugger) {
+        let temp = build_debuggee(case_main_fix("debug_printers"));
+        let dir = temp.path().canonicalize().unwrap();
+        let gdb_script = dir.join(format!("prog{}", GDB_SCRIPT_SUFFIX));
+        let lldb_script = dir.join(LLDB_SCRIPT_FILE_NAME);
+        for script in [&gdb_script, &lldb_script] {
+            assert!(
+                script.exists(),
+                "`fix build -g` did not write {}",
+                script.display()
+            );
+        }
+        let (init_commands, commands) = match debugger {
+            Debugger::Gdb => (
+                vec![format!("add-auto-load-safe-path {}", dir.display())],
+                to_commands(&[
+                    format!("break main.fix:{}", LINE_PRINTERS_BREAK).as_str(),
+                    "run",
+                    "print arr",
+                    "print s",
+                    "print some",
+                    "print none",
+                    "print pt",
+                    "continue",
+                ]),
+            ),
+            Debugger::Lldb => (
+                vec![format!("command script import {}", lldb_script.display())],
+                to_commands(&[
+                    format!(
+                        "breakpoint set --file main.fix --line {}",
+                        LINE_PRINTERS_BREAK
+                    )
+                    .as_str(),
+                    "run",
+                    "frame variable arr",
+                    "frame variable s",
+                    "frame variable some",
+                    "frame variable none",
+                    "frame variable pt",
+                    "continue",
+                ]),
+            ),
+        };
+        let out = drive_with_init(debugger, &dir, &init_commands, &commands);
+
+        assert_stopped_at_line(&out, debugger, LINE_PRINTERS_BREAK);
+        // gdb lists an array's elements inline; lldb lists them as `[i] = value`, one per line.
+        let elements: &[&str] = match debugger {
+            Debugger::Gdb => &["{10, 20, 30}"],
+            Debugger::Lldb => &["[0] = 10", "[1] = 20", "[2] = 30"],
+        };
+        for elem in elements {
+            assert_contains(&out, elem, "Array elements");
+        }
+        for (needle, what) in [
+            ("size=3", "Array size"),
+            ("\"hello\"", "String text"),
+            ("some(5)", "union with its active variant `some`"),
+            ("= none", "union with its active variant `none`"),
+            ("x = 3", "field `x` of a boxed struct"),
+            ("y = 4", "field `y` of a boxed struct"),
+        ] {
+            assert_contains(&out, needle, what);
+        }
+    }
+
+    /// Fix values are shown through the pretty-printers `fix build -g` writes, as gdb reads them.
+    #[test]
+    fn test_debug_info_pretty_printers_gdb() {
+        run_under_debugger(
+            Debugger::Gdb,
+            "test_debug_info_pretty_printers_gdb",
+            printers_impl,
+        );
+    }
+
+    /// Fix values are shown through the formatters `fix build -g` writes, as lldb reads them. lldb
+    /// is the debugger of a macOS host, and of a Linux host that has it installed.
+    #[test]
+    fn test_debug_info_pretty_printers_lldb() {
+        run_under_debugger(
+            Debugger::Lldb,
+            "test_debug_info_pretty_printers_lldb",
+            printers_impl,
+        );
+    }
 }
@@ -64,6 +64,23 @@ mod integration_tests {
         let _ = fix_command().arg("clean").current_dir(project_dir).output();
     }
 
+    /// Runs `fix deps update` with `extra_args` in `project_dir`, which writes the lock file and
+    /// installs the dependencies it resolves.
+    fn update_deps(project_dir: &PathBuf, extra_args: &[&str]) {
+        let output = fix_command()
+            .args(["deps", "update"])
+            .args(extra_args)
+            .current_dir(project_dir)
+            .output()
+            .expect("Failed to execute fix deps update");
+        assert!(
+            output.status.success(),
+            "fix deps update failed:\nstdout: {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     /// A build writes `fixdeps.lock` alone, and locks the ordinary dependencies alone: the test
     /// dependencies of the project and the test dependencies of the projects it depends on both
     /// stay out of the lock file.
@@ -664,4 +681,131 @@ mod integration_tests {
             stderr
         );
     }
+
+    /// `fix deps tree` prints the ordinary dependencies under the root, and with `--test` also the
+    /// test dependencies of the root, marked; the test dependencies of a dependency stay out. It
+    /// reads the lock file without writing one, so it fails before the dependencies are installed.
+    #[test]
+    fn test_deps_tree() {
+        let (_temp_dir, project_dir) = setup_case_env("dependencies_for_test/main_project");
+        cleanup_test_project(&project_dir);
+
+        let output = fix_command()
+            .args(["deps", "tree"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps tree");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !output.status.success() && stderr.contains("Run `fix deps install` first."),
+            "fix deps tree should ask for the dependencies to be installed:\nstderr: {}",
+            stderr
+        );
+        assert!(
+            !project_dir.join(LOCK_FILE_PATH).exists(),
+            "fix deps tree should not create the lock file"
+        );
+
+        update_deps(&project_dir, &[]);
+        update_deps(&project_dir, &["--test"]);
+        let output = fix_command()
+            .args(["deps", "tree"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps tree");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "fix deps tree failed:\nstdout: {}\nstderr: {}",
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let lines = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines.len(),
+            2,
+            "the tree should have two lines:\n{}",
+            stdout
+        );
+        assert_eq!(lines[0], "main-project@0.1.0 (root)");
+        assert!(
+            lines[1].starts_with("└── normal-dep@0.1.0 (path ")
+                && lines[1].ends_with("normal_dep)"),
+            "normal-dep should be printed as a path dependency:\n{}",
+            stdout
+        );
+
+        let output = fix_command()
+            .args(["deps", "tree", "--test"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps tree --test");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines.len(),
+            3,
+            "the tree should have three lines:\n{}",
+            stdout
+        );
+        assert!(
+            lines[1].starts_with("├── normal-dep@0.1.0 ")
+                && lines[2].starts_with("└── test-dep@0.1.0 ")
+                && lines[2].ends_with(" [test]"),
+            "test-dep should be printed as a test dependency of the root:\n{}",
+            stdout
+        );
+    }
+
+    /// `fix deps why` prints the paths from the root to a project through test dependencies as well,
+    /// and writes them as lists of steps with `--json`.
+    #[test]
+    fn test_deps_why() {
+        let (_temp_dir, project_dir) = setup_case_env("dependencies_for_test/main_project");
+        cleanup_test_project(&project_dir);
+        update_deps(&project_dir, &["--test"]);
+
+        let output = fix_command()
+            .args(["deps", "why", "test-dep"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps why");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "fix deps why failed:\nstdout: {}\nstderr: {}",
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            stdout.trim(),
+            "main-project@0.1.0 -> [test] test-dep@0.1.0",
+            "the only path to test-dep is the test dependency of the root"
+        );
+
+        let output = fix_command()
+            .args(["deps", "why", "normal-dep", "--json"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps why --json");
+        let paths: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("the output should be valid JSON");
+        assert_eq!(
+            paths,
+            serde_json::json!([[
+                { "name": "main-project", "version": "0.1.0", "test": false },
+                { "name": "normal-dep", "version": "0.1.0", "test": false }
+            ]])
+        );
+
+        let output = fix_command()
+            .args(["deps", "why", "no-such-project"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix deps why");
+        assert!(
+            !output.status.success(),
+            "fix deps why should fail for a project outside the graph"
+        );
+    }
 }
@@ -0,0 +1,98 @@
+use crate::{
+    configuration::Configuration,
+    tests::test_util::{test_source, test_source_fail},
+};
+
+/// Verifies the implementations `DERIVE` generates for a generic struct: equality field by field,
+/// lexicographic comparison, and the `Name { field: value }` form of `to_string`.
+#[test]
+pub fn test_derive_struct() {
+    let source = r##"
+        module Main;
+
+        type Point a = struct { x : a, y : a };
+        DERIVE[Point, Eq, LessThan, LessThanOrEq, ToString];
+
+        main : IO ();
+        main = (
+            let p = Point { x : 1, y : 2 };
+            assert_eq(|_|"eq", p == Point { x : 1, y : 2 }, true);;
+            assert_eq(|_|"ne", p == Point { x : 1, y : 3 }, false);;
+            assert_eq(|_|"lt by y", p < Point { x : 1, y : 3 }, true);;
+            assert_eq(|_|"lt by x", p < Point { x : 0, y : 3 }, false);;
+            assert_eq(|_|"lt equal", p < p, false);;
+            assert_eq(|_|"le equal", p <= p, true);;
+            assert_eq(|_|"to_string", p.to_string, "Point { x: 1, y: 2 }");;
+            pure()
+        );
+    "##;
+    test_source(source, Configuration::develop_mode());
+}
+
+/// Verifies the implementations `DERIVE` generates for a union in a namespace: values of different
+/// variants are unequal and ordered by the declaration order of the variants.
+#[test]
+pub fn test_derive_union_in_namespace() {
+    let source = r##"
+        module Main;
+
+        namespace Geo {
+            type Shape = union { circle : I64, rect : (I64, I64) };
+            DERIVE[Shape, Eq, LessThan, ToString];
+        }
+
+        main : IO ();
+        main = (
+            let c = Geo::Shape::circle(1);
+            let r = Geo::Shape::rect((2, 3));
+            assert_eq(|_|"eq", c == Geo::Shape::circle(1), true);;
+            assert_eq(|_|"ne variant", c == r, false);;
+            assert_eq(|_|"lt variant", c < r, true);;
+            assert_eq(|_|"gt variant", r < c, false);;
+            assert_eq(|_|"lt content", r < Geo::Shape::rect((2, 4)), true);;
+            assert_eq(|_|"to_string", c.to_string, "circle(1)");;
+            assert_eq(|_|"to_string tuple", r.to_string, "rect((2, 3))");;
+            pure()
+        );
+    "##;
+    test_source(source, Configuration::develop_mode());
+}
+
+/// Verifies that a field whose type does not implement a derived trait is reported at the field.
+#[test]
+pub fn test_derive_field_lacks_instance() {
+    let source = r##"
+        module Main;
+
+        type Opaque = struct { n : I64 };
+        type Wrapper = struct { inner : Opaque };
+        DERIVE[Wrapper, Eq];
+
+        main : IO ();
+        main = pure();
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "Cannot derive `Eq` for `Main::Wrapper`: the field `inner` is of type",
+    );
+}
+
+/// Verifies that a trait `DERIVE` cannot implement is rejected.
+#[test]
+pub fn test_derive_unsupported_trait() {
+    let source = r##"
+        module Main;
+
+        type Point = struct { x : I64 };
+        DERIVE[Point, Functor];
+
+        main : IO ();
+        main = pure();
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "`Functor` cannot be derived.",
+    );
+}
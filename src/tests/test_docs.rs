@@ -405,4 +405,192 @@ mod integration_tests {
             );
         }
     }
+
+    #[test]
+    fn test_docs_html_output() {
+        // This test verifies that `fix docs --format html`:
+        // 1. Writes a page for each module, the index page and the files of the search.
+        // 2. Links the names in signatures to the sections documenting them.
+        // 3. Lists the implementations of a type on its section.
+        // 4. Puts the documented entries into the search index.
+
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let test_case_src = get_test_project_dir().join("cases/comprehensive_docs");
+        let test_case_dst = temp_dir.path().join("comprehensive_docs");
+        copy_dir_recursive(&test_case_src, &test_case_dst).expect("Failed to copy test case");
+        cleanup_test_docs(&test_case_dst);
+
+        let output = fix_command()
+            .args(["docs", "--format", "html"])
+            .current_dir(&test_case_dst)
+            .output()
+            .expect("Failed to execute fix docs");
+        if !output.status.success() {
+            eprintln!("fix docs --format html failed:");
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!("fix docs --format html command failed");
+        }
+
+        let docs_dir = test_case_dst.join("docs");
+        for file in [
+            "Main.html",
+            "index.html",
+            "search-index.js",
+            "search.js",
+            "style.css",
+        ] {
+            assert!(
+                docs_dir.join(file).exists(),
+                "{} should be generated by `fix docs --format html`",
+                file
+            );
+        }
+        assert!(
+            !docs_dir.join("Main.md").exists(),
+            "Main.md should NOT be generated in the HTML format"
+        );
+
+        let main_content =
+            fs::read_to_string(docs_dir.join("Main.html")).expect("Failed to read Main.html");
+        assert!(
+            main_content.contains("<section id=\"type.Main::MyStruct\">"),
+            "Main.html should have a section for MyStruct"
+        );
+        assert!(
+            main_content.contains("href=\"Main.html#type.Main::MyStruct\""),
+            "Main.html should link MyStruct in signatures"
+        );
+        assert!(
+            main_content.contains("href=\"Main.html#trait.Main::MyTrait\""),
+            "Main.html should link MyTrait in signatures"
+        );
+        let my_struct_section = main_content
+            .split("<section id=\"type.Main::MyStruct\">")
+            .nth(1)
+            .expect("Main.html should have a section for MyStruct");
+        let implementations = my_struct_section
+            .split("Implementations")
+            .nth(1)
+            .expect("the section of MyStruct should list its implementations");
+        assert!(
+            implementations.contains("Main::MyTrait"),
+            "the implementations of MyStruct should include MyTrait"
+        );
+
+        let index_content = fs::read_to_string(docs_dir.join("search-index.js"))
+            .expect("Failed to read search-index.js");
+        assert!(
+            index_content.contains("\"Main::MyStruct\"")
+                && index_content.contains("\"Main::main\""),
+            "the search index should contain the documented entries"
+        );
+    }
+
+    #[test]
+    fn test_docs_json_output() {
+        // This test verifies that `fix docs --format json`:
+        // 1. Writes a JSON file for each module, carrying the schema version.
+        // 2. Describes types with their fields and variants, without the private ones.
+        // 3. Describes traits with their members, trait aliases, impls and values.
+        // 4. Gives the source span of each entry.
+
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let test_case_src = get_test_project_dir().join("cases/comprehensive_docs");
+        let test_case_dst = temp_dir.path().join("comprehensive_docs");
+        copy_dir_recursive(&test_case_src, &test_case_dst).expect("Failed to copy test case");
+        cleanup_test_docs(&test_case_dst);
+
+        let output = fix_command()
+            .args(["docs", "--format", "json"])
+            .current_dir(&test_case_dst)
+            .output()
+            .expect("Failed to execute fix docs");
+        if !output.status.success() {
+            eprintln!("fix docs --format json failed:");
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!("fix docs --format json command failed");
+        }
+
+        let docs_dir = test_case_dst.join("docs");
+        assert!(
+            !docs_dir.join("Main.md").exists(),
+            "Main.md should NOT be generated in the JSON format"
+        );
+        let main_content =
+            fs::read_to_string(docs_dir.join("Main.json")).expect("Failed to read Main.json");
+        let module: serde_json::Value =
+            serde_json::from_str(&main_content).expect("Main.json should be valid JSON");
+
+        assert_eq!(
+            module["schema_version"],
+            crate::commands::docs_json::DOCS_JSON_SCHEMA_VERSION
+        );
+        assert_eq!(module["name"], "Main");
+
+        let find = |list: &str, name: &str| {
+            module[list]
+                .as_array()
+                .unwrap_or_else(|| panic!("Main.json should have a list \"{}\"", list))
+                .iter()
+                .find(|entry| entry["name"] == name)
+                .unwrap_or_else(|| panic!("\"{}\" of Main.json should contain {}", list, name))
+                .clone()
+        };
+        let names = |entries: &serde_json::Value| {
+            entries
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| entry["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let my_struct = find("types", "Main::MyStruct");
+        assert_eq!(my_struct["variant"], "struct");
+        assert_eq!(my_struct["document"], "A struct with one field");
+        assert_eq!(names(&my_struct["fields"]), vec!["field"]);
+        assert_eq!(my_struct["span"]["start_line"], 14);
+        assert!(my_struct["span"]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("main.fix"));
+        let my_union = find("types", "Main::MyUnion");
+        assert_eq!(my_union["variant"], "union");
+        assert_eq!(names(&my_union["variants"]), vec!["variant"]);
+        find("type_aliases", "Main::MyTypeAlias");
+
+        let my_trait = find("traits", "Main::MyTrait");
+        assert_eq!(my_trait["type_var"]["name"], "a");
+        assert_eq!(names(&my_trait["members"]), vec!["my_method"]);
+        assert_eq!(
+            my_trait["members"][0]["document"],
+            "Converts the value to a descriptive string"
+        );
+        find("trait_aliases", "Main::MyTraitAlias");
+        assert!(
+            module["impls"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|impl_| impl_["trait"] == "Main::MyTrait"
+                    && impl_["type"] == "Main::MyStruct"),
+            "Main.json should describe the impl of MyTrait for MyStruct"
+        );
+
+        let main = find("values", "Main::main");
+        assert_eq!(main["document"], "Main entry point");
+        assert!(main["trait"].is_null() && main["deprecated"].is_null());
+        let my_method = find("values", "Main::MyTrait::my_method");
+        assert_eq!(my_method["trait"], "Main::MyTrait");
+        assert!(
+            module["values"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|value| !value["name"].as_str().unwrap().contains("_private")),
+            "Main.json should not describe private values"
+        );
+    }
 }
@@ -0,0 +1,89 @@
+//! `fix test --doc` builds and runs the code blocks fenced with "```fix" in the documentation
+//! comments of the project's modules, each as a module `Main` importing the module documented.
+//!
+//! The case under `test_doctest/cases` documents a module `Lib` with examples that pass, one built
+//! without being run, one ignored, one that fails when it runs and one that does not compile,
+//! beside a block of another language. Its module `Main` has an example too, which is not tested.
+
+use crate::tests::test_util::{assert_failed, assert_succeeded, run_fix, setup_case_projects};
+
+/// The directory holding this module's case projects.
+const CASES: &str = "src/tests/test_doctest/cases";
+
+/// Each example is reported on a line of its own by the documented entry and the line of its
+/// fence, and a failure is reported at the lines of the documentation comment.
+#[test]
+fn test_runs_each_example() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "with_examples");
+    let output = run_fix(&project_dir, &["test", "--doc"]);
+    assert_failed(
+        &output,
+        "`fix test --doc` should fail, because two of the examples fail.",
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    let outcome_of = |entry: &str, line: usize| {
+        lines
+            .iter()
+            .find(|l| {
+                l.starts_with(&format!("doctest {} (", entry))
+                    && l.contains(&format!("lib.fix:{}) ... ", line))
+            })
+            .map(|l| l.rsplit(" ... ").next().unwrap().to_string())
+    };
+    for (entry, line, outcome) in [
+        ("Lib", 3, "ok"),
+        ("Lib::double", 10, "ok"),
+        ("Lib::increment", 25, "ok"),
+        ("Lib::increment", 30, "ignored"),
+        ("Lib::broken", 38, "FAILED"),
+        ("Lib::broken", 42, "FAILED"),
+    ] {
+        assert_eq!(
+            outcome_of(entry, line).as_deref(),
+            Some(outcome),
+            "the example of {} at line {} should be reported as {}.\nstdout: {}\nstderr: {}",
+            entry,
+            line,
+            outcome,
+            stdout,
+            stderr
+        );
+    }
+    assert!(
+        stdout.contains("running 6 doctests")
+            && stdout
+                .contains("doctest result: FAILED. 3 passed; 2 failed; 1 ignored; 0 filtered out"),
+        "the examples should be counted, without the block of another language.\nstdout: {}",
+        stdout
+    );
+
+    // The error of the example that does not compile quotes the line of the comment.
+    assert!(
+        stdout.contains("lib.fix") && stdout.contains("// let y : Bool = broken(1);"),
+        "the type error should be reported in the documentation comment.\nstdout: {}",
+        stdout
+    );
+    assert!(
+        stderr.contains("The examples in the documentation of the module `Main` are not tested"),
+        "the example of `Main` should be left out with a warning.\nstderr: {}",
+        stderr
+    );
+}
+
+/// The arguments after `--` select the examples whose names contain one of them.
+#[test]
+fn test_selects_examples_by_name() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "with_examples");
+    let output = run_fix(&project_dir, &["test", "--doc", "--", "double"]);
+    assert_succeeded(&output, "the example of `double` should pass.");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("running 1 doctest\n")
+            && stdout.contains("doctest result: ok. 1 passed; 0 failed; 0 ignored; 5 filtered out"),
+        "only the example of `double` should be run.\nstdout: {}",
+        stdout
+    );
+}
@@ -6,7 +6,8 @@
 //! `object_file_symbol_name` gives a Fix name.
 //!
 //! A C program reaches the library either by opening it at run time or by naming it on its own link
-//! line, and the case projects here cover one each.
+//! line, and the case projects here cover one each. A program of the second kind can take the
+//! declarations of the exported functions from the C header the build writes beside the library.
 
 use crate::configuration::{Configuration, FixOptimizationLevel, OutputFileType};
 use crate::tests::test_util::{
@@ -219,3 +220,49 @@ fn test_a_value_named_with_the_getter_symbol_crosses_compilation_units() {
     config.max_cu_size = 1;
     test_source(SOURCE, config);
 }
+
+/// A C program built against the library through the header the build writes, at the path the
+/// project file's `c_header` names. The driver declares nothing itself and is compiled with its
+/// warnings as errors, so a prototype the header lacks or gives wrongly stops it from building, and
+/// the documentation of each exported value is carried into the header above its prototype.
+#[test]
+fn test_a_c_program_is_built_against_the_generated_header() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "header_consumer");
+    let library = build_library(&project_dir, "max");
+    let header = std::fs::read_to_string(project_dir.join("include/header_consumer.h"))
+        .expect("`fix build` should write the header at the path `c_header` names.");
+    assert!(
+        header.contains("#ifndef HEADER_CONSUMER_H") && header.contains("extern \"C\""),
+        "the header should be guarded and declare C linkage for C++:\n{}",
+        header
+    );
+    assert!(
+        header.contains("// Adds two numbers.\n//\n// The sum wraps around as `I32` addition does.\nint32_t fix_add(int32_t, int32_t);"),
+        "the header should carry the documentation of `add` above its prototype:\n{}",
+        header
+    );
+    assert!(
+        header.contains("int64_t fix_count(void);"),
+        "the header should declare `fix_count` with an empty parameter list:\n{}",
+        header
+    );
+
+    let library_directory = library.parent().unwrap().to_str().unwrap().to_string();
+    let driver = build_driver(
+        &project_dir,
+        &[
+            "-Wall",
+            "-Werror",
+            library.to_str().unwrap(),
+            &format!("-Wl,-rpath,{}", library_directory),
+        ],
+    );
+    let output = Command::new(&driver)
+        .current_dir(&library_directory)
+        .output()
+        .expect("Failed to execute the driver");
+    assert_succeeded(
+        &output,
+        "the driver should get the answers of the functions the header declares.",
+    );
+}
@@ -0,0 +1,21 @@
+// Calls the functions the library built from `main.fix` exports through the declarations of the
+// header the build writes beside it, and reports through its exit status whether each answer is the
+// one the Fix source gives.
+//
+// The driver declares nothing itself: a prototype missing from the header, or one that disagrees
+// with a call here, stops the driver from compiling.
+
+#include "include/header_consumer.h"
+
+int main(void) {
+    if (fix_add(40, 2) != 42) {
+        return 1;
+    }
+    if (fix_half(5.0) != 2.5) {
+        return 2;
+    }
+    if (fix_count() != 1) {
+        return 3;
+    }
+    return 0;
+}
@@ -0,0 +1,145 @@
+//! `--explain-uniqueness` reports each mutating operation in the user's sources that kept its
+//! run-time uniqueness check, as a note saying why the container is not known to be unique there.
+//! These tests build programs whose checks are kept for a known reason, or removed, and read the
+//! notes from the JSON diagnostics.
+
+#[cfg(test)]
+mod integration_tests {
+    use crate::error::NOTE_KEPT_UNIQUE_CHECK;
+    use crate::tests::test_util::fix_build_source_command;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    /// Builds `source` at `opt_level` with `--message-format=json` and `build_args`, asserting it
+    /// succeeds, and returns the notes on kept uniqueness checks it reported, in the order reported.
+    fn kept_check_notes(source: &str, opt_level: &str, build_args: &[&str]) -> Vec<Value> {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let output = fix_build_source_command(temp_dir.path(), source, opt_level)
+            .arg("-o")
+            .arg(temp_dir.path().join("program"))
+            .arg("--message-format=json")
+            .args(build_args)
+            .output()
+            .expect("Failed to execute fix build");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "fix build failed:\nstdout: {}\nstderr: {}",
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        stdout
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<Value>(line)
+                    .unwrap_or_else(|e| panic!("stdout line {:?} is not JSON: {}", line, e))
+            })
+            .filter(|diagnostic| diagnostic["code"] == NOTE_KEPT_UNIQUE_CHECK)
+            .collect()
+    }
+
+    /// The line the primary span of `note` starts at.
+    fn primary_line(note: &Value) -> u64 {
+        note["spans"][0]["line_start"]
+            .as_u64()
+            .unwrap_or_else(|| panic!("the note points at no line: {}", note))
+    }
+
+    /// The message of `note` and the labels of its spans, joined for searching.
+    fn text(note: &Value) -> String {
+        let mut text = note["message"].as_str().unwrap_or_default().to_string();
+        for span in note["spans"].as_array().into_iter().flatten() {
+            if let Some(label) = span["label"].as_str() {
+                text += "\n";
+                text += label;
+            }
+        }
+        text
+    }
+
+    /// Sets element 0 of `arr` while `arr` is read again afterwards, so the write keeps its check.
+    const USED_LATER: &str = r#"module Main;
+
+clone_times : I64 -> Array I64 -> I64 -> I64;
+clone_times = |n, arr, acc| (
+    if n == 0 { acc } else {
+        let copy = arr.set(0, n);
+        clone_times(n - 1, arr, acc + copy.@(0) + arr.@(0))
+    }
+);
+
+main : IO ();
+main = println(clone_times(5, Array::fill(10, 0), 0).to_string);
+"#;
+
+    /// A write to an array read again after it is reported at the write, and the note shows where
+    /// the array is used again.
+    #[test]
+    fn test_explain_uniqueness_of_a_value_used_later() {
+        let notes = kept_check_notes(USED_LATER, "max", &["--explain-uniqueness"]);
+        let note = notes
+            .iter()
+            .find(|note| primary_line(note) == 6)
+            .unwrap_or_else(|| panic!("the write at line 6 was not reported: {:?}", notes));
+        assert_eq!(note["severity"], "note");
+        assert!(
+            text(note).contains("because it is used again after the operation")
+                && text(note).contains("It is used again here"),
+            "the note does not say the array is used again: {}",
+            note
+        );
+    }
+
+    /// Writes to a copy of a global array, which every reader of the global shares.
+    const GLOBAL: &str = r#"module Main;
+
+table : Array I64;
+table = Array::fill(3, 0);
+
+main : IO ();
+main = println(table.set(0, 1).@(0).to_string);
+"#;
+
+    /// A write to the value of a global is reported, naming the global.
+    #[test]
+    fn test_explain_uniqueness_of_a_global() {
+        let notes = kept_check_notes(GLOBAL, "max", &["--explain-uniqueness"]);
+        assert!(
+            notes
+                .iter()
+                .any(|note| primary_line(note) == 7
+                    && text(note).contains("the global `Main::table`")),
+            "the write to the global was not reported as such: {:?}",
+            notes
+        );
+    }
+
+    /// Writes to an array made just before, which is unique there.
+    const FRESH: &str = r#"module Main;
+
+main : IO ();
+main = println(Array::fill(3, 0).set(0, 1).@(0).to_string);
+"#;
+
+    /// A write whose check the optimization removed is not reported.
+    #[test]
+    fn test_explain_uniqueness_skips_a_removed_check() {
+        let notes = kept_check_notes(FRESH, "max", &["--explain-uniqueness"]);
+        assert!(
+            notes.is_empty(),
+            "a write to a fresh array was reported: {:?}",
+            notes
+        );
+    }
+
+    /// Without the option, nothing is reported.
+    #[test]
+    fn test_no_notes_without_explain_uniqueness() {
+        let notes = kept_check_notes(USED_LATER, "max", &[]);
+        assert!(
+            notes.is_empty(),
+            "notes were reported without the option: {:?}",
+            notes
+        );
+    }
+}
@@ -1240,3 +1240,216 @@ pub fn test_ffi_call_wrong_argument_count() {
         "Wrong number of arguments in FFI_CALL_IO expression.",
     );
 }
+
+/// A struct passed by value reaches C the way the C ABI of the target carries it, which depends on
+/// its size and on the class of its fields. The structs below cover each way: one general-purpose
+/// register, a general-purpose and a floating-point register, floating-point registers alone (a
+/// homogeneous aggregate on AArch64), memory, padding between fields, a nested struct, and a struct
+/// of one field, which Fix lays down as the field alone.
+#[test]
+pub fn test_ffi_call_passes_structs_by_value() {
+    let source = r##"
+        module Main;
+
+        type IntPair = unbox struct { a : I32, b : I32 };
+        type Mixed = unbox struct { n : I64, x : F64 };
+        type Floats = unbox struct { x : F32, y : F32, z : F32 };
+        type Big = unbox struct { a : I64, b : I64, c : I64 };
+        type Narrow = unbox struct { c : U8, s : I16, n : CInt };
+        type Nested = unbox struct { p : IntPair, x : F64 };
+        type Wrapped = unbox struct { n : I64 };
+
+        main : IO ();
+        main = (
+            let p = FFI_CALL[IntPair int_pair_swap(IntPair), IntPair { a : 1_I32, b : 2_I32 }];
+            assert_eq(|_|"int_pair_swap", (p.@a, p.@b), (2_I32, 1_I32));;
+
+            let m = FFI_CALL[Mixed mixed_scale(Mixed, I64), Mixed { n : 3, x : 0.5 }, 4];
+            assert_eq(|_|"mixed_scale", (m.@n, m.@x), (12, 2.0));;
+
+            let f = FFI_CALL[Floats floats_make(F32, F32, F32), 1.5_F32, 2.5_F32, 4.0_F32];
+            assert_eq(|_|"floats_sum", FFI_CALL[F64 floats_sum(Floats), f], 8.0);;
+
+            let b = FFI_CALL[Big big_add(Big, Big), Big { a : 1, b : 2, c : 3 }, Big { a : 10, b : 20, c : 30 }];
+            assert_eq(|_|"big_add", (b.@a, b.@b, b.@c), (11, 22, 33));;
+
+            let n = FFI_CALL[Narrow narrow_bump(Narrow), Narrow { c : 254_U8, s : -2_I16, n : 41.c_int }];
+            assert_eq(|_|"narrow_bump", (n.@c, n.@s, n.@n), (255_U8, -1_I16, 42.c_int));;
+
+            let v = FFI_CALL[Nested nested_flip(Nested), Nested { p : IntPair { a : 5_I32, b : 6_I32 }, x : 1.25 }];
+            assert_eq(|_|"nested_flip", (v.@p.@a, v.@p.@b, v.@x), (6_I32, 5_I32, -1.25));;
+
+            let w = FFI_CALL[Wrapped wrapped_make(I64), 7];
+            assert_eq(|_|"wrapped", FFI_CALL[I64 wrapped_get(Wrapped), w], 7);;
+
+            // On System V AMD64, `Mixed` takes the sixth general-purpose register after five
+            // integers, and goes to memory after six.
+            let m = Mixed { n : 100, x : 0.5 };
+            assert_eq(|_|"after five", FFI_CALL[F64 sum_after_five(I64, I64, I64, I64, I64, Mixed), 1, 2, 3, 4, 5, m], 115.5);;
+            assert_eq(|_|"after six", FFI_CALL[F64 sum_after_six(I64, I64, I64, I64, I64, I64, Mixed), 1, 2, 3, 4, 5, 6, m], 121.5);;
+
+            pure()
+        );
+    "##;
+    let c_source = r##"
+        #include <stdint.h>
+
+        typedef struct { int32_t a; int32_t b; } IntPair;
+        typedef struct { int64_t n; double x; } Mixed;
+        typedef struct { float x; float y; float z; } Floats;
+        typedef struct { int64_t a; int64_t b; int64_t c; } Big;
+        typedef struct { uint8_t c; int16_t s; int n; } Narrow;
+        typedef struct { IntPair p; double x; } Nested;
+        typedef struct { int64_t n; } Wrapped;
+
+        IntPair int_pair_swap(IntPair p) { IntPair q = { p.b, p.a }; return q; }
+        Mixed mixed_scale(Mixed m, int64_t k) { Mixed r = { m.n * k, m.x * k }; return r; }
+        Floats floats_make(float x, float y, float z) { Floats f = { x, y, z }; return f; }
+        double floats_sum(Floats f) { return (double)f.x + f.y + f.z; }
+        Big big_add(Big a, Big b) { Big r = { a.a + b.a, a.b + b.b, a.c + b.c }; return r; }
+        Narrow narrow_bump(Narrow n) { Narrow r = { n.c + 1, n.s + 1, n.n + 1 }; return r; }
+        Nested nested_flip(Nested v) { Nested r = { { v.p.b, v.p.a }, -v.x }; return r; }
+        Wrapped wrapped_make(int64_t n) { Wrapped w = { n }; return w; }
+        int64_t wrapped_get(Wrapped w) { return w.n; }
+        double sum_after_five(int64_t a, int64_t b, int64_t c, int64_t d, int64_t e, Mixed m) {
+            return a + b + c + d + e + m.n + m.x;
+        }
+        double sum_after_six(int64_t a, int64_t b, int64_t c, int64_t d, int64_t e, int64_t f, Mixed m) {
+            return a + b + c + d + e + f + m.n + m.x;
+        }
+    "##;
+    test_source_with_c(&source, &c_source, function_name!());
+}
+
+/// A struct named in an `FFI_CALL` signature is one C can hold: an `unbox struct` without type
+/// parameters, whose fields are C scalars or such structs, written by its own name.
+#[test]
+pub fn test_ffi_call_struct_c_cannot_hold_fails() {
+    let cases = [
+        ("type S = box struct { n : I64 };", "S", "is a boxed struct"),
+        (
+            "type S = unbox struct { b : Bool };",
+            "S",
+            "which C cannot hold in a struct",
+        ),
+        (
+            "type S = unbox struct { a : Array I64 };",
+            "S",
+            "which C cannot hold in a struct",
+        ),
+        (
+            "type S a = unbox struct { x : a };",
+            "S I64",
+            "takes type parameters",
+        ),
+        (
+            "type S = unbox struct { n : I64 }; type T = S;",
+            "T",
+            "cannot use type alias `Main::T`",
+        ),
+        (
+            "type S = unbox union { n : I64, x : F64 };",
+            "S",
+            "is not a struct",
+        ),
+    ];
+    for (decl, ty, msg) in cases {
+        let source = format!(
+            r##"
+            module Main;
+
+            {}
+
+            main : IO ();
+            main = (
+                let s : {} = undefined("");
+                eval FFI_CALL[I64 c_take(S), s];
+                pure()
+            );
+        "##,
+            decl, ty
+        );
+        test_source_fail(&source, Configuration::develop_mode(), msg);
+    }
+}
+
+/// A Fix function reaches C as a function pointer and a context pointer to the box holding it. The
+/// C side calls it as many times as it likes, and the box keeps the values the function captures
+/// alive until it is released, from C by the function `get_funptr_release` gives or from Fix by
+/// `boxed_from_retained_ptr`.
+#[test]
+pub fn test_get_funptr_callback() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            // A pure callback capturing a value.
+            let offset = 10;
+            let f = |i| i * 2 + offset;
+            let callback = (|_| f).get_funptr_callback;
+            let context = *Box::make(f).boxed_to_retained_ptr;
+            let release = (|_| undefined("") : Box (I64 -> I64)).get_funptr_release;
+            let sum = *FFI_CALL_IO[I64 c_sum_with(I64, Ptr, Ptr, Ptr), 4, callback, context, release];
+            assert_eq(|_|"pure callback", sum, 52);;
+
+            // A callback of two arguments running an IO action, which C counts the runs of.
+            let cmp = |a : I64, b : I64| FFI_CALL_IO[() c_count()].map(|_|
+                if a < b { -1.c_int } else if a > b { 1.c_int } else { 0.c_int }
+            );
+            let callback = (|_| cmp).get_funptr_callback;
+            let context = *Box::make(cmp).boxed_to_retained_ptr;
+            let sorted = *FFI_CALL_IO[CInt c_is_sorted_by(Ptr, Ptr), callback, context];
+            assert_eq(|_|"sorted", sorted, 1.c_int);;
+            let calls = *FFI_CALL_IO[I64 c_counted()];
+            assert_eq(|_|"calls", calls, 3);;
+            let _ = *(boxed_from_retained_ptr(context) : IO (Box (I64 -> I64 -> IO CInt)));
+
+            pure()
+        );
+    "##;
+    let c_source = r##"
+        #include <stdint.h>
+
+        int64_t c_sum_with(int64_t n, int64_t (*callback)(int64_t, void*), void* context, void (*release)(void*)) {
+            int64_t sum = 0;
+            for (int64_t i = 0; i < n; i++) { sum += callback(i, context); }
+            release(context);
+            return sum;
+        }
+
+        static int64_t counted = 0;
+        void c_count(void) { counted++; }
+        int64_t c_counted(void) { return counted; }
+
+        int c_is_sorted_by(int (*cmp)(int64_t, int64_t, void*), void* context) {
+            int64_t values[] = { 1, 2, 2, 5 };
+            for (int i = 0; i + 1 < 4; i++) {
+                if (cmp(values[i], values[i + 1], context) > 0) { return 0; }
+            }
+            return 1;
+        }
+    "##;
+    test_source_with_c(&source, &c_source, function_name!());
+}
+
+/// The C function `get_funptr_callback` gives is built the way an exported function is, so a Fix
+/// function of a type `FFI_EXPORT` rejects is rejected here too, at the use.
+#[test]
+pub fn test_get_funptr_callback_of_unexportable_type_fails() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            let lazy_f : Lazy (String -> I64) = |_| |s| s.get_size;
+            let callback = lazy_f.get_funptr_callback;
+            pure()
+        );
+    "##;
+    test_source_fail(
+        &source,
+        Configuration::develop_mode(),
+        "cannot be used at the type `Std::String -> Std::I64`",
+    );
+}
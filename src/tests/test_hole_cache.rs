@@ -15,6 +15,7 @@
 
 #[cfg(test)]
 mod integration_tests {
+    use crate::elaboration::typecheckcache::type_check_cache_dir;
     use crate::tests::test_util::{copy_dir_recursive, fix_command};
     use std::fs;
     use std::path::{Path, PathBuf};
@@ -50,10 +51,11 @@ mod integration_tests {
             .expect("Failed to execute fix check")
     }
 
-    /// List the names of cache files under `<project>/.fixlang/cache/typecheck/`.
+    /// List the names of the cache files this compiler files under
+    /// `<project>/.fixlang/cache/typecheck/`.
     /// Returns an empty Vec if the directory doesn't exist.
     fn list_cache_files(project_dir: &Path) -> Vec<String> {
-        let cache_dir = project_dir.join(".fixlang/cache/typecheck");
+        let cache_dir = project_dir.join(type_check_cache_dir());
         if !cache_dir.exists() {
             return vec![];
         }
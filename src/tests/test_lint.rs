@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod integration_tests {
+    use crate::tests::test_util::{copy_dir_recursive, fix_command};
+    use serde_json::Value;
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn get_test_cases_dir() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/tests/test_lint/cases");
+        path
+    }
+
+    fn setup_test_env(case_name: &str) -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let src = get_test_cases_dir().join(case_name);
+        let dst = temp_dir.path().join(case_name);
+        copy_dir_recursive(&src, &dst).expect("Failed to copy test case");
+        (temp_dir, dst)
+    }
+
+    /// Append `text` to the project file of `project_dir`.
+    fn append_to_project_file(project_dir: &PathBuf, text: &str) {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(project_dir.join("fixproj.toml"))
+            .expect("Failed to open fixproj.toml");
+        file.write_all(text.as_bytes())
+            .expect("Failed to write fixproj.toml");
+    }
+
+    /// Run `fix check --message-format=json`, returning whether it succeeded and the lint findings
+    /// it reported, each as `(code, severity, line)`, sorted.
+    fn lint_findings(project_dir: &PathBuf) -> (bool, Vec<(String, String, u64)>) {
+        let output = fix_command()
+            .arg("check")
+            .arg("--message-format=json")
+            .current_dir(project_dir)
+            .output()
+            .expect("Failed to execute fix check");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut findings = stdout
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<Value>(line)
+                    .unwrap_or_else(|e| panic!("stdout line {:?} is not JSON: {}", line, e))
+            })
+            .filter(|diagnostic| {
+                diagnostic["code"]
+                    .as_str()
+                    .map_or(false, |code| code.starts_with("unused-"))
+            })
+            .map(|diagnostic| {
+                (
+                    diagnostic["code"].as_str().unwrap().to_string(),
+                    diagnostic["severity"].as_str().unwrap().to_string(),
+                    diagnostic["spans"][0]["line_start"].as_u64().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        findings.sort();
+        (output.status.success(), findings)
+    }
+
+    fn finding(code: &str, severity: &str, line: u64) -> (String, String, u64) {
+        (code.to_string(), severity.to_string(), line)
+    }
+
+    /// Each lint reports what it should, as a warning by default; `_`-prefixed binders and private
+    /// globals reached from a public one are left alone.
+    #[test]
+    fn test_lint_default_warnings() {
+        let (_temp_dir, project_dir) = setup_test_env("unused_project");
+
+        let (success, findings) = lint_findings(&project_dir);
+        assert!(success, "warnings should not fail fix check");
+        assert_eq!(
+            findings,
+            vec![
+                // `triple` of `import Lib::{double, triple};`, and the whole of `import Other;`.
+                finding("unused-import", "warning", 3),
+                finding("unused-import", "warning", 4),
+                // `_helper` is reached by no public value; `_used_helper` is reached by `main`.
+                finding("unused-private-global", "warning", 6),
+                // `let unused = 1;`, and the parameter `y` of `|y| 0`.
+                finding("unused-variable", "warning", 14),
+                finding("unused-variable", "warning", 16),
+            ]
+        );
+    }
+
+    /// The `lints` section of the project file sets the level of each lint.
+    #[test]
+    fn test_lint_levels_from_project_file() {
+        let (_temp_dir, project_dir) = setup_test_env("unused_project");
+        append_to_project_file(
+            &project_dir,
+            "\n[lints]\nunused-import = \"allow\"\nunused-variable = \"deny\"\n",
+        );
+
+        let (success, findings) = lint_findings(&project_dir);
+        assert!(!success, "a denied lint should fail fix check");
+        assert_eq!(
+            findings,
+            vec![
+                finding("unused-private-global", "warning", 6),
+                finding("unused-variable", "error", 14),
+                finding("unused-variable", "error", 16),
+            ]
+        );
+    }
+
+    /// A lint the compiler does not know is an error located in the project file.
+    #[test]
+    fn test_lint_unknown_lint_in_project_file() {
+        let (_temp_dir, project_dir) = setup_test_env("unused_project");
+        append_to_project_file(&project_dir, "\n[lints]\nunused-imports = \"allow\"\n");
+
+        let output = fix_command()
+            .arg("check")
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix check");
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Unknown lint \"unused-imports\""),
+            "Got: {}",
+            stderr
+        );
+        assert!(stderr.contains("fixproj.toml"), "Got: {}", stderr);
+    }
+
+    /// `fix edit unused-imports` removes what `unused-import` reports, and nothing else.
+    #[test]
+    fn test_edit_unused_imports() {
+        let (_temp_dir, project_dir) = setup_test_env("unused_project");
+        // A denied lint makes the program have errors, which must not stop the removal.
+        append_to_project_file(&project_dir, "\n[lints]\nunused-import = \"deny\"\n");
+
+        let output = fix_command()
+            .arg("edit")
+            .arg("unused-imports")
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to run fix edit unused-imports");
+        if !output.status.success() {
+            panic!(
+                "fix edit unused-imports failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let expected = fs::read_to_string(project_dir.join("main.edited.fix"))
+            .expect("Failed to read main.edited.fix");
+        let actual =
+            fs::read_to_string(project_dir.join("main.fix")).expect("Failed to read main.fix");
+        assert_eq!(expected, actual);
+
+        let (success, findings) = lint_findings(&project_dir);
+        assert!(success, "no unused import should be left to deny");
+        assert!(
+            findings.iter().all(|(code, _, _)| code != "unused-import"),
+            "Got: {:?}",
+            findings
+        );
+    }
+}
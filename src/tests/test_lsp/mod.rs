@@ -8,9 +8,11 @@ pub mod test_diagnostics;
 pub mod test_goto_definition;
 pub mod test_hover;
 pub mod test_import_completion;
+pub mod test_inlay_hints;
 pub mod test_references;
 pub mod test_rename;
 pub mod test_semantic_tokens;
+pub mod test_signature_help;
 pub mod test_stdin_eof;
 pub mod test_workspace_symbol;
 
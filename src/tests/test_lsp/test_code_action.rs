@@ -494,4 +494,72 @@ mod tests {
 
         ctx.shutdown();
     }
+
+    /// A `non-exhaustive-match` diagnostic draws a quick fix that adds a `variant(_) => ?` arm
+    /// for each uncovered variant, in the order the union declares them, each on its own line
+    /// after a comma added to the last written arm. Applying it clears the diagnostic.
+    #[test]
+    fn test_quickfix_non_exhaustive_match() {
+        let mut ctx = LspQuickFixCtx::setup("quickfix_non_exhaustive_match", &["main.fix"]);
+
+        let diagnostics = ctx.client.get_diagnostics(Path::new("main.fix"));
+        let match_diag = diagnostic_with_code(&diagnostics, "non-exhaustive-match");
+
+        let (start_line, start_col, end_line, end_col) = range_of(match_diag);
+
+        let actions = ctx.code_actions(
+            "main.fix",
+            vec![match_diag.clone()],
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        );
+
+        let action = actions
+            .iter()
+            .find(|a| {
+                a.get("title").and_then(|t| t.as_str())
+                    == Some("Add missing variants `circle`, `triangle`")
+            })
+            .expect("Should find an 'Add missing variants' action");
+
+        let edit = action.get("edit").expect("Action should have edit");
+        let changes = edit.get("changes").expect("Edit should have changes");
+        let uri = ctx.file_uri("main.fix");
+        let file_edits = changes
+            .get(&uri)
+            .expect("Should have edits for main.fix")
+            .as_array()
+            .expect("Edits should be an array");
+
+        let main_path = ctx.project_dir.join("main.fix");
+        let original = fs::read_to_string(&main_path).expect("Failed to read main.fix");
+        let parsed_edits = parse_text_edits(file_edits);
+        let updated = apply_text_edits(&original, &parsed_edits);
+        assert!(
+            updated.contains(
+                "match s {\n    square(a) => a,\n    circle(_) => ?,\n    triangle(_) => ?,\n};"
+            ),
+            "Updated source should contain the added arms. Got: {}",
+            updated
+        );
+        fs::write(&main_path, &updated).expect("Failed to write main.fix");
+        ctx.client
+            .change_document(Path::new("main.fix"))
+            .expect("Failed to send didChange");
+        ctx.client
+            .trigger_and_wait_for_diagnostics(Path::new("main.fix"));
+
+        let diagnostics = ctx.client.get_diagnostics(Path::new("main.fix"));
+        assert!(
+            !diagnostics
+                .iter()
+                .any(|d| has_code(d, "non-exhaustive-match")),
+            "non-exhaustive-match diagnostic should be gone after applying the quick fix. Got: {:?}",
+            diagnostics
+        );
+
+        ctx.shutdown();
+    }
 }
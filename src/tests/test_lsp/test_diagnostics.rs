@@ -8,10 +8,12 @@
 mod tests {
     use super::super::completion_harness::LspCompletionCtx;
     use super::super::lsp_client::LspClient;
-    use crate::tests::test_util::copy_dir_recursive;
+    use crate::elaboration::typecheckcache::type_check_cache_dir;
+    use crate::tests::test_util::{copy_dir_recursive, fix_command};
     use serde_json::Value;
+    use std::fs;
     use std::path::{Path, PathBuf};
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
     use tempfile::TempDir;
 
     /// Copies the named case project into a temporary directory and returns it with the project's
@@ -360,4 +362,56 @@ mod tests {
 
         ctx.shutdown();
     }
+
+    /// The entries of the type-check cache of `project_dir`, with when each was last written.
+    fn cache_entries(project_dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+        let mut entries = fs::read_dir(project_dir.join(type_check_cache_dir()))
+            .expect("the type-check cache directory should exist after a check")
+            .map(|entry| {
+                let entry = entry.expect("a cache entry should be readable");
+                let written = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .expect("a cache entry should carry a modification time");
+                (entry.path(), written)
+            })
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    /// A language server starting on a project that `fix check` has checked finds every value in
+    /// the cache the check filed: it writes no entry of its own and rewrites none, since a run
+    /// writes an entry only for a value it had to check.
+    #[test]
+    fn test_a_new_session_reuses_the_type_check_cache_of_the_cli() {
+        let (_temp_dir, project_dir) = setup_test_env("typecheck_cache_reuse");
+        let output = fix_command()
+            .arg("check")
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute fix check");
+        assert!(
+            output.status.success(),
+            "fix check failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let filed_by_check = cache_entries(&project_dir);
+        assert!(
+            !filed_by_check.is_empty(),
+            "fix check filed no entry in the type-check cache"
+        );
+
+        let diagnostics = diagnostics_of(&project_dir, Path::new("main.fix"));
+        assert!(
+            diagnostics.is_empty(),
+            "the project has no error, but the diagnostics are {:?}",
+            diagnostics
+        );
+        assert_eq!(
+            cache_entries(&project_dir),
+            filed_by_check,
+            "the language server checked values the cache of `fix check` already held"
+        );
+    }
 }
@@ -0,0 +1,144 @@
+//! LSP integration tests for `textDocument/inlayHint`: the inferred types
+//! of unannotated `let` binders, lambda parameters and match-arm pattern
+//! variables, and the `fix.inlayHints.*` settings that turn each kind off.
+
+#[cfg(test)]
+mod tests {
+    use super::super::lsp_client::LspClient;
+    use crate::tests::test_util::copy_dir_recursive;
+    use serde_json::json;
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+    use tempfile::TempDir;
+
+    /// Absolute path to the LSP `cases/` directory.
+    fn get_test_cases_dir() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/tests/test_lsp/cases");
+        path
+    }
+
+    /// Copy the named test project into a fresh temp directory and
+    /// return both the temp dir handle (to keep it alive) and the
+    /// canonicalised path of the copied project.
+    fn setup_test_env(project_name: &str) -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let test_case_src = get_test_cases_dir().join(project_name);
+        let test_case_dst = temp_dir.path().join(project_name);
+        copy_dir_recursive(&test_case_src, &test_case_dst).expect("Failed to copy test case");
+        let test_case_dst = test_case_dst
+            .canonicalize()
+            .expect("Failed to canonicalize test case path");
+        (temp_dir, test_case_dst)
+    }
+
+    /// Send textDocument/inlayHint over the whole of `file` and return
+    /// the hints as `(line, character, label)`, in order.
+    fn inlay_hints(
+        client: &mut LspClient,
+        project_dir: &Path,
+        file: &str,
+    ) -> Vec<(u64, u64, String)> {
+        let uri = format!("file://{}", project_dir.join(file).display());
+        let id = client
+            .send_request(
+                "textDocument/inlayHint",
+                json!({
+                    "textDocument": { "uri": uri },
+                    "range": {
+                        "start": { "line": 0, "character": 0 },
+                        "end": { "line": 100, "character": 0 }
+                    }
+                }),
+            )
+            .expect("Failed to send inlayHint request");
+        client.wait_for_server(Duration::from_secs(5));
+        let response = client
+            .get_response(id)
+            .expect("Should receive an inlayHint response");
+        let hints = response
+            .get("result")
+            .and_then(|r| r.as_array())
+            .cloned()
+            .expect("inlayHint should return an array");
+        hints
+            .iter()
+            .map(|hint| {
+                let position = hint.get("position").unwrap();
+                (
+                    position.get("line").and_then(|l| l.as_u64()).unwrap(),
+                    position.get("character").and_then(|c| c.as_u64()).unwrap(),
+                    hint.get("label")
+                        .and_then(|l| l.as_str())
+                        .unwrap()
+                        .to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_inlay_hints() {
+        let (_temp_dir, project_dir) = setup_test_env("inlay_hints");
+        let mut client = LspClient::new(&project_dir).expect("Failed to start LSP");
+        client
+            .initialize(&project_dir, Duration::from_secs(5))
+            .expect("Failed to initialize LSP");
+        client
+            .open_document(Path::new("main.fix"))
+            .expect("Failed to open main.fix");
+        client.trigger_and_wait_for_diagnostics(Path::new("main.fix"));
+
+        // Source layout (0-based lines):
+        //
+        //   3:     let n = 3;
+        //   4:     let f = |x| x + n;
+        //   5:     let s : I64 = match Option::some(f(1)) { some(v) => v, none(_) => 0 };
+        //
+        // `s` is annotated and `_` binds nothing, so neither gets a hint.
+        let hints = inlay_hints(&mut client, &project_dir, "main.fix");
+        assert_eq!(
+            hints,
+            vec![
+                (3, 9, ": I64".to_string()),
+                (4, 9, ": I64 -> I64".to_string()),
+                (4, 13, ": I64".to_string()),
+                (5, 51, ": I64".to_string()),
+            ]
+        );
+
+        // Turn off the hints of lambda parameters and pattern variables.
+        client
+            .send_notification(
+                "workspace/didChangeConfiguration",
+                json!({
+                    "settings": {
+                        "fix": {
+                            "inlayHints": {
+                                "lambdaParameters": false,
+                                "patternVariables": false
+                            }
+                        }
+                    }
+                }),
+            )
+            .expect("Failed to send didChangeConfiguration");
+        let hints = inlay_hints(&mut client, &project_dir, "main.fix");
+        assert_eq!(
+            hints,
+            vec![
+                (3, 9, ": I64".to_string()),
+                (4, 9, ": I64 -> I64".to_string()),
+            ]
+        );
+
+        client
+            .shutdown(Duration::from_millis(500))
+            .expect("Failed to shutdown LSP");
+        client
+            .finish()
+            .expect("Reader thread should not have errors");
+    }
+}
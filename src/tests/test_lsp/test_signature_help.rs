@@ -0,0 +1,143 @@
+//! LSP integration tests for `textDocument/signatureHelp`: the callee is
+//! found before the unclosed `(` at the cursor, in plain and in
+//! method-call syntax, and the argument the cursor is in is highlighted.
+
+#[cfg(test)]
+mod tests {
+    use super::super::lsp_client::LspClient;
+    use crate::tests::test_util::copy_dir_recursive;
+    use serde_json::{json, Value};
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+    use tempfile::TempDir;
+
+    /// Absolute path to the LSP `cases/` directory.
+    fn get_test_cases_dir() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/tests/test_lsp/cases");
+        path
+    }
+
+    /// Copy the named test project into a fresh temp directory and
+    /// return both the temp dir handle (to keep it alive) and the
+    /// canonicalised path of the copied project.
+    fn setup_test_env(project_name: &str) -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let test_case_src = get_test_cases_dir().join(project_name);
+        let test_case_dst = temp_dir.path().join(project_name);
+        copy_dir_recursive(&test_case_src, &test_case_dst).expect("Failed to copy test case");
+        let test_case_dst = test_case_dst
+            .canonicalize()
+            .expect("Failed to canonicalize test case path");
+        (temp_dir, test_case_dst)
+    }
+
+    /// Send textDocument/signatureHelp at `line`:`col` of `file` and
+    /// return the result value (a SignatureHelp object or null).
+    fn signature_help(
+        client: &mut LspClient,
+        project_dir: &Path,
+        file: &str,
+        line: u32,
+        col: u32,
+    ) -> Value {
+        let uri = format!("file://{}", project_dir.join(file).display());
+        let id = client
+            .send_request(
+                "textDocument/signatureHelp",
+                json!({
+                    "textDocument": { "uri": uri },
+                    "position": { "line": line, "character": col }
+                }),
+            )
+            .expect("Failed to send signatureHelp request");
+        client.wait_for_server(Duration::from_secs(5));
+        let response = client
+            .get_response(id)
+            .expect("Should receive a signatureHelp response");
+        response
+            .get("result")
+            .cloned()
+            .expect("Response should have a result field")
+    }
+
+    /// The part of a signature's label its active parameter points at.
+    fn active_parameter_label(signature: &Value) -> Option<String> {
+        let label: Vec<u16> = signature.get("label")?.as_str()?.encode_utf16().collect();
+        let active = signature.get("activeParameter")?.as_u64()? as usize;
+        let offsets = signature
+            .get("parameters")?
+            .as_array()?
+            .get(active)?
+            .get("label")?
+            .as_array()?;
+        let start = offsets.get(0)?.as_u64()? as usize;
+        let end = offsets.get(1)?.as_u64()? as usize;
+        String::from_utf16(&label[start..end]).ok()
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let (_temp_dir, project_dir) = setup_test_env("signature_help");
+        let mut client = LspClient::new(&project_dir).expect("Failed to start LSP");
+        client
+            .initialize(&project_dir, Duration::from_secs(5))
+            .expect("Failed to initialize LSP");
+        client
+            .open_document(Path::new("main.fix"))
+            .expect("Failed to open main.fix");
+        client.trigger_and_wait_for_diagnostics(Path::new("main.fix"));
+
+        // Source layout (0-based lines):
+        //
+        //   3:     let sum = [1, 2, 3].to_iter.fold(0, |acc, x| acc + x);
+        //   4:     let arr = Array::fill(3, sum);
+
+        // In `Array::fill(3, |sum)`, the second parameter is active.
+        let help = signature_help(&mut client, &project_dir, "main.fix", 4, 29);
+        let signatures = help
+            .get("signatures")
+            .and_then(|s| s.as_array())
+            .expect("signatureHelp should return signatures");
+        assert_eq!(signatures.len(), 1, "Got: {:?}", signatures);
+        let label = signatures[0].get("label").and_then(|l| l.as_str()).unwrap();
+        assert!(
+            label.starts_with("Std::Array::fill : I64 -> a -> Array a"),
+            "Got: {:?}",
+            label
+        );
+        assert_eq!(active_parameter_label(&signatures[0]).as_deref(), Some("a"));
+
+        // In the method call `.fold(0, |...`, the receiver is the last
+        // argument of `fold`, so the second parameter is active.
+        let help = signature_help(&mut client, &project_dir, "main.fix", 3, 40);
+        let signatures = help
+            .get("signatures")
+            .and_then(|s| s.as_array())
+            .expect("signatureHelp should return signatures");
+        assert!(!signatures.is_empty());
+        for signature in signatures {
+            let label = signature.get("label").and_then(|l| l.as_str()).unwrap();
+            assert!(label.contains("::fold : "), "Got: {:?}", label);
+            assert_eq!(
+                signature.get("activeParameter").and_then(|p| p.as_u64()),
+                Some(1),
+                "Got: {:?}",
+                signature
+            );
+        }
+
+        // Outside of any argument list there is no signature to show.
+        let help = signature_help(&mut client, &project_dir, "main.fix", 4, 8);
+        assert!(help.is_null(), "Got: {:?}", help);
+
+        client
+            .shutdown(Duration::from_millis(500))
+            .expect("Failed to shutdown LSP");
+        client
+            .finish()
+            .expect("Reader thread should not have errors");
+    }
+}
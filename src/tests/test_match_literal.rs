@@ -0,0 +1,217 @@
+use crate::{
+    configuration::Configuration,
+    tests::test_util::{test_source, test_source_fail},
+};
+
+/// Verifies matches on integers of several types and on `U8` characters, negative values included.
+#[test]
+pub fn test_match_integer_literals() {
+    let source = r##"
+        module Main;
+
+        describe : I64 -> String;
+        describe = |n| match n {
+            0 => "zero",
+            1 => "one",
+            -1 => "minus one",
+            0x10 => "sixteen",
+            _ => "other"
+        };
+
+        classify : U8 -> I64;
+        classify = |c| match c {
+            'a' => 1,
+            'b' => 2,
+            255_U8 => 3,
+            _ => 0
+        };
+
+        sign : I8 -> I64;
+        sign = |n| match n {
+            -128_I8 => -2,
+            0_I8 => 0,
+            m => if m < 0_I8 { -1 } else { 1 }
+        };
+
+        main : IO ();
+        main = (
+            assert_eq(|_|"0", describe(0), "zero");;
+            assert_eq(|_|"1", describe(1), "one");;
+            assert_eq(|_|"-1", describe(-1), "minus one");;
+            assert_eq(|_|"16", describe(16), "sixteen");;
+            assert_eq(|_|"2", describe(2), "other");;
+            assert_eq(|_|"a", classify('a'), 1);;
+            assert_eq(|_|"b", classify('b'), 2);;
+            assert_eq(|_|"255", classify(255_U8), 3);;
+            assert_eq(|_|"c", classify('c'), 0);;
+            assert_eq(|_|"min", sign(-128_I8), -2);;
+            assert_eq(|_|"neg", sign(-5_I8), -1);;
+            assert_eq(|_|"zero", sign(0_I8), 0);;
+            assert_eq(|_|"pos", sign(7_I8), 1);;
+            pure()
+        );
+    "##;
+    test_source(source, Configuration::develop_mode());
+}
+
+/// Verifies matches on strings and on booleans.
+#[test]
+pub fn test_match_string_and_bool_literals() {
+    let source = r##"
+        module Main;
+
+        answer : String -> I64;
+        answer = |s| match s {
+            "yes" => 1,
+            "no" => 0,
+            _ => -1
+        };
+
+        to_int : Bool -> I64;
+        to_int = |b| match b {
+            true => 1,
+            false => 0
+        };
+
+        main : IO ();
+        main = (
+            assert_eq(|_|"yes", answer("yes"), 1);;
+            assert_eq(|_|"no", answer("no"), 0);;
+            assert_eq(|_|"other", answer("maybe"), -1);;
+            assert_eq(|_|"true", to_int(true), 1);;
+            assert_eq(|_|"false", to_int(false), 0);;
+            pure()
+        );
+    "##;
+    test_source(source, Configuration::develop_mode());
+}
+
+/// Verifies that a guard is evaluated with the variables of its pattern bound, and that the arms after
+/// an arm whose guard fails are tried.
+#[test]
+pub fn test_match_guard() {
+    let source = r##"
+        module Main;
+
+        type Shape = union { circle : I64, rect : (I64, I64) };
+
+        describe : Shape -> String;
+        describe = |s| match s {
+            circle(r) if r == 0 => "point",
+            circle(_) => "circle",
+            rect((w, h)) if w == h => "square",
+            rect(_) => "rect"
+        };
+
+        bucket : I64 -> I64;
+        bucket = |n| match n {
+            0 => 0,
+            m if m < 0 => -1,
+            m if m < 10 => 1,
+            _ => 2
+        };
+
+        main : IO ();
+        main = (
+            assert_eq(|_|"point", describe(Shape::circle(0)), "point");;
+            assert_eq(|_|"circle", describe(Shape::circle(3)), "circle");;
+            assert_eq(|_|"square", describe(Shape::rect((2, 2))), "square");;
+            assert_eq(|_|"rect", describe(Shape::rect((2, 3))), "rect");;
+            assert_eq(|_|"0", bucket(0), 0);;
+            assert_eq(|_|"-4", bucket(-4), -1);;
+            assert_eq(|_|"4", bucket(4), 1);;
+            assert_eq(|_|"40", bucket(40), 2);;
+            pure()
+        );
+    "##;
+    test_source(source, Configuration::develop_mode());
+}
+
+/// Verifies that a match whose literals leave values uncovered is rejected.
+#[test]
+pub fn test_match_literal_not_exhaustive() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            let x = match 3 {
+                0 => "zero",
+                1 => "one"
+            };
+            println(x)
+        );
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "The literal patterns do not cover every value of",
+    );
+}
+
+/// Verifies that a match all of whose arms have guards is rejected.
+#[test]
+pub fn test_match_guard_not_exhaustive() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            let x = match Option::some(3) {
+                some(v) if v > 0 => v,
+                none() if true => 0
+            };
+            println(x.to_string)
+        );
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "Every arm of this `match` has a guard or a string literal",
+    );
+}
+
+/// Verifies that a literal matched twice is reported as unreachable.
+#[test]
+pub fn test_match_literal_duplicate() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            let x = match 3 {
+                1 => "one",
+                1 => "uno",
+                _ => "other"
+            };
+            println(x)
+        );
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "Pattern `1` is unreachable: an earlier arm matches the same value.",
+    );
+}
+
+/// Verifies that a floating point literal cannot be a pattern.
+#[test]
+pub fn test_match_float_literal() {
+    let source = r##"
+        module Main;
+
+        main : IO ();
+        main = (
+            let x = match 1.0 {
+                1.0 => "one",
+                _ => "other"
+            };
+            println(x)
+        );
+    "##;
+    test_source_fail(
+        source,
+        Configuration::develop_mode(),
+        "A floating point number cannot be a pattern.",
+    );
+}
@@ -0,0 +1,121 @@
+//! The object files of the compilation units are generated over as many threads as `-j` asks for.
+//! Whichever thread generates a unit, its object file must come out the same, so these tests build
+//! a program split into many units with one thread and with several, and compare what they wrote.
+
+#[cfg(test)]
+mod integration_tests {
+    use crate::constants::{COMPILATION_UNITS_PATH, DOT_FIXLANG};
+    use crate::tests::test_util::fix_build_source_command;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    /// A program of several globals, which `--max-cu-size 1` puts into units of their own.
+    const SOURCE: &str = r#"module Main;
+
+table : Array I64;
+table = Array::from_map(8, |i| i * i);
+
+total : I64;
+total = table.to_iter.fold(0, add);
+
+twice : I64 -> I64;
+twice = |x| x * 2;
+
+shifted : I64 -> Array I64;
+shifted = |n| table.to_iter.map(|x| x + n).to_array;
+
+main : IO ();
+main = println((total + twice(3)).to_string + " " + shifted(3).to_string);
+"#;
+
+    const EXPECTED: &str = "146 [3, 4, 7, 12, 19, 28, 39, 52]\n";
+
+    /// Builds `SOURCE` in `dir` at the `basic` level, split into units of one symbol, with
+    /// `-j <jobs>`, runs the program, and returns the object files of the units by name. The
+    /// compiler's working files are removed afterwards, so the next build in `dir` generates every
+    /// unit again.
+    fn build_units(dir: &Path, jobs: &str) -> BTreeMap<String, Vec<u8>> {
+        let program = dir.join("program");
+        let output = fix_build_source_command(dir, SOURCE, "basic")
+            .arg("-o")
+            .arg(&program)
+            .args(["--max-cu-size", "1", "-j", jobs])
+            .output()
+            .expect("Failed to execute fix build");
+        assert!(
+            output.status.success(),
+            "fix build -j {} failed:\nstdout: {}\nstderr: {}",
+            jobs,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let run = Command::new(&program)
+            .output()
+            .expect("Failed to run the built program");
+        assert_eq!(
+            String::from_utf8_lossy(&run.stdout),
+            EXPECTED,
+            "built with -j {}",
+            jobs
+        );
+
+        let mut objects = BTreeMap::new();
+        for entry in fs::read_dir(dir.join(COMPILATION_UNITS_PATH)).expect("no units were written")
+        {
+            let path = entry.expect("Failed to read the units directory").path();
+            if path.extension().map_or(false, |ext| ext == "o") {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                objects.insert(
+                    name,
+                    fs::read(&path).expect("Failed to read an object file"),
+                );
+            }
+        }
+        fs::remove_dir_all(dir.join(DOT_FIXLANG)).expect("Failed to remove the working files");
+        objects
+    }
+
+    /// Generating the units over four threads writes the same object files, byte for byte, as
+    /// generating them one after another on a single thread.
+    #[test]
+    fn test_parallel_codegen_is_deterministic() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let serial = build_units(temp_dir.path(), "1");
+        assert!(
+            serial.len() > 2,
+            "the program was not split into several units: {:?}",
+            serial.keys()
+        );
+        let parallel = build_units(temp_dir.path(), "4");
+        assert_eq!(
+            serial.keys().collect::<Vec<_>>(),
+            parallel.keys().collect::<Vec<_>>(),
+            "the builds made different units"
+        );
+        for (name, object) in &serial {
+            assert!(
+                &parallel[name] == object,
+                "the object file {} differs between -j 1 and -j 4",
+                name
+            );
+        }
+    }
+
+    /// `-j 0` asks for no thread at all, and is rejected.
+    #[test]
+    fn test_zero_jobs_is_rejected() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let output = fix_build_source_command(temp_dir.path(), SOURCE, "basic")
+            .args(["-j", "0"])
+            .output()
+            .expect("Failed to execute fix build");
+        assert!(
+            !output.status.success(),
+            "fix build -j 0 succeeded:\nstderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! A program built with `--profile=rc` counts its allocations, retains, releases and force-unique
+//! clones by source location, and writes a report ranking the locations when it exits. These tests
+//! build a program that clones a shared array a known number of times and read the report it
+//! writes.
+
+#[cfg(test)]
+mod integration_tests {
+    use crate::tests::test_util::fix_build_source_command;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    /// Sets element 0 of `arr` while `arr` is still read afterwards, so each of the `n` writes finds
+    /// the array shared and clones it.
+    const SOURCE: &str = r#"module Main;
+
+clone_times : I64 -> Array I64 -> I64 -> I64;
+clone_times = |n, arr, acc| (
+    if n == 0 { acc } else {
+        let copy = arr.set(0, n);
+        clone_times(n - 1, arr, acc + copy.@(0) + arr.@(0))
+    }
+);
+
+main : IO ();
+main = println(clone_times(5, Array::fill(10, 0), 0).to_string);
+"#;
+
+    /// Builds `SOURCE` into `dir` with `build_args`, and returns the path of the program.
+    fn build(dir: &Path, build_args: &[&str]) -> std::path::PathBuf {
+        let program = dir.join("program");
+        let output = fix_build_source_command(dir, SOURCE, "none")
+            .arg("-o")
+            .arg(&program)
+            .args(build_args)
+            .output()
+            .expect("Failed to execute fix build");
+        assert!(
+            output.status.success(),
+            "fix build failed:\nstdout: {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        program
+    }
+
+    /// Runs `program` in `dir` with the report directed to `report`, asserts it prints the sum
+    /// `SOURCE` computes, and returns what it wrote to stderr.
+    fn run(program: &Path, dir: &Path, report: &Path) -> String {
+        let output = Command::new(program)
+            .current_dir(dir)
+            .env("FIX_PROFILE_RC_OUTPUT", report)
+            .output()
+            .expect("Failed to run the built program");
+        assert!(
+            output.status.success(),
+            "the program exited with {}",
+            output.status
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "15");
+        String::from_utf8_lossy(&output.stderr).to_string()
+    }
+
+    /// The count the summary line of `report` gives before `label`, e.g. "force-unique clones".
+    fn summary_count(report: &str, label: &str) -> u64 {
+        let summary = report.lines().next().expect("the report is empty");
+        let before = match summary.find(&format!(" {}", label)) {
+            Some(pos) => &summary[..pos],
+            None => panic!("the report has no count of {}:\n{}", label, report),
+        };
+        let count = before.rsplit(' ').next().expect("the count is missing");
+        count.parse().expect("the count is not a number")
+    }
+
+    /// The report counts every clone of the shared array, along with the allocations and the
+    /// reference counting around them, and lists the location cloning the array in a section of
+    /// its own.
+    #[test]
+    fn test_profile_rc_reports_clones_of_a_shared_array() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let program = build(temp_dir.path(), &["--profile", "rc"]);
+        let report_path = temp_dir.path().join("profile.txt");
+        let stderr = run(&program, temp_dir.path(), &report_path);
+        assert!(
+            stderr.contains("Reference-count profile written to"),
+            "the program did not say where it wrote the report:\n{}",
+            stderr
+        );
+
+        let report = fs::read_to_string(&report_path).expect("the report was not written");
+        assert!(
+            summary_count(&report, "force-unique clones") >= 5,
+            "the five clones of the shared array were not all counted:\n{}",
+            report
+        );
+        for label in ["allocations", "retains", "releases"] {
+            assert!(
+                summary_count(&report, label) > 0,
+                "the report counts no {}:\n{}",
+                label,
+                report
+            );
+        }
+        assert!(
+            report.contains("Top locations by events:")
+                && report.contains("Locations cloning a shared value to modify it:"),
+            "the report lacks its rankings:\n{}",
+            report
+        );
+        assert!(
+            report.contains(".fix:"),
+            "the report names no source location:\n{}",
+            report
+        );
+    }
+
+    /// A program built without the profile counts nothing and writes no report.
+    #[test]
+    fn test_program_without_profile_writes_no_report() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let program = build(temp_dir.path(), &[]);
+        let report_path = temp_dir.path().join("profile.txt");
+        let stderr = run(&program, temp_dir.path(), &report_path);
+        assert!(
+            !report_path.exists() && !stderr.contains("Reference-count profile"),
+            "a program built without `--profile=rc` wrote a report:\n{}",
+            stderr
+        );
+    }
+}
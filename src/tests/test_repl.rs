@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod integration_tests {
+    use crate::tests::test_util::{copy_dir_recursive, fix_command};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process::{Output, Stdio};
+    use tempfile::TempDir;
+
+    fn get_test_cases_dir() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/tests/test_repl/cases");
+        path
+    }
+
+    fn setup_test_env(case_name: &str) -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let src = get_test_cases_dir().join(case_name);
+        let dst = temp_dir.path().join(case_name);
+        copy_dir_recursive(&src, &dst).expect("Failed to copy test case");
+        (temp_dir, dst)
+    }
+
+    /// Run `fix repl` in `project_dir`, feeding it `input` as its standard input.
+    fn run_repl(project_dir: &PathBuf, input: &str) -> Output {
+        let mut child = fix_command()
+            .arg("repl")
+            .current_dir(project_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to start fix repl");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .expect("Failed to write to fix repl");
+        child
+            .wait_with_output()
+            .expect("Failed to wait for fix repl")
+    }
+
+    /// Values are printed, `IO` actions are run, and bindings and imports are kept for the inputs
+    /// that follow.
+    #[test]
+    fn test_repl_evaluates_inputs() {
+        let (_temp_dir, project_dir) = setup_test_env("project");
+
+        let output = run_repl(
+            &project_dir,
+            "1 + 2\n\
+             import Main;\n\
+             let xs = [3, 1, 2];\n\
+             xs.sort\n\
+             double(\n  xs.get_size\n)\n\
+             println(\"hello\")\n\
+             pure(true)\n\
+             :type xs\n\
+             :quit\n\
+             1 + 1\n",
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "stderr: {}", stderr);
+        assert_eq!(
+            stdout, "3\n[1, 2, 3]\n6\nhello\ntrue\nxs : Array I64\n",
+            "stderr: {}",
+            stderr
+        );
+    }
+
+    /// An input that fails to compile or exits with an error is reported, and the session goes on
+    /// without it.
+    #[test]
+    fn test_repl_recovers_from_errors() {
+        let (_temp_dir, project_dir) = setup_test_env("project");
+
+        let output = run_repl(
+            &project_dir,
+            "let x = undefined_name;\n\
+             1 + \"one\"\n\
+             (exit(3) : IO ())\n\
+             let x = 10;\n\
+             x * x\n",
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "stderr: {}", stderr);
+        assert_eq!(stdout, "100\n", "stderr: {}", stderr);
+        assert!(stderr.contains("undefined_name"), "stderr: {}", stderr);
+        assert!(
+            stderr.contains("The input ended with exit status 3."),
+            "stderr: {}",
+            stderr
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! `fix semver-check` compares the API of a library with the one at a git revision, and checks the
+//! version bump in the project file against the changes.
+//!
+//! Each test commits a library at version 1.0.0 under the tag `v1.0.0` into a fresh repository,
+//! changes its source and its version in the working tree, and checks the changes against the tag.
+
+use crate::tests::test_util::{assert_failed, assert_succeeded, run_fix};
+use std::{fs, path::Path, process::Command};
+use tempfile::TempDir;
+
+/// The library as it is tagged `v1.0.0`.
+const BASELINE_SOURCE: &str = "module Lib;
+
+type Point = struct { x : I64, y : I64 };
+
+double : I64 -> I64;
+double = |x| 2 * x;
+
+show : a -> String;
+show = |_| \"value\";
+";
+
+/// Runs `git args` in `dir`, failing the test when it fails.
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run `git {:?}`: {}", args, e));
+    assert!(status.success(), "`git {:?}` failed", args);
+}
+
+/// Writes the project file of the library at `version`.
+fn write_project_file(dir: &Path, version: &str) {
+    let proj_toml = format!(
+        "[general]\n\
+         name = \"semver-lib\"\n\
+         version = \"{}\"\n\
+         \n\
+         [build]\n\
+         files = [\"lib.fix\"]\n",
+        version
+    );
+    fs::write(dir.join("fixproj.toml"), proj_toml).expect("Failed to write fixproj.toml");
+}
+
+/// A repository holding the library tagged `v1.0.0`, whose working tree is changed to `source` at
+/// `version`.
+fn setup_library(source: &str, version: &str) -> TempDir {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let dir = temp_dir.path();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "Test"]);
+    fs::write(dir.join(".gitignore"), ".fixlang\n").expect("Failed to write .gitignore");
+    write_project_file(dir, "1.0.0");
+    fs::write(dir.join("lib.fix"), BASELINE_SOURCE).expect("Failed to write lib.fix");
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "release 1.0.0"]);
+    git(dir, &["tag", "v1.0.0"]);
+
+    write_project_file(dir, version);
+    fs::write(dir.join("lib.fix"), source).expect("Failed to write lib.fix");
+    temp_dir
+}
+
+/// Removing a value, adding a field and constraining a value are breaking, so a minor version bump
+/// is refused, and the value added with the type of the removed one is suggested as its new name.
+#[test]
+fn test_breaking_changes_need_major_bump() {
+    let source = "module Lib;
+
+type Point = struct { x : I64, y : I64, z : I64 };
+
+twice : I64 -> I64;
+twice = |x| 2 * x;
+
+show : [a : ToString] a -> String;
+show = |x| x.to_string;
+";
+    let temp_dir = setup_library(source, "1.1.0");
+    let output = run_fix(temp_dir.path(), &["semver-check", "v1.0.0"]);
+    assert_failed(&output, "a minor version bump should be refused.");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for expected in [
+        "major: value `Lib::double` was removed (renamed to `Lib::twice`?).",
+        "major: field `z` was added to type `Lib::Point`.",
+        "major: value `Lib::show` has the new constraints `a : Std::ToString`.",
+        "minor: value `Lib::twice` was added.",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "the output should contain \"{}\".\nstdout: {}",
+            expected,
+            stdout
+        );
+    }
+    assert!(
+        stderr.contains("Raise `[general] version` to 2.0.0 or later."),
+        "the error should name the least version allowed.\nstderr: {}",
+        stderr
+    );
+
+    // The same changes pass under a major version bump.
+    write_project_file(temp_dir.path(), "2.0.0");
+    let output = run_fix(temp_dir.path(), &["semver-check", "v1.0.0"]);
+    assert_succeeded(&output, "a major version bump should be accepted.");
+}
+
+/// Adding a value needs a minor version bump, which a patch version bump is not.
+#[test]
+fn test_additions_need_minor_bump() {
+    let source = format!(
+        "{}\ntriple : I64 -> I64;\ntriple = |x| 3 * x;\n",
+        BASELINE_SOURCE
+    );
+    let temp_dir = setup_library(&source, "1.0.1");
+    let output = run_fix(temp_dir.path(), &["semver-check", "v1.0.0"]);
+    assert_failed(&output, "a patch version bump should be refused.");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("minor: value `Lib::triple` was added.") && !stdout.contains("major:"),
+        "the addition should be the only change.\nstdout: {}",
+        stdout
+    );
+
+    write_project_file(temp_dir.path(), "1.1.0");
+    let output = run_fix(temp_dir.path(), &["semver-check", "v1.0.0"]);
+    assert_succeeded(&output, "a minor version bump should be accepted.");
+}
@@ -0,0 +1,67 @@
+//! A static library `fix build` produces, linked into the C program that uses it.
+//!
+//! The archive carries the object files of the program and the runtime, and the build writes beside
+//! it the C header declaring the exported functions and the flags a program linking the archive
+//! also needs. A C program built from the three runs with nothing of the library left to find.
+
+use crate::configuration::OutputFileType;
+use crate::tests::test_util::{assert_succeeded, fix_command_at_opt_level, setup_case_projects};
+use std::process::Command;
+
+/// The directory holding this module's case projects.
+const CASES: &str = "src/tests/test_static_library/cases";
+
+/// Builds `linked_program` as a static library at `opt_level`, compiles the driver against the
+/// header the build writes and links it with the archive and the flags the build writes, and runs
+/// the driver, which reports through its exit status whether the exported function answers what
+/// the Fix source says it does.
+fn assert_a_linked_library_is_called(opt_level: &str) {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "linked_program");
+    let output = fix_command_at_opt_level("build", opt_level)
+        .current_dir(&project_dir)
+        .output()
+        .expect("Failed to execute `fix build`");
+    assert_succeeded(
+        &output,
+        &format!("`fix build -O {}` should build the library.", opt_level),
+    );
+    let library = project_dir.join(OutputFileType::StaticLibrary.default_file_name());
+    let link_flags = std::fs::read_to_string(library.with_extension("ldflags"))
+        .expect("`fix build` should write the link flags beside the library.");
+
+    let driver = project_dir.join("driver");
+    let output = Command::new("gcc")
+        .args(["-Wall", "-Werror", "-o", driver.to_str().unwrap()])
+        .arg(project_dir.join("driver.c"))
+        .arg(&library)
+        .args(link_flags.split_whitespace())
+        .output()
+        .expect("Failed to execute gcc");
+    assert_succeeded(
+        &output,
+        "gcc should link the driver with the library and the flags the build wrote.",
+    );
+
+    // The library is gone once it is linked in: the driver finds nothing of it at run time.
+    std::fs::remove_file(&library).unwrap();
+    let output = Command::new(&driver)
+        .output()
+        .expect("Failed to execute the driver");
+    assert_succeeded(
+        &output,
+        "the driver should get the answer of the exported function.",
+    );
+}
+
+/// A library built without optimization, where the code is split into several object files, every
+/// one of which the archive has to carry.
+#[test]
+fn test_a_static_library_built_at_none_is_linked_and_called() {
+    assert_a_linked_library_is_called("none");
+}
+
+/// A library built at the level that compiles the whole program as one object file.
+#[test]
+fn test_a_static_library_built_at_max_is_linked_and_called() {
+    assert_a_linked_library_is_called("max");
+}
@@ -0,0 +1,12 @@
+// Calls the function the static library built from `main.fix` exports, through the header the build
+// writes beside it, and reports through its exit status whether the answer is the one the Fix source
+// gives.
+//
+// The library is linked into the driver itself, together with the flags the build writes to
+// `lib.ldflags`, so the driver runs with no library to find at run time.
+
+#include "lib.h"
+
+int main(void) {
+    return fix_sum_squares(10) == 285 ? 0 : 1;
+}
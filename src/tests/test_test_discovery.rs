@@ -0,0 +1,68 @@
+//! `fix test` runs every value named `test_*` of type `IO ()` or `IO (Result ErrMsg ())` that the
+//! test source files define, each in a process of its own, selected by the arguments after `--`.
+//!
+//! The case under `test_test_discovery/cases` has two passing tests and two failing ones in its
+//! test source file, beside a helper and a `test_*` value of the ordinary sources, which are not
+//! tests.
+
+use crate::tests::test_util::{assert_failed, assert_succeeded, run_fix, setup_case_projects};
+
+/// The directory holding this module's case projects.
+const CASES: &str = "src/tests/test_test_discovery/cases";
+
+/// Each test is reported on a line of its own, one failing by `undefined` does not hide the others,
+/// and the run fails with a summary naming the failed tests.
+#[test]
+fn test_runs_each_test_in_its_own_process() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "root_with_tests");
+    let output = run_fix(&project_dir, &["test"]);
+    assert_failed(
+        &output,
+        "`fix test` should fail, because two of the tests fail.",
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in [
+        "running 4 tests",
+        "test Test::test_double ... ok",
+        "test Test::test_double_result ... ok",
+        "test Test::test_failing_result ... FAILED (exit status 1)",
+        "test Test::test_undefined ... FAILED",
+        "test result: FAILED. 2 passed; 2 failed; 0 filtered out",
+    ] {
+        assert!(
+            stdout.contains(line),
+            "the output should contain `{}`.\nstdout: {}\nstderr: {}",
+            line,
+            stdout,
+            stderr
+        );
+    }
+    assert!(
+        stderr.contains("failing on purpose") && stderr.contains("aborting on purpose"),
+        "the failing tests should write their messages.\nstderr: {}",
+        stderr
+    );
+    assert!(
+        !stdout.contains("test_helper") && !stdout.contains("test_in_main"),
+        "a helper and a value of the ordinary sources should not be run as tests.\nstdout: {}",
+        stdout
+    );
+}
+
+/// The arguments after `--` select the tests whose names contain one of them.
+#[test]
+fn test_filters_tests_by_name() {
+    let (_temp_dir, project_dir) = setup_case_projects(CASES, "root_with_tests");
+    let output = run_fix(&project_dir, &["test", "--", "double"]);
+    assert_succeeded(&output, "the tests of `double` should pass.");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("running 2 tests")
+            && stdout.contains("test result: ok. 2 passed; 0 failed; 2 filtered out"),
+        "two tests should be selected.\nstdout: {}",
+        stdout
+    );
+}
@@ -63,7 +63,10 @@ fn test_the_module_dependency_hash_covers_the_c_type_sizes() {
 #[cfg(test)]
 mod integration_tests {
     use crate::configuration::CTypeSizes;
-    use crate::constants::{C_TYPES_JSON_PATH, DOT_FIXLANG, TYPE_CHECK_CACHE_PATH};
+    use crate::constants::{
+        C_TYPES_JSON_PATH, DOT_FIXLANG, INTERMEDIATE_PATH, TYPE_CHECK_CACHE_PATH,
+    };
+    use crate::elaboration::typecheckcache::type_check_cache_dir;
     use crate::tests::test_util::fix_command;
     use std::fs;
     use std::path::Path;
@@ -380,7 +383,7 @@ main = println $ (1, 2, 3, 4).to_string + 65.c_int.i64.to_string;
         )
         .expect("Failed to write main.fix");
         let cache_entries = || {
-            let mut names = fs::read_dir(dir.join(TYPE_CHECK_CACHE_PATH))
+            let mut names = fs::read_dir(dir.join(type_check_cache_dir()))
                 .expect("Failed to read the type-check cache directory")
                 .map(|entry| entry.expect("Failed to read a cache entry").file_name())
                 .collect::<Vec<_>>();
@@ -431,4 +434,179 @@ main = println $ (1, 2, 3, 4).to_string + 65.c_int.i64.to_string;
             "the program built from \"b/main.fix\" computes what its own source says"
         );
     }
+
+    /// Writes a project of `main.fix`, which imports `Lib` and adds one to `Lib::base`, and of
+    /// `lib.fix`, which declares `base` at `base_type` and defines it as `base_value`.
+    fn write_project_importing_lib(dir: &Path, base_type: &str, base_value: &str) {
+        fs::write(
+            dir.join("fixproj.toml"),
+            "[general]\nname = \"imports-lib\"\nversion = \"0.1.0\"\n[build]\nfiles = [\"main.fix\", \"lib.fix\"]\n",
+        )
+        .expect("Failed to write fixproj.toml");
+        fs::write(
+            dir.join("main.fix"),
+            "module Main;\n\nimport Lib;\n\nmain : IO ();\nmain = println $ (base + 1).to_string;\n",
+        )
+        .expect("Failed to write main.fix");
+        fs::write(
+            dir.join("lib.fix"),
+            format!(
+                "module Lib;\n\nbase : {};\nbase = {};\n",
+                base_type, base_value
+            ),
+        )
+        .expect("Failed to write lib.fix");
+    }
+
+    /// The entry of a value is filed under the sources of every module its module imports, so an
+    /// edit to an imported module reaches the values that use it, though their own module is
+    /// untouched. A stale entry would let `Main::main` pass the check after `Lib::base` became a
+    /// string, and would have the program print what the first version of `Lib` computed.
+    #[test]
+    fn an_edit_to_an_imported_module_rechecks_the_modules_importing_it() {
+        let temp = TempDir::new().expect("Failed to create temp directory");
+        let dir = temp.path();
+
+        write_project_importing_lib(dir, "I64", "1");
+        let report = check(dir);
+        assert!(
+            report.contains("No errors found."),
+            "the first version of the project has no error:\n{}",
+            report
+        );
+
+        write_project_importing_lib(dir, "String", "\"one\"");
+        let report = check(dir);
+        assert!(
+            report.contains("main.fix") && !report.contains("No errors found."),
+            "adding one to a string is an error of `main.fix`, which the check did not report:\n{}",
+            report
+        );
+
+        write_project_importing_lib(dir, "I64", "41");
+        let output = fix_command()
+            .arg("run")
+            .current_dir(dir)
+            .output()
+            .expect("failed to run fix run");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "42",
+            "the program computes what the last version of `Lib` says:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// Runs `fix build` on the project in `dir`, which is to succeed.
+    fn build_project(dir: &Path) {
+        let output = fix_command()
+            .arg("build")
+            .current_dir(dir)
+            .output()
+            .expect("failed to run fix build");
+        assert!(
+            output.status.success(),
+            "fix build failed in {}:\n{}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// The names of the entries this compiler filed in the type-check cache under `dir`, sorted.
+    fn cache_entry_names(dir: &Path) -> Vec<String> {
+        let mut names = fs::read_dir(dir.join(type_check_cache_dir()))
+            .expect("Failed to read the type-check cache directory")
+            .map(|entry| {
+                let entry = entry.expect("Failed to read a cache entry");
+                entry.file_name().to_string_lossy().to_string()
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// `fix clean` removes the intermediate files and the entries of other builds of the compiler,
+    /// and keeps the entries of this one, which a build after it finds. `fix clean --all` removes
+    /// the cache as well.
+    #[test]
+    fn fix_clean_keeps_the_entries_this_compiler_can_use() {
+        let temp = TempDir::new().expect("Failed to create temp directory");
+        let dir = temp.path();
+        write_project_importing_lib(dir, "I64", "1");
+        build_project(dir);
+        let entries = cache_entry_names(dir);
+        let other_build = dir.join(TYPE_CHECK_CACHE_PATH).join("0.0.0-other");
+        fs::create_dir_all(&other_build).expect("Failed to create a directory of another build");
+        fs::write(other_build.join("Main__main_0_0"), "").expect("Failed to write an entry");
+
+        let clean = fix_command()
+            .arg("clean")
+            .current_dir(dir)
+            .output()
+            .expect("failed to run fix clean");
+        assert!(clean.status.success(), "fix clean failed");
+        assert!(
+            !dir.join(INTERMEDIATE_PATH).exists(),
+            "fix clean left the intermediate files"
+        );
+        assert!(
+            !other_build.exists(),
+            "fix clean left the entries of another build of the compiler"
+        );
+        assert_eq!(
+            cache_entry_names(dir),
+            entries,
+            "fix clean removed entries this compiler can use"
+        );
+
+        build_project(dir);
+        assert_eq!(
+            cache_entry_names(dir),
+            entries,
+            "the build after fix clean checked values the kept entries held"
+        );
+
+        let clean_all = fix_command()
+            .args(["clean", "--all"])
+            .current_dir(dir)
+            .output()
+            .expect("failed to run fix clean --all");
+        assert!(clean_all.status.success(), "fix clean --all failed");
+        assert!(
+            !dir.join(DOT_FIXLANG).exists(),
+            "fix clean --all left the working files"
+        );
+    }
+
+    /// Of the entries of one value, `fix clean` keeps the three written last, as many as the cache
+    /// the language server holds in memory keeps of one value.
+    #[test]
+    fn fix_clean_removes_all_but_the_latest_entries_of_a_value() {
+        let temp = TempDir::new().expect("Failed to create temp directory");
+        let dir = temp.path();
+        let entries_of_base = || {
+            cache_entry_names(dir)
+                .into_iter()
+                .filter(|name| name.starts_with("Lib__base_"))
+                .count()
+        };
+        for value in 1..=5 {
+            write_project_importing_lib(dir, "I64", &value.to_string());
+            let report = check(dir);
+            assert!(report.contains("No errors found."), "{}", report);
+        }
+        assert_eq!(entries_of_base(), 5, "each version of `Lib::base` is filed");
+
+        let clean = fix_command()
+            .arg("clean")
+            .current_dir(dir)
+            .output()
+            .expect("failed to run fix clean");
+        assert!(clean.status.success(), "fix clean failed");
+        assert_eq!(
+            entries_of_base(),
+            3,
+            "fix clean keeps the three latest versions of `Lib::base`"
+        );
+    }
 }
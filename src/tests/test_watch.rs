@@ -0,0 +1,146 @@
+//! `--watch` runs the command again after each change to a file it read. These tests start a
+//! watching command on a project, edit the project underneath it, and read what the runs print.
+
+#[cfg(test)]
+mod integration_tests {
+    use crate::tests::test_util::fix_command;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Read};
+    use std::path::Path;
+    use std::process::{Child, Stdio};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// How long a run of the watched command may take, the first one building the standard library.
+    const RUN_TIMEOUT: Duration = Duration::from_secs(180);
+
+    const FIXPROJ: &str = r#"[general]
+name = "watched"
+version = "0.1.0"
+
+[build]
+files = ["main.fix", "lib.fix"]
+"#;
+
+    const MAIN_FIX: &str = r#"module Main;
+
+import Lib;
+
+main : IO ();
+main = println $ message;
+"#;
+
+    /// The dependency module of the project, defining `message` as `body`.
+    fn lib_fix(body: &str) -> String {
+        format!("module Lib;\n\nmessage : String;\nmessage = {};\n", body)
+    }
+
+    /// A `fix` command running with `--watch` in a project, and the lines it writes to stdout and
+    /// stderr, in the order they arrive. The command is killed when this is dropped.
+    struct Watched {
+        child: Child,
+        lines: Receiver<String>,
+        seen: Vec<String>,
+    }
+
+    impl Watched {
+        /// Writes the project into `dir` with `message` defined as `body`, and starts
+        /// `fix <args> --watch` there.
+        fn start(dir: &Path, args: &[&str], body: &str) -> Self {
+            fs::write(dir.join("fixproj.toml"), FIXPROJ).expect("Failed to write fixproj.toml");
+            fs::write(dir.join("main.fix"), MAIN_FIX).expect("Failed to write main.fix");
+            fs::write(dir.join("lib.fix"), lib_fix(body)).expect("Failed to write lib.fix");
+            let mut child = fix_command()
+                .args(args)
+                .arg("--watch")
+                .current_dir(dir)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("Failed to start the watching command");
+            let (send, lines) = channel();
+            let stdout: Box<dyn Read + Send> = Box::new(child.stdout.take().unwrap());
+            let stderr: Box<dyn Read + Send> = Box::new(child.stderr.take().unwrap());
+            for stream in [stdout, stderr] {
+                let send = send.clone();
+                thread::spawn(move || {
+                    for line in BufReader::new(stream).lines() {
+                        let Ok(line) = line else { break };
+                        if send.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            Watched {
+                child,
+                lines,
+                seen: vec![],
+            }
+        }
+
+        /// Waits for a line containing `text`, failing the test after `RUN_TIMEOUT`. The lines
+        /// before it are consumed.
+        fn wait_for(&mut self, text: &str) {
+            let deadline = Instant::now() + RUN_TIMEOUT;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                match self.lines.recv_timeout(remaining) {
+                    Ok(line) => {
+                        let found = line.contains(text);
+                        self.seen.push(line);
+                        if found {
+                            return;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            panic!(
+                "the watching command printed no line containing {:?}; it printed:\n{}",
+                text,
+                self.seen.join("\n")
+            );
+        }
+    }
+
+    impl Drop for Watched {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    /// Editing the dependency module of the project runs the program again, built from the edit.
+    #[test]
+    fn test_run_watch_runs_again_after_an_edit() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let dir = temp_dir.path();
+        let mut watched = Watched::start(dir, &["run"], r#""first version""#);
+        watched.wait_for("first version");
+        watched.wait_for("Watching");
+
+        fs::write(dir.join("lib.fix"), lib_fix(r#""second version""#))
+            .expect("Failed to edit lib.fix");
+        watched.wait_for("second version");
+    }
+
+    /// An edit that breaks the project is reported, and the watch goes on: the edit that fixes it
+    /// is checked in turn.
+    #[test]
+    fn test_check_watch_survives_an_error() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let dir = temp_dir.path();
+        let mut watched = Watched::start(dir, &["check"], r#""fine""#);
+        watched.wait_for("No errors found.");
+        watched.wait_for("Watching");
+
+        fs::write(dir.join("lib.fix"), lib_fix("42")).expect("Failed to edit lib.fix");
+        watched.wait_for("error");
+        watched.wait_for("Watching");
+
+        fs::write(dir.join("lib.fix"), lib_fix(r#""fine again""#)).expect("Failed to edit lib.fix");
+        watched.wait_for("No errors found.");
+    }
+}